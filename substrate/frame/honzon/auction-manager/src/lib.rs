@@ -0,0 +1,1116 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Auction Manager Pallet
+//!
+//! Manages the collateral auctions of the Honzon protocol.
+//!
+//! ## Overview
+//!
+//! When the CDP engine liquidates a position, the confiscated collateral is handed to this
+//! pallet via [`AuctionManager::new_collateral_auction`] and put up for sale against a target
+//! amount of stable currency. The auction schedule itself is kept by the configured [`Auction`]
+//! implementation; this pallet is its [`AuctionHandler`] and holds all the business logic.
+//!
+//! Auctions only start while the collateral can be priced against the stable currency: a
+//! request arriving while the price feed is down is parked in a bounded deferred queue and
+//! started by `on_initialize` once the feed returns, the collateral staying custodied by the
+//! CDP treasury meanwhile. A full queue falls back to immediate creation, since availability
+//! beats optimal pricing.
+//!
+//! Bids are payments: a new bidder refunds the outgoing bidder directly and pays the rest of
+//! their bid - capped at the auction target - into the CDP treasury surplus. Once bids exceed
+//! the target the lot shrinks, the freed collateral going back to the refund recipient (the
+//! owner of the liquidated position). The winner receives the remaining lot when the auction
+//! ends. Because bids are payments already made, they cannot be cancelled. A bid whose
+//! implied unit price exceeds the configured multiple of the oracle price is rejected as
+//! anomalous, even though it would favour the treasury. Since every accepted bid pushes the
+//! closing block back, an account cannot raise its own leading bid again within
+//! `MinBlocksBetweenBids` blocks, curbing self-outbidding games without throttling
+//! competition between distinct bidders.
+//!
+//! An optional reserve guards liquidation auctions from closing far below oracle value in a
+//! thin market: with `MinAuctionReserveFraction` configured, a winning bid under that
+//! fraction of the lot's oracle value does not close the sale. The bidder is refunded and
+//! the lot is relisted as a fresh auction, up to `MaxAuctionRelists` times; after that the
+//! collateral goes back to the refund recipient instead of selling off cheaply.
+//!
+//! Treasury-initiated collateral sales use always-forward auctions, created with
+//! [`AuctionManager::new_forward_auction`]: there is no target and no reverse stage, every
+//! bid wins an ever-increasing price, and bids below the per-auction price floor are
+//! rejected. Forward auctions run on their own durations - an explicit initial one and
+//! `ForwardAuctionDuration` after each accepted bid.
+//!
+//! Paying the surplus into the treasury can fail for reasons outside any bidder's control,
+//! e.g. the treasury being in a bad state. A circuit breaker counts consecutive surplus-pay
+//! failures and, once `MaxConsecutiveSurplusFailures` is reached, suspends all bidding instead
+//! of letting every arriving bid fail the same way. Governance re-enables bidding with
+//! `resume_bidding` once the cause is fixed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use frame_support::{
+	pallet_prelude::*,
+	storage::with_storage_layer,
+	traits::{
+		fungibles::Mutate,
+		tokens::{Balance, Preservation},
+	},
+};
+use frame_system::pallet_prelude::*;
+use honzon_support::{
+	Auction, AuctionHandler, AuctionManager, CDPTreasury, Change, OnNewBidResult, Price,
+	PriceProvider, Rate, Ratio,
+};
+use sp_runtime::{
+	traits::{Bounded, CheckedAdd, Saturating, Zero},
+	ArithmeticError, DispatchResult, FixedPointNumber, RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// A collateral auction in progress.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct CollateralAuctionItem<AccountId, CurrencyId, Balance, BlockNumber> {
+	/// The account refunded with the collateral freed when bids exceed the target, normally
+	/// the owner of the liquidated position. `None` for always-forward auctions, which
+	/// never free collateral.
+	pub refund_recipient: Option<AccountId>,
+	/// The collateral currency on sale.
+	pub currency_id: CurrencyId,
+	/// The amount of collateral initially put up for sale.
+	pub initial_amount: Balance,
+	/// The amount of collateral still on sale.
+	pub amount: Balance,
+	/// The amount of stable currency the auction aims to recover. Zero makes this an
+	/// always-forward auction: every bid wins an ever-increasing price, with no reverse
+	/// stage.
+	pub target: Balance,
+	/// The lowest unit price always-forward bids are accepted at. Zero means no floor;
+	/// ignored for auctions with a target.
+	pub min_price_per_unit: Price,
+	/// The block the auction started at.
+	pub start_time: BlockNumber,
+	/// The block the latest bid was accepted at, if any. Used to throttle a bidder
+	/// outbidding themselves.
+	pub last_bid_block: Option<BlockNumber>,
+	/// How many times the auction has been relisted after closing below its reserve.
+	pub relist_count: u32,
+}
+
+impl<AccountId, CurrencyId, Balance: Zero, BlockNumber>
+	CollateralAuctionItem<AccountId, CurrencyId, Balance, BlockNumber>
+{
+	/// Whether this auction runs in always-forward mode, i.e. has no target.
+	pub fn always_forward(&self) -> bool {
+		self.target.is_zero()
+	}
+}
+
+/// A collateral auction request waiting for the price feed of its collateral to return.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct DeferredAuctionItem<AccountId, CurrencyId, Balance> {
+	/// The account refunded with the collateral freed when bids exceed the target.
+	pub refund_recipient: AccountId,
+	/// The collateral currency to put up for sale.
+	pub currency_id: CurrencyId,
+	/// The amount of collateral to put up for sale.
+	pub amount: Balance,
+	/// The amount of stable currency the auction should aim to recover.
+	pub target: Balance,
+}
+
+/// The auction id type of the configured auction scheduler.
+pub type AuctionIdOf<T> = <<T as Config>::Auction as Auction<
+	<T as frame_system::Config>::AccountId,
+	BlockNumberFor<T>,
+>>::AuctionId;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The origin which may resume bidding after the circuit breaker suspended it.
+		type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin which may redirect the refund recipient of a running auction, e.g.
+		/// when the original account was compromised or frozen.
+		type RecoveryOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The currency id type, shared by collateral currencies and the stable currency.
+		type CurrencyId: Parameter + Member + Copy + MaxEncodedLen + Ord;
+
+		/// The balance type.
+		type Balance: Balance + sp_runtime::FixedPointOperand;
+
+		/// The assets bids are paid in. Used to refund the outgoing bidder directly from the
+		/// new bidder.
+		type Currency: Mutate<
+			Self::AccountId,
+			AssetId = Self::CurrencyId,
+			Balance = Self::Balance,
+		>;
+
+		/// The auction scheduler keeping the auctions this pallet manages.
+		type Auction: Auction<Self::AccountId, BlockNumberFor<Self>, Balance = Self::Balance>;
+
+		/// The CDP treasury receiving auction proceeds and custodying the collateral on sale.
+		type CDPTreasury: CDPTreasury<
+			Self::AccountId,
+			Balance = Self::Balance,
+			CurrencyId = Self::CurrencyId,
+		>;
+
+		/// The stable currency bids are denominated in.
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<Self::CurrencyId>;
+
+		/// The minimum amount a bid must exceed the current winning bid by, as a fraction of
+		/// the auction target.
+		#[pallet::constant]
+		type MinimumIncrementSize: Get<Rate>;
+
+		/// The upper bound of a bid's implied collateral unit price, as a multiple of the
+		/// oracle price. Bids above the band are rejected as likely fat-fingered or
+		/// manipulated even though they favour the treasury. `None` disables the check, as
+		/// does a missing oracle price.
+		#[pallet::constant]
+		type MaxBidPriceMultiple: Get<Option<Ratio>>;
+
+		/// The reserve of a collateral auction, as a fraction of the lot's oracle value. A
+		/// winning bid below the reserve does not close the sale: the bidder is refunded
+		/// and the lot is relisted, up to [`Config::MaxAuctionRelists`] times. `None`
+		/// disables reserve pricing, as does a missing oracle price. Always-forward
+		/// auctions carry their own explicit price floor and are not affected.
+		#[pallet::constant]
+		type MinAuctionReserveFraction: Get<Option<Ratio>>;
+
+		/// The maximum number of times a collateral auction is relisted after closing below
+		/// its reserve. Once exhausted, the collateral goes back to the refund recipient
+		/// instead.
+		#[pallet::constant]
+		type MaxAuctionRelists: Get<u32>;
+
+		/// The number of blocks a bidder must wait before raising their own leading bid
+		/// again. Bids from distinct accounts are never throttled.
+		#[pallet::constant]
+		type MinBlocksBetweenBids: Get<BlockNumberFor<Self>>;
+
+		/// How long an auction stays open after its start or its latest accepted bid.
+		#[pallet::constant]
+		type AuctionTimeToClose: Get<BlockNumberFor<Self>>;
+
+		/// How long an always-forward auction stays open after its latest accepted bid. The
+		/// initial duration of a forward auction is chosen per auction at creation.
+		#[pallet::constant]
+		type ForwardAuctionDuration: Get<BlockNumberFor<Self>>;
+
+		/// The number of consecutive surplus-pay failures after which the circuit breaker
+		/// suspends all bidding until governance calls `resume_bidding`.
+		#[pallet::constant]
+		type MaxConsecutiveSurplusFailures: Get<u32>;
+
+		/// The price feed gating auction creation. An auction started while its collateral
+		/// cannot be priced against the stable currency is deferred instead.
+		type PriceSource: PriceProvider<Self::CurrencyId>;
+
+		/// The maximum number of auction requests deferred while the price feed is down.
+		/// When the queue is full, further auctions are created immediately despite the
+		/// missing price: availability beats optimal pricing.
+		#[pallet::constant]
+		type MaxDeferredAuctions: Get<u32>;
+
+		/// The maximum number of deferred auctions started per block once the price feed
+		/// returns.
+		#[pallet::constant]
+		type MaxDeferredDrainPerBlock: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The auction does not exist.
+		AuctionNotExist,
+		/// The collateral amount put up for auction is zero.
+		InvalidAmount,
+		/// The bid does not meet the minimum increment over the current winning bid.
+		InvalidBidPrice,
+		/// The bid's implied unit price is above the allowed multiple of the oracle price.
+		BidPriceOutOfBand,
+		/// The bidder must wait `MinBlocksBetweenBids` blocks before raising their own
+		/// leading bid again.
+		BidTooSoon,
+		/// The bid is below the price floor of the always-forward auction.
+		BidBelowMinimumPrice,
+		/// Bidding is not currently suspended.
+		BiddingNotSuspended,
+		/// Always-forward auctions never free collateral and carry no refund recipient.
+		NoRefundRecipient,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A collateral auction was started.
+		CollateralAuctionCreated {
+			auction_id: AuctionIdOf<T>,
+			currency_id: T::CurrencyId,
+			amount: T::Balance,
+			target: T::Balance,
+			always_forward: bool,
+		},
+		/// A collateral auction concluded with a winning bid.
+		CollateralAuctionDealt {
+			auction_id: AuctionIdOf<T>,
+			currency_id: T::CurrencyId,
+			amount: T::Balance,
+			winner: T::AccountId,
+			payment: T::Balance,
+			always_forward: bool,
+		},
+		/// A collateral auction ended without any bid; the collateral stays in the treasury.
+		CollateralAuctionAborted {
+			auction_id: AuctionIdOf<T>,
+			currency_id: T::CurrencyId,
+			amount: T::Balance,
+		},
+		/// A collateral auction was cancelled, refunding the leading bidder if there was one.
+		CollateralAuctionCancelled { auction_id: AuctionIdOf<T> },
+		/// A collateral auction closed below its reserve; the leading bidder was refunded
+		/// and the lot was relisted as a fresh auction.
+		CollateralAuctionRelisted {
+			auction_id: AuctionIdOf<T>,
+			currency_id: T::CurrencyId,
+			amount: T::Balance,
+			reserve: T::Balance,
+			best_bid: T::Balance,
+			relist_count: u32,
+		},
+		/// A collateral auction closed below its reserve with its relists exhausted; the
+		/// leading bidder was refunded and the collateral went back to the refund
+		/// recipient.
+		CollateralAuctionReserveNotMet {
+			auction_id: AuctionIdOf<T>,
+			currency_id: T::CurrencyId,
+			amount: T::Balance,
+			reserve: T::Balance,
+			best_bid: T::Balance,
+		},
+		/// A collateral auction was deferred because its collateral cannot currently be
+		/// priced. The collateral stays custodied by the CDP treasury meanwhile.
+		AuctionDeferred {
+			refund_recipient: T::AccountId,
+			currency_id: T::CurrencyId,
+			amount: T::Balance,
+			target: T::Balance,
+		},
+		/// The deferred auction queue was full; the auction was created immediately
+		/// despite the missing price.
+		DeferredAuctionQueueFull {
+			currency_id: T::CurrencyId,
+			amount: T::Balance,
+			target: T::Balance,
+		},
+		/// Too many consecutive surplus payments failed; bidding is suspended until
+		/// governance calls `resume_bidding`.
+		BiddingSuspended { failures: u32 },
+		/// Bidding has been resumed.
+		BiddingResumed,
+		/// The refund recipient of an auction was redirected by the recovery origin.
+		AuctionRefundRecipientUpdated { auction_id: AuctionIdOf<T>, new_recipient: T::AccountId },
+	}
+
+	/// The collateral auctions in progress.
+	#[pallet::storage]
+	pub type CollateralAuctions<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AuctionIdOf<T>,
+		CollateralAuctionItem<T::AccountId, T::CurrencyId, T::Balance, BlockNumberFor<T>>,
+	>;
+
+	/// The total amount of collateral in auction, per collateral currency.
+	#[pallet::storage]
+	pub type TotalCollateralInAuction<T: Config> =
+		StorageMap<_, Twox64Concat, T::CurrencyId, T::Balance, ValueQuery>;
+
+	/// The total stable currency target of all collateral auctions in progress.
+	#[pallet::storage]
+	pub type TotalTargetInAuction<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// The number of consecutive failed surplus payments in `on_new_bid`. Reset by a
+	/// successful payment or by `resume_bidding`.
+	#[pallet::storage]
+	pub type ConsecutiveSurplusFailures<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Whether bidding is suspended by the surplus-pay circuit breaker.
+	#[pallet::storage]
+	pub type BiddingSuspended<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Auction requests deferred until the price feed of their collateral returns.
+	#[pallet::storage]
+	pub type DeferredAuctions<T: Config> = StorageValue<
+		_,
+		BoundedVec<
+			DeferredAuctionItem<T::AccountId, T::CurrencyId, T::Balance>,
+			T::MaxDeferredAuctions,
+		>,
+		ValueQuery,
+	>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			let started = Self::drain_deferred_auctions();
+			T::WeightInfo::on_initialize(started)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Resume bidding after the surplus-pay circuit breaker suspended it, resetting the
+		/// failure counter.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::resume_bidding())]
+		pub fn resume_bidding(origin: OriginFor<T>) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(BiddingSuspended::<T>::get(), Error::<T>::BiddingNotSuspended);
+
+			BiddingSuspended::<T>::kill();
+			ConsecutiveSurplusFailures::<T>::kill();
+			Self::deposit_event(Event::<T>::BiddingResumed);
+			Ok(())
+		}
+
+		/// Redirect the refund recipient of a collateral auction. Collateral freed by
+		/// reverse-stage bids and any reserve-not-met return then go to `new_recipient`
+		/// instead of the account recorded when the auction was created.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::set_auction_refund_recipient())]
+		pub fn set_auction_refund_recipient(
+			origin: OriginFor<T>,
+			auction_id: AuctionIdOf<T>,
+			new_recipient: T::AccountId,
+		) -> DispatchResult {
+			T::RecoveryOrigin::ensure_origin(origin)?;
+
+			CollateralAuctions::<T>::try_mutate(auction_id, |maybe_auction| -> DispatchResult {
+				let auction = maybe_auction.as_mut().ok_or(Error::<T>::AuctionNotExist)?;
+				ensure!(auction.refund_recipient.is_some(), Error::<T>::NoRefundRecipient);
+				auction.refund_recipient = Some(new_recipient.clone());
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::AuctionRefundRecipientUpdated {
+				auction_id,
+				new_recipient,
+			});
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether `new_price` exceeds `last_price` by at least `MinimumIncrementSize` of
+	/// `target`.
+	fn check_minimum_increment(
+		new_price: T::Balance,
+		last_price: T::Balance,
+		target: T::Balance,
+	) -> bool {
+		T::MinimumIncrementSize::get().saturating_mul_int(target) <=
+			new_price.saturating_sub(last_price)
+	}
+
+	/// Settle an accepted bid: refund the outgoing bidder from the new bidder, pay the rest
+	/// of the bid into the treasury surplus and shrink the lot once the target is exceeded.
+	///
+	/// Sets `surplus_pay_failed` when the failure came from the surplus payment, so the
+	/// caller can feed the circuit breaker after rolling the fund movements back.
+	fn collateral_auction_bid_handler(
+		now: BlockNumberFor<T>,
+		auction_id: AuctionIdOf<T>,
+		new_bid: (T::AccountId, T::Balance),
+		last_bid: Option<(T::AccountId, T::Balance)>,
+		surplus_pay_failed: &mut bool,
+	) -> DispatchResult {
+		CollateralAuctions::<T>::try_mutate(auction_id, |maybe_auction| -> DispatchResult {
+			let auction = maybe_auction.as_mut().ok_or(Error::<T>::AuctionNotExist)?;
+			let always_forward = auction.always_forward();
+			let (new_bidder, new_price) = new_bid;
+			let last_price = last_bid.as_ref().map(|(_, price)| *price).unwrap_or_else(Zero::zero);
+			ensure!(
+				Self::check_minimum_increment(new_price, last_price, auction.target),
+				Error::<T>::InvalidBidPrice
+			);
+			// A zero target makes the minimum increment vacuous, so forward auctions
+			// enforce strictly increasing bids and an absolute price floor instead.
+			if always_forward {
+				ensure!(new_price > last_price, Error::<T>::InvalidBidPrice);
+				ensure!(
+					new_price >= auction.min_price_per_unit.saturating_mul_int(auction.amount),
+					Error::<T>::BidBelowMinimumPrice
+				);
+			}
+			// Each accepted bid pushes the closing block back, so an account repeatedly
+			// outbidding itself could stall the auction. Throttle self-outbidding; bids
+			// from other accounts pass freely.
+			if let (Some((last_bidder, _)), Some(last_bid_block)) =
+				(&last_bid, auction.last_bid_block)
+			{
+				ensure!(
+					*last_bidder != new_bidder ||
+						now >= last_bid_block.saturating_add(T::MinBlocksBetweenBids::get()),
+					Error::<T>::BidTooSoon
+				);
+			}
+			// A bid implying a unit price far above the oracle smells of a fat finger or
+			// price manipulation, so it is rejected despite favouring the treasury. With
+			// no oracle price there is no band to hold the bid against.
+			if let Some(max_multiple) = T::MaxBidPriceMultiple::get() {
+				if let Some(feed_price) = T::PriceSource::get_relative_price(
+					auction.currency_id,
+					T::GetStableCurrencyId::get(),
+				) {
+					let implied_price =
+						Ratio::checked_from_rational(new_price, auction.amount)
+							.unwrap_or_else(Ratio::max_value);
+					ensure!(
+						implied_price <= max_multiple.saturating_mul(feed_price),
+						Error::<T>::BidPriceOutOfBand
+					);
+				}
+			}
+
+			// The new bidder refunds the outgoing bidder directly and pays the rest of
+			// their bid - capped at the target, if there is one - into the treasury
+			// surplus.
+			let mut payment =
+				if always_forward { new_price } else { auction.target.min(new_price) };
+			if let Some((last_bidder, _)) = &last_bid {
+				let refund =
+					if always_forward { last_price } else { auction.target.min(last_price) };
+				T::Currency::transfer(
+					T::GetStableCurrencyId::get(),
+					&new_bidder,
+					last_bidder,
+					refund,
+					Preservation::Expendable,
+				)?;
+				payment = payment.saturating_sub(refund);
+			}
+			if let Err(e) = T::CDPTreasury::deposit_surplus(&new_bidder, payment) {
+				*surplus_pay_failed = true;
+				return Err(e);
+			}
+
+			// A bid above the target shrinks the lot; the freed collateral goes back to
+			// the refund recipient immediately. Forward auctions have no reverse stage.
+			if !always_forward && new_price > auction.target {
+				let new_amount = Ratio::checked_from_rational(auction.target, new_price)
+					.map(|ratio| ratio.saturating_mul_int(auction.initial_amount))
+					.unwrap_or(auction.amount)
+					.min(auction.amount);
+				let refund_amount = auction.amount.saturating_sub(new_amount);
+				if let (Some(refund_recipient), false) =
+					(&auction.refund_recipient, refund_amount.is_zero())
+				{
+					T::CDPTreasury::withdraw_collateral(
+						refund_recipient,
+						auction.currency_id,
+						refund_amount,
+					)?;
+					auction.amount = new_amount;
+					TotalCollateralInAuction::<T>::mutate(auction.currency_id, |total| {
+						*total = total.saturating_sub(refund_amount)
+					});
+				}
+			}
+			auction.last_bid_block = Some(now);
+			Ok(())
+		})
+	}
+
+	/// Register the auction with the scheduler and start tracking it. A zero `target`
+	/// creates an always-forward auction with `min_price_per_unit` as its price floor.
+	fn create_collateral_auction(
+		refund_recipient: Option<&T::AccountId>,
+		currency_id: T::CurrencyId,
+		amount: T::Balance,
+		target: T::Balance,
+		min_price_per_unit: Price,
+		duration: BlockNumberFor<T>,
+		relist_count: u32,
+	) -> DispatchResult {
+		TotalCollateralInAuction::<T>::try_mutate(currency_id, |total| -> DispatchResult {
+			*total = total.checked_add(&amount).ok_or(ArithmeticError::Overflow)?;
+			Ok(())
+		})?;
+		TotalTargetInAuction::<T>::try_mutate(|total| -> DispatchResult {
+			*total = total.checked_add(&target).ok_or(ArithmeticError::Overflow)?;
+			Ok(())
+		})?;
+
+		let start_time = frame_system::Pallet::<T>::block_number();
+		let end = start_time.saturating_add(duration);
+		let auction_id = T::Auction::new_auction(start_time, Some(end))?;
+		let auction = CollateralAuctionItem {
+			refund_recipient: refund_recipient.cloned(),
+			currency_id,
+			initial_amount: amount,
+			amount,
+			target,
+			min_price_per_unit,
+			start_time,
+			last_bid_block: None,
+			relist_count,
+		};
+		let always_forward = auction.always_forward();
+		CollateralAuctions::<T>::insert(auction_id, auction);
+
+		Self::deposit_event(Event::<T>::CollateralAuctionCreated {
+			auction_id,
+			currency_id,
+			amount,
+			target,
+			always_forward,
+		});
+		Ok(())
+	}
+
+	/// Start up to `MaxDeferredDrainPerBlock` deferred auctions whose collateral can be
+	/// priced again, returning the number started.
+	fn drain_deferred_auctions() -> u32 {
+		let mut queue = DeferredAuctions::<T>::get();
+		if queue.is_empty() {
+			return 0
+		}
+
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let max = T::MaxDeferredDrainPerBlock::get();
+		let mut started = 0u32;
+		queue.retain(|item| {
+			if started >= max ||
+				T::PriceSource::get_relative_price(item.currency_id, stable_currency_id)
+					.is_none()
+			{
+				return true
+			}
+			// Creation can only fail on counter overflow; keep the item and retry later.
+			let created = Self::create_collateral_auction(
+				Some(&item.refund_recipient),
+				item.currency_id,
+				item.amount,
+				item.target,
+				Price::zero(),
+				T::AuctionTimeToClose::get(),
+				0,
+			)
+			.is_ok();
+			if created {
+				started += 1;
+			}
+			!created
+		});
+		if !started.is_zero() {
+			DeferredAuctions::<T>::put(queue);
+		}
+		started
+	}
+
+	/// The reserve of a collateral auction: [`Config::MinAuctionReserveFraction`] of the
+	/// lot's oracle value. `None` when reserve pricing is disabled or the feed is down.
+	fn reserve_price(
+		auction: &CollateralAuctionItem<T::AccountId, T::CurrencyId, T::Balance, BlockNumberFor<T>>,
+	) -> Option<T::Balance> {
+		let fraction = T::MinAuctionReserveFraction::get()?;
+		let feed_price = T::PriceSource::get_relative_price(
+			auction.currency_id,
+			T::GetStableCurrencyId::get(),
+		)?;
+		Some(fraction.saturating_mul(feed_price).saturating_mul_int(auction.amount))
+	}
+
+	/// Handle an auction whose winning bid stayed below its reserve: refund the bidder and
+	/// relist the lot, or hand the collateral back to the refund recipient once the relists
+	/// are exhausted.
+	fn handle_bid_below_reserve(
+		auction_id: AuctionIdOf<T>,
+		auction: CollateralAuctionItem<T::AccountId, T::CurrencyId, T::Balance, BlockNumberFor<T>>,
+		bidder: T::AccountId,
+		best_bid: T::Balance,
+		reserve: T::Balance,
+	) {
+		// The bid was dispersed to outbid bidders and the surplus pool, so the refund is
+		// issued as unbacked stable currency, like a cancellation's.
+		let paid = auction.target.min(best_bid);
+		if T::CDPTreasury::issue_debit(&bidder, paid, false).is_err() {
+			frame_support::defensive!("failed to refund the bidder of an auction below reserve");
+		}
+
+		if auction.relist_count < T::MaxAuctionRelists::get() {
+			let relist_count = auction.relist_count.saturating_add(1);
+			// Creation can only fail on counter overflow; the collateral then stays in the
+			// treasury like an aborted auction's.
+			if Self::create_collateral_auction(
+				auction.refund_recipient.as_ref(),
+				auction.currency_id,
+				auction.amount,
+				auction.target,
+				Price::zero(),
+				T::AuctionTimeToClose::get(),
+				relist_count,
+			)
+			.is_err()
+			{
+				frame_support::defensive!("failed to relist collateral auction");
+			}
+			Self::deposit_event(Event::<T>::CollateralAuctionRelisted {
+				auction_id,
+				currency_id: auction.currency_id,
+				amount: auction.amount,
+				reserve,
+				best_bid,
+				relist_count,
+			});
+		} else {
+			if let Some(refund_recipient) = &auction.refund_recipient {
+				if T::CDPTreasury::withdraw_collateral(
+					refund_recipient,
+					auction.currency_id,
+					auction.amount,
+				)
+				.is_err()
+				{
+					frame_support::defensive!("collateral in auction missing from the treasury");
+				}
+			}
+			Self::deposit_event(Event::<T>::CollateralAuctionReserveNotMet {
+				auction_id,
+				currency_id: auction.currency_id,
+				amount: auction.amount,
+				reserve,
+				best_bid,
+			});
+		}
+	}
+
+	/// Record a failed surplus payment, suspending bidding once
+	/// `MaxConsecutiveSurplusFailures` is reached.
+	fn note_surplus_pay_failure() {
+		let failures = ConsecutiveSurplusFailures::<T>::mutate(|failures| {
+			*failures = failures.saturating_add(1);
+			*failures
+		});
+		if failures >= T::MaxConsecutiveSurplusFailures::get() && !BiddingSuspended::<T>::get() {
+			BiddingSuspended::<T>::put(true);
+			Self::deposit_event(Event::<T>::BiddingSuspended { failures });
+		}
+	}
+}
+
+impl<T: Config> AuctionHandler<T::AccountId, T::Balance, BlockNumberFor<T>, AuctionIdOf<T>>
+	for Pallet<T>
+{
+	fn on_new_bid(
+		now: BlockNumberFor<T>,
+		id: AuctionIdOf<T>,
+		new_bid: (T::AccountId, T::Balance),
+		last_bid: Option<(T::AccountId, T::Balance)>,
+	) -> OnNewBidResult<BlockNumberFor<T>> {
+		let reject = OnNewBidResult {
+			accept_bid: false,
+			auction_end_change: Change::NoChange,
+			actual_weight: None,
+		};
+		if BiddingSuspended::<T>::get() {
+			return reject;
+		}
+		let new_price = new_bid.1;
+		let last_price = last_bid.as_ref().map(|(_, price)| *price);
+
+		// Settle in a storage layer: a failed bid must not move any funds. The circuit
+		// breaker is fed outside the layer, so the failure record survives the rollback.
+		let mut surplus_pay_failed = false;
+		let result: DispatchResult = with_storage_layer(|| {
+			Self::collateral_auction_bid_handler(now, id, new_bid, last_bid, &mut surplus_pay_failed)
+		});
+		match result {
+			Ok(()) => {
+				ConsecutiveSurplusFailures::<T>::kill();
+				let auction = CollateralAuctions::<T>::get(id);
+				let close_after =
+					if auction.as_ref().is_some_and(|auction| auction.always_forward()) {
+						T::ForwardAuctionDuration::get()
+					} else {
+						T::AuctionTimeToClose::get()
+					};
+				// Report the weight of the path actually taken, so forward-stage bids are
+				// not charged the reverse stage's lot recomputation.
+				let actual_weight = match &auction {
+					Some(auction)
+						if !auction.always_forward() && new_price > auction.target =>
+						if last_price.is_some_and(|last_price| last_price > auction.target) {
+							T::WeightInfo::bid_reverse_continuation()
+						} else {
+							T::WeightInfo::bid_reverse_entry()
+						},
+					_ =>
+						if last_price.is_none() {
+							T::WeightInfo::bid_first()
+						} else {
+							T::WeightInfo::bid_forward_outbid()
+						},
+				};
+				OnNewBidResult {
+					accept_bid: true,
+					auction_end_change: Change::NewValue(Some(now.saturating_add(close_after))),
+					actual_weight: Some(actual_weight),
+				}
+			},
+			Err(_) => {
+				if surplus_pay_failed {
+					Self::note_surplus_pay_failure();
+				}
+				reject
+			},
+		}
+	}
+
+	fn on_bid_cancelled(
+		_id: AuctionIdOf<T>,
+		_bidder: &T::AccountId,
+		_amount: T::Balance,
+	) -> Option<T::Balance> {
+		// Bids are payments already dispersed to outbid bidders and the surplus pool; they
+		// cannot be withdrawn.
+		None
+	}
+
+	fn on_auction_ended(id: AuctionIdOf<T>, winner: Option<(T::AccountId, T::Balance)>) {
+		let Some(auction) = CollateralAuctions::<T>::take(id) else { return };
+		TotalCollateralInAuction::<T>::mutate(auction.currency_id, |total| {
+			*total = total.saturating_sub(auction.amount)
+		});
+		TotalTargetInAuction::<T>::mutate(|total| *total = total.saturating_sub(auction.target));
+
+		if let Some((winner, price)) = winner {
+			// A winning bid below the reserve does not close the sale; always-forward
+			// auctions carry their own explicit price floor instead.
+			if !auction.always_forward() {
+				if let Some(reserve) =
+					Self::reserve_price(&auction).filter(|reserve| price < *reserve)
+				{
+					Self::handle_bid_below_reserve(id, auction, winner, price, reserve);
+					return
+				}
+			}
+			// The payment was collected bid by bid; hand the remaining lot to the winner.
+			if T::CDPTreasury::withdraw_collateral(&winner, auction.currency_id, auction.amount)
+				.is_err()
+			{
+				frame_support::defensive!("collateral in auction missing from the treasury");
+			}
+			let always_forward = auction.always_forward();
+			Self::deposit_event(Event::<T>::CollateralAuctionDealt {
+				auction_id: id,
+				currency_id: auction.currency_id,
+				amount: auction.amount,
+				winner,
+				payment: if always_forward { price } else { auction.target.min(price) },
+				always_forward,
+			});
+		} else {
+			Self::deposit_event(Event::<T>::CollateralAuctionAborted {
+				auction_id: id,
+				currency_id: auction.currency_id,
+				amount: auction.amount,
+			});
+		}
+	}
+}
+
+impl<T: Config> AuctionManager<T::AccountId> for Pallet<T> {
+	type Balance = T::Balance;
+	type CurrencyId = T::CurrencyId;
+	type AuctionId = AuctionIdOf<T>;
+	type BlockNumber = BlockNumberFor<T>;
+
+	fn new_collateral_auction(
+		refund_recipient: &T::AccountId,
+		currency_id: T::CurrencyId,
+		amount: T::Balance,
+		target: T::Balance,
+	) -> DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+		// An auction started while the collateral cannot be priced is exactly the auction
+		// bidders can extract the most value from, so defer it until the feed returns. The
+		// caller's liquidation has already completed - the collateral simply stays
+		// custodied by the CDP treasury until the deferred auction starts. If the queue is
+		// full the auction is created anyway: availability beats optimal pricing.
+		if T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+			.is_none()
+		{
+			let item = DeferredAuctionItem {
+				refund_recipient: refund_recipient.clone(),
+				currency_id,
+				amount,
+				target,
+			};
+			if DeferredAuctions::<T>::try_mutate(|queue| queue.try_push(item).map_err(|_| ()))
+				.is_ok()
+			{
+				Self::deposit_event(Event::<T>::AuctionDeferred {
+					refund_recipient: refund_recipient.clone(),
+					currency_id,
+					amount,
+					target,
+				});
+				return Ok(())
+			}
+			Self::deposit_event(Event::<T>::DeferredAuctionQueueFull {
+				currency_id,
+				amount,
+				target,
+			});
+		}
+
+		Self::create_collateral_auction(
+			Some(refund_recipient),
+			currency_id,
+			amount,
+			target,
+			Price::zero(),
+			T::AuctionTimeToClose::get(),
+			0,
+		)
+	}
+
+	fn new_forward_auction(
+		currency_id: T::CurrencyId,
+		amount: T::Balance,
+		min_price_per_unit: Price,
+		duration: BlockNumberFor<T>,
+	) -> DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+		// The collateral is already custodied by the CDP treasury and the sale is an
+		// explicit treasury decision, so a missing price feed defers nothing here: the
+		// caller-supplied floor is the price protection.
+		Self::create_collateral_auction(
+			None,
+			currency_id,
+			amount,
+			Zero::zero(),
+			min_price_per_unit,
+			duration,
+			0,
+		)
+	}
+
+	fn cancel_auction(id: Self::AuctionId) -> DispatchResult {
+		let auction = CollateralAuctions::<T>::get(id).ok_or(Error::<T>::AuctionNotExist)?;
+
+		if let Some(info) = T::Auction::auction_info(id) {
+			if let Some((bidder, price)) = info.bid {
+				// Refund what the leading bidder has paid so far. The payment has been
+				// dispersed to outbid bidders and the surplus pool, so the refund is
+				// issued as unbacked stable currency.
+				let paid =
+					if auction.always_forward() { price } else { auction.target.min(price) };
+				T::CDPTreasury::issue_debit(&bidder, paid, false)?;
+			}
+		}
+
+		T::Auction::remove_auction(id);
+		CollateralAuctions::<T>::remove(id);
+		TotalCollateralInAuction::<T>::mutate(auction.currency_id, |total| {
+			*total = total.saturating_sub(auction.amount)
+		});
+		TotalTargetInAuction::<T>::mutate(|total| *total = total.saturating_sub(auction.target));
+
+		Self::deposit_event(Event::<T>::CollateralAuctionCancelled { auction_id: id });
+		Ok(())
+	}
+
+	fn get_total_collateral_in_auction(currency_id: Self::CurrencyId) -> Self::Balance {
+		TotalCollateralInAuction::<T>::get(currency_id)
+	}
+
+	fn get_total_target_in_auction() -> Self::Balance {
+		TotalTargetInAuction::<T>::get()
+	}
+}
+
+pub mod migrations {
+	use super::*;
+	use frame_support::{migrations::VersionedMigration, traits::UncheckedOnRuntimeUpgrade};
+
+	mod v0 {
+		use super::*;
+
+		/// A collateral auction as stored before the always-forward auction support.
+		#[derive(Encode, Decode)]
+		pub struct CollateralAuctionItem<AccountId, CurrencyId, Balance, BlockNumber> {
+			pub refund_recipient: AccountId,
+			pub currency_id: CurrencyId,
+			pub initial_amount: Balance,
+			pub amount: Balance,
+			pub target: Balance,
+			pub start_time: BlockNumber,
+			pub last_bid_block: Option<BlockNumber>,
+		}
+	}
+
+	/// Rewrites every stored auction into the always-forward-aware layout: the refund
+	/// recipient moves into `Some` and the price floor defaults to zero, so zero-target
+	/// auctions created through the old path keep their behaviour.
+	pub struct InnerMigrateV0ToV1<T>(core::marker::PhantomData<T>);
+	impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut count = 0u64;
+			CollateralAuctions::<T>::translate_values(
+				|old: v0::CollateralAuctionItem<
+					T::AccountId,
+					T::CurrencyId,
+					T::Balance,
+					BlockNumberFor<T>,
+				>| {
+					count += 1;
+					Some(CollateralAuctionItem {
+						refund_recipient: Some(old.refund_recipient),
+						currency_id: old.currency_id,
+						initial_amount: old.initial_amount,
+						amount: old.amount,
+						target: old.target,
+						min_price_per_unit: Price::zero(),
+						start_time: old.start_time,
+						last_bid_block: old.last_bid_block,
+						relist_count: 0,
+					})
+				},
+			);
+			T::DbWeight::get().reads_writes(count, count)
+		}
+	}
+
+	/// [`InnerMigrateV0ToV1`] wrapped in a [`VersionedMigration`], the form to plug into a
+	/// runtime's migration tuple.
+	pub type MigrateV0ToV1<T> = VersionedMigration<
+		0,
+		1,
+		InnerMigrateV0ToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	mod v1 {
+		use super::*;
+
+		/// A collateral auction as stored before reserve-price relisting.
+		#[derive(Encode, Decode)]
+		pub struct CollateralAuctionItem<AccountId, CurrencyId, Balance, BlockNumber> {
+			pub refund_recipient: Option<AccountId>,
+			pub currency_id: CurrencyId,
+			pub initial_amount: Balance,
+			pub amount: Balance,
+			pub target: Balance,
+			pub min_price_per_unit: Price,
+			pub start_time: BlockNumber,
+			pub last_bid_block: Option<BlockNumber>,
+		}
+	}
+
+	/// Rewrites every stored auction with a zero relist count, the value every auction
+	/// started before reserve pricing effectively had.
+	pub struct InnerMigrateV1ToV2<T>(core::marker::PhantomData<T>);
+	impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV1ToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut count = 0u64;
+			CollateralAuctions::<T>::translate_values(
+				|old: v1::CollateralAuctionItem<
+					T::AccountId,
+					T::CurrencyId,
+					T::Balance,
+					BlockNumberFor<T>,
+				>| {
+					count += 1;
+					Some(CollateralAuctionItem {
+						refund_recipient: old.refund_recipient,
+						currency_id: old.currency_id,
+						initial_amount: old.initial_amount,
+						amount: old.amount,
+						target: old.target,
+						min_price_per_unit: old.min_price_per_unit,
+						start_time: old.start_time,
+						last_bid_block: old.last_bid_block,
+						relist_count: 0,
+					})
+				},
+			);
+			T::DbWeight::get().reads_writes(count, count)
+		}
+	}
+
+	/// [`InnerMigrateV1ToV2`] wrapped in a [`VersionedMigration`], the form to plug into a
+	/// runtime's migration tuple.
+	pub type MigrateV1ToV2<T> = VersionedMigration<
+		1,
+		2,
+		InnerMigrateV1ToV2<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+}
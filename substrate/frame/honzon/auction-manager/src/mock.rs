@@ -0,0 +1,280 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocks for the auction manager pallet.
+
+use super::*;
+use crate as pallet_auction_manager;
+
+use frame_support::{derive_impl, parameter_types, traits::fungibles::Mutate};
+use frame_system::EnsureRoot;
+use honzon_support::Price;
+use sp_runtime::{BuildStorage, DispatchError};
+use std::collections::BTreeMap;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type CurrencyId = u32;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const TREASURY: AccountId = 100;
+
+pub const DOT: CurrencyId = 1;
+pub const AUSD: CurrencyId = 9;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Assets: pallet_assets,
+		AuctionModule: pallet_auction,
+		AuctionManagerModule: pallet_auction_manager,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+#[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type CreateOrigin =
+		frame_support::traits::AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+impl pallet_auction::Config for Test {
+	type Balance = Balance;
+	type AuctionId = u32;
+	type Handler = AuctionManagerModule;
+	type PostAuctionHooks = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub static SurplusPayFails: bool = false;
+	pub static DebitPool: Balance = 0;
+	pub static LivePrices: BTreeMap<CurrencyId, Price> = BTreeMap::new();
+}
+
+/// Make every surplus payment into the treasury fail, simulating a treasury in a bad state.
+pub fn set_surplus_pay_fails(fails: bool) {
+	SurplusPayFails::set(fails);
+}
+
+/// Change the live price of `currency_id`.
+pub fn set_price(currency_id: CurrencyId, price: Option<Price>) {
+	let mut prices = LivePrices::get();
+	match price {
+		Some(price) => {
+			prices.insert(currency_id, price);
+		},
+		None => {
+			prices.remove(&currency_id);
+		},
+	}
+	LivePrices::set(prices);
+}
+
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_price(currency_id: CurrencyId) -> Option<Price> {
+		LivePrices::get().get(&currency_id).copied()
+	}
+}
+
+/// Issues and burns the stable asset directly and parks collateral on `TREASURY`. Surplus
+/// payments can be switched to fail via [`set_surplus_pay_fails`].
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		Assets::balance(AUSD, TREASURY)
+	}
+
+	fn get_debit_pool() -> Balance {
+		DebitPool::get()
+	}
+
+	fn get_total_collaterals(currency_id: CurrencyId) -> Balance {
+		Assets::balance(currency_id, TREASURY)
+	}
+
+	fn on_system_debit(amount: Balance) -> DispatchResult {
+		DebitPool::mutate(|pool| *pool += amount);
+		Ok(())
+	}
+
+	fn on_system_surplus(amount: Balance) -> DispatchResult {
+		Assets::mint_into(AUSD, &TREASURY, amount).map(|_| ())
+	}
+
+	fn issue_debit(who: &AccountId, debit: Balance, _backed: bool) -> DispatchResult {
+		Assets::mint_into(AUSD, who, debit).map(|_| ())
+	}
+
+	fn burn_debit(who: &AccountId, debit: Balance) -> DispatchResult {
+		Assets::burn_from(
+			AUSD,
+			who,
+			debit,
+			frame_support::traits::tokens::Preservation::Expendable,
+			frame_support::traits::tokens::Precision::Exact,
+			frame_support::traits::tokens::Fortitude::Polite,
+		)
+		.map(|_| ())
+	}
+
+	fn deposit_surplus(from: &AccountId, surplus: Balance) -> DispatchResult {
+		if SurplusPayFails::get() {
+			return Err(DispatchError::Other("surplus pay disabled"));
+		}
+		<Assets as Mutate<AccountId>>::transfer(
+			AUSD,
+			from,
+			&TREASURY,
+			surplus,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+
+	fn deposit_collateral(
+		from: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(
+			currency_id,
+			from,
+			&TREASURY,
+			amount,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+
+	fn withdraw_collateral(
+		to: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(
+			currency_id,
+			&TREASURY,
+			to,
+			amount,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+}
+
+parameter_types! {
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	// 5% of the auction target.
+	pub MinimumIncrementSize: Rate = Rate::saturating_from_rational(1, 20);
+	pub const AuctionTimeToClose: u64 = 100;
+	pub const ForwardAuctionDuration: u64 = 50;
+	pub const MaxConsecutiveSurplusFailures: u32 = 3;
+	pub const MaxDeferredAuctions: u32 = 2;
+	pub static MaxDeferredDrainPerBlock: u32 = 10;
+	pub static MaxBidPriceMultiple: Option<Ratio> = None;
+	pub static MinBlocksBetweenBids: u64 = 0;
+	pub static MinAuctionReserveFraction: Option<Ratio> = None;
+	pub static MaxAuctionRelists: u32 = 2;
+}
+
+impl Config for Test {
+	type UpdateOrigin = EnsureRoot<AccountId>;
+	type RecoveryOrigin = EnsureRoot<AccountId>;
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+	type Currency = Assets;
+	type Auction = AuctionModule;
+	type CDPTreasury = MockCDPTreasury;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type MinimumIncrementSize = MinimumIncrementSize;
+	type MaxBidPriceMultiple = MaxBidPriceMultiple;
+	type MinAuctionReserveFraction = MinAuctionReserveFraction;
+	type MaxAuctionRelists = MaxAuctionRelists;
+	type MinBlocksBetweenBids = MinBlocksBetweenBids;
+	type AuctionTimeToClose = AuctionTimeToClose;
+	type ForwardAuctionDuration = ForwardAuctionDuration;
+	type MaxConsecutiveSurplusFailures = MaxConsecutiveSurplusFailures;
+	type PriceSource = MockPriceSource;
+	type MaxDeferredAuctions = MaxDeferredAuctions;
+	type MaxDeferredDrainPerBlock = MaxDeferredDrainPerBlock;
+	type WeightInfo = ();
+}
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		SurplusPayFails::set(false);
+		DebitPool::set(0);
+		LivePrices::set(BTreeMap::from([
+			(DOT, Price::saturating_from_integer(1)),
+			(AUSD, Price::saturating_from_integer(1)),
+		]));
+		MaxDeferredDrainPerBlock::set(10);
+		MaxBidPriceMultiple::set(None);
+		MinBlocksBetweenBids::set(0);
+		MinAuctionReserveFraction::set(None);
+		MaxAuctionRelists::set(2);
+
+		let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			for currency_id in [DOT, AUSD] {
+				assert_eq!(
+					Assets::force_create(RuntimeOrigin::root(), currency_id, TREASURY, true, 1),
+					Ok(())
+				);
+			}
+			// The treasury custodies the confiscated collateral put up for auction.
+			assert_eq!(Assets::mint_into(DOT, &TREASURY, 1000), Ok(1000));
+			for who in [ALICE, BOB, CAROL] {
+				assert_eq!(Assets::mint_into(AUSD, &who, 1000), Ok(1000));
+			}
+		});
+		ext
+	}
+}
@@ -0,0 +1,646 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the auction manager pallet.
+
+use super::*;
+use mock::*;
+
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use honzon_support::Price;
+
+fn new_auction(amount: mock::Balance, target: mock::Balance) -> u32 {
+	assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, DOT, amount, target));
+	0
+}
+
+#[test]
+fn new_collateral_auction_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			AuctionManagerModule::new_collateral_auction(&ALICE, DOT, 0, 50),
+			Error::<Test>::InvalidAmount
+		);
+
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, DOT, 100, 50));
+		System::assert_last_event(
+			Event::<Test>::CollateralAuctionCreated {
+				auction_id: 0,
+				currency_id: DOT,
+				amount: 100,
+				target: 50,
+				always_forward: false,
+			}
+			.into(),
+		);
+
+		let auction = CollateralAuctions::<Test>::get(0).unwrap();
+		assert_eq!(auction.refund_recipient, Some(ALICE));
+		assert_eq!(auction.currency_id, DOT);
+		assert_eq!(auction.initial_amount, 100);
+		assert_eq!(auction.amount, 100);
+		assert_eq!(auction.target, 50);
+		assert_eq!(auction.start_time, 1);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 100);
+		assert_eq!(AuctionManagerModule::get_total_target_in_auction(), 50);
+
+		// The auction is scheduled to close after `AuctionTimeToClose`.
+		let info = AuctionModule::auction_info(0).unwrap();
+		assert_eq!(info.start, 1);
+		assert_eq!(info.end, Some(101));
+	});
+}
+
+#[test]
+fn bids_pay_surplus_and_refund_the_outbid_bidder() {
+	ExtBuilder::default().build().execute_with(|| {
+		new_auction(100, 50);
+
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 30));
+		assert_eq!(Assets::balance(AUSD, BOB), 970);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 30);
+
+		// The new bidder refunds BOB directly and tops the surplus up to their bid.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 50));
+		assert_eq!(Assets::balance(AUSD, BOB), 1000);
+		assert_eq!(Assets::balance(AUSD, CAROL), 950);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 50);
+		assert_eq!(CollateralAuctions::<Test>::get(0).unwrap().amount, 100);
+
+		// A bid above the target pays nothing extra but shrinks the lot, refunding the
+		// freed collateral to the refund recipient.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 100));
+		assert_eq!(Assets::balance(AUSD, BOB), 950);
+		assert_eq!(Assets::balance(AUSD, CAROL), 1000);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 50);
+		assert_eq!(CollateralAuctions::<Test>::get(0).unwrap().amount, 50);
+		assert_eq!(Assets::balance(DOT, ALICE), 50);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 50);
+
+		// The winner receives the remaining lot when the auction ends.
+		<AuctionModule as OnInitialize<u64>>::on_initialize(101);
+		System::assert_has_event(
+			Event::<Test>::CollateralAuctionDealt {
+				auction_id: 0,
+				currency_id: DOT,
+				amount: 50,
+				winner: BOB,
+				payment: 50,
+				always_forward: false,
+			}
+			.into(),
+		);
+		assert_eq!(Assets::balance(DOT, BOB), 50);
+		assert_eq!(CollateralAuctions::<Test>::get(0), None);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 0);
+		assert_eq!(AuctionManagerModule::get_total_target_in_auction(), 0);
+	});
+}
+
+#[test]
+fn bids_below_the_minimum_increment_are_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		// The minimum increment is 5% of the target of 100.
+		new_auction(100, 100);
+
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 4));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, None);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 0);
+
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 5));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 5)));
+
+		// The next bid must exceed the last by the increment again.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 9));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 5)));
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 10));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((CAROL, 10)));
+	});
+}
+
+#[test]
+fn rejected_bids_move_no_funds() {
+	ExtBuilder::default().build().execute_with(|| {
+		new_auction(100, 50);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 30));
+
+		// The refund to BOB rolls back together with the failed surplus payment.
+		set_surplus_pay_fails(true);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 40));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 30)));
+		assert_eq!(Assets::balance(AUSD, BOB), 970);
+		assert_eq!(Assets::balance(AUSD, CAROL), 1000);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 30);
+		assert_eq!(ConsecutiveSurplusFailures::<Test>::get(), 1);
+	});
+}
+
+#[test]
+fn consecutive_surplus_failures_suspend_bidding() {
+	ExtBuilder::default().build().execute_with(|| {
+		new_auction(100, 50);
+		set_surplus_pay_fails(true);
+
+		for expected_failures in 1..=2u32 {
+			assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+			assert_eq!(ConsecutiveSurplusFailures::<Test>::get(), expected_failures);
+			assert!(!BiddingSuspended::<Test>::get());
+		}
+
+		// The third consecutive failure trips the circuit breaker.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+		assert!(BiddingSuspended::<Test>::get());
+		System::assert_has_event(Event::<Test>::BiddingSuspended { failures: 3 }.into());
+
+		// Bids stay rejected while suspended, even once surplus payments work again.
+		set_surplus_pay_fails(false);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, None);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 0);
+
+		// Only the update origin may resume bidding.
+		assert_noop!(
+			AuctionManagerModule::resume_bidding(RuntimeOrigin::signed(ALICE)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(AuctionManagerModule::resume_bidding(RuntimeOrigin::root()));
+		System::assert_last_event(Event::<Test>::BiddingResumed.into());
+		assert!(!BiddingSuspended::<Test>::get());
+		assert_eq!(ConsecutiveSurplusFailures::<Test>::get(), 0);
+
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 10)));
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 10);
+	});
+}
+
+#[test]
+fn successful_bid_resets_the_failure_counter() {
+	ExtBuilder::default().build().execute_with(|| {
+		new_auction(100, 50);
+
+		set_surplus_pay_fails(true);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+		assert_eq!(ConsecutiveSurplusFailures::<Test>::get(), 2);
+
+		set_surplus_pay_fails(false);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+		assert_eq!(ConsecutiveSurplusFailures::<Test>::get(), 0);
+
+		// Only an unbroken run of failures trips the breaker.
+		set_surplus_pay_fails(true);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 20));
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 20));
+		assert_eq!(ConsecutiveSurplusFailures::<Test>::get(), 2);
+		assert!(!BiddingSuspended::<Test>::get());
+	});
+}
+
+#[test]
+fn resume_bidding_requires_suspension() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			AuctionManagerModule::resume_bidding(RuntimeOrigin::root()),
+			Error::<Test>::BiddingNotSuspended
+		);
+	});
+}
+
+#[test]
+fn cancel_auction_refunds_the_leading_bidder() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(AuctionManagerModule::cancel_auction(0), Error::<Test>::AuctionNotExist);
+
+		new_auction(100, 50);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 30));
+
+		assert_ok!(AuctionManagerModule::cancel_auction(0));
+		System::assert_last_event(Event::<Test>::CollateralAuctionCancelled { auction_id: 0 }.into());
+		// The payment is refunded as unbacked stable currency.
+		assert_eq!(Assets::balance(AUSD, BOB), 1000);
+		assert_eq!(CollateralAuctions::<Test>::get(0), None);
+		assert_eq!(AuctionModule::auction_info(0), None);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 0);
+		assert_eq!(AuctionManagerModule::get_total_target_in_auction(), 0);
+	});
+}
+
+#[test]
+fn auction_without_bids_is_aborted() {
+	ExtBuilder::default().build().execute_with(|| {
+		new_auction(100, 50);
+
+		<AuctionModule as OnInitialize<u64>>::on_initialize(101);
+		System::assert_has_event(
+			Event::<Test>::CollateralAuctionAborted { auction_id: 0, currency_id: DOT, amount: 100 }
+				.into(),
+		);
+		// The collateral stays in the treasury for governance to deal with.
+		assert_eq!(Assets::balance(DOT, TREASURY), 1000);
+		assert_eq!(CollateralAuctions::<Test>::get(0), None);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 0);
+		assert_eq!(AuctionManagerModule::get_total_target_in_auction(), 0);
+	});
+}
+
+#[test]
+fn auction_is_deferred_without_a_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_price(DOT, None);
+
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, DOT, 100, 50));
+		System::assert_last_event(
+			Event::<Test>::AuctionDeferred {
+				refund_recipient: ALICE,
+				currency_id: DOT,
+				amount: 100,
+				target: 50,
+			}
+			.into(),
+		);
+		// No auction exists yet; only the deferred request does.
+		assert_eq!(CollateralAuctions::<Test>::get(0), None);
+		assert_eq!(AuctionModule::auction_info(0), None);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 0);
+		assert_eq!(DeferredAuctions::<Test>::get().len(), 1);
+
+		// The feed is still down, so the queue is not drained.
+		<AuctionManagerModule as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(DeferredAuctions::<Test>::get().len(), 1);
+
+		// Once the price returns, the real auction starts.
+		set_price(DOT, Some(Price::saturating_from_integer(1)));
+		<AuctionManagerModule as OnInitialize<u64>>::on_initialize(3);
+		assert!(DeferredAuctions::<Test>::get().is_empty());
+		System::assert_last_event(
+			Event::<Test>::CollateralAuctionCreated {
+				auction_id: 0,
+				currency_id: DOT,
+				amount: 100,
+				target: 50,
+				always_forward: false,
+			}
+			.into(),
+		);
+		let auction = CollateralAuctions::<Test>::get(0).unwrap();
+		assert_eq!(auction.refund_recipient, Some(ALICE));
+		assert_eq!(auction.amount, 100);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 100);
+		assert_eq!(AuctionManagerModule::get_total_target_in_auction(), 50);
+	});
+}
+
+#[test]
+fn deferred_queue_drains_boundedly_per_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaxDeferredDrainPerBlock::set(1);
+		set_price(DOT, None);
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, DOT, 100, 50));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&BOB, DOT, 200, 80));
+		assert_eq!(DeferredAuctions::<Test>::get().len(), 2);
+
+		set_price(DOT, Some(Price::saturating_from_integer(1)));
+		<AuctionManagerModule as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(DeferredAuctions::<Test>::get().len(), 1);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 100);
+
+		<AuctionManagerModule as OnInitialize<u64>>::on_initialize(3);
+		assert!(DeferredAuctions::<Test>::get().is_empty());
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 300);
+		assert_eq!(AuctionManagerModule::get_total_target_in_auction(), 130);
+		assert_eq!(CollateralAuctions::<Test>::get(1).unwrap().refund_recipient, Some(BOB));
+	});
+}
+
+#[test]
+fn full_deferred_queue_falls_back_to_immediate_creation() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_price(DOT, None);
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, DOT, 100, 50));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, DOT, 100, 50));
+		assert_eq!(DeferredAuctions::<Test>::get().len(), 2);
+
+		// The queue holds two requests at most; the third creates its auction right away,
+		// price or no price.
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, DOT, 100, 50));
+		System::assert_has_event(
+			Event::<Test>::DeferredAuctionQueueFull { currency_id: DOT, amount: 100, target: 50 }
+				.into(),
+		);
+		assert_eq!(DeferredAuctions::<Test>::get().len(), 2);
+		assert!(CollateralAuctions::<Test>::get(0).is_some());
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 100);
+	});
+}
+
+#[test]
+fn bid_price_band_rejects_anomalous_bids() {
+	ExtBuilder::default().build().execute_with(|| {
+		// At the oracle price of 1 the band allows a unit price of at most 2.
+		MaxBidPriceMultiple::set(Some(Ratio::saturating_from_integer(2)));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, DOT, 100, 500));
+
+		// 150 for a lot of 100 implies a unit price of 1.5, within the band.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 150));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 150)));
+
+		// 250 implies 2.5x the oracle price; the minimum increment alone would accept
+		// it, but the band rejects it as anomalous.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 250));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 150)));
+
+		// The band edge itself passes.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 200));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((CAROL, 200)));
+
+		// Without an oracle price there is no band to hold bids against.
+		set_price(DOT, None);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 400));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 400)));
+	});
+}
+
+#[test]
+fn same_bidder_cannot_rebid_within_the_window() {
+	ExtBuilder::default().build().execute_with(|| {
+		MinBlocksBetweenBids::set(5);
+		new_auction(100, 100);
+
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 10)));
+
+		// BOB raising their own leading bid within the window is rejected and the
+		// rejected payment rolls back...
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 20));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 10)));
+		assert_eq!(Assets::balance(AUSD, BOB), 990);
+
+		// ...while a distinct bidder passes freely in the same block.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 20));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((CAROL, 20)));
+
+		// The window restarts at the latest accepted bid, so CAROL now sits it out...
+		System::set_block_number(5);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 30));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((CAROL, 20)));
+
+		// ...until it has passed.
+		System::set_block_number(6);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 30));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((CAROL, 30)));
+	});
+}
+
+#[test]
+fn forward_auction_enforces_the_price_floor() {
+	ExtBuilder::default().build().execute_with(|| {
+		// 100 DOT with a floor of 0.5 stable per unit: bids below 50 are rejected.
+		assert_ok!(AuctionManagerModule::new_forward_auction(
+			DOT,
+			100,
+			Price::saturating_from_rational(1, 2),
+			30,
+		));
+		System::assert_last_event(
+			Event::<Test>::CollateralAuctionCreated {
+				auction_id: 0,
+				currency_id: DOT,
+				amount: 100,
+				target: 0,
+				always_forward: true,
+			}
+			.into(),
+		);
+		assert_eq!(CollateralAuctions::<Test>::get(0).unwrap().refund_recipient, None);
+
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 49));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, None);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 0);
+
+		// A bid at the floor passes and pays its full price into the surplus.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 50));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().bid, Some((BOB, 50)));
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 50);
+
+		// With no target the minimum increment is vacuous; the auction scheduler still
+		// requires strictly increasing bids.
+		assert_noop!(
+			AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 50),
+			pallet_auction::Error::<Test>::InvalidBidPrice
+		);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 60));
+		assert_eq!(Assets::balance(AUSD, BOB), 1000);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 60);
+
+		// There is no reverse stage: the lot never shrinks however high the price goes.
+		assert_eq!(CollateralAuctions::<Test>::get(0).unwrap().amount, 100);
+
+		// The winner takes the whole lot at the full price.
+		<AuctionModule as OnInitialize<u64>>::on_initialize(51);
+		System::assert_has_event(
+			Event::<Test>::CollateralAuctionDealt {
+				auction_id: 0,
+				currency_id: DOT,
+				amount: 100,
+				winner: CAROL,
+				payment: 60,
+				always_forward: true,
+			}
+			.into(),
+		);
+		assert_eq!(Assets::balance(DOT, CAROL), 100);
+	});
+}
+
+#[test]
+fn under_reserve_auction_is_relisted() {
+	ExtBuilder::default().build().execute_with(|| {
+		// 100 DOT at the oracle price of 1 with a fraction of 50%: the reserve is 50.
+		MinAuctionReserveFraction::set(Some(Ratio::saturating_from_rational(1, 2)));
+		new_auction(100, 60);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 30));
+
+		// The best bid of 30 stays below the reserve: BOB is refunded and the lot is
+		// relisted as a fresh auction instead of selling far below oracle value.
+		System::set_block_number(101);
+		<AuctionModule as OnInitialize<u64>>::on_initialize(101);
+		System::assert_has_event(
+			Event::<Test>::CollateralAuctionRelisted {
+				auction_id: 0,
+				currency_id: DOT,
+				amount: 100,
+				reserve: 50,
+				best_bid: 30,
+				relist_count: 1,
+			}
+			.into(),
+		);
+		assert_eq!(Assets::balance(AUSD, BOB), 1000);
+		assert_eq!(Assets::balance(DOT, BOB), 0);
+		assert_eq!(CollateralAuctions::<Test>::get(0), None);
+		let relisted = CollateralAuctions::<Test>::get(1).unwrap();
+		assert_eq!(relisted.refund_recipient, Some(ALICE));
+		assert_eq!(relisted.amount, 100);
+		assert_eq!(relisted.target, 60);
+		assert_eq!(relisted.relist_count, 1);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 100);
+		assert_eq!(AuctionManagerModule::get_total_target_in_auction(), 60);
+
+		// A bid meeting the reserve deals the relisted auction normally.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(CAROL), 1, 50));
+		System::set_block_number(201);
+		<AuctionModule as OnInitialize<u64>>::on_initialize(201);
+		System::assert_has_event(
+			Event::<Test>::CollateralAuctionDealt {
+				auction_id: 1,
+				currency_id: DOT,
+				amount: 100,
+				winner: CAROL,
+				payment: 50,
+				always_forward: false,
+			}
+			.into(),
+		);
+		assert_eq!(Assets::balance(DOT, CAROL), 100);
+	});
+}
+
+#[test]
+fn under_reserve_auction_falls_back_to_refunding_the_recipient() {
+	ExtBuilder::default().build().execute_with(|| {
+		MinAuctionReserveFraction::set(Some(Ratio::saturating_from_rational(1, 2)));
+		MaxAuctionRelists::set(1);
+		new_auction(100, 60);
+
+		// The first close below the reserve relists once...
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 30));
+		System::set_block_number(101);
+		<AuctionModule as OnInitialize<u64>>::on_initialize(101);
+		assert_eq!(CollateralAuctions::<Test>::get(1).unwrap().relist_count, 1);
+
+		// ...the second exhausts the relists: BOB is refunded and the collateral goes back
+		// to ALICE instead of selling at 30 against a reserve of 50.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 1, 30));
+		System::set_block_number(201);
+		<AuctionModule as OnInitialize<u64>>::on_initialize(201);
+		System::assert_has_event(
+			Event::<Test>::CollateralAuctionReserveNotMet {
+				auction_id: 1,
+				currency_id: DOT,
+				amount: 100,
+				reserve: 50,
+				best_bid: 30,
+			}
+			.into(),
+		);
+		assert_eq!(Assets::balance(AUSD, BOB), 1000);
+		assert_eq!(Assets::balance(DOT, ALICE), 100);
+		assert_eq!(Assets::balance(DOT, TREASURY), 900);
+		assert_eq!(CollateralAuctions::<Test>::get(2), None);
+		assert_eq!(AuctionManagerModule::get_total_collateral_in_auction(DOT), 0);
+		assert_eq!(AuctionManagerModule::get_total_target_in_auction(), 0);
+	});
+}
+
+#[test]
+fn forward_auction_runs_on_its_own_durations() {
+	ExtBuilder::default().build().execute_with(|| {
+		// The initial close comes from the per-auction duration, not `AuctionTimeToClose`.
+		assert_ok!(AuctionManagerModule::new_forward_auction(DOT, 100, Price::zero(), 30));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().end, Some(31));
+
+		// An accepted bid extends the close by `ForwardAuctionDuration`.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().end, Some(51));
+	});
+}
+
+#[test]
+fn bids_refund_weight_according_to_the_path_taken() {
+	ExtBuilder::default().build().execute_with(|| {
+		new_auction(100, 50);
+
+		// The very first bid only records the new bid.
+		let post = AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 10).unwrap();
+		assert_eq!(post.actual_weight, Some(<() as WeightInfo>::bid_first()));
+
+		// Outbidding below the target additionally refunds the previous bidder.
+		let post = AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 30).unwrap();
+		assert_eq!(post.actual_weight, Some(<() as WeightInfo>::bid_forward_outbid()));
+
+		// Crossing the target shrinks the lot and returns collateral to the seller.
+		let post = AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 60).unwrap();
+		assert_eq!(post.actual_weight, Some(<() as WeightInfo>::bid_reverse_entry()));
+
+		// Further reverse-stage bids shrink the lot again but skip the stage change.
+		let post = AuctionModule::bid(RuntimeOrigin::signed(CAROL), 0, 80).unwrap();
+		assert_eq!(post.actual_weight, Some(<() as WeightInfo>::bid_reverse_continuation()));
+
+		// The fee actually charged differs between the stages.
+		assert!(
+			<() as WeightInfo>::bid_forward_outbid().any_lt(<() as WeightInfo>::bid_reverse_entry())
+		);
+	});
+}
+
+#[test]
+fn recovery_origin_can_redirect_the_refund_recipient() {
+	ExtBuilder::default().build().execute_with(|| {
+		new_auction(100, 50);
+
+		assert_noop!(
+			AuctionManagerModule::set_auction_refund_recipient(
+				RuntimeOrigin::signed(BOB),
+				0,
+				CAROL
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			AuctionManagerModule::set_auction_refund_recipient(RuntimeOrigin::root(), 1, CAROL),
+			Error::<Test>::AuctionNotExist
+		);
+
+		assert_ok!(AuctionManagerModule::set_auction_refund_recipient(
+			RuntimeOrigin::root(),
+			0,
+			CAROL
+		));
+		System::assert_last_event(
+			Event::<Test>::AuctionRefundRecipientUpdated { auction_id: 0, new_recipient: CAROL }
+				.into(),
+		);
+		assert_eq!(CollateralAuctions::<Test>::get(0).unwrap().refund_recipient, Some(CAROL));
+
+		// The collateral freed by a reverse-stage bid now goes to the new recipient.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), 0, 100));
+		assert_eq!(Assets::balance(DOT, ALICE), 0);
+		assert_eq!(Assets::balance(DOT, CAROL), 50);
+	});
+}
+
+#[test]
+fn always_forward_auctions_have_no_refund_recipient_to_redirect() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(AuctionManagerModule::new_forward_auction(DOT, 100, Price::zero(), 30));
+		assert_noop!(
+			AuctionManagerModule::set_auction_refund_recipient(RuntimeOrigin::root(), 0, CAROL),
+			Error::<Test>::NoRefundRecipient
+		);
+	});
+}
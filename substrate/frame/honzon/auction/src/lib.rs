@@ -0,0 +1,374 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Auction Pallet
+//!
+//! A generic auction scheduler.
+//!
+//! ## Overview
+//!
+//! This pallet keeps the schedule of auctions: other pallets create and remove auctions via the
+//! [`Auction`] trait, bids arrive as extrinsics, and all business logic - judging bids, moving
+//! the funds they are made with and acting on the outcome - lives in the runtime's
+//! [`AuctionHandler`] implementation. Auctions with a deadline are closed in `on_initialize` of
+//! the block they end at, handing the winning bid (if any) to the handler.
+//!
+//! A leading bidder may withdraw their bid again with `cancel_bid`, if the handler permits it.
+//! The handler may charge a penalty for this, and any auction end extension it granted for the
+//! cancelled bid is reverted.
+//!
+//! The pallet is instantiable: a runtime can host several independent auction domains - say
+//! collateral auctions and surplus auctions - as separate instances with isolated storage,
+//! their own handlers and their own `Balance`/`AuctionId` types.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod property_tests;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use frame_support::pallet_prelude::*;
+use frame_system::pallet_prelude::*;
+use honzon_support::{Auction, AuctionHandler, AuctionInfo, Change, OnAuctionEnded};
+use sp_runtime::{
+	traits::{
+		AtLeast32BitUnsigned, Bounded, CheckedAdd, MaybeSerializeDeserialize, Member, One,
+		Saturating, Zero,
+	},
+	DispatchError, DispatchResult,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T, I = ()>(_);
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The balance type for bidding.
+		type Balance: Parameter
+			+ Member
+			+ AtLeast32BitUnsigned
+			+ Default
+			+ Copy
+			+ MaybeSerializeDeserialize
+			+ MaxEncodedLen;
+
+		/// The auction id type.
+		type AuctionId: Parameter
+			+ Member
+			+ AtLeast32BitUnsigned
+			+ Default
+			+ Copy
+			+ Bounded
+			+ MaybeSerializeDeserialize
+			+ MaxEncodedLen;
+
+		/// The handler judging bids and acting on auction outcomes. It is responsible for all
+		/// fund movements; this pallet only keeps the schedule.
+		type Handler: AuctionHandler<
+			Self::AccountId,
+			Self::Balance,
+			BlockNumberFor<Self>,
+			Self::AuctionId,
+		>;
+
+		/// Secondary observers of auction completion, run after `Handler` when an auction
+		/// closes. Observers must be infallible and cheap: their `weight_hint` is charged
+		/// for every auction closed in `on_initialize`. Use `()` for none.
+		type PostAuctionHooks: OnAuctionEnded<Self::AccountId, Self::Balance, Self::AuctionId>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The auction does not exist.
+		AuctionNotExist,
+		/// The auction has not started yet.
+		AuctionNotStarted,
+		/// The bid is not higher than the current winning bid.
+		InvalidBidPrice,
+		/// The auction id space is exhausted.
+		NoAvailableAuctionId,
+		/// The caller is not the current leading bidder.
+		NotLeadingBidder,
+		/// The handler does not allow the bid to be cancelled.
+		CancelNotAllowed,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// A bid was placed.
+		Bid { auction_id: T::AuctionId, bidder: T::AccountId, amount: T::Balance },
+		/// The handler turned a bid down.
+		BidRejected { auction_id: T::AuctionId, bidder: T::AccountId, amount: T::Balance },
+		/// The leading bid was cancelled, refunding the bidder minus the penalty.
+		BidCancelled {
+			auction_id: T::AuctionId,
+			bidder: T::AccountId,
+			amount: T::Balance,
+			penalty: T::Balance,
+		},
+	}
+
+	/// Auctions that have been created and not yet concluded.
+	#[pallet::storage]
+	pub type Auctions<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AuctionId,
+		AuctionInfo<T::AccountId, T::Balance, BlockNumberFor<T>>,
+	>;
+
+	/// The id to assign to the next auction.
+	#[pallet::storage]
+	pub type AuctionsIndex<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::AuctionId, ValueQuery>;
+
+	/// Index of auctions by their end block, drained in `on_initialize`.
+	#[pallet::storage]
+	pub type AuctionEndTime<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Twox64Concat, BlockNumberFor<T>, Twox64Concat, T::AuctionId, ()>;
+
+	/// The auction end time before the handler first extended it, kept so cancelling the
+	/// leading bid can revert the extension.
+	#[pallet::storage]
+	pub type PreExtensionAuctionEnd<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, T::AuctionId, Option<BlockNumberFor<T>>>;
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Bid `value` on the auction `id`. The bid must be higher than the current winning
+		/// bid, at least the handler's [`minimum_bid`](AuctionHandler::minimum_bid) floor if
+		/// it imposes one, and accepted by the handler, which takes care of holding the bid
+		/// funds.
+		///
+		/// A bid the handler turns down is not a dispatch error: the extrinsic succeeds
+		/// without recording the bid and emits [`Event::BidRejected`]. Anything the handler
+		/// recorded about the rejection (such as failure counters) thereby persists, which
+		/// an error rolling back the whole dispatch would revert.
+		///
+		/// The handler may report the actual weight of the path it took; the difference to
+		/// the worst-case weight is then refunded to the bidder post-dispatch.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::bid())]
+		pub fn bid(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AuctionId,
+			#[pallet::compact] value: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let (accepted, actual_weight) = Auctions::<T, I>::try_mutate_exists(
+				id,
+				|maybe_auction| -> Result<(bool, Option<Weight>), DispatchError> {
+					let auction = maybe_auction.as_mut().ok_or(Error::<T, I>::AuctionNotExist)?;
+
+					let now = frame_system::Pallet::<T>::block_number();
+					ensure!(now >= auction.start, Error::<T, I>::AuctionNotStarted);
+					if let Some((_, current_amount)) = auction.bid {
+						ensure!(value > current_amount, Error::<T, I>::InvalidBidPrice);
+					} else {
+						ensure!(!value.is_zero(), Error::<T, I>::InvalidBidPrice);
+					}
+					if let Some(floor) = T::Handler::minimum_bid(id) {
+						ensure!(value >= floor, Error::<T, I>::InvalidBidPrice);
+					}
+
+					let bid_result =
+						T::Handler::on_new_bid(now, id, (who.clone(), value), auction.bid.clone());
+					if !bid_result.accept_bid {
+						return Ok((false, bid_result.actual_weight));
+					}
+
+					if let Change::NewValue(new_end) = bid_result.auction_end_change {
+						// Remember the pre-extension end so a cancelled bid can restore it.
+						if !PreExtensionAuctionEnd::<T, I>::contains_key(id) {
+							PreExtensionAuctionEnd::<T, I>::insert(id, auction.end);
+						}
+						Self::reschedule_end(id, auction.end, new_end);
+						auction.end = new_end;
+					}
+
+					auction.bid = Some((who.clone(), value));
+					Ok((true, bid_result.actual_weight))
+				},
+			)?;
+
+			if accepted {
+				Self::deposit_event(Event::<T, I>::Bid { auction_id: id, bidder: who, amount: value });
+			} else {
+				Self::deposit_event(Event::<T, I>::BidRejected {
+					auction_id: id,
+					bidder: who,
+					amount: value,
+				});
+			}
+			Ok(actual_weight.into())
+		}
+
+		/// Cancel the caller's leading bid on the auction `id`, if the handler allows it.
+		///
+		/// The handler refunds the bid minus the penalty it imposes, and any auction end
+		/// extension granted for the cancelled bid is reverted.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::cancel_bid())]
+		pub fn cancel_bid(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AuctionId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let (amount, penalty) =
+				Auctions::<T, I>::try_mutate_exists(id, |maybe_auction| -> Result<_, DispatchError> {
+					let auction = maybe_auction.as_mut().ok_or(Error::<T, I>::AuctionNotExist)?;
+
+					let (bidder, amount) = match &auction.bid {
+						Some((bidder, amount)) if *bidder == who => (bidder.clone(), *amount),
+						_ => return Err(Error::<T, I>::NotLeadingBidder.into()),
+					};
+
+					let penalty = T::Handler::on_bid_cancelled(id, &bidder, amount)
+						.ok_or(Error::<T, I>::CancelNotAllowed)?;
+
+					auction.bid = None;
+					if let Some(original_end) = PreExtensionAuctionEnd::<T, I>::take(id) {
+						Self::reschedule_end(id, auction.end, original_end);
+						auction.end = original_end;
+					}
+
+					Ok((amount, penalty))
+				})?;
+
+			Self::deposit_event(Event::<T, I>::BidCancelled {
+				auction_id: id,
+				bidder: who,
+				amount,
+				penalty,
+			});
+			Ok(())
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let mut count: u32 = 0;
+			for (auction_id, _) in AuctionEndTime::<T, I>::drain_prefix(now) {
+				if let Some(auction) = Auctions::<T, I>::take(auction_id) {
+					PreExtensionAuctionEnd::<T, I>::remove(auction_id);
+					T::Handler::on_auction_ended(auction_id, auction.bid.clone());
+					T::PostAuctionHooks::on_auction_ended(auction_id, auction.bid);
+					count.saturating_inc();
+				}
+			}
+			T::WeightInfo::on_initialize(count)
+				.saturating_add(T::PostAuctionHooks::weight_hint().saturating_mul(count.into()))
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// The number of blocks until the auction `id` ends, saturating to zero once the end
+	/// block has passed.
+	///
+	/// Returns `None` if the auction does not exist or has no end set.
+	pub fn time_remaining(id: T::AuctionId) -> Option<BlockNumberFor<T>> {
+		let end = Auctions::<T, I>::get(id)?.end?;
+		let now = frame_system::Pallet::<T>::block_number();
+		Some(end.saturating_sub(now))
+	}
+
+	/// Move the `AuctionEndTime` index entry of `id` from `old_end` to `new_end`.
+	fn reschedule_end(
+		id: T::AuctionId,
+		old_end: Option<BlockNumberFor<T>>,
+		new_end: Option<BlockNumberFor<T>>,
+	) {
+		if let Some(old_end) = old_end {
+			AuctionEndTime::<T, I>::remove(old_end, id);
+		}
+		if let Some(new_end) = new_end {
+			AuctionEndTime::<T, I>::insert(new_end, id, ());
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Auction<T::AccountId, BlockNumberFor<T>> for Pallet<T, I> {
+	type AuctionId = T::AuctionId;
+	type Balance = T::Balance;
+
+	fn auction_info(
+		id: Self::AuctionId,
+	) -> Option<AuctionInfo<T::AccountId, Self::Balance, BlockNumberFor<T>>> {
+		Auctions::<T, I>::get(id)
+	}
+
+	fn update_auction(
+		id: Self::AuctionId,
+		info: AuctionInfo<T::AccountId, Self::Balance, BlockNumberFor<T>>,
+	) -> DispatchResult {
+		let auction = Auctions::<T, I>::get(id).ok_or(Error::<T, I>::AuctionNotExist)?;
+		if auction.end != info.end {
+			Self::reschedule_end(id, auction.end, info.end);
+		}
+		Auctions::<T, I>::insert(id, info);
+		Ok(())
+	}
+
+	fn new_auction(
+		start: BlockNumberFor<T>,
+		end: Option<BlockNumberFor<T>>,
+	) -> Result<Self::AuctionId, DispatchError> {
+		let auction_id =
+			AuctionsIndex::<T, I>::try_mutate(|id| -> Result<Self::AuctionId, DispatchError> {
+				let current = *id;
+				*id = id.checked_add(&One::one()).ok_or(Error::<T, I>::NoAvailableAuctionId)?;
+				Ok(current)
+			})?;
+
+		Auctions::<T, I>::insert(auction_id, AuctionInfo { bid: None, start, end });
+		if let Some(end) = end {
+			AuctionEndTime::<T, I>::insert(end, auction_id, ());
+		}
+		Ok(auction_id)
+	}
+
+	fn remove_auction(id: Self::AuctionId) {
+		if let Some(auction) = Auctions::<T, I>::take(id) {
+			if let Some(end) = auction.end {
+				AuctionEndTime::<T, I>::remove(end, id);
+			}
+			PreExtensionAuctionEnd::<T, I>::remove(id);
+		}
+	}
+}
@@ -0,0 +1,216 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocks for the auction pallet.
+
+use super::*;
+use crate as pallet_auction;
+
+use frame_support::{
+	derive_impl, instances::Instance1, parameter_types,
+	traits::{Currency, ExistenceRequirement},
+};
+use honzon_support::OnNewBidResult;
+use sp_runtime::BuildStorage;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type AuctionId = u32;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+/// The account the mock handler parks held bids on.
+pub const HOLDING: AccountId = 100;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		AuctionModule: pallet_auction,
+		SecondAuctionModule: pallet_auction::<Instance1>,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+parameter_types! {
+	/// The penalty `on_bid_cancelled` imposes; `None` forbids cancellation.
+	pub static CancelPenalty: Option<Balance> = Some(0);
+	/// Auctions concluded by `on_initialize`, with their winning bid.
+	pub static EndedAuctions: Vec<(AuctionId, Option<(AccountId, Balance)>)> = Vec::new();
+	/// Overrides the end change returned by `on_new_bid`; `None` keeps the default ten-block
+	/// extension. Used by the randomized bookkeeping tests.
+	pub static NextEndChange: Option<Change<Option<u64>>> = None;
+	/// Completion notifications in invocation order, tagged 0 for the handler and 1/2 for
+	/// the two post-auction observers.
+	pub static CompletionLog: Vec<(u8, AuctionId, Option<(AccountId, Balance)>)> = Vec::new();
+	/// The absolute bid floor `minimum_bid` returns; `None` imposes none.
+	pub static MinimumBid: Option<Balance> = None;
+	/// Auctions concluded on the second instance, with their winning bid.
+	pub static SecondEndedAuctions: Vec<(AuctionId, Option<(AccountId, Balance)>)> = Vec::new();
+}
+
+/// Holds bids on `HOLDING`, extends every auction by ten blocks on a new bid and applies
+/// `CancelPenalty` on cancellation.
+pub struct MockHandler;
+impl AuctionHandler<AccountId, Balance, u64, AuctionId> for MockHandler {
+	fn on_new_bid(
+		now: u64,
+		_id: AuctionId,
+		new_bid: (AccountId, Balance),
+		last_bid: Option<(AccountId, Balance)>,
+	) -> OnNewBidResult<u64> {
+		let (bidder, amount) = new_bid;
+		if <Balances as Currency<AccountId>>::transfer(
+			&bidder,
+			&HOLDING,
+			amount,
+			ExistenceRequirement::AllowDeath,
+		)
+		.is_err()
+		{
+			return OnNewBidResult {
+				accept_bid: false,
+				auction_end_change: Change::NoChange,
+				actual_weight: None,
+			}
+		}
+		if let Some((last_bidder, last_amount)) = last_bid {
+			let _ = <Balances as Currency<AccountId>>::transfer(
+				&HOLDING,
+				&last_bidder,
+				last_amount,
+				ExistenceRequirement::AllowDeath,
+			);
+		}
+		let auction_end_change =
+			NextEndChange::get().unwrap_or(Change::NewValue(Some(now + 10)));
+		OnNewBidResult { accept_bid: true, auction_end_change, actual_weight: None }
+	}
+
+	fn minimum_bid(_id: AuctionId) -> Option<Balance> {
+		MinimumBid::get()
+	}
+
+	fn on_bid_cancelled(_id: AuctionId, bidder: &AccountId, amount: Balance) -> Option<Balance> {
+		let penalty = CancelPenalty::get()?;
+		let _ = <Balances as Currency<AccountId>>::transfer(
+			&HOLDING,
+			bidder,
+			amount.saturating_sub(penalty),
+			ExistenceRequirement::AllowDeath,
+		);
+		Some(penalty)
+	}
+
+	fn on_auction_ended(id: AuctionId, winner: Option<(AccountId, Balance)>) {
+		CompletionLog::mutate(|log| log.push((0, id, winner)));
+		EndedAuctions::mutate(|ended| ended.push((id, winner)));
+	}
+}
+
+/// A post-auction observer recording its invocations under tag `TAG`.
+pub struct MockObserver<const TAG: u8>;
+impl<const TAG: u8> OnAuctionEnded<AccountId, Balance, AuctionId> for MockObserver<TAG> {
+	fn on_auction_ended(id: AuctionId, winner: Option<(AccountId, Balance)>) {
+		CompletionLog::mutate(|log| log.push((TAG, id, winner)));
+	}
+
+	fn weight_hint() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+}
+
+impl Config for Test {
+	type Balance = Balance;
+	type AuctionId = AuctionId;
+	type Handler = MockHandler;
+	type PostAuctionHooks = (MockObserver<1>, MockObserver<2>);
+	type WeightInfo = ();
+}
+
+/// The handler of the second auction instance: accepts every bid without holding funds,
+/// never extends the auction and forbids cancellation.
+pub struct SecondHandler;
+impl AuctionHandler<AccountId, Balance, u64, AuctionId> for SecondHandler {
+	fn on_new_bid(
+		_now: u64,
+		_id: AuctionId,
+		_new_bid: (AccountId, Balance),
+		_last_bid: Option<(AccountId, Balance)>,
+	) -> OnNewBidResult<u64> {
+		OnNewBidResult { accept_bid: true, auction_end_change: Change::NoChange, actual_weight: None }
+	}
+
+	fn on_bid_cancelled(_id: AuctionId, _bidder: &AccountId, _amount: Balance) -> Option<Balance> {
+		None
+	}
+
+	fn on_auction_ended(id: AuctionId, winner: Option<(AccountId, Balance)>) {
+		SecondEndedAuctions::mutate(|ended| ended.push((id, winner)));
+	}
+}
+
+impl Config<Instance1> for Test {
+	type Balance = Balance;
+	type AuctionId = AuctionId;
+	type Handler = SecondHandler;
+	type PostAuctionHooks = ();
+	type WeightInfo = ();
+}
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		CancelPenalty::set(Some(0));
+		EndedAuctions::set(Vec::new());
+		NextEndChange::set(None);
+		CompletionLog::set(Vec::new());
+		MinimumBid::set(None);
+		SecondEndedAuctions::set(Vec::new());
+
+		let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(ALICE, 100), (BOB, 100), (HOLDING, 1)],
+			..Default::default()
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
@@ -0,0 +1,260 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Randomized invariant tests for the auction bookkeeping.
+//!
+//! Applies random interleavings of auction creation, bidding (with randomized end changes
+//! from the handler), updates, removals and block advancement, and checks the [`Auctions`] /
+//! [`AuctionEndTime`] bookkeeping invariants after every step. Failing sequences are greedily
+//! shrunk before being reported, so the panic message carries a minimal reproducer.
+
+use super::*;
+use frame_support::traits::OnInitialize;
+use mock::*;
+
+use std::collections::BTreeSet;
+
+/// How the handler should answer the next bid.
+#[derive(Clone, Debug)]
+enum EndChangeSpec {
+	/// The mock handler's default: extend to ten blocks from now.
+	HandlerDefault,
+	/// Keep the current end.
+	KeepEnd,
+	/// Remove the deadline entirely.
+	Clear,
+	/// Move the end to this many blocks from now.
+	ExtendBy(u64),
+}
+
+/// One step of a randomized sequence.
+#[derive(Clone, Debug)]
+enum Op {
+	NewAuction { duration: Option<u64> },
+	Bid { bidder: AccountId, id_seed: u32, step: Balance, end_change: EndChangeSpec },
+	UpdateAuction { id_seed: u32, duration: Option<u64> },
+	RemoveAuction { id_seed: u32 },
+	AdvanceBlock,
+}
+
+/// A small xorshift PRNG, so sequences are reproducible from the seed alone.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		let mut x = self.0;
+		x ^= x << 13;
+		x ^= x >> 7;
+		x ^= x << 17;
+		self.0 = x;
+		x
+	}
+
+	fn below(&mut self, n: u64) -> u64 {
+		self.next() % n
+	}
+}
+
+fn gen_ops(rng: &mut Rng, len: usize) -> Vec<Op> {
+	(0..len)
+		.map(|_| match rng.below(10) {
+			0 | 1 => Op::NewAuction {
+				duration: if rng.below(4) == 0 { None } else { Some(1 + rng.below(6)) },
+			},
+			2..=5 => Op::Bid {
+				bidder: [ALICE, BOB][rng.below(2) as usize],
+				id_seed: rng.below(16) as u32,
+				step: 1 + rng.below(3),
+				end_change: match rng.below(8) {
+					0 | 1 => EndChangeSpec::HandlerDefault,
+					2 | 3 => EndChangeSpec::KeepEnd,
+					4 => EndChangeSpec::Clear,
+					_ => EndChangeSpec::ExtendBy(1 + rng.below(8)),
+				},
+			},
+			6 => Op::UpdateAuction {
+				id_seed: rng.below(16) as u32,
+				duration: if rng.below(4) == 0 { None } else { Some(1 + rng.below(6)) },
+			},
+			7 => Op::RemoveAuction { id_seed: rng.below(16) as u32 },
+			_ => Op::AdvanceBlock,
+		})
+		.collect()
+}
+
+/// Pick an auction id from the ids handed out so far; dead ids are deliberately included to
+/// exercise the error paths.
+fn pick_id(id_seed: u32) -> AuctionId {
+	id_seed % AuctionsIndex::<Test>::get().max(1)
+}
+
+fn apply(op: &Op) {
+	let now = System::block_number();
+	match op {
+		Op::NewAuction { duration } => {
+			let _ = AuctionModule::new_auction(now, duration.map(|d| now + d));
+		},
+		Op::Bid { bidder, id_seed, step, end_change } => {
+			let id = pick_id(*id_seed);
+			let amount = Auctions::<Test>::get(id)
+				.and_then(|auction| auction.bid.map(|(_, amount)| amount))
+				.unwrap_or(0)
+				.saturating_add(*step);
+			NextEndChange::set(Some(match end_change {
+				EndChangeSpec::HandlerDefault => Change::NewValue(Some(now + 10)),
+				EndChangeSpec::KeepEnd => Change::NoChange,
+				EndChangeSpec::Clear => Change::NewValue(None),
+				EndChangeSpec::ExtendBy(offset) => Change::NewValue(Some(now + offset)),
+			}));
+			let _ = AuctionModule::bid(RuntimeOrigin::signed(*bidder), id, amount);
+			NextEndChange::set(None);
+		},
+		Op::UpdateAuction { id_seed, duration } => {
+			let id = pick_id(*id_seed);
+			if let Some(mut auction) = AuctionModule::auction_info(id) {
+				auction.end = duration.map(|d| now + d);
+				let _ = AuctionModule::update_auction(id, auction);
+			}
+		},
+		Op::RemoveAuction { id_seed } => {
+			AuctionModule::remove_auction(pick_id(*id_seed));
+		},
+		Op::AdvanceBlock => {
+			System::set_block_number(now + 1);
+			<AuctionModule as OnInitialize<u64>>::on_initialize(now + 1);
+		},
+	}
+}
+
+/// Check the bookkeeping invariants over the whole storage.
+fn check_invariants() -> Result<(), String> {
+	let live: Vec<_> = Auctions::<Test>::iter().collect();
+	let schedule: Vec<(u64, AuctionId)> =
+		AuctionEndTime::<Test>::iter().map(|(end, id, ())| (end, id)).collect();
+
+	for (id, auction) in &live {
+		let entries: Vec<_> = schedule.iter().filter(|(_, sid)| sid == id).collect();
+		match auction.end {
+			Some(end) => {
+				if entries.len() != 1 || entries[0].0 != end {
+					return Err(format!(
+						"auction {id} ends at {end} but has schedule entries {entries:?}"
+					))
+				}
+			},
+			None =>
+				if !entries.is_empty() {
+					return Err(format!(
+						"auction {id} has no deadline but schedule entries {entries:?}"
+					))
+				},
+		}
+	}
+	for (end, id) in &schedule {
+		match live.iter().find(|(lid, _)| lid == id) {
+			Some((_, auction)) if auction.end == Some(*end) => {},
+			Some(_) => return Err(format!("schedule entry ({end}, {id}) disagrees with auction")),
+			None => return Err(format!("schedule entry ({end}, {id}) references no auction")),
+		}
+	}
+	Ok(())
+}
+
+/// Run `ops` on fresh externalities, checking all invariants after every step.
+fn run_sequence(ops: &[Op]) -> Result<(), String> {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut last_index = 0;
+		let mut ended_so_far = 0;
+		for (i, op) in ops.iter().enumerate() {
+			// Auctions expected to conclude if this op advances the block.
+			let now = System::block_number();
+			let mut expected_ended: Vec<AuctionId> = Auctions::<Test>::iter()
+				.filter(|(_, auction)| auction.end == Some(now + 1))
+				.map(|(id, _)| id)
+				.collect();
+			expected_ended.sort_unstable();
+
+			apply(op);
+			let fail = |msg: String| Err(format!("op {i} ({op:?}): {msg}"));
+
+			if let Err(msg) = check_invariants() {
+				return fail(msg)
+			}
+
+			let ended = EndedAuctions::get();
+			if matches!(op, Op::AdvanceBlock) {
+				let mut newly: Vec<AuctionId> =
+					ended[ended_so_far..].iter().map(|(id, _)| *id).collect();
+				newly.sort_unstable();
+				if newly != expected_ended {
+					return fail(format!(
+						"expected auctions {expected_ended:?} to end, handler saw {newly:?}"
+					))
+				}
+			} else if ended.len() != ended_so_far {
+				return fail("handler saw an auction end outside block advancement".into())
+			}
+			ended_so_far = ended.len();
+
+			let unique: BTreeSet<AuctionId> = ended.iter().map(|(id, _)| *id).collect();
+			if unique.len() != ended.len() {
+				return fail(format!("an auction ended more than once: {ended:?}"))
+			}
+
+			let index = AuctionsIndex::<Test>::get();
+			if index < last_index {
+				return fail(format!("AuctionsIndex decreased from {last_index} to {index}"))
+			}
+			last_index = index;
+		}
+		Ok(())
+	})
+}
+
+/// Greedily drop ops while the sequence still fails, yielding a minimal reproducer.
+fn shrink(mut ops: Vec<Op>) -> Vec<Op> {
+	loop {
+		let mut improved = false;
+		let mut i = 0;
+		while i < ops.len() {
+			let mut candidate = ops.clone();
+			candidate.remove(i);
+			if run_sequence(&candidate).is_err() {
+				ops = candidate;
+				improved = true;
+			} else {
+				i += 1;
+			}
+		}
+		if !improved {
+			return ops
+		}
+	}
+}
+
+#[test]
+fn randomized_bookkeeping_invariants_hold() {
+	for seed in 1..=64u64 {
+		let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+		let ops = gen_ops(&mut rng, 40);
+		if let Err(msg) = run_sequence(&ops) {
+			let minimized = shrink(ops);
+			let msg = run_sequence(&minimized).err().unwrap_or(msg);
+			panic!("auction invariants violated (seed {seed}): {msg}\nminimized sequence: {minimized:#?}");
+		}
+	}
+}
@@ -0,0 +1,293 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the auction pallet.
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, instances::Instance1, traits::OnInitialize};
+use mock::*;
+
+#[test]
+fn new_auction_and_bid_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+		assert_eq!(id, 0);
+		assert!(AuctionEndTime::<Test>::contains_key(100, id));
+
+		assert_noop!(
+			AuctionModule::bid(RuntimeOrigin::signed(ALICE), 1, 20),
+			Error::<Test>::AuctionNotExist
+		);
+		assert_noop!(
+			AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 0),
+			Error::<Test>::InvalidBidPrice
+		);
+
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 20));
+		System::assert_last_event(
+			Event::<Test>::Bid { auction_id: id, bidder: ALICE, amount: 20 }.into(),
+		);
+		assert_eq!(Balances::free_balance(ALICE), 80);
+
+		// The handler extended the auction to `now + 10`; the original end is remembered.
+		let auction = AuctionModule::auction_info(id).unwrap();
+		assert_eq!(auction.bid, Some((ALICE, 20)));
+		assert_eq!(auction.end, Some(11));
+		assert!(AuctionEndTime::<Test>::contains_key(11, id));
+		assert!(!AuctionEndTime::<Test>::contains_key(100, id));
+		assert_eq!(PreExtensionAuctionEnd::<Test>::get(id), Some(Some(100)));
+
+		// A new bid must be higher than the current one, and refunds the outbid bidder.
+		assert_noop!(
+			AuctionModule::bid(RuntimeOrigin::signed(BOB), id, 20),
+			Error::<Test>::InvalidBidPrice
+		);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), id, 30));
+		assert_eq!(Balances::free_balance(ALICE), 100);
+		assert_eq!(Balances::free_balance(BOB), 70);
+	});
+}
+
+#[test]
+fn handler_minimum_bid_floor_is_enforced() {
+	ExtBuilder::default().build().execute_with(|| {
+		MinimumBid::set(Some(30));
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+
+		// A bid below the handler's floor is a hard error, unlike a soft handler
+		// rejection: the handler is never consulted.
+		assert_noop!(
+			AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 29),
+			Error::<Test>::InvalidBidPrice
+		);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 30));
+		assert_eq!(AuctionModule::auction_info(id).unwrap().bid, Some((ALICE, 30)));
+
+		// Raising the floor also gates bids that would outbid the current winner.
+		MinimumBid::set(Some(50));
+		assert_noop!(
+			AuctionModule::bid(RuntimeOrigin::signed(BOB), id, 40),
+			Error::<Test>::InvalidBidPrice
+		);
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), id, 50));
+		assert_eq!(AuctionModule::auction_info(id).unwrap().bid, Some((BOB, 50)));
+	});
+}
+
+#[test]
+fn bid_turned_down_by_handler_is_rejected_softly() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+
+		// The handler rejects the bid (ALICE cannot pay for it), but the extrinsic
+		// succeeds so any state the handler recorded about the rejection persists.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 200));
+		System::assert_last_event(
+			Event::<Test>::BidRejected { auction_id: id, bidder: ALICE, amount: 200 }.into(),
+		);
+		let auction = AuctionModule::auction_info(id).unwrap();
+		assert_eq!(auction.bid, None);
+		assert_eq!(auction.end, Some(100));
+	});
+}
+
+#[test]
+fn cancel_bid_requires_leading_bidder() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+
+		// No bid to cancel yet.
+		assert_noop!(
+			AuctionModule::cancel_bid(RuntimeOrigin::signed(ALICE), id),
+			Error::<Test>::NotLeadingBidder
+		);
+
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 20));
+		assert_noop!(
+			AuctionModule::cancel_bid(RuntimeOrigin::signed(BOB), id),
+			Error::<Test>::NotLeadingBidder
+		);
+
+		// The handler can forbid cancellation entirely.
+		CancelPenalty::set(None);
+		assert_noop!(
+			AuctionModule::cancel_bid(RuntimeOrigin::signed(ALICE), id),
+			Error::<Test>::CancelNotAllowed
+		);
+	});
+}
+
+#[test]
+fn cancel_bid_applies_penalty_and_reverts_extension() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 20));
+		assert_eq!(Balances::free_balance(ALICE), 80);
+		assert_eq!(AuctionModule::auction_info(id).unwrap().end, Some(11));
+
+		CancelPenalty::set(Some(5));
+		assert_ok!(AuctionModule::cancel_bid(RuntimeOrigin::signed(ALICE), id));
+		System::assert_last_event(
+			Event::<Test>::BidCancelled { auction_id: id, bidder: ALICE, amount: 20, penalty: 5 }
+				.into(),
+		);
+
+		// The bid is refunded minus the penalty and the auction reverts to its original end.
+		assert_eq!(Balances::free_balance(ALICE), 95);
+		let auction = AuctionModule::auction_info(id).unwrap();
+		assert_eq!(auction.bid, None);
+		assert_eq!(auction.end, Some(100));
+		assert!(AuctionEndTime::<Test>::contains_key(100, id));
+		assert!(!AuctionEndTime::<Test>::contains_key(11, id));
+		assert_eq!(PreExtensionAuctionEnd::<Test>::get(id), None);
+
+		// Bidding remains possible afterwards.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(BOB), id, 20));
+		assert_eq!(AuctionModule::auction_info(id).unwrap().bid, Some((BOB, 20)));
+	});
+}
+
+#[test]
+fn on_initialize_concludes_ended_auctions() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 20));
+
+		// Extended to block 11 by the handler.
+		<AuctionModule as OnInitialize<u64>>::on_initialize(10);
+		assert!(EndedAuctions::get().is_empty());
+
+		<AuctionModule as OnInitialize<u64>>::on_initialize(11);
+		assert_eq!(EndedAuctions::get(), vec![(id, Some((ALICE, 20)))]);
+		assert_eq!(AuctionModule::auction_info(id), None);
+		assert_eq!(PreExtensionAuctionEnd::<Test>::get(id), None);
+	});
+}
+
+#[test]
+fn post_auction_hooks_observe_completion_after_the_handler() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), id, 20));
+
+		// Extended to block 11 by the handler.
+		let weight = <AuctionModule as OnInitialize<u64>>::on_initialize(11);
+		// Both observers are notified with the clearing data, after the primary handler.
+		assert_eq!(
+			CompletionLog::get(),
+			vec![
+				(0, id, Some((ALICE, 20))),
+				(1, id, Some((ALICE, 20))),
+				(2, id, Some((ALICE, 20))),
+			]
+		);
+		// Their weight hints are charged on top of the close-path weight.
+		assert_eq!(
+			weight,
+			<<Test as Config>::WeightInfo as WeightInfo>::on_initialize(1)
+				.saturating_add(Weight::from_parts(2_000_000, 0))
+		);
+
+		// An auction that ends without a bid is reported with no winner.
+		let id = AuctionModule::new_auction(11, Some(20)).unwrap();
+		<AuctionModule as OnInitialize<u64>>::on_initialize(20);
+		assert_eq!(CompletionLog::get().last(), Some(&(2, id, None)));
+	});
+}
+
+#[test]
+fn update_and_remove_auction_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+		assert_noop!(
+			AuctionModule::update_auction(
+				1,
+				AuctionInfo { bid: None, start: 1, end: Some(50) }
+			),
+			Error::<Test>::AuctionNotExist
+		);
+
+		assert_ok!(AuctionModule::update_auction(
+			id,
+			AuctionInfo { bid: Some((ALICE, 10)), start: 1, end: Some(50) }
+		));
+		assert!(AuctionEndTime::<Test>::contains_key(50, id));
+		assert!(!AuctionEndTime::<Test>::contains_key(100, id));
+
+		AuctionModule::remove_auction(id);
+		assert_eq!(AuctionModule::auction_info(id), None);
+		assert!(!AuctionEndTime::<Test>::contains_key(50, id));
+	});
+}
+
+#[test]
+fn time_remaining_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = AuctionModule::new_auction(1, Some(100)).unwrap();
+		let open_ended_id = AuctionModule::new_auction(1, None).unwrap();
+
+		// An active auction counts down to its end block.
+		System::set_block_number(40);
+		assert_eq!(AuctionModule::time_remaining(id), Some(60));
+
+		// Past the end block the remaining time saturates to zero.
+		System::set_block_number(150);
+		assert_eq!(AuctionModule::time_remaining(id), Some(0));
+
+		// Auctions without an end and unknown ids have no countdown.
+		assert_eq!(AuctionModule::time_remaining(open_ended_id), None);
+		assert_eq!(AuctionModule::time_remaining(42), None);
+	});
+}
+
+#[test]
+fn instances_keep_isolated_state_and_handlers() {
+	ExtBuilder::default().build().execute_with(|| {
+		// Ids are assigned per instance, from isolated storage.
+		let first = AuctionModule::new_auction(1, Some(50)).unwrap();
+		let second = SecondAuctionModule::new_auction(1, Some(60)).unwrap();
+		assert_eq!(first, 0);
+		assert_eq!(second, 0);
+		assert!(Auctions::<Test>::contains_key(first));
+		assert!(Auctions::<Test, Instance1>::contains_key(second));
+
+		// Each instance consults its own handler: the default one holds the bid funds and
+		// extends the auction, the second accepts without touching balances or the end.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), first, 20));
+		assert_eq!(Balances::free_balance(ALICE), 80);
+		assert_ok!(SecondAuctionModule::bid(RuntimeOrigin::signed(BOB), second, 5));
+		assert_eq!(Balances::free_balance(BOB), 100);
+		assert_eq!(AuctionModule::auction_info(first).unwrap().end, Some(11));
+		assert_eq!(SecondAuctionModule::auction_info(second).unwrap().end, Some(60));
+
+		// The second handler forbids what the default one allows.
+		assert_noop!(
+			SecondAuctionModule::cancel_bid(RuntimeOrigin::signed(BOB), second),
+			Error::<Test, Instance1>::CancelNotAllowed
+		);
+		assert_ok!(AuctionModule::cancel_bid(RuntimeOrigin::signed(ALICE), first));
+
+		// Closing is per instance and reports to the instance's own handler.
+		assert_ok!(AuctionModule::bid(RuntimeOrigin::signed(ALICE), first, 25));
+		<AuctionModule as OnInitialize<u64>>::on_initialize(11);
+		<SecondAuctionModule as OnInitialize<u64>>::on_initialize(11);
+		assert_eq!(EndedAuctions::get(), vec![(first, Some((ALICE, 25)))]);
+		assert!(SecondEndedAuctions::get().is_empty());
+		<SecondAuctionModule as OnInitialize<u64>>::on_initialize(60);
+		assert_eq!(SecondEndedAuctions::get(), vec![(second, Some((BOB, 5)))]);
+	});
+}
@@ -0,0 +1,45 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the CDP engine pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::Codec;
+use honzon_support::Ratio;
+
+sp_api::decl_runtime_apis! {
+	/// Runtime API for keepers looking for liquidatable CDPs, so they do not have to
+	/// replicate the offchain worker's scan logic over archive state.
+	pub trait CdpEngineApi<AccountId, CurrencyId>
+	where
+		AccountId: Codec,
+		CurrencyId: Codec,
+	{
+		/// Up to `max` liquidatable CDPs, scanning the open positions from the opaque
+		/// `start_key` cursor (`None` starts from the beginning). Returns the unsafe
+		/// positions with their collateral ratio at the live price and the cursor to
+		/// continue from; a `None` cursor means the scan is complete.
+		fn liquidatable(
+			max: u32,
+			start_key: Option<Vec<u8>>,
+		) -> (Vec<(CurrencyId, AccountId, Ratio)>, Option<Vec<u8>>);
+	}
+}
@@ -0,0 +1,2058 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # CDP Engine Pallet
+//!
+//! The risk management and liquidation engine of the Honzon protocol.
+//!
+//! ## Overview
+//!
+//! The CDP engine holds the per-collateral risk parameters - liquidation ratio, liquidation
+//! penalty, required collateral ratio, stability fee and the debit hard cap - and the debit
+//! exchange rate, which converts a position's debit units into stable currency value and
+//! accrues the stability fee on every block. It implements [`RiskManager`] for the loans
+//! pallet, so every position change is validated against these parameters.
+//!
+//! An offchain worker scans open positions each block. While the system is live it submits
+//! unsigned `liquidate` transactions for CDPs whose collateral ratio has fallen below the
+//! liquidation ratio, grouping them into `liquidate_batch` submissions of up to
+//! `OffchainLiquidationBatchSize` accounts; the confiscated collateral is handed to the
+//! auction manager to recover the debt plus the liquidation penalty. After emergency shutdown
+//! it submits unsigned
+//! `settle` transactions instead, which confiscate just enough collateral to cover each
+//! position's debt.
+//!
+//! While the system is live the engine also recapitalizes the CDP treasury on every block: if
+//! the debit pool exceeds the surplus pool by at least `DebtAuctionThreshold`, the shortfall is
+//! put up in a debt auction via [`CDPTreasuryExtended::create_debt_auction`]; surplus beyond
+//! the debit pool and `SurplusBufferSize` is offered in a surplus auction. The amounts already
+//! in flight are tracked so the same shortfall is not auctioned twice while an auction runs.
+//!
+//! Settlement deliberately prices collateral at the price locked when shutdown was triggered,
+//! read from [`LockedPriceProvider::locked_price`]: the live market price may keep moving
+//! after shutdown, and all CDPs must settle at the same rate regardless of when their
+//! settlement transaction lands. If no locked price exists for a currency, settlement fails
+//! (and its unsigned transaction does not validate) rather than falling back to the live feed.
+//! The wind-down's progress is tracked in a cumulative settled-debit accumulator: every
+//! `SettlementProgressInterval` settlements a `SettlementProgress` snapshot is emitted, and a
+//! `SettlementComplete` event fires once no debit remains, signalling that collateral refunds
+//! can open.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use frame_support::{pallet_prelude::*, storage::with_storage_layer, traits::FindAuthor};
+use frame_system::{
+	offchain::{CreateBare, SubmitTransaction},
+	pallet_prelude::*,
+};
+use honzon_support::{
+	AuctionManager, CDPTreasury, CDPTreasuryExtended, Change, EmergencyShutdown, ExchangeRate,
+	LiquidationGuard, LockedPriceProvider, OnLiquidation, OnUpdateLoan, Price, PriceProvider,
+	Rate, Ratio, RiskManager, SettlementCounter,
+};
+use alloc::{collections::BTreeSet, vec::Vec};
+use pallet_loans::Position;
+use sp_arithmetic::traits::Signed;
+use sp_runtime::{
+	traits::{Bounded, CheckedDiv, One, Saturating, Zero},
+	ArithmeticError, DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+const LOG_TARGET: &str = "runtime::cdp-engine";
+
+/// The number of risk buckets positions are indexed into. Bucket 0 is the riskiest.
+pub const NUM_RISK_BUCKETS: u8 = 8;
+
+/// The liquidation status of a CDP.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum CDPStatus {
+	/// The position is at or above the liquidation ratio, or has no debit.
+	Safe,
+	/// The position is below the liquidation ratio and may be liquidated.
+	Unsafe,
+	/// The status cannot be determined because no live price is available.
+	ChecksFailed,
+}
+
+/// Risk management parameters of a collateral currency.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct RiskManagementParams<Balance> {
+	/// The hard cap on the total debit value of this collateral's CDPs.
+	pub maximum_total_debit_value: Balance,
+	/// The per-block fee accrued on outstanding debit, compounded into the debit exchange
+	/// rate. `None` means no fee.
+	pub stability_fee: Option<Rate>,
+	/// Positions whose collateral ratio falls below this become liquidatable. `None` means the
+	/// default liquidation ratio applies.
+	pub liquidation_ratio: Option<Ratio>,
+	/// The share of the debt added on top of it as the liquidation target. `None` means the
+	/// default penalty applies.
+	pub liquidation_penalty: Option<Rate>,
+	/// The collateral ratio every position adjustment must leave the position at or above.
+	/// `None` means only the liquidation ratio is enforced.
+	pub required_collateral_ratio: Option<Ratio>,
+	/// The largest debit value a single position may have, preventing one account from
+	/// consuming the whole debit hard cap. `None` means unlimited.
+	pub per_account_debit_ceiling: Option<Balance>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + pallet_loans::Config + CreateBare<Call<Self>>
+	{
+		/// The origin which may update the risk parameters of a collateral currency.
+		type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The currencies accepted as CDP collateral.
+		type CollateralCurrencyIds: Get<Vec<Self::CurrencyId>>;
+
+		/// The liquidation ratio applying to collaterals without a bespoke one.
+		#[pallet::constant]
+		type DefaultLiquidationRatio: Get<Ratio>;
+
+		/// The debit exchange rate applying to collaterals without a stored one.
+		#[pallet::constant]
+		type DefaultDebitExchangeRate: Get<ExchangeRate>;
+
+		/// The liquidation penalty applying to collaterals without a bespoke one.
+		#[pallet::constant]
+		type DefaultLiquidationPenalty: Get<Rate>;
+
+		/// The smallest debit value a non-empty position may have, preventing dust CDPs.
+		#[pallet::constant]
+		type MinimumDebitValue: Get<Self::Balance>;
+
+		/// The smallest collateral amount a debit-free position may keep; smaller remainders
+		/// must be withdrawn in full instead of lingering as dust.
+		#[pallet::constant]
+		type MinimumCollateralAmount: Get<Self::Balance>;
+
+		/// The stable currency all debit values are denominated in.
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<Self::CurrencyId>;
+
+		/// The price feed. Liquidation safety checks use the live price; settlement after
+		/// emergency shutdown uses the locked price only.
+		type PriceSource: LockedPriceProvider<Self::CurrencyId>;
+
+		/// The emergency shutdown state of the system.
+		type EmergencyShutdown: EmergencyShutdown;
+
+		/// Observer notified when a position is liquidated or settled, e.g. to journal
+		/// position history.
+		type OnLiquidation: OnLiquidation<Self::AccountId, Self::CurrencyId, Self::Balance>;
+
+		/// External risk manager that can veto the liquidation of specific positions.
+		type LiquidationGuard: LiquidationGuard<Self::AccountId>;
+
+		/// The minimum number of blocks that must have passed since the collateral price
+		/// was last published before it may back a liquidation, a defense in depth against
+		/// same-block oracle manipulation. Feeds that do not report a price age are exempt.
+		#[pallet::constant]
+		type MinBlocksSinceLastOracleUpdate: Get<u32>;
+
+		/// The auction manager receiving confiscated collateral during liquidation.
+		type AuctionManagerHandler: AuctionManager<
+			Self::AccountId,
+			Balance = Self::Balance,
+			CurrencyId = Self::CurrencyId,
+		>;
+
+		/// The CDP treasury, asked to start debt and surplus auctions when its pools drift past
+		/// the configured thresholds.
+		type CDPTreasuryHandler: CDPTreasuryExtended<
+			Self::AccountId,
+			Balance = Self::Balance,
+			CurrencyId = Self::CurrencyId,
+		>;
+
+		/// The rebate credited to the block author for including a successful unsigned
+		/// liquidation, encouraging validators to not deprioritize them under load. Zero
+		/// disables the rebate.
+		///
+		/// The rebate is issued by the CDP treasury as unbacked stable currency, so it is
+		/// netted against the surplus pool like any other system debit.
+		#[pallet::constant]
+		type LiquidationInclusionReward: Get<Self::Balance>;
+
+		/// The block author finder used to pay the liquidation inclusion reward.
+		type FindAuthor: FindAuthor<Self::AccountId>;
+
+		/// The reward paid to the signed caller of [`Pallet::settle_signed`] for settling a
+		/// CDP after emergency shutdown, encouraging third parties to settle stragglers the
+		/// offchain worker misses. Zero disables the reward; settlement itself stays free
+		/// through the unsigned [`Pallet::settle`].
+		///
+		/// Like the liquidation inclusion reward, the incentive is issued by the CDP
+		/// treasury as unbacked stable currency and netted against the surplus accumulated
+		/// during the wind-down.
+		#[pallet::constant]
+		type SettlementIncentive: Get<Self::Balance>;
+
+		/// The largest debit value, in stable currency, a single liquidation may handle. A
+		/// bigger position is confiscated in proportional chunks of at most this value, with
+		/// the remainder left behind as a still-unsafe position for subsequent liquidations,
+		/// so one enormous CDP cannot dump its whole collateral on the market at once. Zero
+		/// disables the cap.
+		#[pallet::constant]
+		type MaxLiquidationValue: Get<Self::Balance>;
+
+		/// The smallest uncovered debit pool shortfall that triggers a debt auction.
+		#[pallet::constant]
+		type DebtAuctionThreshold: Get<Self::Balance>;
+
+		/// The surplus pool buffer that is never offered in surplus auctions.
+		#[pallet::constant]
+		type SurplusBufferSize: Get<Self::Balance>;
+
+		/// The maximum number of positions tracked per risk bucket. Positions that do not fit
+		/// are still found by the offchain worker's full scan, just without priority.
+		#[pallet::constant]
+		type MaxRiskBucketSize: Get<u32>;
+
+		/// The fraction of the liquidation ratio below which a CDP counts as critical. The
+		/// offchain worker submits liquidations for critical CDPs before walking the risk
+		/// buckets, whose stamps may be stale after a sharp price move.
+		#[pallet::constant]
+		type CriticalRatioThreshold: Get<Ratio>;
+
+		/// The fractional band above the liquidation ratio a position must recover past
+		/// before it stops being eligible for liquidation, once its ratio has been observed
+		/// below the liquidation ratio on-chain. Dampens oracle jitter at the boundary:
+		/// without it a position oscillating within a fraction of a percent repeatedly
+		/// enters and leaves the unsafe set. Zero disables the hysteresis.
+		#[pallet::constant]
+		type HysteresisBand: Get<Ratio>;
+
+		/// The maximum number of accounts a single `settle_cdps_batch` call may settle.
+		#[pallet::constant]
+		type MaxSettlementBatch: Get<u32>;
+
+		/// Every how many settled CDPs a [`Event::SettlementProgress`] snapshot is emitted
+		/// during the post-shutdown wind-down. Zero disables the snapshots.
+		#[pallet::constant]
+		type SettlementProgressInterval: Get<u32>;
+
+		/// The number of unsafe CDPs the offchain worker groups into one `liquidate_batch`
+		/// submission, so a scan that finds many unsafe positions does not flood the
+		/// transaction pool with single-account transactions. A value of one makes the
+		/// worker submit plain `liquidate` calls instead.
+		#[pallet::constant]
+		type OffchainLiquidationBatchSize: Get<u32>;
+
+		/// The priority of the unsigned liquidation and settlement transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The total debit value of this collateral would exceed its hard cap.
+		ExceedDebitValueHardCap,
+		/// The position would fall below the required collateral ratio.
+		BelowRequiredCollateralRatio,
+		/// The position would fall below the liquidation ratio.
+		BelowLiquidationRatio,
+		/// The CDP to liquidate is not unsafe.
+		MustBeUnsafe,
+		/// The currency is not accepted as CDP collateral.
+		InvalidCollateralType,
+		/// The position's remaining debit value would be below the minimum.
+		RemainDebitValueTooSmall,
+		/// No price is available for the collateral currency.
+		InvalidFeedPrice,
+		/// The CDP has no debit to settle.
+		NoDebitValue,
+		/// The operation is only available after emergency shutdown.
+		MustAfterShutdown,
+		/// The operation is no longer available after emergency shutdown.
+		AlreadyShutdown,
+		/// The position's debit value would exceed the per-account debit ceiling.
+		ExceedAccountDebitCeiling,
+		/// No locked price exists to settle against.
+		NoLockedPrice,
+		/// The required collateral ratio would be below the liquidation ratio, or the
+		/// liquidation ratio below one.
+		InvalidRatioOrdering,
+		/// A settle-all step must be allowed to visit at least one position.
+		ZeroLimit,
+		/// The liquidation of this position is vetoed by the liquidation guard.
+		LiquidationVetoed,
+		/// The position was adjusted by its owner in this block; liquidation is deferred.
+		AdjustedInCurrentBlock,
+		/// The collateral price is too recent to back a liquidation.
+		PriceTooRecent,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An unsafe CDP has been liquidated.
+		LiquidateUnsafeCDP {
+			collateral_type: T::CurrencyId,
+			owner: T::AccountId,
+			collateral_amount: T::Balance,
+			bad_debt_value: T::Balance,
+			target_amount: T::Balance,
+		},
+		/// A CDP has been settled against the locked shutdown price.
+		SettleCDPInDebit { collateral_type: T::CurrencyId, owner: T::AccountId },
+		/// A periodic snapshot of the post-shutdown wind-down: the cumulative debit value
+		/// settled so far and the debit value still outstanding across all collaterals.
+		SettlementProgress { settled: T::Balance, remaining: T::Balance },
+		/// The last outstanding debit has been settled; collateral refunds can open.
+		SettlementComplete { settled: T::Balance },
+		/// A [`Pallet::settle_all`] step settled `count` CDPs; `done` signals that the sweep
+		/// reached the end of the position book and the cursor has been cleared.
+		SettledBatch { count: u32, done: bool },
+		/// The stability fee of a collateral has been updated.
+		StabilityFeeUpdated { collateral_type: T::CurrencyId, new_stability_fee: Option<Rate> },
+		/// The liquidation ratio of a collateral has been updated.
+		LiquidationRatioUpdated {
+			collateral_type: T::CurrencyId,
+			new_liquidation_ratio: Option<Ratio>,
+		},
+		/// The liquidation penalty of a collateral has been updated.
+		LiquidationPenaltyUpdated {
+			collateral_type: T::CurrencyId,
+			new_liquidation_penalty: Option<Rate>,
+		},
+		/// The required collateral ratio of a collateral has been updated.
+		RequiredCollateralRatioUpdated {
+			collateral_type: T::CurrencyId,
+			new_required_collateral_ratio: Option<Ratio>,
+		},
+		/// The debit hard cap of a collateral has been updated.
+		MaximumTotalDebitValueUpdated {
+			collateral_type: T::CurrencyId,
+			new_total_debit_value: T::Balance,
+		},
+		/// The per-account debit ceiling of a collateral has been updated.
+		PerAccountDebitCeilingUpdated {
+			collateral_type: T::CurrencyId,
+			new_per_account_debit_ceiling: Option<T::Balance>,
+		},
+		/// Debit has been repaid with stable currency provided by another account.
+		DebitRepaidOnBehalf {
+			collateral_type: T::CurrencyId,
+			owner: T::AccountId,
+			payer: T::AccountId,
+			repaid_value: T::Balance,
+		},
+		/// The stored debit exchange rate of a debt-free currency has been swept.
+		DebitExchangeRateSwept { collateral_type: T::CurrencyId },
+		/// A debt auction has been started to cover the uncovered debit pool.
+		DebtAuctionTriggered { amount: T::Balance },
+		/// A surplus auction has been started for surplus beyond the buffer.
+		SurplusAuctionTriggered { amount: T::Balance },
+		/// The interest reconciliation drift warning threshold has been updated.
+		ReconciliationDriftThresholdUpdated { new_threshold: Option<T::Balance> },
+		/// Whether the required collateral ratio is enforced on every position check has been
+		/// updated.
+		RequiredRatioEnforcementUpdated { always_enforced: bool },
+		/// The drift between cumulative interest issued and accounted debit value growth has
+		/// exceeded the governance-set threshold.
+		InterestReconciliationDriftWarning {
+			issued: T::Balance,
+			growth: T::Balance,
+			drift: T::Balance,
+		},
+	}
+
+	/// The debit exchange rate of each collateral currency, if it diverged from the default.
+	///
+	/// Converts debit units into stable currency value and grows with the stability fee.
+	#[pallet::storage]
+	pub type DebitExchangeRate<T: Config> =
+		StorageMap<_, Twox64Concat, T::CurrencyId, ExchangeRate, OptionQuery>;
+
+	/// The risk management parameters of each collateral currency.
+	#[pallet::storage]
+	pub type CollateralParams<T: Config> =
+		StorageMap<_, Twox64Concat, T::CurrencyId, RiskManagementParams<T::Balance>, OptionQuery>;
+
+	/// Open positions indexed by a coarse risk bucket (0 is the riskiest), per collateral
+	/// currency, so the offchain worker can visit the positions closest to insolvency first.
+	///
+	/// The bucket reflects the collateral ratio at the position's last update, not the
+	/// current price.
+	#[pallet::storage]
+	pub type RiskBuckets<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CurrencyId,
+		Twox64Concat,
+		u8,
+		BoundedVec<T::AccountId, T::MaxRiskBucketSize>,
+		ValueQuery,
+	>;
+
+	/// The risk bucket each indexed position currently sits in.
+	#[pallet::storage]
+	pub type RiskBucketOf<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::CurrencyId, Twox64Concat, T::AccountId, u8, OptionQuery>;
+
+	/// The block a position's collateral ratio was last observed crossing below the
+	/// liquidation ratio, per collateral currency. While present, the position stays
+	/// eligible for liquidation; the marker is only cleared once the position closes or its
+	/// ratio recovers past the liquidation ratio scaled up by [`Config::HysteresisBand`].
+	#[pallet::storage]
+	pub type BelowLiquidationSince<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CurrencyId,
+		Twox64Concat,
+		T::AccountId,
+		BlockNumberFor<T>,
+		OptionQuery,
+	>;
+
+	/// The amount of system debt currently being covered by in-flight debt auctions, so the
+	/// same shortfall is not auctioned twice.
+	#[pallet::storage]
+	pub type DebtAuctionsInFlight<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// The amount of surplus currently offered in in-flight surplus auctions.
+	#[pallet::storage]
+	pub type SurplusAuctionsInFlight<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Cumulative stable currency issued to the treasury as interest revenue by the accrual
+	/// in `on_initialize`.
+	#[pallet::storage]
+	pub type CumulativeInterestIssued<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Cumulative growth in the valuation of all open debit from debit exchange rate
+	/// increments, accounted independently of [`CumulativeInterestIssued`] in the same accrual
+	/// loop.
+	///
+	/// The two counters form a double entry: any lasting divergence beyond per-event rounding
+	/// means unbacked stable currency was issued (or revenue was lost).
+	#[pallet::storage]
+	pub type CumulativeDebitValueGrowth<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// The number of interest accrual events, stored in balance units.
+	///
+	/// Each event may round [`CumulativeInterestIssued`] and [`CumulativeDebitValueGrowth`]
+	/// apart by at most one unit, so this is the tolerated reconciliation drift.
+	#[pallet::storage]
+	pub type InterestAccrualEvents<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// The governance-set reconciliation drift above which a warning event is emitted, if any.
+	#[pallet::storage]
+	pub type ReconciliationDriftThreshold<T: Config> = StorageValue<_, T::Balance, OptionQuery>;
+
+	/// Whether the drift warning has already been emitted for the current threshold.
+	#[pallet::storage]
+	pub type DriftWarningEmitted<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Whether the required collateral ratio is enforced on every position check, overriding
+	/// callers that would skip it for ratio-improving operations. Set by governance during
+	/// heightened risk.
+	#[pallet::storage]
+	pub type RequiredRatioAlwaysEnforced<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Cumulative debit value settled since emergency shutdown.
+	#[pallet::storage]
+	pub type SettledDebitTotal<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// The number of CDPs settled since emergency shutdown, pacing the progress snapshots.
+	#[pallet::storage]
+	pub type SettledCdpCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The last position visited by [`Pallet::settle_all`]; the next call resumes after it.
+	/// `None` when no sweep is in progress.
+	#[pallet::storage]
+	pub type SettleAllCursor<T: Config> =
+		StorageValue<_, (T::CurrencyId, T::AccountId), OptionQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			// Debt stops accruing and auctions stop starting once the system has been shut down.
+			if T::EmergencyShutdown::is_shutdown() {
+				return T::WeightInfo::on_initialize(0, 0)
+			}
+
+			let (iterated, updated) = Self::accumulate_interest();
+			Self::trigger_treasury_auctions();
+			T::WeightInfo::on_initialize(iterated, updated)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+
+		fn offchain_worker(now: BlockNumberFor<T>) {
+			let is_shutdown = T::EmergencyShutdown::is_shutdown();
+			for currency_id in T::CollateralCurrencyIds::get() {
+				if is_shutdown {
+					for (who, Position { debit, .. }) in
+						pallet_loans::Positions::<T>::iter_prefix(currency_id)
+					{
+						if !debit.is_zero() {
+							Self::submit_unsigned(Call::settle { currency_id, who }, now);
+						}
+					}
+					continue
+				}
+
+				// Unsafe accounts are buffered and flushed as `liquidate_batch` submissions
+				// of up to `OffchainLiquidationBatchSize` accounts, so a scan that finds
+				// many of them does not flood the transaction pool.
+				let mut pending = Vec::new();
+
+				// The risk buckets are stamped at the last position update, so a sharp price
+				// move can leave a deeply underwater CDP in a safe-looking bucket. Submit
+				// critical positions first, at the current price, regardless of their stamp.
+				let mut visited = BTreeSet::new();
+				for (who, Position { collateral, debit }) in
+					pallet_loans::Positions::<T>::iter_prefix(currency_id)
+				{
+					if Self::is_cdp_critical(currency_id, collateral, debit) {
+						Self::buffer_liquidation(currency_id, who.clone(), &mut pending, now);
+						visited.insert(who);
+					}
+				}
+				// Then visit the risk buckets from riskiest to safest, so the positions
+				// closest to insolvency are submitted even if the pass is cut short.
+				for bucket in 0..NUM_RISK_BUCKETS {
+					for who in RiskBuckets::<T>::get(currency_id, bucket) {
+						if visited.contains(&who) {
+							continue
+						}
+						let Position { collateral, debit } =
+							pallet_loans::Positions::<T>::get(currency_id, &who);
+						// Deferred or vetoed accounts are left out of the batch entirely,
+						// mirroring the skips `liquidate_batch` applies at dispatch.
+						if Self::is_cdp_eligible_for_liquidation(
+							currency_id,
+							&who,
+							collateral,
+							debit,
+						) && !Self::is_liquidation_deferred(currency_id, &who) &&
+							T::LiquidationGuard::can_liquidate(&who)
+						{
+							Self::buffer_liquidation(
+								currency_id,
+								who.clone(),
+								&mut pending,
+								now,
+							);
+						}
+						visited.insert(who);
+					}
+				}
+				// Fall back to a full scan for positions the index missed, e.g. because
+				// their bucket was full.
+				for (who, Position { collateral, debit }) in
+					pallet_loans::Positions::<T>::iter_prefix(currency_id)
+				{
+					if !visited.contains(&who) &&
+						Self::is_cdp_eligible_for_liquidation(
+							currency_id, &who, collateral, debit,
+						) && !Self::is_liquidation_deferred(currency_id, &who) &&
+						T::LiquidationGuard::can_liquidate(&who)
+					{
+						Self::buffer_liquidation(currency_id, who, &mut pending, now);
+					}
+				}
+				// Batches never span collateral currencies.
+				Self::flush_liquidations(currency_id, &mut pending, now);
+			}
+		}
+	}
+
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T> {
+		/// The liquidation status of the `currency_id` CDP of `who` at the live price.
+		///
+		/// A read-only wrapper over [`Pallet::check_cdp_status`] for UIs and keeper bots, so
+		/// they do not have to replicate the collateral ratio math.
+		pub fn cdp_status(currency_id: T::CurrencyId, who: T::AccountId) -> CDPStatus {
+			let Position { collateral, debit } =
+				pallet_loans::Positions::<T>::get(currency_id, &who);
+			Self::check_cdp_status(currency_id, collateral, debit)
+		}
+
+		/// The current collateral ratio of the `currency_id` CDP of `who`, if the position has
+		/// debit and a live price is available.
+		pub fn collateral_ratio(currency_id: T::CurrencyId, who: T::AccountId) -> Option<Ratio> {
+			let Position { collateral, debit } =
+				pallet_loans::Positions::<T>::get(currency_id, &who);
+			if debit.is_zero() {
+				return None
+			}
+			let feed_price =
+				T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())?;
+			let debit_value = Self::get_debit_value(currency_id, debit);
+			Some(Self::calculate_collateral_ratio(collateral, debit_value, feed_price))
+		}
+
+		/// The total debit units across collateral currencies whose stability fee equals
+		/// `stability_fee`, letting indexers group the open debit by accrual rate without
+		/// walking every currency's parameters themselves.
+		pub fn total_debit_by_fee(stability_fee: Option<Rate>) -> T::Balance {
+			T::CollateralCurrencyIds::get()
+				.into_iter()
+				.filter(|currency_id| Self::get_stability_fee(*currency_id) == stability_fee)
+				.fold(T::Balance::zero(), |total, currency_id| {
+					total.saturating_add(
+						pallet_loans::TotalPositions::<T>::get(currency_id).debit,
+					)
+				})
+		}
+
+		/// The cumulative interest issued to the treasury, the independently accounted growth
+		/// in open debit value, and the absolute drift between the two.
+		pub fn interest_reconciliation() -> (T::Balance, T::Balance, T::Balance) {
+			let issued = CumulativeInterestIssued::<T>::get();
+			let growth = CumulativeDebitValueGrowth::<T>::get();
+			let drift = issued.saturating_sub(growth).max(growth.saturating_sub(issued));
+			(issued, growth, drift)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Update the risk management parameters of `currency_id`.
+		///
+		/// Each parameter can be changed or kept independently; an event is emitted per
+		/// changed parameter. The resulting parameter set must keep the required collateral
+		/// ratio at or above the liquidation ratio, and the liquidation ratio at or above
+		/// one.
+		///
+		/// May only be called from `T::UpdateOrigin`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_collateral_params())]
+		pub fn set_collateral_params(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			stability_fee: Change<Option<Rate>>,
+			liquidation_ratio: Change<Option<Ratio>>,
+			liquidation_penalty: Change<Option<Rate>>,
+			required_collateral_ratio: Change<Option<Ratio>>,
+			maximum_total_debit_value: Change<T::Balance>,
+			per_account_debit_ceiling: Change<Option<T::Balance>>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
+
+			let mut params = CollateralParams::<T>::get(currency_id).unwrap_or_default();
+			if let Change::NewValue(update) = stability_fee {
+				params.stability_fee = update;
+				Self::deposit_event(Event::<T>::StabilityFeeUpdated {
+					collateral_type: currency_id,
+					new_stability_fee: update,
+				});
+			}
+			if let Change::NewValue(update) = liquidation_ratio {
+				params.liquidation_ratio = update;
+				Self::deposit_event(Event::<T>::LiquidationRatioUpdated {
+					collateral_type: currency_id,
+					new_liquidation_ratio: update,
+				});
+			}
+			if let Change::NewValue(update) = liquidation_penalty {
+				params.liquidation_penalty = update;
+				Self::deposit_event(Event::<T>::LiquidationPenaltyUpdated {
+					collateral_type: currency_id,
+					new_liquidation_penalty: update,
+				});
+			}
+			if let Change::NewValue(update) = required_collateral_ratio {
+				params.required_collateral_ratio = update;
+				Self::deposit_event(Event::<T>::RequiredCollateralRatioUpdated {
+					collateral_type: currency_id,
+					new_required_collateral_ratio: update,
+				});
+			}
+			if let Change::NewValue(update) = maximum_total_debit_value {
+				params.maximum_total_debit_value = update;
+				Self::deposit_event(Event::<T>::MaximumTotalDebitValueUpdated {
+					collateral_type: currency_id,
+					new_total_debit_value: update,
+				});
+			}
+			if let Change::NewValue(update) = per_account_debit_ceiling {
+				params.per_account_debit_ceiling = update;
+				Self::deposit_event(Event::<T>::PerAccountDebitCeilingUpdated {
+					collateral_type: currency_id,
+					new_per_account_debit_ceiling: update,
+				});
+			}
+			// A required ratio below the liquidation ratio would make positions that pass
+			// the required-ratio check immediately liquidatable, and a liquidation ratio
+			// below one would call an under-collateralized position safe.
+			if let (Some(required), Some(liquidation)) =
+				(params.required_collateral_ratio, params.liquidation_ratio)
+			{
+				ensure!(required >= liquidation, Error::<T>::InvalidRatioOrdering);
+			}
+			if let Some(liquidation) = params.liquidation_ratio {
+				ensure!(liquidation >= Ratio::one(), Error::<T>::InvalidRatioOrdering);
+			}
+			CollateralParams::<T>::insert(currency_id, params);
+			Ok(())
+		}
+
+		/// Liquidate the unsafe CDP of `who`, confiscating its collateral into the CDP
+		/// treasury and auctioning it off for the debt plus the liquidation penalty.
+		///
+		/// Unsigned; submitted by the offchain worker and validated against the live price.
+		/// The block author is credited `T::LiquidationInclusionReward` for including it.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::liquidate())]
+		pub fn liquidate(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			Self::liquidate_unsafe_cdp(who, currency_id)?;
+			Self::pay_liquidation_inclusion_reward();
+			Ok(())
+		}
+
+		/// Settle the CDP of `who` at the locked shutdown price, confiscating just enough
+		/// collateral to cover its debt.
+		///
+		/// Unsigned; submitted by the offchain worker after emergency shutdown.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::settle())]
+		pub fn settle(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			ensure!(T::EmergencyShutdown::is_shutdown(), Error::<T>::MustAfterShutdown);
+			Self::settle_cdp_has_debit(who, currency_id)
+		}
+
+		/// Settle a batch of `currency_id` CDPs at the locked shutdown price, skipping
+		/// accounts without debit.
+		///
+		/// Unsigned and permissionless; only available after emergency shutdown. Settling
+		/// one account at a time through the offchain worker's `settle` submissions is slow
+		/// for a large book, so anyone may push the wind-down along with a batch. One
+		/// `SettleCDPInDebit` event is emitted per settled account.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::settle_cdps_batch(accounts.len() as u32))]
+		pub fn settle_cdps_batch(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			accounts: BoundedVec<T::AccountId, T::MaxSettlementBatch>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			ensure!(T::EmergencyShutdown::is_shutdown(), Error::<T>::MustAfterShutdown);
+
+			for who in accounts {
+				let Position { debit, .. } =
+					pallet_loans::Positions::<T>::get(currency_id, &who);
+				// Already-settled accounts are skipped rather than failing the batch, so
+				// concurrent submissions with overlapping accounts do not trip each other.
+				if debit.is_zero() {
+					continue
+				}
+				Self::settle_cdp_has_debit(who, currency_id)?;
+			}
+			Ok(())
+		}
+
+		/// Settle the whole position book in bounded steps, visiting up to `max_positions`
+		/// entries per call and resuming from a stored cursor.
+		///
+		/// Signed and permissionless; only available after emergency shutdown. Unlike
+		/// [`Pallet::settle_cdps_batch`] the caller does not need to enumerate accounts, so
+		/// the wind-down can proceed even with unhealthy offchain workers. Fees for the
+		/// unvisited part of the limit are refunded.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::settle_all(*max_positions))]
+		pub fn settle_all(
+			origin: OriginFor<T>,
+			max_positions: u32,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(T::EmergencyShutdown::is_shutdown(), Error::<T>::MustAfterShutdown);
+			ensure!(!max_positions.is_zero(), Error::<T>::ZeroLimit);
+
+			let mut iter = match SettleAllCursor::<T>::get() {
+				Some((currency_id, who)) => pallet_loans::Positions::<T>::iter_from(
+					pallet_loans::Positions::<T>::hashed_key_for(currency_id, who),
+				),
+				None => pallet_loans::Positions::<T>::iter(),
+			};
+
+			let mut visited: u32 = 0;
+			let mut count: u32 = 0;
+			let mut cursor = None;
+			while visited < max_positions {
+				let Some((currency_id, who, Position { debit, .. })) = iter.next() else {
+					cursor = None;
+					break
+				};
+				// Settling removes the visited entry, which is safe while iterating; debit-
+				// free positions are merely stepped over.
+				if !debit.is_zero() {
+					Self::settle_cdp_has_debit(who.clone(), currency_id)?;
+					count.saturating_inc();
+				}
+				visited.saturating_inc();
+				cursor = Some((currency_id, who));
+			}
+
+			// Only pause when another entry follows, so a sweep that exactly drains the book
+			// still finishes in this call.
+			let done = match cursor {
+				Some(cursor) if iter.next().is_some() => {
+					SettleAllCursor::<T>::put(cursor);
+					false
+				},
+				_ => {
+					SettleAllCursor::<T>::kill();
+					true
+				},
+			};
+			Self::deposit_event(Event::<T>::SettledBatch { count, done });
+
+			Ok(Some(T::WeightInfo::settle_all(visited)).into())
+		}
+
+		/// Settle the CDP of `who` at the locked shutdown price, crediting the caller
+		/// `T::SettlementIncentive` for pushing the wind-down along.
+		///
+		/// Signed and permissionless; only available after emergency shutdown. The incentive
+		/// is only paid when the settlement succeeds, so debit-free accounts cannot be
+		/// farmed for rewards.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::settle_signed())]
+		pub fn settle_signed(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			let settler = ensure_signed(origin)?;
+			ensure!(T::EmergencyShutdown::is_shutdown(), Error::<T>::MustAfterShutdown);
+			Self::settle_cdp_has_debit(who, currency_id)?;
+
+			let incentive = T::SettlementIncentive::get();
+			if !incentive.is_zero() {
+				// Best effort: a failing treasury issuance must not revert the settlement
+				// the incentive is paid for.
+				let _ = T::CDPTreasuryHandler::issue_debit(&settler, incentive, false);
+			}
+			Ok(())
+		}
+
+		/// Remove up to `limit` `DebitExchangeRate` entries of currencies without any
+		/// outstanding debit.
+		///
+		/// The stored rate only exists to value open debit, so once a currency's total debit
+		/// drops to zero — typically after it was retired as collateral and the last positions
+		/// closed — the entry is dead weight. Permissionless: removal is a pure cleanup and
+		/// the rate of a debt-free currency simply resets to the default.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::sweep_empty_buckets(*limit))]
+		pub fn sweep_empty_buckets(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+			ensure_signed(origin)?;
+			let mut remaining = limit;
+			for (currency_id, _) in DebitExchangeRate::<T>::iter() {
+				if remaining.is_zero() {
+					break
+				}
+				if pallet_loans::TotalPositions::<T>::get(currency_id).debit.is_zero() {
+					DebitExchangeRate::<T>::remove(currency_id);
+					remaining.saturating_dec();
+					Self::deposit_event(Event::DebitExchangeRateSwept {
+						collateral_type: currency_id,
+					});
+				}
+			}
+			Ok(())
+		}
+
+		/// Set or clear the interest reconciliation drift above which a warning event is
+		/// emitted.
+		///
+		/// The warning fires at most once per configured threshold; updating the threshold
+		/// re-arms it.
+		///
+		/// May only be called from `T::UpdateOrigin`.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::set_reconciliation_drift_threshold())]
+		pub fn set_reconciliation_drift_threshold(
+			origin: OriginFor<T>,
+			new_threshold: Option<T::Balance>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			match new_threshold {
+				Some(threshold) => ReconciliationDriftThreshold::<T>::put(threshold),
+				None => ReconciliationDriftThreshold::<T>::kill(),
+			}
+			DriftWarningEmitted::<T>::kill();
+
+			Self::deposit_event(Event::<T>::ReconciliationDriftThresholdUpdated { new_threshold });
+			Ok(())
+		}
+
+		/// Set whether the required collateral ratio is enforced on every position check.
+		///
+		/// Normally only risky operations (taking on debit or withdrawing collateral) are
+		/// held to the required ratio, while ratio-improving operations such as repayments
+		/// pass with `check_required_ratio` disabled. During heightened risk governance can
+		/// force the required ratio onto all operations regardless of the caller's argument.
+		///
+		/// May only be called from `T::UpdateOrigin`.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::set_required_ratio_always_enforced())]
+		pub fn set_required_ratio_always_enforced(
+			origin: OriginFor<T>,
+			always_enforced: bool,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			RequiredRatioAlwaysEnforced::<T>::put(always_enforced);
+			Self::deposit_event(Event::<T>::RequiredRatioEnforcementUpdated { always_enforced });
+			Ok(())
+		}
+
+		/// Liquidate a batch of unsafe `currency_id` CDPs, skipping accounts that are no
+		/// longer unsafe.
+		///
+		/// Unsigned; submitted by the offchain worker when [`Config::OffchainLiquidationBatchSize`]
+		/// is greater than one. CDPs rescued between submission and inclusion are skipped
+		/// rather than failing the batch, as are accounts whose liquidation is deferred or
+		/// vetoed — otherwise one such account would shield every other CDP batched with
+		/// it. The block author is credited the inclusion reward once per liquidated
+		/// account.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::liquidate_batch(accounts.len() as u32))]
+		pub fn liquidate_batch(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			accounts: BoundedVec<T::AccountId, T::OffchainLiquidationBatchSize>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+
+			for who in accounts {
+				let Position { collateral, debit } =
+					pallet_loans::Positions::<T>::get(currency_id, &who);
+				if !Self::is_cdp_eligible_for_liquidation(currency_id, &who, collateral, debit) ||
+					Self::is_liquidation_deferred(currency_id, &who) ||
+					!T::LiquidationGuard::can_liquidate(&who)
+				{
+					continue
+				}
+				Self::liquidate_unsafe_cdp(who, currency_id)?;
+				Self::pay_liquidation_inclusion_reward();
+			}
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::liquidate { currency_id, who } => {
+					let Position { collateral, debit } =
+						pallet_loans::Positions::<T>::get(currency_id, who);
+					if T::EmergencyShutdown::is_shutdown() ||
+						Self::is_liquidation_deferred(*currency_id, who) ||
+						!Self::is_cdp_eligible_for_liquidation(
+							*currency_id,
+							who,
+							collateral,
+							debit,
+						) {
+						return InvalidTransaction::Stale.into()
+					}
+				},
+				Call::settle { currency_id, who } => {
+					let Position { debit, .. } =
+						pallet_loans::Positions::<T>::get(currency_id, who);
+					// Settlement without a locked price would follow the live market, so
+					// refuse to validate until one exists.
+					if !T::EmergencyShutdown::is_shutdown() ||
+						debit.is_zero() || Self::get_settlement_price(*currency_id).is_none()
+					{
+						return InvalidTransaction::Stale.into()
+					}
+				},
+				Call::liquidate_batch { currency_id, accounts } => {
+					// A batch is worth including as long as it liquidates at least one CDP.
+					if T::EmergencyShutdown::is_shutdown() ||
+						!accounts.iter().any(|who| {
+							let Position { collateral, debit } =
+								pallet_loans::Positions::<T>::get(currency_id, who);
+							!Self::is_liquidation_deferred(*currency_id, who) &&
+								Self::is_cdp_eligible_for_liquidation(
+									*currency_id,
+									who,
+									collateral,
+									debit,
+								)
+						}) {
+						return InvalidTransaction::Stale.into()
+					}
+					return ValidTransaction::with_tag_prefix("CdpEngineOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides((currency_id, accounts))
+						.longevity(64)
+						.propagate(true)
+						.build()
+				},
+				Call::settle_cdps_batch { currency_id, accounts } => {
+					// A batch is worth including as long as it settles at least one account.
+					if !T::EmergencyShutdown::is_shutdown() ||
+						Self::get_settlement_price(*currency_id).is_none() ||
+						!accounts.iter().any(|who| {
+							!pallet_loans::Positions::<T>::get(currency_id, who).debit.is_zero()
+						}) {
+						return InvalidTransaction::Stale.into()
+					}
+					return ValidTransaction::with_tag_prefix("CdpEngineOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides((currency_id, accounts))
+						.longevity(64)
+						.propagate(true)
+						.build()
+				},
+				_ => return InvalidTransaction::Call.into(),
+			}
+
+			let (currency_id, who) = match call {
+				Call::liquidate { currency_id, who } | Call::settle { currency_id, who } =>
+					(currency_id, who),
+				_ => return InvalidTransaction::Call.into(),
+			};
+			ValidTransaction::with_tag_prefix("CdpEngineOffchainWorker")
+				.priority(T::UnsignedPriority::get())
+				.and_provides((currency_id, who))
+				.longevity(64)
+				.propagate(true)
+				.build()
+		}
+	}
+}
+
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Config> Pallet<T> {
+	/// Ensure the correctness of the state of this pallet.
+	pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+		let (_, _, drift) = Self::interest_reconciliation();
+		ensure!(
+			drift <= InterestAccrualEvents::<T>::get(),
+			"interest reconciliation drift exceeds the per-event rounding tolerance"
+		);
+		Ok(())
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The debit exchange rate of `currency_id`: the stored one, or the default.
+	pub fn get_debit_exchange_rate(currency_id: T::CurrencyId) -> ExchangeRate {
+		DebitExchangeRate::<T>::get(currency_id)
+			.unwrap_or_else(T::DefaultDebitExchangeRate::get)
+	}
+
+	/// The liquidation ratio of `currency_id`: the bespoke one, or the default.
+	pub fn get_liquidation_ratio(currency_id: T::CurrencyId) -> Ratio {
+		CollateralParams::<T>::get(currency_id)
+			.and_then(|params| params.liquidation_ratio)
+			.unwrap_or_else(T::DefaultLiquidationRatio::get)
+	}
+
+	/// The liquidation penalty of `currency_id`: the bespoke one, or the default.
+	pub fn get_liquidation_penalty(currency_id: T::CurrencyId) -> Rate {
+		CollateralParams::<T>::get(currency_id)
+			.and_then(|params| params.liquidation_penalty)
+			.unwrap_or_else(T::DefaultLiquidationPenalty::get)
+	}
+
+	/// The required collateral ratio of `currency_id`, if one is set.
+	pub fn get_required_collateral_ratio(currency_id: T::CurrencyId) -> Option<Ratio> {
+		CollateralParams::<T>::get(currency_id)
+			.and_then(|params| params.required_collateral_ratio)
+	}
+
+	/// The stability fee of `currency_id`, if one is set.
+	pub fn get_stability_fee(currency_id: T::CurrencyId) -> Option<Rate> {
+		CollateralParams::<T>::get(currency_id).and_then(|params| params.stability_fee)
+	}
+
+	/// Compound the stability fee of every collateral currency into its debit exchange rate,
+	/// issuing the accrued interest to the treasury as surplus.
+	///
+	/// Returns the number of collaterals visited and the number whose stored rate was
+	/// actually updated, so `on_initialize` can charge reads and writes separately: only
+	/// collaterals with a nonzero stability fee cost a write.
+	pub(crate) fn accumulate_interest() -> (u32, u32) {
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
+		let iterated = collateral_currency_ids.len() as u32;
+		let mut updated = 0u32;
+		for currency_id in collateral_currency_ids {
+			if let Some(stability_fee) = Self::get_stability_fee(currency_id) {
+				if !stability_fee.is_zero() {
+					let old_rate = Self::get_debit_exchange_rate(currency_id);
+					let rate =
+						old_rate.saturating_mul(ExchangeRate::one().saturating_add(stability_fee));
+					DebitExchangeRate::<T>::insert(currency_id, rate);
+					updated.saturating_inc();
+					Self::settle_accrued_interest(currency_id, old_rate, rate);
+				}
+			}
+		}
+		(iterated, updated)
+	}
+
+	/// Issue the interest revenue of one debit exchange rate increment to the treasury and
+	/// track both sides of the accrual for reconciliation.
+	///
+	/// The issued amount is derived from the rate increment, the accounted growth from the
+	/// difference of the debit valuations before and after. Each side may round down
+	/// independently by less than one unit, so the cumulative counters may drift apart by at
+	/// most one unit per accrual event; any drift beyond that tolerance means the accrual
+	/// created unbacked stable currency or lost revenue.
+	fn settle_accrued_interest(
+		currency_id: T::CurrencyId,
+		old_rate: ExchangeRate,
+		new_rate: ExchangeRate,
+	) {
+		let total_debit = pallet_loans::TotalPositions::<T>::get(currency_id).debit;
+		if total_debit.is_zero() {
+			return
+		}
+
+		let issued = new_rate.saturating_sub(old_rate).saturating_mul_int(total_debit);
+		let growth = new_rate
+			.saturating_mul_int(total_debit)
+			.saturating_sub(old_rate.saturating_mul_int(total_debit));
+
+		if !issued.is_zero() {
+			// Backed by the increased debt the open positions now owe.
+			let _ = T::CDPTreasuryHandler::on_system_surplus(issued);
+		}
+		CumulativeInterestIssued::<T>::mutate(|value| *value = value.saturating_add(issued));
+		CumulativeDebitValueGrowth::<T>::mutate(|value| *value = value.saturating_add(growth));
+		InterestAccrualEvents::<T>::mutate(|events| *events = events.saturating_add(One::one()));
+		Self::check_reconciliation_drift();
+	}
+
+	/// Emit a single warning event once the reconciliation drift passes the governance-set
+	/// threshold.
+	fn check_reconciliation_drift() {
+		let Some(threshold) = ReconciliationDriftThreshold::<T>::get() else { return };
+		if DriftWarningEmitted::<T>::get() {
+			return
+		}
+		let (issued, growth, drift) = Self::interest_reconciliation();
+		if drift > threshold {
+			DriftWarningEmitted::<T>::put(true);
+			Self::deposit_event(Event::<T>::InterestReconciliationDriftWarning {
+				issued,
+				growth,
+				drift,
+			});
+		}
+	}
+
+	/// The hard cap on the total debit value of `currency_id` CDPs. Zero until set.
+	pub fn get_maximum_total_debit_value(currency_id: T::CurrencyId) -> T::Balance {
+		CollateralParams::<T>::get(currency_id)
+			.map(|params| params.maximum_total_debit_value)
+			.unwrap_or_default()
+	}
+
+	/// The largest debit value a single `currency_id` CDP may have, if a ceiling is set.
+	pub fn get_per_account_debit_ceiling(currency_id: T::CurrencyId) -> Option<T::Balance> {
+		CollateralParams::<T>::get(currency_id).and_then(|params| params.per_account_debit_ceiling)
+	}
+
+	/// The price of `currency_id` in stable currency locked at emergency shutdown, if both
+	/// locked prices exist.
+	///
+	/// This is the only price settlement may use: the live feed keeps moving after shutdown,
+	/// but all CDPs must settle at the same rate.
+	pub fn get_settlement_price(currency_id: T::CurrencyId) -> Option<Price> {
+		let collateral_price = T::PriceSource::locked_price(currency_id)?;
+		let stable_price = T::PriceSource::locked_price(T::GetStableCurrencyId::get())?;
+		collateral_price.checked_div(&stable_price)
+	}
+
+	/// The collateral ratio of a position given a `price` for its collateral.
+	pub fn calculate_collateral_ratio(
+		collateral_balance: T::Balance,
+		debit_value: T::Balance,
+		price: Price,
+	) -> Ratio {
+		let collateral_value = price.saturating_mul_int(collateral_balance);
+		Ratio::checked_from_rational(collateral_value, debit_value)
+			.unwrap_or_else(Ratio::max_value)
+	}
+
+	/// The liquidation status of a position at the live price.
+	///
+	/// A position with no debit is always [`CDPStatus::Safe`]; without a live price the
+	/// status cannot be determined and [`CDPStatus::ChecksFailed`] is returned.
+	pub fn check_cdp_status(
+		currency_id: T::CurrencyId,
+		collateral_balance: T::Balance,
+		debit_balance: T::Balance,
+	) -> CDPStatus {
+		if debit_balance.is_zero() {
+			return CDPStatus::Safe
+		}
+		match T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get()) {
+			Some(feed_price) => {
+				let debit_value = Self::get_debit_value(currency_id, debit_balance);
+				let collateral_ratio =
+					Self::calculate_collateral_ratio(collateral_balance, debit_value, feed_price);
+				if collateral_ratio < Self::get_liquidation_ratio(currency_id) {
+					CDPStatus::Unsafe
+				} else {
+					CDPStatus::Safe
+				}
+			},
+			None => CDPStatus::ChecksFailed,
+		}
+	}
+
+	/// Whether a position is below [`Config::CriticalRatioThreshold`] of the liquidation
+	/// ratio at the live price, i.e. so deeply underwater that its liquidation must not wait
+	/// for the risk bucket walk.
+	///
+	/// Returns `false` when the position has no debit or no live price is available.
+	pub fn is_cdp_critical(
+		currency_id: T::CurrencyId,
+		collateral_balance: T::Balance,
+		debit_balance: T::Balance,
+	) -> bool {
+		if debit_balance.is_zero() {
+			return false
+		}
+		let Some(feed_price) =
+			T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+		else {
+			return false
+		};
+		let debit_value = Self::get_debit_value(currency_id, debit_balance);
+		let collateral_ratio =
+			Self::calculate_collateral_ratio(collateral_balance, debit_value, feed_price);
+		let critical_ratio = T::CriticalRatioThreshold::get()
+			.saturating_mul(Self::get_liquidation_ratio(currency_id));
+		collateral_ratio < critical_ratio
+	}
+
+	/// Whether a position is below the liquidation ratio at the live price.
+	///
+	/// Returns `false` when the position has no debit or no live price is available.
+	pub fn is_cdp_unsafe(
+		currency_id: T::CurrencyId,
+		collateral_balance: T::Balance,
+		debit_balance: T::Balance,
+	) -> bool {
+		Self::unsafe_collateral_ratio(currency_id, collateral_balance, debit_balance).is_some()
+	}
+
+	/// The per-position evaluation shared by the offchain worker and the
+	/// [`liquidatable`](Self::liquidatable) scan: the collateral ratio of the position at the
+	/// live price, if it is below the liquidation ratio.
+	///
+	/// Returns `None` when the position is safe, has no debit or no live price is available.
+	pub fn unsafe_collateral_ratio(
+		currency_id: T::CurrencyId,
+		collateral_balance: T::Balance,
+		debit_balance: T::Balance,
+	) -> Option<Ratio> {
+		if debit_balance.is_zero() {
+			return None
+		}
+		let feed_price =
+			T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())?;
+		let debit_value = Self::get_debit_value(currency_id, debit_balance);
+		let collateral_ratio =
+			Self::calculate_collateral_ratio(collateral_balance, debit_value, feed_price);
+		(collateral_ratio < Self::get_liquidation_ratio(currency_id)).then_some(collateral_ratio)
+	}
+
+	/// The collateral ratio a position that dipped below the liquidation ratio must recover
+	/// past to become ineligible for liquidation again: the liquidation ratio scaled up by
+	/// [`Config::HysteresisBand`].
+	fn hysteresis_exit_ratio(currency_id: T::CurrencyId) -> Ratio {
+		Self::get_liquidation_ratio(currency_id)
+			.saturating_mul(Ratio::one().saturating_add(T::HysteresisBand::get()))
+	}
+
+	/// Whether liquidating this position must wait for a later block, because its owner
+	/// adjusted it in the current block or the collateral price is younger than
+	/// [`Config::MinBlocksSinceLastOracleUpdate`].
+	fn is_liquidation_deferred(currency_id: T::CurrencyId, who: &T::AccountId) -> bool {
+		pallet_loans::LastAdjustment::<T>::get(currency_id, who)
+			.map_or(false, |at| at >= frame_system::Pallet::<T>::block_number()) ||
+			T::PriceSource::price_age(currency_id)
+				.map_or(false, |age| age < T::MinBlocksSinceLastOracleUpdate::get())
+	}
+
+	/// Whether a position may be liquidated at the live price, accounting for hysteresis: a
+	/// position below the liquidation ratio is always eligible, and one carrying a
+	/// [`BelowLiquidationSince`] marker stays eligible until its ratio reaches the
+	/// [exit ratio](Self::hysteresis_exit_ratio).
+	///
+	/// Read-only; used by the offchain worker and the unsigned validation, which must not
+	/// write the marker. [`Self::liquidate_unsafe_cdp`] maintains it on-chain.
+	///
+	/// Returns `false` when the position has no debit or no live price is available.
+	pub fn is_cdp_eligible_for_liquidation(
+		currency_id: T::CurrencyId,
+		who: &T::AccountId,
+		collateral_balance: T::Balance,
+		debit_balance: T::Balance,
+	) -> bool {
+		if debit_balance.is_zero() {
+			return false
+		}
+		let Some(feed_price) =
+			T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+		else {
+			return false
+		};
+		let debit_value = Self::get_debit_value(currency_id, debit_balance);
+		let collateral_ratio =
+			Self::calculate_collateral_ratio(collateral_balance, debit_value, feed_price);
+		collateral_ratio < Self::get_liquidation_ratio(currency_id) ||
+			(collateral_ratio < Self::hysteresis_exit_ratio(currency_id) &&
+				BelowLiquidationSince::<T>::contains_key(currency_id, who))
+	}
+
+	/// Bring the [`BelowLiquidationSince`] marker of a position in line with its collateral
+	/// ratio at the live price and return whether the position is eligible for liquidation.
+	///
+	/// The marker is set when the ratio is below the liquidation ratio and cleared once the
+	/// position closes or its ratio reaches the [exit ratio](Self::hysteresis_exit_ratio);
+	/// inside the band the eligibility sticks to whichever boundary the position last
+	/// crossed. Without a live price the marker is left untouched and the position counts
+	/// as ineligible, matching [`Self::is_cdp_unsafe`].
+	fn refresh_liquidation_eligibility(
+		currency_id: T::CurrencyId,
+		who: &T::AccountId,
+		collateral_balance: T::Balance,
+		debit_balance: T::Balance,
+	) -> bool {
+		if debit_balance.is_zero() {
+			BelowLiquidationSince::<T>::remove(currency_id, who);
+			return false
+		}
+		let Some(feed_price) =
+			T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+		else {
+			return false
+		};
+		let debit_value = Self::get_debit_value(currency_id, debit_balance);
+		let collateral_ratio =
+			Self::calculate_collateral_ratio(collateral_balance, debit_value, feed_price);
+		if collateral_ratio < Self::get_liquidation_ratio(currency_id) {
+			if !BelowLiquidationSince::<T>::contains_key(currency_id, who) {
+				BelowLiquidationSince::<T>::insert(
+					currency_id,
+					who,
+					frame_system::Pallet::<T>::block_number(),
+				);
+			}
+			true
+		} else if collateral_ratio >= Self::hysteresis_exit_ratio(currency_id) {
+			BelowLiquidationSince::<T>::remove(currency_id, who);
+			false
+		} else {
+			BelowLiquidationSince::<T>::contains_key(currency_id, who)
+		}
+	}
+
+	/// Up to `max` liquidatable CDPs, scanning [`pallet_loans::Positions`] from the opaque
+	/// `start_key` cursor (`None` starts from the beginning). Returns the unsafe positions
+	/// with their collateral ratio and the cursor to continue from; a `None` cursor means the
+	/// scan is complete.
+	///
+	/// Exposed to keepers through the `pallet-cdp-engine-runtime-api` crate, so they do not
+	/// have to replicate the offchain worker's scan over archive state.
+	pub fn liquidatable(
+		max: u32,
+		start_key: Option<Vec<u8>>,
+	) -> (Vec<(T::CurrencyId, T::AccountId, Ratio)>, Option<Vec<u8>>) {
+		if max == 0 {
+			return (Vec::new(), start_key)
+		}
+		let mut iter = match start_key {
+			Some(key) => pallet_loans::Positions::<T>::iter_from(key),
+			None => pallet_loans::Positions::<T>::iter(),
+		};
+		let mut unsafe_cdps = Vec::new();
+		loop {
+			let Some((currency_id, who, Position { collateral, debit })) = iter.next() else {
+				return (unsafe_cdps, None)
+			};
+			if let Some(ratio) = Self::unsafe_collateral_ratio(currency_id, collateral, debit) {
+				unsafe_cdps.push((currency_id, who, ratio));
+				if unsafe_cdps.len() as u32 >= max {
+					return (unsafe_cdps, Some(iter.last_raw_key().to_vec()))
+				}
+			}
+		}
+	}
+
+	/// Start debt or surplus auctions if the treasury pools have drifted past the configured
+	/// thresholds, net of the amounts already covered by in-flight auctions.
+	fn trigger_treasury_auctions() {
+		let debit_pool = T::CDPTreasuryHandler::get_debit_pool();
+		let surplus_pool = T::CDPTreasuryHandler::get_surplus_pool();
+
+		let shortfall = debit_pool
+			.saturating_sub(surplus_pool)
+			.saturating_sub(DebtAuctionsInFlight::<T>::get());
+		if !shortfall.is_zero() &&
+			shortfall >= T::DebtAuctionThreshold::get() &&
+			T::CDPTreasuryHandler::create_debt_auction(shortfall).is_ok()
+		{
+			DebtAuctionsInFlight::<T>::mutate(|in_flight| {
+				*in_flight = in_flight.saturating_add(shortfall)
+			});
+			Self::deposit_event(Event::<T>::DebtAuctionTriggered { amount: shortfall });
+		}
+
+		let excess = surplus_pool
+			.saturating_sub(debit_pool)
+			.saturating_sub(T::SurplusBufferSize::get())
+			.saturating_sub(SurplusAuctionsInFlight::<T>::get());
+		if !excess.is_zero() && T::CDPTreasuryHandler::create_surplus_auction(excess).is_ok() {
+			SurplusAuctionsInFlight::<T>::mutate(|in_flight| {
+				*in_flight = in_flight.saturating_add(excess)
+			});
+			Self::deposit_event(Event::<T>::SurplusAuctionTriggered { amount: excess });
+		}
+	}
+
+	/// Submit `call` as an unsigned transaction from the offchain worker, logging failures.
+	fn submit_unsigned(call: Call<T>, now: BlockNumberFor<T>) {
+		let xt = T::create_bare(call.into());
+		if SubmitTransaction::<T, Call<T>>::submit_transaction(xt).is_err() {
+			log::info!(
+				target: LOG_TARGET,
+				"offchain worker: failed to submit unsigned tx at {:?}",
+				now,
+			);
+		}
+	}
+
+	/// Buffer an unsafe CDP discovered by the offchain worker, flushing a full batch.
+	///
+	/// With a batch size of one the account is submitted as a plain `liquidate` call
+	/// immediately instead.
+	fn buffer_liquidation(
+		currency_id: T::CurrencyId,
+		who: T::AccountId,
+		pending: &mut Vec<T::AccountId>,
+		now: BlockNumberFor<T>,
+	) {
+		if T::OffchainLiquidationBatchSize::get() <= 1 {
+			Self::submit_unsigned(Call::liquidate { currency_id, who }, now);
+			return
+		}
+		pending.push(who);
+		if pending.len() as u32 >= T::OffchainLiquidationBatchSize::get() {
+			Self::flush_liquidations(currency_id, pending, now);
+		}
+	}
+
+	/// Submit the buffered unsafe CDPs of `currency_id` as one `liquidate_batch` call.
+	fn flush_liquidations(
+		currency_id: T::CurrencyId,
+		pending: &mut Vec<T::AccountId>,
+		now: BlockNumberFor<T>,
+	) {
+		match pending.len() {
+			0 => {},
+			// A left-over batch of one is cheaper to include as a plain `liquidate`.
+			1 => Self::submit_unsigned(
+				Call::liquidate { currency_id, who: pending.remove(0) },
+				now,
+			),
+			_ => Self::submit_unsigned(
+				Call::liquidate_batch {
+					currency_id,
+					accounts: BoundedVec::truncate_from(core::mem::take(pending)),
+				},
+				now,
+			),
+		}
+	}
+
+	/// The risk bucket of a position at the current price: 0 (riskiest) holds positions below
+	/// or within a quarter of the liquidation ratio, each further bucket widens the band by
+	/// another quarter, and the last collects everything safer.
+	///
+	/// Positions without a live price cannot be shown safe, so they land in bucket 0.
+	pub fn risk_bucket(
+		currency_id: T::CurrencyId,
+		collateral_balance: T::Balance,
+		debit_balance: T::Balance,
+	) -> u8 {
+		let Some(feed_price) =
+			T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+		else {
+			return 0
+		};
+		let debit_value = Self::get_debit_value(currency_id, debit_balance);
+		let collateral_ratio =
+			Self::calculate_collateral_ratio(collateral_balance, debit_value, feed_price);
+		let relative = collateral_ratio
+			.checked_div(&Self::get_liquidation_ratio(currency_id))
+			.unwrap_or_else(Ratio::max_value);
+		let quarters =
+			relative.saturating_sub(Ratio::one()).saturating_mul_int(4u32);
+		quarters.min(u32::from(NUM_RISK_BUCKETS - 1)) as u8
+	}
+
+	/// Move the position of `who` to the risk bucket matching its new values, or drop it from
+	/// the index when the position is closed.
+	fn update_risk_bucket(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		collateral: T::Balance,
+		debit: T::Balance,
+	) {
+		if let Some(old_bucket) = RiskBucketOf::<T>::take(currency_id, who) {
+			RiskBuckets::<T>::mutate(currency_id, old_bucket, |accounts| {
+				if let Some(index) = accounts.iter().position(|account| account == who) {
+					accounts.swap_remove(index);
+				}
+			});
+		}
+		if debit.is_zero() {
+			return
+		}
+		let bucket = Self::risk_bucket(currency_id, collateral, debit);
+		// Best effort: a position that does not fit its bucket is left to the offchain
+		// worker's full scan.
+		RiskBuckets::<T>::mutate(currency_id, bucket, |accounts| {
+			if accounts.try_push(who.clone()).is_ok() {
+				RiskBucketOf::<T>::insert(currency_id, who, bucket);
+			}
+		});
+	}
+
+	/// Credit the block author the liquidation inclusion reward, if one is configured.
+	///
+	/// Best effort: a missing author or a failing treasury issuance must not revert the
+	/// liquidation the reward is paid for.
+	fn pay_liquidation_inclusion_reward() {
+		let reward = T::LiquidationInclusionReward::get();
+		if reward.is_zero() {
+			return
+		}
+		let digest = frame_system::Pallet::<T>::digest();
+		let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+		if let Some(author) = T::FindAuthor::find_author(pre_runtime_digests) {
+			let _ = T::CDPTreasuryHandler::issue_debit(&author, reward, false);
+		}
+	}
+
+	/// Note that debt auctions covering `amount` have concluded or been cancelled.
+	///
+	/// Called by the auction manager, so any remaining shortfall becomes eligible for auction
+	/// again.
+	pub fn on_debt_auction_concluded(amount: T::Balance) {
+		DebtAuctionsInFlight::<T>::mutate(|in_flight| *in_flight = in_flight.saturating_sub(amount));
+	}
+
+	/// Note that surplus auctions offering `amount` have concluded or been cancelled.
+	pub fn on_surplus_auction_concluded(amount: T::Balance) {
+		SurplusAuctionsInFlight::<T>::mutate(|in_flight| {
+			*in_flight = in_flight.saturating_sub(amount)
+		});
+	}
+
+	/// Liquidate the CDP of `who`: confiscate the position into the CDP treasury and start a
+	/// collateral auction targeting the debt plus the liquidation penalty.
+	///
+	/// A position whose debit value exceeds [`Config::MaxLiquidationValue`] is only
+	/// confiscated up to that value, collateral and debit shrinking proportionally; the
+	/// remainder keeps the same collateral ratio, stays unsafe and is picked up by later
+	/// liquidation calls.
+	///
+	/// The auction manager may defer the auction while the collateral cannot be priced; the
+	/// liquidation is complete either way, with the confiscated collateral sitting in the
+	/// treasury account until the auction starts.
+	///
+	/// Eligibility is subject to the [`Config::HysteresisBand`]: a position whose ratio has
+	/// recovered above the liquidation ratio but not yet past the band remains liquidatable,
+	/// so oracle jitter at the boundary cannot flap it in and out of the unsafe set.
+	pub fn liquidate_unsafe_cdp(who: T::AccountId, currency_id: T::CurrencyId) -> DispatchResult {
+		let Position { collateral, debit } = pallet_loans::Positions::<T>::get(currency_id, &who);
+		ensure!(
+			Self::refresh_liquidation_eligibility(currency_id, &who, collateral, debit),
+			Error::<T>::MustBeUnsafe
+		);
+		ensure!(T::LiquidationGuard::can_liquidate(&who), Error::<T>::LiquidationVetoed);
+		// Defense in depth against atomic oracle manipulation: an owner active in this very
+		// block is no abandoned position, and a price published moments ago may still be
+		// the product of a same-block market push.
+		ensure!(
+			pallet_loans::LastAdjustment::<T>::get(currency_id, &who)
+				.map_or(true, |at| at < frame_system::Pallet::<T>::block_number()),
+			Error::<T>::AdjustedInCurrentBlock
+		);
+		ensure!(
+			T::PriceSource::price_age(currency_id)
+				.map_or(true, |age| age >= T::MinBlocksSinceLastOracleUpdate::get()),
+			Error::<T>::PriceTooRecent
+		);
+
+		let mut bad_debt_value = Self::get_debit_value(currency_id, debit);
+		let max_value = T::MaxLiquidationValue::get();
+		let (collateral, debit) = if !max_value.is_zero() && bad_debt_value > max_value {
+			// Rounding down keeps the confiscated value within the cap; the dust error it
+			// leaves on the position is cleared by the final, uncapped chunk.
+			let fraction = Ratio::checked_from_rational(max_value, bad_debt_value)
+				.unwrap_or_else(Ratio::one);
+			let collateral = fraction.saturating_mul_int(collateral);
+			let debit = fraction.saturating_mul_int(debit);
+			bad_debt_value = Self::get_debit_value(currency_id, debit);
+			(collateral, debit)
+		} else {
+			(collateral, debit)
+		};
+		let target_amount = bad_debt_value
+			.saturating_add(Self::get_liquidation_penalty(currency_id).saturating_mul_int(bad_debt_value));
+
+		pallet_loans::Pallet::<T>::confiscate_collateral_and_debit(
+			&who, currency_id, collateral, debit,
+		)?;
+		T::AuctionManagerHandler::new_collateral_auction(
+			&who,
+			currency_id,
+			collateral,
+			target_amount,
+		)?;
+		T::OnLiquidation::on_liquidation(&who, currency_id, collateral, debit);
+
+		Self::deposit_event(Event::<T>::LiquidateUnsafeCDP {
+			collateral_type: currency_id,
+			owner: who,
+			collateral_amount: collateral,
+			bad_debt_value,
+			target_amount,
+		});
+		Ok(())
+	}
+
+	/// Settle the CDP of `who` after emergency shutdown: confiscate collateral worth the
+	/// position's debt, valued at the locked shutdown price.
+	pub fn settle_cdp_has_debit(who: T::AccountId, currency_id: T::CurrencyId) -> DispatchResult {
+		let Position { collateral, debit } = pallet_loans::Positions::<T>::get(currency_id, &who);
+		ensure!(!debit.is_zero(), Error::<T>::NoDebitValue);
+
+		// Never fall back to the live price here: every CDP must settle at the rate locked
+		// when shutdown was triggered.
+		let settlement_price =
+			Self::get_settlement_price(currency_id).ok_or(Error::<T>::NoLockedPrice)?;
+		let bad_debt_value = Self::get_debit_value(currency_id, debit);
+		let confiscate_collateral = collateral.min(
+			settlement_price
+				.reciprocal()
+				.ok_or(Error::<T>::InvalidFeedPrice)?
+				.saturating_mul_int(bad_debt_value),
+		);
+
+		pallet_loans::Pallet::<T>::confiscate_collateral_and_debit(
+			&who,
+			currency_id,
+			confiscate_collateral,
+			debit,
+		)?;
+		T::OnLiquidation::on_settlement(&who, currency_id, confiscate_collateral, debit);
+
+		Self::deposit_event(Event::<T>::SettleCDPInDebit {
+			collateral_type: currency_id,
+			owner: who,
+		});
+
+		let settled = SettledDebitTotal::<T>::mutate(|total| {
+			*total = total.saturating_add(bad_debt_value);
+			*total
+		});
+		let count = SettledCdpCount::<T>::mutate(|count| {
+			count.saturating_inc();
+			*count
+		});
+		let remaining = T::CollateralCurrencyIds::get().into_iter().fold(
+			T::Balance::zero(),
+			|acc, currency_id| {
+				acc.saturating_add(Self::get_debit_value(
+					currency_id,
+					pallet_loans::TotalPositions::<T>::get(currency_id).debit,
+				))
+			},
+		);
+		let interval = T::SettlementProgressInterval::get();
+		if !interval.is_zero() && count.is_multiple_of(interval) {
+			Self::deposit_event(Event::<T>::SettlementProgress { settled, remaining });
+		}
+		if remaining.is_zero() {
+			Self::deposit_event(Event::<T>::SettlementComplete { settled });
+		}
+		Ok(())
+	}
+
+	/// Repay up to `repay_value` of the debt of `owner`'s CDP with stable currency burned
+	/// from `payer`, without touching the collateral.
+	///
+	/// Lets a third party — e.g. a liquidation-protection service — top up a position it
+	/// does not own. The repayment can only improve the collateral ratio, but unless the
+	/// position is closed entirely its remaining debt must stay above the minimum.
+	pub fn repay_debit_on_behalf(
+		payer: &T::AccountId,
+		owner: &T::AccountId,
+		currency_id: T::CurrencyId,
+		repay_value: T::Balance,
+	) -> DispatchResult {
+		with_storage_layer(|| {
+			Self::do_repay_debit_on_behalf(payer, owner, currency_id, repay_value)
+		})
+	}
+
+	fn do_repay_debit_on_behalf(
+		payer: &T::AccountId,
+		owner: &T::AccountId,
+		currency_id: T::CurrencyId,
+		repay_value: T::Balance,
+	) -> DispatchResult {
+		ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+
+		let repaid_value =
+			Self::decrease_position_debit_by_value(owner, currency_id, repay_value)?;
+		T::CDPTreasuryHandler::burn_debit(payer, repaid_value)?;
+
+		Self::deposit_event(Event::<T>::DebitRepaidOnBehalf {
+			collateral_type: currency_id,
+			owner: owner.clone(),
+			payer: payer.clone(),
+			repaid_value,
+		});
+		Ok(())
+	}
+
+	/// Adjust the position of `who` by a collateral delta and a debit delta expressed in
+	/// stable currency value ("draw/repay X stable currency") rather than debit units.
+	///
+	/// A positive `debit_value_adjustment` draws new debit worth that value; a negative one
+	/// repays, clamped at the position's outstanding debit so overpaying just closes the
+	/// debt. The adjustment is validated like any other position update.
+	pub fn adjust_position_by_debit_value(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		collateral_adjustment: T::Amount,
+		debit_value_adjustment: T::Amount,
+	) -> DispatchResult {
+		let debit_value: T::Balance = debit_value_adjustment
+			.abs()
+			.try_into()
+			.map_err(|_| ArithmeticError::Overflow)?;
+
+		let debit_units = if debit_value_adjustment.is_negative() {
+			let Position { debit, .. } = pallet_loans::Positions::<T>::get(currency_id, who);
+			Self::debit_units_for_decrease_value(currency_id, debit, debit_value)
+		} else {
+			Self::get_debit_exchange_rate(currency_id)
+				.reciprocal()
+				.map(|rate| rate.saturating_mul_int(debit_value))
+				.unwrap_or_default()
+		};
+
+		let mut debit_adjustment: T::Amount =
+			debit_units.try_into().map_err(|_| ArithmeticError::Overflow)?;
+		if debit_value_adjustment.is_negative() {
+			debit_adjustment = -debit_adjustment;
+		}
+
+		pallet_loans::Pallet::<T>::adjust_position(
+			who,
+			currency_id,
+			collateral_adjustment,
+			debit_adjustment,
+		)
+	}
+
+	/// The debit units removed by repaying `decrease_value` of stable currency from a
+	/// position holding `debit` units.
+	///
+	/// Repaying the whole debt removes all debit units outright, so rounding the value
+	/// down to units can never leave unpayable dust behind.
+	fn debit_units_for_decrease_value(
+		currency_id: T::CurrencyId,
+		debit: T::Balance,
+		decrease_value: T::Balance,
+	) -> T::Balance {
+		if decrease_value >= Self::get_debit_value(currency_id, debit) {
+			debit
+		} else {
+			Self::get_debit_exchange_rate(currency_id)
+				.reciprocal()
+				.map(|rate| rate.saturating_mul_int(decrease_value))
+				.unwrap_or_default()
+		}
+	}
+
+	/// Reduce the debit of `owner`'s CDP by up to `decrease_value` of stable currency value
+	/// and validate the resulting position. Returns the value actually removed, which the
+	/// caller must settle against the debit pool.
+	fn decrease_position_debit_by_value(
+		owner: &T::AccountId,
+		currency_id: T::CurrencyId,
+		decrease_value: T::Balance,
+	) -> Result<T::Balance, DispatchError> {
+		let Position { debit, .. } = pallet_loans::Positions::<T>::get(currency_id, owner);
+		ensure!(!debit.is_zero(), Error::<T>::NoDebitValue);
+
+		let debit_decrease =
+			Self::debit_units_for_decrease_value(currency_id, debit, decrease_value);
+
+		let debit_adjustment: T::Amount =
+			debit_decrease.try_into().map_err(|_| ArithmeticError::Overflow)?;
+		pallet_loans::Pallet::<T>::update_loan(
+			owner,
+			currency_id,
+			Default::default(),
+			-debit_adjustment,
+		)?;
+
+		let Position { collateral, debit } = pallet_loans::Positions::<T>::get(currency_id, owner);
+		// A repayment can only improve the collateral ratio, so the required ratio is not
+		// demanded here (unless governance enforces it globally).
+		Self::check_position_valid(currency_id, collateral, debit, false)?;
+
+		Ok(Self::get_debit_value(currency_id, debit_decrease))
+	}
+}
+
+impl<T: Config> SettlementCounter for Pallet<T> {
+	fn settlements_since_shutdown() -> u32 {
+		SettledCdpCount::<T>::get()
+	}
+}
+
+impl<T: Config> OnUpdateLoan<T::AccountId, T::CurrencyId, T::Balance> for Pallet<T> {
+	fn on_update_loan(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		collateral: T::Balance,
+		debit: T::Balance,
+	) {
+		Self::update_risk_bucket(who, currency_id, collateral, debit);
+		let _ = Self::refresh_liquidation_eligibility(currency_id, who, collateral, debit);
+	}
+}
+
+impl<T: Config> RiskManager<T::AccountId, T::CurrencyId, T::Balance> for Pallet<T> {
+	fn get_debit_value(currency_id: T::CurrencyId, debit_balance: T::Balance) -> T::Balance {
+		Self::get_debit_exchange_rate(currency_id).saturating_mul_int(debit_balance)
+	}
+
+	fn check_position_valid(
+		currency_id: T::CurrencyId,
+		collateral_balance: T::Balance,
+		debit_balance: T::Balance,
+		check_required_ratio: bool,
+	) -> DispatchResult {
+		if !debit_balance.is_zero() {
+			let debit_value = Self::get_debit_value(currency_id, debit_balance);
+			ensure!(
+				debit_value >= T::MinimumDebitValue::get(),
+				Error::<T>::RemainDebitValueTooSmall,
+			);
+			if let Some(ceiling) = Self::get_per_account_debit_ceiling(currency_id) {
+				ensure!(debit_value <= ceiling, Error::<T>::ExceedAccountDebitCeiling);
+			}
+
+			let feed_price = T::PriceSource::get_relative_price(
+				currency_id,
+				T::GetStableCurrencyId::get(),
+			)
+			.ok_or(Error::<T>::InvalidFeedPrice)?;
+			let collateral_ratio =
+				Self::calculate_collateral_ratio(collateral_balance, debit_value, feed_price);
+
+			// Governance can force the required ratio onto every operation during
+			// heightened risk, overriding the caller's opt-out.
+			if check_required_ratio || RequiredRatioAlwaysEnforced::<T>::get() {
+				if let Some(required_collateral_ratio) =
+					Self::get_required_collateral_ratio(currency_id)
+				{
+					ensure!(
+						collateral_ratio >= required_collateral_ratio,
+						Error::<T>::BelowRequiredCollateralRatio,
+					);
+				}
+			}
+			ensure!(
+				collateral_ratio >= Self::get_liquidation_ratio(currency_id),
+				Error::<T>::BelowLiquidationRatio,
+			);
+		}
+		Ok(())
+	}
+
+	fn check_debit_cap(currency_id: T::CurrencyId, total_debit_balance: T::Balance) -> DispatchResult {
+		ensure!(
+			Self::get_debit_value(currency_id, total_debit_balance) <=
+				Self::get_maximum_total_debit_value(currency_id),
+			Error::<T>::ExceedDebitValueHardCap,
+		);
+		Ok(())
+	}
+
+	fn minimum_collateral() -> T::Balance {
+		T::MinimumCollateralAmount::get()
+	}
+}
+
+/// Storage migrations for the CDP engine pallet.
+pub mod migrations {
+	use super::*;
+	use frame_support::traits::OnRuntimeUpgrade;
+
+	/// Backfill the [`RiskBuckets`] index from the open positions of chains that predate it.
+	///
+	/// Only needed once: positions are indexed as they change afterwards.
+	pub struct BuildRiskBucketIndex<T>(core::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for BuildRiskBucketIndex<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut count: u64 = 0;
+			for (currency_id, who, Position { collateral, debit }) in
+				pallet_loans::Positions::<T>::iter()
+			{
+				Pallet::<T>::update_risk_bucket(&who, currency_id, collateral, debit);
+				count.saturating_inc();
+			}
+			T::DbWeight::get().reads_writes(count, count.saturating_mul(3))
+		}
+	}
+
+	/// Migration to storage version 1.
+	pub mod v1 {
+		use super::*;
+
+		/// The layout of [`RiskManagementParams`] before the per-account debit ceiling was
+		/// added.
+		#[derive(Encode, Decode)]
+		pub struct OldRiskManagementParams<Balance> {
+			pub maximum_total_debit_value: Balance,
+			pub stability_fee: Option<Rate>,
+			pub liquidation_ratio: Option<Ratio>,
+			pub liquidation_penalty: Option<Rate>,
+			pub required_collateral_ratio: Option<Ratio>,
+		}
+
+		/// Re-encode all stored [`CollateralParams`] with `per_account_debit_ceiling: None`,
+		/// the new field's "unlimited" value, matching the pre-upgrade behaviour.
+		pub struct AddPerAccountDebitCeiling<T>(core::marker::PhantomData<T>);
+
+		impl<T: Config> OnRuntimeUpgrade for AddPerAccountDebitCeiling<T> {
+			fn on_runtime_upgrade() -> Weight {
+				if Pallet::<T>::on_chain_storage_version() != 0 {
+					log::info!(
+						target: LOG_TARGET,
+						"AddPerAccountDebitCeiling: storage version is not 0, skipping",
+					);
+					return T::DbWeight::get().reads(1)
+				}
+
+				let mut count: u64 = 0;
+				CollateralParams::<T>::translate::<OldRiskManagementParams<T::Balance>, _>(
+					|_, old| {
+						count.saturating_inc();
+						Some(RiskManagementParams {
+							maximum_total_debit_value: old.maximum_total_debit_value,
+							stability_fee: old.stability_fee,
+							liquidation_ratio: old.liquidation_ratio,
+							liquidation_penalty: old.liquidation_penalty,
+							required_collateral_ratio: old.required_collateral_ratio,
+							per_account_debit_ceiling: None,
+						})
+					},
+				);
+				StorageVersion::new(1).put::<Pallet<T>>();
+				T::DbWeight::get().reads_writes(count.saturating_add(1), count.saturating_add(1))
+			}
+		}
+	}
+}
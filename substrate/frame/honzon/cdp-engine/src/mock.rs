@@ -0,0 +1,462 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocks for the CDP engine pallet.
+
+use super::*;
+use crate as pallet_cdp_engine;
+
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{fungibles::Mutate, FindAuthor},
+	PalletId,
+};
+use frame_system::EnsureRoot;
+use honzon_support::{CDPTreasury, CDPTreasuryExtended, LockablePrice};
+use sp_runtime::{testing::TestXt, BuildStorage, ConsensusEngineId};
+use std::collections::BTreeMap;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type Amount = i128;
+pub type CurrencyId = u32;
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const AUTHOR: AccountId = 50;
+pub const TREASURY: AccountId = 100;
+
+pub const DOT: CurrencyId = 1;
+pub const BTC: CurrencyId = 2;
+pub const AUSD: CurrencyId = 9;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Assets: pallet_assets,
+		Loans: pallet_loans,
+		CDPEngine: pallet_cdp_engine,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+#[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type CreateOrigin =
+		frame_support::traits::AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+impl<LocalCall> frame_system::offchain::CreateTransactionBase<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type RuntimeCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateBare<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_bare(call: Self::RuntimeCall) -> Self::Extrinsic {
+		Extrinsic::new_bare(call)
+	}
+}
+
+parameter_types! {
+	pub static LivePrices: BTreeMap<CurrencyId, Price> = BTreeMap::new();
+	pub static LockedPrices: BTreeMap<CurrencyId, Price> = BTreeMap::new();
+	pub static IsShutdownFlag: bool = false;
+	pub static VetoedAccount: Option<AccountId> = None;
+	pub static MinBlocksSinceLastOracleUpdate: u32 = 0;
+	pub static PriceAge: Option<u32> = None;
+	pub static Auctions: Vec<(AccountId, CurrencyId, Balance, Balance)> = Vec::new();
+	pub static DebitPool: Balance = 0;
+	pub static DebtAuctions: Vec<Balance> = Vec::new();
+	pub static SurplusAuctions: Vec<Balance> = Vec::new();
+	pub static LiquidationInclusionReward: Balance = 0;
+	pub static SettlementIncentive: Balance = 0;
+	pub static MaxLiquidationValue: Balance = 0;
+	pub static HysteresisBand: Ratio = Ratio::zero();
+}
+
+/// Always finds `AUTHOR` as the block author.
+pub struct MockFindAuthor;
+impl FindAuthor<AccountId> for MockFindAuthor {
+	fn find_author<'a, I>(_digests: I) -> Option<AccountId>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		Some(AUTHOR)
+	}
+}
+
+/// Change the live price of `currency_id`. Locked prices are unaffected.
+pub fn set_price(currency_id: CurrencyId, price: Option<Price>) {
+	let mut prices = LivePrices::get();
+	match price {
+		Some(price) => {
+			prices.insert(currency_id, price);
+		},
+		None => {
+			prices.remove(&currency_id);
+		},
+	}
+	LivePrices::set(prices);
+}
+
+pub fn set_shutdown(is_shutdown: bool) {
+	IsShutdownFlag::set(is_shutdown);
+}
+
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_price(currency_id: CurrencyId) -> Option<Price> {
+		LivePrices::get().get(&currency_id).copied()
+	}
+
+	fn price_age(_currency_id: CurrencyId) -> Option<u32> {
+		PriceAge::get()
+	}
+}
+impl LockablePrice<CurrencyId> for MockPriceSource {
+	fn lock_price(currency_id: CurrencyId) -> DispatchResult {
+		if let Some(price) = Self::get_price(currency_id) {
+			let mut prices = LockedPrices::get();
+			prices.insert(currency_id, price);
+			LockedPrices::set(prices);
+		}
+		Ok(())
+	}
+
+	fn unlock_price(currency_id: CurrencyId) -> DispatchResult {
+		let mut prices = LockedPrices::get();
+		prices.remove(&currency_id);
+		LockedPrices::set(prices);
+		Ok(())
+	}
+}
+impl LockedPriceProvider<CurrencyId> for MockPriceSource {
+	fn locked_price(currency_id: CurrencyId) -> Option<Price> {
+		LockedPrices::get().get(&currency_id).copied()
+	}
+}
+
+pub struct MockEmergencyShutdown;
+impl EmergencyShutdown for MockEmergencyShutdown {
+	fn is_shutdown() -> bool {
+		IsShutdownFlag::get()
+	}
+}
+
+/// Vetoes the liquidation of `VetoedAccount`, allowing everyone else.
+pub struct MockLiquidationGuard;
+impl LiquidationGuard<AccountId> for MockLiquidationGuard {
+	fn can_liquidate(who: &AccountId) -> bool {
+		VetoedAccount::get() != Some(*who)
+	}
+}
+
+/// Records started auctions without running them.
+pub struct MockAuctionManager;
+impl AuctionManager<AccountId> for MockAuctionManager {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+	type AuctionId = u32;
+	type BlockNumber = u64;
+
+	fn new_collateral_auction(
+		refund_recipient: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+		target: Balance,
+	) -> DispatchResult {
+		let mut auctions = Auctions::get();
+		auctions.push((*refund_recipient, currency_id, amount, target));
+		Auctions::set(auctions);
+		Ok(())
+	}
+
+	fn new_forward_auction(
+		_currency_id: CurrencyId,
+		_amount: Balance,
+		_min_price_per_unit: Price,
+		_duration: u64,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn cancel_auction(_id: u32) -> DispatchResult {
+		Ok(())
+	}
+
+	fn get_total_collateral_in_auction(currency_id: CurrencyId) -> Balance {
+		Auctions::get()
+			.iter()
+			.filter(|(_, auction_currency, _, _)| *auction_currency == currency_id)
+			.map(|(_, _, amount, _)| amount)
+			.sum()
+	}
+
+	fn get_total_target_in_auction() -> Balance {
+		Auctions::get().iter().map(|(_, _, _, target)| target).sum()
+	}
+}
+
+/// Issues and burns the stable asset directly and parks collateral on `TREASURY`.
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		Assets::balance(AUSD, TREASURY)
+	}
+
+	fn get_debit_pool() -> Balance {
+		DebitPool::get()
+	}
+
+	fn get_total_collaterals(currency_id: CurrencyId) -> Balance {
+		Assets::balance(currency_id, TREASURY)
+	}
+
+	fn on_system_debit(amount: Balance) -> DispatchResult {
+		DebitPool::mutate(|pool| *pool += amount);
+		Ok(())
+	}
+
+	fn on_system_surplus(amount: Balance) -> DispatchResult {
+		Assets::mint_into(AUSD, &TREASURY, amount).map(|_| ())
+	}
+
+	fn issue_debit(who: &AccountId, debit: Balance, _backed: bool) -> DispatchResult {
+		Assets::mint_into(AUSD, who, debit).map(|_| ())
+	}
+
+	fn burn_debit(who: &AccountId, debit: Balance) -> DispatchResult {
+		Assets::burn_from(
+			AUSD,
+			who,
+			debit,
+			frame_support::traits::tokens::Preservation::Expendable,
+			frame_support::traits::tokens::Precision::Exact,
+			frame_support::traits::tokens::Fortitude::Polite,
+		)
+		.map(|_| ())
+	}
+
+	fn deposit_surplus(from: &AccountId, surplus: Balance) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(
+			AUSD,
+			from,
+			&TREASURY,
+			surplus,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+
+	fn deposit_collateral(
+		from: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(
+			currency_id,
+			from,
+			&TREASURY,
+			amount,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+
+	fn withdraw_collateral(
+		to: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(
+			currency_id,
+			&TREASURY,
+			to,
+			amount,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+}
+
+impl CDPTreasuryExtended<AccountId> for MockCDPTreasury {
+	fn create_debt_auction(amount: Balance) -> DispatchResult {
+		let mut auctions = DebtAuctions::get();
+		auctions.push(amount);
+		DebtAuctions::set(auctions);
+		Ok(())
+	}
+
+	fn create_surplus_auction(amount: Balance) -> DispatchResult {
+		let mut auctions = SurplusAuctions::get();
+		auctions.push(amount);
+		SurplusAuctions::set(auctions);
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const LoansPalletId: PalletId = PalletId(*b"py/loans");
+	pub const MaxPositionsPageSize: u32 = 100;
+}
+
+impl pallet_loans::Config for Test {
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+	type Amount = Amount;
+	type Currency = Assets;
+	type RiskManager = CDPEngine;
+	type CDPTreasury = MockCDPTreasury;
+	type OnUpdateLoan = CDPEngine;
+	type PalletId = LoansPalletId;
+	type MaxPositionsPageSize = MaxPositionsPageSize;
+}
+
+parameter_types! {
+	pub CollateralCurrencies: Vec<CurrencyId> = vec![DOT, BTC];
+	pub DefaultLiquidationRatio: Ratio = Ratio::saturating_from_rational(3, 2);
+	pub DefaultDebitExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(1, 2);
+	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(1, 10);
+	pub const MinimumDebitValue: Balance = 2;
+	pub const MinimumCollateralAmount: Balance = 10;
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	pub const MaxRiskBucketSize: u32 = 4;
+	// 2/3 of the default liquidation ratio of 3/2: critical below break-even.
+	pub CriticalRatioThreshold: Ratio = Ratio::saturating_from_rational(2, 3);
+	pub const DebtAuctionThreshold: Balance = 100;
+	pub const SurplusBufferSize: Balance = 200;
+	pub const MaxSettlementBatch: u32 = 10;
+	pub static SettlementProgressInterval: u32 = 1;
+	pub static OffchainLiquidationBatchSize: u32 = 1;
+	pub const CdpEngineUnsignedPriority: TransactionPriority = 1 << 20;
+}
+
+impl Config for Test {
+	type UpdateOrigin = EnsureRoot<AccountId>;
+	type CollateralCurrencyIds = CollateralCurrencies;
+	type DefaultLiquidationRatio = DefaultLiquidationRatio;
+	type DefaultDebitExchangeRate = DefaultDebitExchangeRate;
+	type DefaultLiquidationPenalty = DefaultLiquidationPenalty;
+	type MinimumDebitValue = MinimumDebitValue;
+	type MinimumCollateralAmount = MinimumCollateralAmount;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type PriceSource = MockPriceSource;
+	type EmergencyShutdown = MockEmergencyShutdown;
+	type OnLiquidation = ();
+	type LiquidationGuard = MockLiquidationGuard;
+	type MinBlocksSinceLastOracleUpdate = MinBlocksSinceLastOracleUpdate;
+	type AuctionManagerHandler = MockAuctionManager;
+	type CDPTreasuryHandler = MockCDPTreasury;
+	type LiquidationInclusionReward = LiquidationInclusionReward;
+	type SettlementIncentive = SettlementIncentive;
+	type MaxRiskBucketSize = MaxRiskBucketSize;
+	type CriticalRatioThreshold = CriticalRatioThreshold;
+	type HysteresisBand = HysteresisBand;
+	type MaxLiquidationValue = MaxLiquidationValue;
+	type FindAuthor = MockFindAuthor;
+	type DebtAuctionThreshold = DebtAuctionThreshold;
+	type SurplusBufferSize = SurplusBufferSize;
+	type MaxSettlementBatch = MaxSettlementBatch;
+	type SettlementProgressInterval = SettlementProgressInterval;
+	type OffchainLiquidationBatchSize = OffchainLiquidationBatchSize;
+	type UnsignedPriority = CdpEngineUnsignedPriority;
+	type WeightInfo = ();
+}
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		LivePrices::set(BTreeMap::from([
+			(DOT, Price::one()),
+			(BTC, Price::saturating_from_integer(2)),
+			(AUSD, Price::one()),
+		]));
+		LockedPrices::set(BTreeMap::new());
+		IsShutdownFlag::set(false);
+		SettlementProgressInterval::set(1);
+		Auctions::set(Vec::new());
+		DebitPool::set(0);
+		DebtAuctions::set(Vec::new());
+		SurplusAuctions::set(Vec::new());
+		LiquidationInclusionReward::set(0);
+		SettlementIncentive::set(0);
+		MaxLiquidationValue::set(0);
+		HysteresisBand::set(Ratio::zero());
+		VetoedAccount::set(None);
+		MinBlocksSinceLastOracleUpdate::set(0);
+		PriceAge::set(None);
+		OffchainLiquidationBatchSize::set(1);
+
+		let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			for currency_id in [DOT, BTC, AUSD] {
+				assert_eq!(
+					Assets::force_create(RuntimeOrigin::root(), currency_id, TREASURY, true, 1),
+					Ok(())
+				);
+			}
+			for (who, currency_id, balance) in
+				[
+					(ALICE, DOT, 1000),
+					(ALICE, BTC, 1000),
+					(BOB, DOT, 1000),
+					(BOB, BTC, 1000),
+					(CAROL, DOT, 1000),
+				]
+			{
+				assert_eq!(Assets::mint_into(currency_id, &who, balance), Ok(balance));
+			}
+		});
+		ext
+	}
+}
@@ -0,0 +1,1749 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the CDP engine pallet.
+
+use super::*;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OffchainWorker, OnInitialize, OnRuntimeUpgrade},
+};
+use honzon_support::LockablePrice;
+use mock::*;
+use sp_core::offchain::{testing, OffchainWorkerExt, TransactionPoolExt};
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource};
+
+/// Allow `currency_id` CDPs to accrue debit by raising the hard cap from its zero default.
+fn setup_collateral(currency_id: CurrencyId) {
+	assert_ok!(CDPEngine::set_collateral_params(
+		RuntimeOrigin::root(),
+		currency_id,
+		Change::NoChange,
+		Change::NoChange,
+		Change::NoChange,
+		Change::NoChange,
+		Change::NewValue(10_000),
+		Change::NoChange,
+	));
+}
+
+#[test]
+fn set_collateral_params_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			CDPEngine::set_collateral_params(
+				RuntimeOrigin::signed(ALICE),
+				DOT,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NewValue(10_000),
+				Change::NoChange,
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		// The stable currency is not a collateral.
+		assert_noop!(
+			CDPEngine::set_collateral_params(
+				RuntimeOrigin::root(),
+				AUSD,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NewValue(10_000),
+				Change::NoChange,
+			),
+			Error::<Test>::InvalidCollateralType
+		);
+
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(8, 5))),
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10_000),
+			Change::NewValue(Some(5_000)),
+		));
+		let params = CollateralParams::<Test>::get(DOT).unwrap();
+		assert_eq!(params.stability_fee, Some(Rate::saturating_from_rational(1, 100)));
+		assert_eq!(params.liquidation_ratio, Some(Ratio::saturating_from_rational(8, 5)));
+		assert_eq!(params.liquidation_penalty, None);
+		assert_eq!(
+			params.required_collateral_ratio,
+			Some(Ratio::saturating_from_rational(9, 5))
+		);
+		assert_eq!(params.maximum_total_debit_value, 10_000);
+		assert_eq!(params.per_account_debit_ceiling, Some(5_000));
+
+		System::assert_has_event(
+			Event::<Test>::StabilityFeeUpdated {
+				collateral_type: DOT,
+				new_stability_fee: Some(Rate::saturating_from_rational(1, 100)),
+			}
+			.into(),
+		);
+		System::assert_has_event(
+			Event::<Test>::LiquidationRatioUpdated {
+				collateral_type: DOT,
+				new_liquidation_ratio: Some(Ratio::saturating_from_rational(8, 5)),
+			}
+			.into(),
+		);
+		System::assert_has_event(
+			Event::<Test>::RequiredCollateralRatioUpdated {
+				collateral_type: DOT,
+				new_required_collateral_ratio: Some(Ratio::saturating_from_rational(9, 5)),
+			}
+			.into(),
+		);
+		System::assert_has_event(
+			Event::<Test>::MaximumTotalDebitValueUpdated {
+				collateral_type: DOT,
+				new_total_debit_value: 10_000,
+			}
+			.into(),
+		);
+		System::assert_has_event(
+			Event::<Test>::PerAccountDebitCeilingUpdated {
+				collateral_type: DOT,
+				new_per_account_debit_ceiling: Some(5_000),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn set_collateral_params_validates_ratio_ordering() {
+	ExtBuilder::default().build().execute_with(|| {
+		// A required ratio below the liquidation ratio would make positions that pass the
+		// required-ratio check immediately liquidatable.
+		assert_noop!(
+			CDPEngine::set_collateral_params(
+				RuntimeOrigin::root(),
+				DOT,
+				Change::NoChange,
+				Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+				Change::NoChange,
+				Change::NewValue(Some(Ratio::saturating_from_rational(8, 5))),
+				Change::NewValue(10_000),
+				Change::NoChange,
+			),
+			Error::<Test>::InvalidRatioOrdering
+		);
+		// A liquidation ratio below one would call an under-collateralized position safe.
+		assert_noop!(
+			CDPEngine::set_collateral_params(
+				RuntimeOrigin::root(),
+				DOT,
+				Change::NoChange,
+				Change::NewValue(Some(Ratio::saturating_from_rational(4, 5))),
+				Change::NoChange,
+				Change::NoChange,
+				Change::NewValue(10_000),
+				Change::NoChange,
+			),
+			Error::<Test>::InvalidRatioOrdering
+		);
+		assert!(CollateralParams::<Test>::get(DOT).is_none());
+
+		// Equal ratios are allowed.
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(8, 5))),
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(8, 5))),
+			Change::NewValue(10_000),
+			Change::NoChange,
+		));
+		// Raising the liquidation ratio above the stored required ratio is rejected, so
+		// the two cannot drift out of order across separate updates.
+		assert_noop!(
+			CDPEngine::set_collateral_params(
+				RuntimeOrigin::root(),
+				DOT,
+				Change::NoChange,
+				Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+			),
+			Error::<Test>::InvalidRatioOrdering
+		);
+		let params = CollateralParams::<Test>::get(DOT).unwrap();
+		assert_eq!(params.liquidation_ratio, Some(Ratio::saturating_from_rational(8, 5)));
+		assert_eq!(
+			params.required_collateral_ratio,
+			Some(Ratio::saturating_from_rational(8, 5))
+		);
+	});
+}
+
+#[test]
+fn on_initialize_accrues_stability_fee() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 10))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_eq!(
+			CDPEngine::get_debit_exchange_rate(DOT),
+			ExchangeRate::saturating_from_rational(1, 2)
+		);
+
+		<CDPEngine as OnInitialize<u64>>::on_initialize(2);
+		// 1/2 * (1 + 1/10)
+		assert_eq!(
+			CDPEngine::get_debit_exchange_rate(DOT),
+			ExchangeRate::saturating_from_rational(11, 20)
+		);
+		// BTC has no stability fee configured, so its rate stays at the default.
+		assert!(DebitExchangeRate::<Test>::get(BTC).is_none());
+
+		// No further accrual once the system is shut down.
+		set_shutdown(true);
+		<CDPEngine as OnInitialize<u64>>::on_initialize(3);
+		assert_eq!(
+			CDPEngine::get_debit_exchange_rate(DOT),
+			ExchangeRate::saturating_from_rational(11, 20)
+		);
+	});
+}
+
+#[test]
+fn check_position_valid_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// Zero debit positions are always valid.
+		assert_ok!(CDPEngine::check_position_valid(DOT, 100, 0, true));
+
+		// Debit value 1 is below the minimum of 2.
+		assert_noop!(
+			CDPEngine::check_position_valid(DOT, 100, 2, true),
+			Error::<Test>::RemainDebitValueTooSmall
+		);
+
+		// Debit 200 is worth 100; 140 collateral at price 1 is below the default
+		// liquidation ratio of 150%.
+		assert_noop!(
+			CDPEngine::check_position_valid(DOT, 140, 200, true),
+			Error::<Test>::BelowLiquidationRatio
+		);
+		assert_ok!(CDPEngine::check_position_valid(DOT, 170, 200, true));
+
+		// With a required collateral ratio of 180%, a ratio of 170% is no longer enough.
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_noop!(
+			CDPEngine::check_position_valid(DOT, 170, 200, true),
+			Error::<Test>::BelowRequiredCollateralRatio
+		);
+		assert_ok!(CDPEngine::check_position_valid(DOT, 180, 200, true));
+
+		// Without a price feed no debit position can be validated.
+		set_price(DOT, None);
+		assert_noop!(
+			CDPEngine::check_position_valid(DOT, 180, 200, true),
+			Error::<Test>::InvalidFeedPrice
+		);
+	});
+}
+
+#[test]
+fn required_ratio_enforcement_can_be_forced_globally() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NoChange,
+			Change::NoChange,
+		));
+		// Opting out of the required-ratio check only leaves the liquidation ratio: 170%
+		// is below the required 180% but above the liquidation 150%.
+		assert_ok!(CDPEngine::check_position_valid(DOT, 170, 200, false));
+		assert_noop!(
+			CDPEngine::check_position_valid(DOT, 170, 200, true),
+			Error::<Test>::BelowRequiredCollateralRatio
+		);
+
+		assert_noop!(
+			CDPEngine::set_required_ratio_always_enforced(RuntimeOrigin::signed(ALICE), true),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(CDPEngine::set_required_ratio_always_enforced(RuntimeOrigin::root(), true));
+		System::assert_last_event(
+			Event::<Test>::RequiredRatioEnforcementUpdated { always_enforced: true }.into(),
+		);
+		assert!(RequiredRatioAlwaysEnforced::<Test>::get());
+
+		// With the override active the caller's opt-out is ignored.
+		assert_noop!(
+			CDPEngine::check_position_valid(DOT, 170, 200, false),
+			Error::<Test>::BelowRequiredCollateralRatio
+		);
+		assert_ok!(CDPEngine::check_position_valid(DOT, 180, 200, false));
+
+		assert_ok!(CDPEngine::set_required_ratio_always_enforced(RuntimeOrigin::root(), false));
+		assert_ok!(CDPEngine::check_position_valid(DOT, 170, 200, false));
+	});
+}
+
+#[test]
+fn per_account_debit_ceiling_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// No ceiling configured means unlimited.
+		assert_ok!(CDPEngine::check_position_valid(DOT, 10_000, 4_000, true));
+
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(Some(100)),
+		));
+		// Debit 200 is worth exactly the ceiling of 100.
+		assert_ok!(CDPEngine::check_position_valid(DOT, 10_000, 200, true));
+		assert_noop!(
+			CDPEngine::check_position_valid(DOT, 10_000, 202, true),
+			Error::<Test>::ExceedAccountDebitCeiling
+		);
+
+		// Resetting the ceiling to `None` lifts the limit again.
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(None),
+		));
+		assert_ok!(CDPEngine::check_position_valid(DOT, 10_000, 202, true));
+	});
+}
+
+#[test]
+fn check_debit_cap_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// The cap defaults to zero until governance sets it.
+		assert_noop!(
+			CDPEngine::check_debit_cap(DOT, 2),
+			Error::<Test>::ExceedDebitValueHardCap
+		);
+		assert_ok!(CDPEngine::check_debit_cap(DOT, 0));
+
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(100),
+			Change::NoChange,
+		));
+		// Debit 200 is worth exactly the cap of 100.
+		assert_ok!(CDPEngine::check_debit_cap(DOT, 200));
+		assert_noop!(
+			CDPEngine::check_debit_cap(DOT, 202),
+			Error::<Test>::ExceedDebitValueHardCap
+		);
+	});
+}
+
+#[test]
+fn liquidation_guard_can_veto_liquidation() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+		System::set_block_number(2);
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+
+		// The guard protects ALICE, but leaves everyone else liquidatable.
+		VetoedAccount::set(Some(ALICE));
+		assert_noop!(
+			CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::LiquidationVetoed
+		);
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, BOB));
+
+		// Lifting the veto makes the position liquidatable again.
+		VetoedAccount::set(None);
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+	});
+}
+
+#[test]
+fn same_block_adjustment_defers_liquidation() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+
+		// Pushing the price and liquidating in the very block the owner touched the
+		// position is the atomic self-liquidation pattern; it has to wait a block.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_noop!(
+			CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::AdjustedInCurrentBlock
+		);
+		assert_eq!(
+			CDPEngine::validate_unsigned(
+				TransactionSource::Local,
+				&Call::<Test>::liquidate { currency_id: DOT, who: ALICE },
+			),
+			InvalidTransaction::Stale.into()
+		);
+
+		// One block later the deferral lapses.
+		System::set_block_number(2);
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+	});
+}
+
+#[test]
+fn liquidation_waits_out_a_fresh_oracle_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		System::set_block_number(2);
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+
+		// A price younger than the configured minimum age cannot back a liquidation.
+		MinBlocksSinceLastOracleUpdate::set(3);
+		PriceAge::set(Some(2));
+		assert_noop!(
+			CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::PriceTooRecent
+		);
+		assert_eq!(
+			CDPEngine::validate_unsigned(
+				TransactionSource::Local,
+				&Call::<Test>::liquidate { currency_id: DOT, who: ALICE },
+			),
+			InvalidTransaction::Stale.into()
+		);
+
+		// A sufficiently seasoned price goes through; so does a feed without age data.
+		PriceAge::set(Some(3));
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+	});
+}
+
+#[test]
+fn liquidate_unsafe_cdp_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		System::set_block_number(2);
+
+		// Safe at price 1: collateral value 500 against debit value 100.
+		assert_noop!(
+			CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::MustBeUnsafe
+		);
+
+		// At price 1/4 the collateral ratio is 125%, below the 150% liquidation ratio.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+
+		// The whole position is confiscated and auctioned for the debt plus the 10% penalty.
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, ALICE));
+		assert_eq!(Assets::balance(DOT, TREASURY), 500);
+		assert_eq!(Auctions::get(), vec![(ALICE, DOT, 500, 110)]);
+		System::assert_last_event(
+			Event::<Test>::LiquidateUnsafeCDP {
+				collateral_type: DOT,
+				owner: ALICE,
+				collateral_amount: 500,
+				bad_debt_value: 100,
+				target_amount: 110,
+			}
+			.into(),
+		);
+
+		// Liquidation is disabled after emergency shutdown.
+		set_price(DOT, Some(Price::one()));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		set_shutdown(true);
+		assert_noop!(
+			CDPEngine::liquidate(RuntimeOrigin::none(), DOT, BOB),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn liquidation_value_cap_splits_a_huge_position_into_chunks() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		MaxLiquidationValue::set(100);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 800, 400));
+		System::set_block_number(2);
+
+		// At price 1/4 the collateral ratio is 100%, below the 150% liquidation ratio.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+
+		// Debit value 200 against a cap of 100: half the position is confiscated, and
+		// the untouched remainder keeps the same (still unsafe) collateral ratio.
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 400, debit: 200 }
+		);
+		assert_eq!(Auctions::get(), vec![(ALICE, DOT, 400, 110)]);
+		System::assert_last_event(
+			Event::<Test>::LiquidateUnsafeCDP {
+				collateral_type: DOT,
+				owner: ALICE,
+				collateral_amount: 400,
+				bad_debt_value: 100,
+				target_amount: 110,
+			}
+			.into(),
+		);
+
+		// The remainder fits under the cap, so the second call clears it exactly.
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, ALICE));
+		assert_eq!(Auctions::get(), vec![(ALICE, DOT, 400, 110), (ALICE, DOT, 400, 110)]);
+		assert_eq!(Assets::balance(DOT, TREASURY), 800);
+
+		// Nothing is left to liquidate.
+		assert_noop!(
+			CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::MustBeUnsafe
+		);
+	});
+}
+
+#[test]
+fn hysteresis_band_keeps_an_oscillating_position_eligible() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		// Exit ratio: 150% * 1.1 = 165%.
+		HysteresisBand::set(Ratio::saturating_from_rational(1, 10));
+		MaxLiquidationValue::set(100);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 800, 400));
+		assert_eq!(BelowLiquidationSince::<Test>::get(DOT, ALICE), None);
+		System::set_block_number(2);
+
+		// The first chunked liquidation observes the position below the liquidation ratio
+		// and stamps the marker; the remainder of 400/200 stays open.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+		assert_eq!(BelowLiquidationSince::<Test>::get(DOT, ALICE), Some(2));
+
+		// A price series jittering across the liquidation ratio but staying under the exit
+		// ratio of 165% never makes the position ineligible: the remainder's ratio moves
+		// through 140%, 160%, 144% and 164%.
+		for (numerator, denominator) in [(35, 100), (40, 100), (36, 100), (41, 100)] {
+			set_price(DOT, Some(Price::saturating_from_rational(numerator, denominator)));
+			let Position { collateral, debit } = pallet_loans::Positions::<Test>::get(DOT, ALICE);
+			assert!(CDPEngine::is_cdp_eligible_for_liquidation(DOT, &ALICE, collateral, debit));
+		}
+
+		// Even above the liquidation ratio (160%), the marker keeps the position
+		// liquidatable; closing it fully clears the marker.
+		set_price(DOT, Some(Price::saturating_from_rational(2, 5)));
+		assert!(!CDPEngine::is_cdp_unsafe(DOT, 400, 200));
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, ALICE));
+		assert_eq!(BelowLiquidationSince::<Test>::get(DOT, ALICE), None);
+	});
+}
+
+#[test]
+fn recovering_past_the_exit_ratio_clears_the_marker() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		HysteresisBand::set(Ratio::saturating_from_rational(1, 10));
+		MaxLiquidationValue::set(100);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 800, 400));
+		System::set_block_number(2);
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+		assert_eq!(BelowLiquidationSince::<Test>::get(DOT, ALICE), Some(2));
+
+		// The price recovers past the exit ratio (remainder at 200%); the next position
+		// update observes it and clears the marker.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 2)));
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 100, 0));
+		assert_eq!(BelowLiquidationSince::<Test>::get(DOT, ALICE), None);
+
+		// Back inside the band (160%) the position is safe again: without a marker the
+		// band does not make it liquidatable.
+		set_price(DOT, Some(Price::saturating_from_rational(32, 100)));
+		assert!(!CDPEngine::is_cdp_eligible_for_liquidation(DOT, &ALICE, 500, 200));
+		assert_noop!(
+			CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::MustBeUnsafe
+		);
+
+		// Only an actual dip below the liquidation ratio re-arms the liquidation.
+		System::set_block_number(3);
+		set_price(DOT, Some(Price::saturating_from_rational(29, 100)));
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, ALICE));
+	});
+}
+
+#[test]
+fn liquidation_inclusion_reward_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+		System::set_block_number(2);
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+
+		// The reward defaults to zero, which disables the rebate.
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE));
+		assert_eq!(Assets::balance(AUSD, AUTHOR), 0);
+
+		LiquidationInclusionReward::set(5);
+		assert_ok!(CDPEngine::liquidate(RuntimeOrigin::none(), DOT, BOB));
+		assert_eq!(Assets::balance(AUSD, AUTHOR), 5);
+
+		// A failing liquidation pays nothing.
+		assert_noop!(
+			CDPEngine::liquidate(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::MustBeUnsafe
+		);
+		assert_eq!(Assets::balance(AUSD, AUTHOR), 5);
+	});
+}
+
+#[test]
+fn settle_cdp_has_debit_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+
+		assert_noop!(
+			CDPEngine::settle(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::MustAfterShutdown
+		);
+
+		set_shutdown(true);
+		// Settlement must not fall back to the live feed when no price was locked.
+		assert_noop!(
+			CDPEngine::settle(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::NoLockedPrice
+		);
+
+		assert_ok!(MockPriceSource::lock_price(DOT));
+		assert_ok!(MockPriceSource::lock_price(AUSD));
+		assert_ok!(CDPEngine::settle(RuntimeOrigin::none(), DOT, ALICE));
+
+		// Collateral worth the debit value of 100 (at price 1) is confiscated.
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 400, debit: 0 }
+		);
+		assert_eq!(Assets::balance(DOT, TREASURY), 100);
+		// Progress and completion events follow the settlement event itself.
+		System::assert_has_event(
+			Event::<Test>::SettleCDPInDebit { collateral_type: DOT, owner: ALICE }.into(),
+		);
+
+		// Nothing left to settle.
+		assert_noop!(
+			CDPEngine::settle(RuntimeOrigin::none(), DOT, ALICE),
+			Error::<Test>::NoDebitValue
+		);
+	});
+}
+
+#[test]
+fn settle_signed_pays_the_settlement_incentive() {
+	ExtBuilder::default().build().execute_with(|| {
+		SettlementIncentive::set(10);
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+
+		assert_noop!(
+			CDPEngine::settle_signed(RuntimeOrigin::signed(CAROL), DOT, ALICE),
+			Error::<Test>::MustAfterShutdown
+		);
+
+		set_shutdown(true);
+		assert_ok!(MockPriceSource::lock_price(DOT));
+		assert_ok!(MockPriceSource::lock_price(AUSD));
+
+		let carol_before = Assets::balance(AUSD, CAROL);
+		assert_ok!(CDPEngine::settle_signed(RuntimeOrigin::signed(CAROL), DOT, ALICE));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 400, debit: 0 }
+		);
+		assert_eq!(Assets::balance(AUSD, CAROL), carol_before + 10);
+
+		// A failed settlement pays nothing, so debit-free accounts cannot be farmed.
+		assert_noop!(
+			CDPEngine::settle_signed(RuntimeOrigin::signed(CAROL), DOT, ALICE),
+			Error::<Test>::NoDebitValue
+		);
+		assert_eq!(Assets::balance(AUSD, CAROL), carol_before + 10);
+
+		// A zero incentive disables the reward but not the settlement.
+		SettlementIncentive::set(0);
+		assert_ok!(CDPEngine::settle_signed(RuntimeOrigin::signed(CAROL), DOT, BOB));
+		assert_eq!(Assets::balance(AUSD, CAROL), carol_before + 10);
+	});
+}
+
+#[test]
+fn settlement_uses_locked_shutdown_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+
+		// Shutdown locks prices at their current values (DOT = 1, AUSD = 1).
+		set_shutdown(true);
+		assert_ok!(MockPriceSource::lock_price(DOT));
+		assert_ok!(MockPriceSource::lock_price(AUSD));
+
+		assert_ok!(CDPEngine::settle(RuntimeOrigin::none(), DOT, ALICE));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 400, debit: 0 }
+		);
+
+		// The live market keeps moving after shutdown. At the live price of 1/5 a debt of
+		// 100 would cost all 500 collateral, but settlement must stay at the locked price.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 5)));
+		assert_ok!(CDPEngine::settle(RuntimeOrigin::none(), DOT, BOB));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, BOB),
+			Position { collateral: 400, debit: 0 }
+		);
+		// Both CDPs settled at the same rate regardless of when they were settled.
+		assert_eq!(Assets::balance(DOT, TREASURY), 200);
+	});
+}
+
+#[test]
+fn settlement_progress_is_tracked_across_collaterals() {
+	ExtBuilder::default().build().execute_with(|| {
+		SettlementProgressInterval::set(2);
+		setup_collateral(DOT);
+		setup_collateral(BTC);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 100));
+		assert_ok!(Loans::adjust_position(&ALICE, BTC, 500, 60));
+
+		set_shutdown(true);
+		assert_ok!(MockPriceSource::lock_price(DOT));
+		assert_ok!(MockPriceSource::lock_price(BTC));
+		assert_ok!(MockPriceSource::lock_price(AUSD));
+
+		// Debit values (at the exchange rate of 1/2): 100, 50 and 30, totalling 180.
+		assert_ok!(CDPEngine::settle(RuntimeOrigin::none(), DOT, ALICE));
+		assert_eq!(SettledDebitTotal::<Test>::get(), 100);
+		// One settlement in, the interval of two has not elapsed yet.
+		System::assert_last_event(
+			Event::<Test>::SettleCDPInDebit { collateral_type: DOT, owner: ALICE }.into(),
+		);
+
+		// The second settlement emits a snapshot, with the BTC debit still outstanding.
+		assert_ok!(CDPEngine::settle(RuntimeOrigin::none(), DOT, BOB));
+		assert_eq!(SettledDebitTotal::<Test>::get(), 150);
+		System::assert_last_event(
+			Event::<Test>::SettlementProgress { settled: 150, remaining: 30 }.into(),
+		);
+
+		// The last settlement completes the wind-down even though the count is off-interval.
+		assert_ok!(CDPEngine::settle(RuntimeOrigin::none(), BTC, ALICE));
+		assert_eq!(SettledDebitTotal::<Test>::get(), 180);
+		assert_eq!(SettledCdpCount::<Test>::get(), 3);
+		System::assert_last_event(Event::<Test>::SettlementComplete { settled: 180 }.into());
+	});
+}
+
+#[test]
+fn settle_all_sweeps_the_position_book_in_steps() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		setup_collateral(BTC);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 100));
+		assert_ok!(Loans::adjust_position(&ALICE, BTC, 500, 60));
+
+		assert_noop!(
+			CDPEngine::settle_all(RuntimeOrigin::signed(ALICE), 2),
+			Error::<Test>::MustAfterShutdown
+		);
+		set_shutdown(true);
+		assert_ok!(MockPriceSource::lock_price(DOT));
+		assert_ok!(MockPriceSource::lock_price(BTC));
+		assert_ok!(MockPriceSource::lock_price(AUSD));
+		assert_noop!(
+			CDPEngine::settle_all(RuntimeOrigin::signed(ALICE), 0),
+			Error::<Test>::ZeroLimit
+		);
+
+		// The first step pauses with a cursor; another entry remains.
+		assert_ok!(CDPEngine::settle_all(RuntimeOrigin::signed(ALICE), 2));
+		System::assert_last_event(Event::<Test>::SettledBatch { count: 2, done: false }.into());
+		assert!(SettleAllCursor::<Test>::get().is_some());
+		assert_eq!(SettledCdpCount::<Test>::get(), 2);
+
+		// The second step settles the rest and clears the cursor.
+		assert_ok!(CDPEngine::settle_all(RuntimeOrigin::signed(ALICE), 2));
+		System::assert_has_event(Event::<Test>::SettledBatch { count: 1, done: true }.into());
+		assert!(SettleAllCursor::<Test>::get().is_none());
+		assert_eq!(SettledCdpCount::<Test>::get(), 3);
+		// Debit values 100 + 50 + 30 all settled.
+		assert_eq!(SettledDebitTotal::<Test>::get(), 180);
+		System::assert_has_event(Event::<Test>::SettlementComplete { settled: 180 }.into());
+		for (currency_id, who) in [(DOT, ALICE), (DOT, BOB), (BTC, ALICE)] {
+			assert!(pallet_loans::Positions::<Test>::get(currency_id, who).debit.is_zero());
+		}
+
+		// A further sweep visits the debit-free remainder and completes immediately.
+		assert_ok!(CDPEngine::settle_all(RuntimeOrigin::signed(ALICE), 10));
+		System::assert_last_event(Event::<Test>::SettledBatch { count: 0, done: true }.into());
+	});
+}
+
+#[test]
+fn validate_unsigned_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		System::set_block_number(2);
+
+		let liquidate_call = Call::<Test>::liquidate { currency_id: DOT, who: ALICE };
+		let settle_call = Call::<Test>::settle { currency_id: DOT, who: ALICE };
+		let validate = |call: &Call<Test>| {
+			<CDPEngine as frame_support::unsigned::ValidateUnsigned>::validate_unsigned(
+				TransactionSource::Local,
+				call,
+			)
+		};
+
+		// The position is safe, so neither call validates.
+		assert_eq!(validate(&liquidate_call), InvalidTransaction::Stale.into());
+		assert_eq!(validate(&settle_call), InvalidTransaction::Stale.into());
+
+		// Unsafe position: liquidation validates while the system is live.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_ok!(validate(&liquidate_call));
+
+		set_shutdown(true);
+		assert_eq!(validate(&liquidate_call), InvalidTransaction::Stale.into());
+		// Settlement does not validate until a locked price exists.
+		assert_eq!(validate(&settle_call), InvalidTransaction::Stale.into());
+
+		assert_ok!(MockPriceSource::lock_price(DOT));
+		assert_ok!(MockPriceSource::lock_price(AUSD));
+		assert_ok!(validate(&settle_call));
+		// But never for a CDP without debit.
+		assert_eq!(
+			validate(&Call::<Test>::settle { currency_id: DOT, who: BOB }),
+			InvalidTransaction::Stale.into()
+		);
+
+		// Other calls are not valid unsigned.
+		assert_eq!(
+			validate(&Call::<Test>::set_collateral_params {
+				currency_id: DOT,
+				stability_fee: Change::NoChange,
+				liquidation_ratio: Change::NoChange,
+				liquidation_penalty: Change::NoChange,
+				required_collateral_ratio: Change::NoChange,
+				maximum_total_debit_value: Change::NoChange,
+				per_account_debit_ceiling: Change::NoChange,
+			}),
+			InvalidTransaction::Call.into()
+		);
+	});
+}
+
+#[test]
+fn risk_buckets_track_position_updates() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert!(RiskBucketOf::<Test>::get(DOT, ALICE).is_none());
+
+		// Collateral ratio 5 against a liquidation ratio of 3/2 lands in the safest bucket.
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_eq!(RiskBucketOf::<Test>::get(DOT, ALICE), Some(7));
+		assert_eq!(RiskBuckets::<Test>::get(DOT, 7).to_vec(), vec![ALICE]);
+
+		// After the price drop a collateral top-up re-stamps the position as riskiest.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 1, 0));
+		assert_eq!(RiskBucketOf::<Test>::get(DOT, ALICE), Some(0));
+		assert!(RiskBuckets::<Test>::get(DOT, 7).is_empty());
+
+		// Closing the position drops it from the index.
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, -501, -200));
+		assert!(RiskBucketOf::<Test>::get(DOT, ALICE).is_none());
+		assert!(RiskBuckets::<Test>::get(DOT, 0).is_empty());
+	});
+}
+
+#[test]
+fn offchain_worker_prioritizes_risky_buckets() {
+	let mut ext = ExtBuilder::default().build();
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+
+		// Both positions become unsafe; only BOB's is re-stamped into the riskiest bucket,
+		// ALICE stays in the safest one from open time.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 1, 0));
+		assert_eq!(RiskBucketOf::<Test>::get(DOT, BOB), Some(0));
+		assert_eq!(RiskBucketOf::<Test>::get(DOT, ALICE), Some(7));
+
+		System::set_block_number(2);
+		<CDPEngine as OffchainWorker<u64>>::offchain_worker(2);
+
+		// BOB is submitted before ALICE despite both being found via the bucket walk.
+		let transactions = pool_state.read().transactions.clone();
+		assert_eq!(transactions.len(), 2);
+		let calls: Vec<RuntimeCall> = transactions
+			.iter()
+			.map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap().function)
+			.collect();
+		assert_eq!(
+			calls,
+			vec![
+				RuntimeCall::CDPEngine(crate::Call::liquidate { currency_id: DOT, who: BOB }),
+				RuntimeCall::CDPEngine(crate::Call::liquidate { currency_id: DOT, who: ALICE }),
+			]
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_critical_fast_path_beats_stale_buckets() {
+	let mut ext = ExtBuilder::default().build();
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.execute_with(|| {
+		setup_collateral(DOT);
+		// BOB opens near the top of the market and is stamped into the safest bucket.
+		set_price(DOT, Some(Price::saturating_from_integer(2)));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 300, 200));
+		// ALICE opens on the way down and gets a much riskier stamp.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 2)));
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_eq!(RiskBucketOf::<Test>::get(DOT, BOB), Some(7));
+		assert_eq!(RiskBucketOf::<Test>::get(DOT, ALICE), Some(2));
+
+		// The crash makes ALICE merely unsafe (ratio 1.4) but BOB critical (ratio 0.84,
+		// below 2/3 of the liquidation ratio). Neither position is touched afterwards, so
+		// the stale stamps now order the two the wrong way around.
+		set_price(DOT, Some(Price::saturating_from_rational(28, 100)));
+		assert!(CDPEngine::is_cdp_critical(DOT, 300, 200));
+		assert!(!CDPEngine::is_cdp_critical(DOT, 500, 200));
+
+		System::set_block_number(2);
+		<CDPEngine as OffchainWorker<u64>>::offchain_worker(2);
+
+		// The critical fast path submits BOB before the bucket walk reaches ALICE, even
+		// though ALICE sits in the riskier bucket.
+		let transactions = pool_state.read().transactions.clone();
+		assert_eq!(transactions.len(), 2);
+		let calls: Vec<RuntimeCall> = transactions
+			.iter()
+			.map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap().function)
+			.collect();
+		assert_eq!(
+			calls,
+			vec![
+				RuntimeCall::CDPEngine(crate::Call::liquidate { currency_id: DOT, who: BOB }),
+				RuntimeCall::CDPEngine(crate::Call::liquidate { currency_id: DOT, who: ALICE }),
+			]
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_batches_liquidations() {
+	let mut ext = ExtBuilder::default().build();
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.execute_with(|| {
+		OffchainLiquidationBatchSize::set(2);
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&CAROL, DOT, 500, 200));
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+
+		System::set_block_number(2);
+		<CDPEngine as OffchainWorker<u64>>::offchain_worker(2);
+
+		// Three unsafe CDPs at a batch size of two: one full batch and a left-over
+		// account, which is cheaper to include as a plain `liquidate`.
+		let transactions = pool_state.read().transactions.clone();
+		let calls: Vec<RuntimeCall> = transactions
+			.iter()
+			.map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap().function)
+			.collect();
+		assert_eq!(
+			calls,
+			vec![
+				RuntimeCall::CDPEngine(crate::Call::liquidate_batch {
+					currency_id: DOT,
+					accounts: vec![ALICE, BOB].try_into().unwrap(),
+				}),
+				RuntimeCall::CDPEngine(crate::Call::liquidate { currency_id: DOT, who: CAROL }),
+			]
+		);
+	});
+}
+
+#[test]
+fn liquidate_batch_skips_rescued_cdps() {
+	ExtBuilder::default().build().execute_with(|| {
+		OffchainLiquidationBatchSize::set(2);
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 900, 200));
+		System::set_block_number(2);
+
+		// At price 1/4 ALICE (ratio 125%) is unsafe while BOB (ratio 225%) is not; the
+		// batch liquidates ALICE and skips BOB instead of failing.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_ok!(CDPEngine::liquidate_batch(
+			RuntimeOrigin::none(),
+			DOT,
+			vec![ALICE, BOB].try_into().unwrap(),
+		));
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, ALICE));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, BOB),
+			Position { collateral: 900, debit: 200 }
+		);
+		assert_eq!(Auctions::get(), vec![(ALICE, DOT, 500, 110)]);
+
+		// A batch without a single unsafe account does not validate.
+		assert_eq!(
+			CDPEngine::validate_unsigned(
+				TransactionSource::Local,
+				&crate::Call::liquidate_batch {
+					currency_id: DOT,
+					accounts: vec![BOB].try_into().unwrap(),
+				},
+			),
+			InvalidTransaction::Stale.into()
+		);
+
+		// Batched liquidation is disabled after emergency shutdown.
+		set_shutdown(true);
+		assert_noop!(
+			CDPEngine::liquidate_batch(
+				RuntimeOrigin::none(),
+				DOT,
+				vec![BOB].try_into().unwrap(),
+			),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn liquidate_batch_skips_vetoed_and_deferred_cdps() {
+	ExtBuilder::default().build().execute_with(|| {
+		OffchainLiquidationBatchSize::set(3);
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+		System::set_block_number(2);
+		// CAROL adjusts her position in the inclusion block; BOB is vetoed by the guard.
+		assert_ok!(Loans::adjust_position(&CAROL, DOT, 500, 200));
+		VetoedAccount::set(Some(BOB));
+
+		// At price 1/4 all three are unsafe, but only ALICE may actually be liquidated.
+		// The other two are skipped instead of shielding the whole batch.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_ok!(CDPEngine::liquidate_batch(
+			RuntimeOrigin::none(),
+			DOT,
+			vec![ALICE, BOB, CAROL].try_into().unwrap(),
+		));
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, ALICE));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, BOB),
+			Position { collateral: 500, debit: 200 }
+		);
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, CAROL),
+			Position { collateral: 500, debit: 200 }
+		);
+		assert_eq!(Auctions::get(), vec![(ALICE, DOT, 500, 110)]);
+
+		// Once the veto lifts and a block passes, the stragglers are liquidated too.
+		VetoedAccount::set(None);
+		System::set_block_number(3);
+		assert_ok!(CDPEngine::liquidate_batch(
+			RuntimeOrigin::none(),
+			DOT,
+			vec![BOB, CAROL].try_into().unwrap(),
+		));
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, BOB));
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, CAROL));
+	});
+}
+
+#[test]
+fn build_risk_bucket_index_migration_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+
+		// Wipe the index to simulate positions that predate it.
+		let _ = RiskBuckets::<Test>::clear(u32::MAX, None);
+		let _ = RiskBucketOf::<Test>::clear(u32::MAX, None);
+		assert!(RiskBucketOf::<Test>::get(DOT, ALICE).is_none());
+
+		migrations::BuildRiskBucketIndex::<Test>::on_runtime_upgrade();
+		assert_eq!(RiskBucketOf::<Test>::get(DOT, ALICE), Some(7));
+		assert_eq!(RiskBuckets::<Test>::get(DOT, 7).to_vec(), vec![ALICE]);
+	});
+}
+
+#[test]
+fn add_per_account_debit_ceiling_migration_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// Write params in the old five-field layout, as a version 0 chain would hold them.
+		let old = migrations::v1::OldRiskManagementParams::<Balance> {
+			maximum_total_debit_value: 10_000,
+			stability_fee: Some(Rate::saturating_from_rational(1, 100)),
+			liquidation_ratio: Some(Ratio::saturating_from_rational(8, 5)),
+			liquidation_penalty: None,
+			required_collateral_ratio: Some(Ratio::saturating_from_rational(9, 5)),
+		};
+		frame_support::storage::unhashed::put(
+			&CollateralParams::<Test>::hashed_key_for(DOT),
+			&old,
+		);
+		StorageVersion::new(0).put::<CDPEngine>();
+		// The stored value no longer decodes as the current struct.
+		assert!(CollateralParams::<Test>::get(DOT).is_none());
+
+		migrations::v1::AddPerAccountDebitCeiling::<Test>::on_runtime_upgrade();
+
+		let params = CollateralParams::<Test>::get(DOT).unwrap();
+		assert_eq!(params.maximum_total_debit_value, 10_000);
+		assert_eq!(params.stability_fee, Some(Rate::saturating_from_rational(1, 100)));
+		assert_eq!(params.liquidation_ratio, Some(Ratio::saturating_from_rational(8, 5)));
+		assert_eq!(params.liquidation_penalty, None);
+		assert_eq!(
+			params.required_collateral_ratio,
+			Some(Ratio::saturating_from_rational(9, 5))
+		);
+		assert_eq!(params.per_account_debit_ceiling, None);
+		assert_eq!(StorageVersion::get::<CDPEngine>(), StorageVersion::new(1));
+
+		// Running again on a migrated chain is a no-op.
+		CollateralParams::<Test>::mutate(DOT, |params| {
+			params.as_mut().unwrap().per_account_debit_ceiling = Some(100);
+		});
+		migrations::v1::AddPerAccountDebitCeiling::<Test>::on_runtime_upgrade();
+		assert_eq!(
+			CollateralParams::<Test>::get(DOT).unwrap().per_account_debit_ceiling,
+			Some(100)
+		);
+	});
+}
+
+#[test]
+fn on_initialize_triggers_debt_auction() {
+	ExtBuilder::default().build().execute_with(|| {
+		// A shortfall below the threshold of 100 is left alone.
+		assert_ok!(MockCDPTreasury::on_system_debit(99));
+		<CDPEngine as OnInitialize<u64>>::on_initialize(2);
+		assert!(DebtAuctions::get().is_empty());
+
+		assert_ok!(MockCDPTreasury::on_system_debit(1));
+		<CDPEngine as OnInitialize<u64>>::on_initialize(3);
+		assert_eq!(DebtAuctions::get(), vec![100]);
+		assert_eq!(DebtAuctionsInFlight::<Test>::get(), 100);
+		System::assert_has_event(Event::<Test>::DebtAuctionTriggered { amount: 100 }.into());
+
+		// The shortfall already in auction is not auctioned a second time.
+		<CDPEngine as OnInitialize<u64>>::on_initialize(4);
+		assert_eq!(DebtAuctions::get(), vec![100]);
+
+		// Nor does new debt below the threshold on top of the in-flight amount trigger.
+		assert_ok!(MockCDPTreasury::on_system_debit(50));
+		<CDPEngine as OnInitialize<u64>>::on_initialize(5);
+		assert_eq!(DebtAuctions::get(), vec![100]);
+
+		// Once the auction concludes, the remaining shortfall becomes eligible again.
+		CDPEngine::on_debt_auction_concluded(100);
+		assert_eq!(DebtAuctionsInFlight::<Test>::get(), 0);
+		<CDPEngine as OnInitialize<u64>>::on_initialize(6);
+		assert_eq!(DebtAuctions::get(), vec![100, 150]);
+		assert_eq!(DebtAuctionsInFlight::<Test>::get(), 150);
+	});
+}
+
+#[test]
+fn on_initialize_triggers_surplus_auction() {
+	ExtBuilder::default().build().execute_with(|| {
+		// Surplus up to the buffer of 200 is kept in the treasury.
+		assert_ok!(MockCDPTreasury::on_system_surplus(200));
+		<CDPEngine as OnInitialize<u64>>::on_initialize(2);
+		assert!(SurplusAuctions::get().is_empty());
+
+		assert_ok!(MockCDPTreasury::on_system_surplus(50));
+		<CDPEngine as OnInitialize<u64>>::on_initialize(3);
+		assert_eq!(SurplusAuctions::get(), vec![50]);
+		assert_eq!(SurplusAuctionsInFlight::<Test>::get(), 50);
+		System::assert_has_event(Event::<Test>::SurplusAuctionTriggered { amount: 50 }.into());
+
+		// The amount already in auction is not offered a second time.
+		<CDPEngine as OnInitialize<u64>>::on_initialize(4);
+		assert_eq!(SurplusAuctions::get(), vec![50]);
+
+		// Outstanding system debt is netted against the surplus first: 290 - 30 - 200 - 50.
+		assert_ok!(MockCDPTreasury::on_system_surplus(40));
+		assert_ok!(MockCDPTreasury::on_system_debit(30));
+		<CDPEngine as OnInitialize<u64>>::on_initialize(5);
+		assert_eq!(SurplusAuctions::get(), vec![50, 10]);
+
+		CDPEngine::on_surplus_auction_concluded(60);
+		assert_eq!(SurplusAuctionsInFlight::<Test>::get(), 0);
+
+		// No auctions of either kind are started once the system is shut down.
+		assert_ok!(MockCDPTreasury::on_system_surplus(100));
+		set_shutdown(true);
+		<CDPEngine as OnInitialize<u64>>::on_initialize(6);
+		assert_eq!(SurplusAuctions::get(), vec![50, 10]);
+	});
+}
+
+#[test]
+fn cdp_status_view_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+
+		// A position without debit is always safe.
+		assert_eq!(CDPEngine::cdp_status(DOT, ALICE), CDPStatus::Safe);
+
+		// Collateral value 500 against debit value 100 at price 1.
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_eq!(CDPEngine::cdp_status(DOT, ALICE), CDPStatus::Safe);
+
+		// At price 1/4 the collateral ratio of 125% is below the 150% liquidation ratio.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_eq!(CDPEngine::cdp_status(DOT, ALICE), CDPStatus::Unsafe);
+
+		// Without a live price the status cannot be determined.
+		set_price(DOT, None);
+		assert_eq!(CDPEngine::cdp_status(DOT, ALICE), CDPStatus::ChecksFailed);
+	});
+}
+
+#[test]
+fn collateral_ratio_view_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+
+		// No debit, no ratio.
+		assert_eq!(CDPEngine::collateral_ratio(DOT, ALICE), None);
+
+		// Collateral value 500 against debit value 100 at price 1.
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_eq!(
+			CDPEngine::collateral_ratio(DOT, ALICE),
+			Some(Ratio::saturating_from_integer(5))
+		);
+
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+		assert_eq!(
+			CDPEngine::collateral_ratio(DOT, ALICE),
+			Some(Ratio::saturating_from_rational(5, 4))
+		);
+
+		// Without a live price no ratio can be computed.
+		set_price(DOT, None);
+		assert_eq!(CDPEngine::collateral_ratio(DOT, ALICE), None);
+	});
+}
+
+#[test]
+fn repay_debit_on_behalf_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+
+		// BOB repays 40 of ALICE's debit value of 100, burning his own stable currency.
+		assert_ok!(CDPEngine::repay_debit_on_behalf(&BOB, &ALICE, DOT, 40));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 500, debit: 120 }
+		);
+		assert_eq!(Assets::balance(AUSD, BOB), 60);
+		assert_eq!(Assets::balance(AUSD, ALICE), 100);
+		System::assert_last_event(
+			Event::<Test>::DebitRepaidOnBehalf {
+				collateral_type: DOT,
+				owner: ALICE,
+				payer: BOB,
+				repaid_value: 40,
+			}
+			.into(),
+		);
+
+		// Repaying more than the remaining debt closes the position, burning only its
+		// actual value of 60.
+		assert_ok!(CDPEngine::repay_debit_on_behalf(&BOB, &ALICE, DOT, 100));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 500, debit: 0 }
+		);
+		assert_eq!(Assets::balance(AUSD, BOB), 0);
+		System::assert_last_event(
+			Event::<Test>::DebitRepaidOnBehalf {
+				collateral_type: DOT,
+				owner: ALICE,
+				payer: BOB,
+				repaid_value: 60,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn repay_debit_on_behalf_checks_the_resulting_position() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+
+		// Nothing to repay without debit.
+		assert_noop!(
+			CDPEngine::repay_debit_on_behalf(&BOB, &TREASURY, DOT, 40),
+			Error::<Test>::NoDebitValue
+		);
+
+		// A partial repayment may not leave dust behind: 99 of the debit value of 100
+		// would leave 1, below the minimum of 2. The burn rolls back with the position.
+		assert_noop!(
+			CDPEngine::repay_debit_on_behalf(&BOB, &ALICE, DOT, 99),
+			Error::<Test>::RemainDebitValueTooSmall
+		);
+		assert_eq!(Assets::balance(AUSD, BOB), 100);
+
+		// A partial repayment revalidates the position, which needs a live price.
+		set_price(DOT, None);
+		assert_noop!(
+			CDPEngine::repay_debit_on_behalf(&BOB, &ALICE, DOT, 40),
+			Error::<Test>::InvalidFeedPrice
+		);
+		set_price(DOT, Some(Price::saturating_from_integer(1)));
+
+		set_shutdown(true);
+		assert_noop!(
+			CDPEngine::repay_debit_on_behalf(&BOB, &ALICE, DOT, 40),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn liquidatable_scan_matches_offchain_worker_submissions() {
+	let mut ext = ExtBuilder::default().build();
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.execute_with(|| {
+		setup_collateral(DOT);
+		setup_collateral(BTC);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&ALICE, BTC, 1000, 200));
+
+		// The crash makes both DOT positions unsafe; the BTC one stays safe.
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+
+		System::set_block_number(2);
+		<CDPEngine as OffchainWorker<u64>>::offchain_worker(2);
+		let submitted: std::collections::BTreeSet<(CurrencyId, AccountId)> = pool_state
+			.read()
+			.transactions
+			.iter()
+			.map(|tx| match Extrinsic::decode(&mut &tx[..]).unwrap().function {
+				RuntimeCall::CDPEngine(crate::Call::liquidate { currency_id, who }) =>
+					(currency_id, who),
+				call => panic!("unexpected call {:?}", call),
+			})
+			.collect();
+
+		// The scan finds exactly the positions the offchain worker submitted, along with
+		// their collateral ratio (500 * 1/4 against a debit value of 100).
+		let (unsafe_cdps, cursor) = CDPEngine::liquidatable(u32::MAX, None);
+		assert_eq!(cursor, None);
+		assert_eq!(
+			unsafe_cdps
+				.iter()
+				.map(|(currency_id, who, _)| (*currency_id, *who))
+				.collect::<std::collections::BTreeSet<_>>(),
+			submitted,
+		);
+		assert!(unsafe_cdps
+			.iter()
+			.all(|(_, _, ratio)| *ratio == Ratio::saturating_from_rational(5, 4)));
+	});
+}
+
+#[test]
+fn liquidatable_scan_pages_with_a_cursor() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 500, 200));
+		set_price(DOT, Some(Price::saturating_from_rational(1, 4)));
+
+		let (all, cursor) = CDPEngine::liquidatable(u32::MAX, None);
+		assert_eq!(all.len(), 2);
+		assert_eq!(cursor, None);
+
+		// Page through one position at a time: the pages concatenate to the full scan.
+		let (first_page, cursor) = CDPEngine::liquidatable(1, None);
+		assert_eq!(first_page, all[..1]);
+		assert!(cursor.is_some());
+		let (second_page, cursor) = CDPEngine::liquidatable(1, cursor);
+		assert_eq!(second_page, all[1..]);
+
+		// The last page found its full quota, so only the next call sees the end.
+		let (rest, cursor) = CDPEngine::liquidatable(1, cursor);
+		assert_eq!(rest, vec![]);
+		assert_eq!(cursor, None);
+	});
+}
+
+#[test]
+fn sweep_empty_buckets_removes_only_debt_free_rates() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		DebitExchangeRate::<Test>::insert(DOT, ExchangeRate::saturating_from_rational(1, 2));
+		// A leftover rate of a currency whose last position has closed.
+		DebitExchangeRate::<Test>::insert(BTC, ExchangeRate::saturating_from_rational(3, 5));
+
+		assert_ok!(CDPEngine::sweep_empty_buckets(RuntimeOrigin::signed(ALICE), 10));
+		assert!(DebitExchangeRate::<Test>::get(BTC).is_none());
+		System::assert_has_event(Event::<Test>::DebitExchangeRateSwept { collateral_type: BTC }.into());
+
+		// DOT still has open debit, so its rate survives.
+		assert_eq!(
+			DebitExchangeRate::<Test>::get(DOT),
+			Some(ExchangeRate::saturating_from_rational(1, 2))
+		);
+
+		// Once the position closes, a later sweep picks it up.
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, -500, -200));
+		assert_ok!(CDPEngine::sweep_empty_buckets(RuntimeOrigin::signed(ALICE), 10));
+		assert!(DebitExchangeRate::<Test>::get(DOT).is_none());
+	});
+}
+
+#[test]
+fn sweep_empty_buckets_respects_the_limit() {
+	ExtBuilder::default().build().execute_with(|| {
+		DebitExchangeRate::<Test>::insert(DOT, ExchangeRate::saturating_from_rational(3, 5));
+		DebitExchangeRate::<Test>::insert(BTC, ExchangeRate::saturating_from_rational(3, 5));
+
+		// Both entries are sweepable, but only one fits into the limit.
+		assert_ok!(CDPEngine::sweep_empty_buckets(RuntimeOrigin::signed(ALICE), 1));
+		assert_eq!(DebitExchangeRate::<Test>::iter().count(), 1);
+
+		assert_ok!(CDPEngine::sweep_empty_buckets(RuntimeOrigin::signed(ALICE), 1));
+		assert_eq!(DebitExchangeRate::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn accumulate_interest_reports_iterated_and_updated_counts() {
+	ExtBuilder::default().build().execute_with(|| {
+		// Both collaterals are visited; with no stability fees, nothing is written.
+		assert_eq!(CDPEngine::accumulate_interest(), (2, 0));
+		assert!(DebitExchangeRate::<Test>::get(DOT).is_none());
+		assert!(DebitExchangeRate::<Test>::get(BTC).is_none());
+
+		// A fee on DOT only: still two reads' worth of iteration, but a single update.
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 10))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_eq!(CDPEngine::accumulate_interest(), (2, 1));
+		assert_eq!(
+			DebitExchangeRate::<Test>::get(DOT),
+			Some(ExchangeRate::saturating_from_rational(11, 20))
+		);
+		assert!(DebitExchangeRate::<Test>::get(BTC).is_none());
+	});
+}
+
+#[test]
+fn interest_reconciliation_stays_within_tolerance() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		setup_collateral(BTC);
+		for (currency_id, fee) in [
+			(DOT, Rate::saturating_from_rational(1, 10)),
+			// An odd fee, so the valuations round on most accruals.
+			(BTC, Rate::saturating_from_rational(3, 1000)),
+		] {
+			assert_ok!(CDPEngine::set_collateral_params(
+				RuntimeOrigin::root(),
+				currency_id,
+				Change::NewValue(Some(fee)),
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+			));
+		}
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 900, 200));
+		assert_ok!(Loans::adjust_position(&BOB, BTC, 900, 300));
+		assert_eq!(Assets::balance(AUSD, TREASURY), 0);
+
+		for _ in 0..40 {
+			CDPEngine::accumulate_interest();
+		}
+
+		// Every issued unit landed in the treasury surplus pool, and the independently
+		// accounted debit value growth agrees up to one rounding unit per accrual event.
+		let (issued, growth, drift) = CDPEngine::interest_reconciliation();
+		assert!(issued > 0);
+		assert_eq!(Assets::balance(AUSD, TREASURY), issued);
+		assert_eq!(InterestAccrualEvents::<Test>::get(), 80);
+		assert!(drift <= 80);
+		assert!(growth > 0);
+		assert_ok!(CDPEngine::do_try_state());
+	});
+}
+
+#[test]
+fn reconciliation_detects_injected_accounting_bug() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 10))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 900, 200));
+
+		assert_noop!(
+			CDPEngine::set_reconciliation_drift_threshold(RuntimeOrigin::signed(ALICE), Some(5)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(CDPEngine::set_reconciliation_drift_threshold(RuntimeOrigin::root(), Some(5)));
+
+		// A healthy accrual stays within the threshold.
+		CDPEngine::accumulate_interest();
+		assert!(!DriftWarningEmitted::<Test>::get());
+		assert_ok!(CDPEngine::do_try_state());
+
+		// Inject an accounting bug: stable currency issued without matching debit growth.
+		CumulativeInterestIssued::<Test>::mutate(|issued| *issued += 100);
+		CDPEngine::accumulate_interest();
+		let (issued, growth, drift) = CDPEngine::interest_reconciliation();
+		assert!(drift > 5);
+		System::assert_last_event(
+			Event::<Test>::InterestReconciliationDriftWarning { issued, growth, drift }.into(),
+		);
+		assert!(CDPEngine::do_try_state().is_err());
+
+		// The warning fires only once per configured threshold.
+		let warnings = || {
+			System::events()
+				.into_iter()
+				.filter(|record| {
+					matches!(
+						record.event,
+						RuntimeEvent::CDPEngine(
+							Event::<Test>::InterestReconciliationDriftWarning { .. }
+						)
+					)
+				})
+				.count()
+		};
+		assert_eq!(warnings(), 1);
+		CDPEngine::accumulate_interest();
+		assert_eq!(warnings(), 1);
+
+		// Updating the threshold re-arms it.
+		assert_ok!(CDPEngine::set_reconciliation_drift_threshold(RuntimeOrigin::root(), None));
+		assert!(!DriftWarningEmitted::<Test>::get());
+	});
+}
+
+#[test]
+fn settle_cdps_batch_settles_only_accounts_with_debit() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 300, 0));
+		let batch: BoundedVec<AccountId, MaxSettlementBatch> =
+			vec![ALICE, BOB].try_into().unwrap();
+
+		assert_noop!(
+			CDPEngine::settle_cdps_batch(RuntimeOrigin::none(), DOT, batch.clone()),
+			Error::<Test>::MustAfterShutdown
+		);
+
+		set_shutdown(true);
+		assert_ok!(MockPriceSource::lock_price(DOT));
+		assert_ok!(MockPriceSource::lock_price(AUSD));
+
+		// ALICE is settled; BOB has no debit and is skipped rather than failing the batch.
+		assert_ok!(CDPEngine::settle_cdps_batch(RuntimeOrigin::none(), DOT, batch.clone()));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 400, debit: 0 }
+		);
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, BOB),
+			Position { collateral: 300, debit: 0 }
+		);
+		System::assert_has_event(
+			Event::<Test>::SettleCDPInDebit { collateral_type: DOT, owner: ALICE }.into(),
+		);
+
+		// Re-submitting the same batch settles nothing and succeeds.
+		assert_ok!(CDPEngine::settle_cdps_batch(RuntimeOrigin::none(), DOT, batch));
+	});
+}
+
+#[test]
+fn validate_unsigned_accepts_batches_with_work_left() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		let batch = |accounts: Vec<AccountId>| Call::<Test>::settle_cdps_batch {
+			currency_id: DOT,
+			accounts: accounts.try_into().unwrap(),
+		};
+		let validate = |call: &Call<Test>| {
+			<CDPEngine as frame_support::unsigned::ValidateUnsigned>::validate_unsigned(
+				TransactionSource::Local,
+				call,
+			)
+		};
+
+		// Not valid before shutdown, nor before a locked price exists.
+		assert_eq!(validate(&batch(vec![ALICE])), InvalidTransaction::Stale.into());
+		set_shutdown(true);
+		assert_eq!(validate(&batch(vec![ALICE])), InvalidTransaction::Stale.into());
+
+		assert_ok!(MockPriceSource::lock_price(DOT));
+		assert_ok!(MockPriceSource::lock_price(AUSD));
+		assert_ok!(validate(&batch(vec![ALICE, BOB])));
+
+		// A batch with nothing left to settle is stale.
+		assert_eq!(validate(&batch(vec![BOB])), InvalidTransaction::Stale.into());
+	});
+}
+
+#[test]
+fn total_debit_by_fee_groups_currencies_by_stability_fee() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral(DOT);
+		setup_collateral(BTC);
+		assert_ok!(CDPEngine::set_collateral_params(
+			RuntimeOrigin::root(),
+			DOT,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 10))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+		assert_ok!(Loans::adjust_position(&BOB, BTC, 500, 300));
+
+		assert_eq!(
+			CDPEngine::total_debit_by_fee(Some(Rate::saturating_from_rational(1, 10))),
+			200
+		);
+		// BTC has no stability fee configured.
+		assert_eq!(CDPEngine::total_debit_by_fee(None), 300);
+		assert_eq!(
+			CDPEngine::total_debit_by_fee(Some(Rate::saturating_from_rational(1, 100))),
+			0
+		);
+	});
+}
@@ -0,0 +1,557 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Emergency Shutdown Pallet
+//!
+//! The final settlement procedure of the Honzon protocol.
+//!
+//! ## Overview
+//!
+//! Emergency shutdown is the protocol's last line of defense: a privileged origin freezes the
+//! system, locking the price of every collateral currency and of the stable currency at their
+//! current feed values. From then on the CDP engine stops accruing stability fees and settles
+//! positions at the locked prices instead of liquidating them.
+//!
+//! Shutdown may also be scheduled a number of blocks in advance, giving users a window to top
+//! up collateral or repay debt before their positions are frozen. A scheduled shutdown can be
+//! cancelled before it activates; the immediate path remains for true emergencies.
+//!
+//! Shutdown itself is reversible while nothing irreversible has happened: as long as no CDP
+//! has been settled and the refund phase has not been opened, the shutdown origin can resume
+//! operations, unlocking the prices again. Once settlement starts the locked prices have been
+//! acted on and the only way forward is the full wind-down.
+//!
+//! After shutdown, once every collateral auction has concluded and the system debit pool is
+//! fully covered by the surplus pool, the same origin may open the refund phase. Stable
+//! currency holders can then burn their holdings in exchange for a proportional share of all
+//! collateral held by the CDP treasury, unwinding the system completely. The refund terms
+//! are fixed the moment the phase opens: the circulating stable issuance and the treasury's
+//! collateral holdings are snapshotted, so every holder receives the same collateral per
+//! stable currency burnt, however late they claim.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use alloc::vec::Vec;
+use frame_support::{pallet_prelude::*, traits::fungibles::Inspect};
+use frame_system::pallet_prelude::*;
+use honzon_support::{
+	AuctionManager, CDPTreasury, EmergencyShutdown, LockablePrice, Ratio, SettlementCounter,
+};
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{SaturatedConversion, Saturating, Zero},
+	DispatchResult, FixedPointNumber, Rounding, RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// How each collateral share is rounded when refunding stable currency holders.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum RefundRounding {
+	/// Round toward zero. The treasury retains up to one unit of each collateral per refund.
+	Down,
+	/// Round to the nearest unit (exact halves round down), fairer over many refunds.
+	Nearest,
+}
+
+/// A snapshot of how close the system is to opening the refund phase, returned by
+/// [`Pallet::refund_readiness`].
+#[derive(
+	Encode, Decode, DecodeWithMemTracking, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct RefundReadiness<Balance> {
+	/// The debit units still open across the loans of all collateral currencies, i.e. the
+	/// settlement work left before the treasury's holdings are final.
+	pub total_debit: Balance,
+	/// The collateral still locked in running auctions, summed over all collateral
+	/// currencies.
+	pub total_collateral_in_auction: Balance,
+	/// Whether [`Pallet::open_collateral_refund`] would pass its checks right now.
+	pub can_open_refund: bool,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_loans::Config {
+		/// The origin which may trigger emergency shutdown and open the refund phase.
+		type ShutdownOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The currencies accepted as CDP collateral, whose prices are locked at shutdown.
+		type CollateralCurrencyIds: Get<Vec<Self::CurrencyId>>;
+
+		/// The stable currency. Its price is locked alongside the collaterals so relative
+		/// settlement prices are fixed, and it is what refunds are paid against.
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<Self::CurrencyId>;
+
+		/// The price feed whose current prices are locked when shutdown is triggered.
+		type PriceSource: LockablePrice<Self::CurrencyId>;
+
+		/// The auction manager, queried to ensure no auctions remain before refunds open.
+		type AuctionManagerHandler: AuctionManager<
+			Self::AccountId,
+			Balance = Self::Balance,
+			CurrencyId = Self::CurrencyId,
+		>;
+
+		/// How many CDPs the engine has settled since shutdown. Resuming operations is only
+		/// allowed while this is zero, because settled positions cannot be restored.
+		type SettlementCounter: SettlementCounter;
+
+		/// The number of blocks that must pass between shutdown and the refund phase
+		/// opening, giving off-chain parties time to verify the settlement before
+		/// governance locks in the refund terms. Zero disables the delay.
+		#[pallet::constant]
+		type RefundDelay: Get<BlockNumberFor<Self>>;
+
+		/// How each collateral share is rounded during refunds.
+		///
+		/// Payouts are capped at the treasury's holdings, so nearest rounding can never
+		/// over-withdraw.
+		type RefundRounding: Get<RefundRounding>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The system has already been shut down.
+		AlreadyShutdown,
+		/// The operation is only available after emergency shutdown.
+		MustAfterShutdown,
+		/// The refund phase has not been opened.
+		CanNotRefund,
+		/// No shutdown is scheduled.
+		NoScheduledShutdown,
+		/// Collateral auctions are still running, so the final surplus is unknown.
+		ExistPotentialSurplus,
+		/// The system debit pool is not yet covered by the surplus pool.
+		ExistUnhandledDebit,
+		/// The refund phase has been opened; the wind-down is irreversible.
+		RefundAlreadyOpened,
+		/// CDPs have been settled at the locked prices; the shutdown cannot be reverted.
+		AlreadySettled,
+		/// The refund would pay out less collateral than the caller's stated minimum.
+		RefundBelowMinimum,
+		/// Not enough blocks have passed since shutdown to open the refund phase.
+		RefundDelayNotElapsed,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Emergency shutdown has been triggered.
+		Shutdown { block_number: BlockNumberFor<T> },
+		/// A shutdown has been scheduled to activate at `effective_at`.
+		ShutdownScheduled { effective_at: BlockNumberFor<T> },
+		/// The scheduled shutdown has been cancelled before activating.
+		ShutdownCancelled { effective_at: BlockNumberFor<T> },
+		/// The shutdown has been reverted and normal operations have resumed.
+		Resumed { block_number: BlockNumberFor<T> },
+		/// The refund phase has been opened.
+		OpenRefund { block_number: BlockNumberFor<T> },
+		/// Stable currency has been refunded for collateral.
+		Refund {
+			who: T::AccountId,
+			stable_coin_amount: T::Balance,
+			refund_list: Vec<(T::CurrencyId, T::Balance)>,
+		},
+	}
+
+	/// Whether emergency shutdown has been triggered.
+	#[pallet::storage]
+	pub type IsShutdown<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Whether the refund phase has been opened.
+	#[pallet::storage]
+	pub type CanRefund<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The block at which a scheduled shutdown activates, if one is pending.
+	#[pallet::storage]
+	pub type PendingShutdown<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+	/// The block at which shutdown was triggered. The refund phase cannot open until
+	/// [`Config::RefundDelay`] blocks later. Cleared if operations resume.
+	#[pallet::storage]
+	pub type ShutdownAt<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+	/// The freely circulating stable issuance snapshotted when the refund phase opened.
+	/// Refund ratios are computed against this, not against live issuance.
+	#[pallet::storage]
+	pub type RefundStableSnapshot<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// The treasury's collateral holdings snapshotted when the refund phase opened, per
+	/// currency. Refund shares are computed against these, not against live holdings.
+	#[pallet::storage]
+	pub type RefundCollateralSnapshot<T: Config> =
+		StorageMap<_, Twox64Concat, T::CurrencyId, T::Balance, ValueQuery>;
+
+	/// The total collateral refunded so far, per currency. Never exceeds the corresponding
+	/// [`RefundCollateralSnapshot`] entry.
+	#[pallet::storage]
+	pub type TotalRefunded<T: Config> =
+		StorageMap<_, Twox64Concat, T::CurrencyId, T::Balance, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			match PendingShutdown::<T>::get() {
+				Some(effective_at) if now >= effective_at => {
+					// Leave the schedule in place on failure (e.g. a price feed gap) so the
+					// activation retries next block.
+					if Self::do_shutdown().is_ok() {
+						PendingShutdown::<T>::kill();
+					}
+					T::WeightInfo::emergency_shutdown(
+						T::CollateralCurrencyIds::get().len() as u32
+					)
+					.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+				},
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Trigger emergency shutdown: lock the prices of all collateral currencies and of
+		/// the stable currency at their current feed values and freeze the system.
+		///
+		/// May only be called from `T::ShutdownOrigin`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::emergency_shutdown(
+			T::CollateralCurrencyIds::get().len() as u32
+		))]
+		pub fn emergency_shutdown(origin: OriginFor<T>) -> DispatchResult {
+			T::ShutdownOrigin::ensure_origin(origin)?;
+			// An immediate shutdown supersedes a scheduled one.
+			PendingShutdown::<T>::kill();
+			Self::do_shutdown()
+		}
+
+		/// Schedule emergency shutdown to activate `effective_in` blocks from now, giving
+		/// users time to top up collateral or repay debt before settlement freezes their
+		/// positions at the locked prices. The immediate [`Pallet::emergency_shutdown`]
+		/// remains available for true emergencies.
+		///
+		/// Scheduling again moves the activation block. May only be called from
+		/// `T::ShutdownOrigin`.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::schedule_shutdown())]
+		pub fn schedule_shutdown(
+			origin: OriginFor<T>,
+			effective_in: BlockNumberFor<T>,
+		) -> DispatchResult {
+			T::ShutdownOrigin::ensure_origin(origin)?;
+			ensure!(!IsShutdown::<T>::get(), Error::<T>::AlreadyShutdown);
+
+			let effective_at =
+				frame_system::Pallet::<T>::block_number().saturating_add(effective_in);
+			PendingShutdown::<T>::put(effective_at);
+			Self::deposit_event(Event::<T>::ShutdownScheduled { effective_at });
+			Ok(())
+		}
+
+		/// Cancel a scheduled shutdown before it activates, for false alarms.
+		///
+		/// May only be called from `T::ShutdownOrigin`.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::cancel_scheduled_shutdown())]
+		pub fn cancel_scheduled_shutdown(origin: OriginFor<T>) -> DispatchResult {
+			T::ShutdownOrigin::ensure_origin(origin)?;
+			let effective_at =
+				PendingShutdown::<T>::take().ok_or(Error::<T>::NoScheduledShutdown)?;
+			Self::deposit_event(Event::<T>::ShutdownCancelled { effective_at });
+			Ok(())
+		}
+
+		/// Open the refund phase. Requires that no collateral auctions are running and that
+		/// the system debit pool is fully covered by the surplus pool.
+		///
+		/// May only be called from `T::ShutdownOrigin`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::open_collateral_refund())]
+		pub fn open_collateral_refund(origin: OriginFor<T>) -> DispatchResult {
+			T::ShutdownOrigin::ensure_origin(origin)?;
+			ensure!(IsShutdown::<T>::get(), Error::<T>::MustAfterShutdown);
+			ensure!(Self::refund_delay_elapsed(), Error::<T>::RefundDelayNotElapsed);
+
+			// Running auctions could still change the treasury's holdings.
+			ensure!(
+				T::AuctionManagerHandler::get_total_target_in_auction().is_zero(),
+				Error::<T>::ExistPotentialSurplus,
+			);
+			for currency_id in T::CollateralCurrencyIds::get() {
+				ensure!(
+					T::AuctionManagerHandler::get_total_collateral_in_auction(currency_id)
+						.is_zero(),
+					Error::<T>::ExistPotentialSurplus,
+				);
+			}
+			ensure!(
+				T::CDPTreasury::get_debit_pool() == T::CDPTreasury::get_surplus_pool(),
+				Error::<T>::ExistUnhandledDebit,
+			);
+
+			// Fix the refund terms now: every holder gets the same collateral per stable
+			// currency burnt, even if surplus trickles into the treasury later.
+			let circulating = T::Currency::total_issuance(T::GetStableCurrencyId::get())
+				.saturating_sub(T::CDPTreasury::get_surplus_pool());
+			RefundStableSnapshot::<T>::put(circulating);
+			for currency_id in T::CollateralCurrencyIds::get() {
+				RefundCollateralSnapshot::<T>::insert(
+					currency_id,
+					T::CDPTreasury::get_total_collaterals(currency_id),
+				);
+			}
+
+			CanRefund::<T>::put(true);
+			Self::deposit_event(Event::<T>::OpenRefund {
+				block_number: frame_system::Pallet::<T>::block_number(),
+			});
+			Ok(())
+		}
+
+		/// Burn `amount` of the caller's stable currency in exchange for a proportional share
+		/// of every collateral held by the CDP treasury.
+		///
+		/// `min_collateral_out` is a slippage guard: if the total collateral units paid out
+		/// across all currencies would fall below it - e.g. because other refunds in the
+		/// same block drained the treasury past the snapshot cap - the whole refund is
+		/// rejected with [`Error::RefundBelowMinimum`] and nothing is burnt. Zero disables
+		/// the guard.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::refund_collaterals(
+			T::CollateralCurrencyIds::get().len() as u32
+		))]
+		pub fn refund_collaterals(
+			origin: OriginFor<T>,
+			#[pallet::compact] amount: T::Balance,
+			#[pallet::compact] min_collateral_out: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(CanRefund::<T>::get(), Error::<T>::CanNotRefund);
+
+			let refund_list = Self::compute_refund(amount);
+			// Checked before anything moves, so a failing guard leaves no state to roll
+			// back.
+			let total_out = refund_list
+				.iter()
+				.fold(T::Balance::zero(), |acc, (_, refund_amount)| {
+					acc.saturating_add(*refund_amount)
+				});
+			ensure!(total_out >= min_collateral_out, Error::<T>::RefundBelowMinimum);
+			for (currency_id, refund_amount) in &refund_list {
+				T::CDPTreasury::withdraw_collateral(&who, *currency_id, *refund_amount)?;
+				TotalRefunded::<T>::mutate(currency_id, |total| {
+					total.saturating_accrue(*refund_amount)
+				});
+			}
+			T::CDPTreasury::burn_debit(&who, amount)?;
+
+			Self::deposit_event(Event::<T>::Refund {
+				who,
+				stable_coin_amount: amount,
+				refund_list,
+			});
+			Ok(())
+		}
+
+		/// Revert an emergency shutdown and resume normal operations, unlocking the prices
+		/// of all collateral currencies and of the stable currency.
+		///
+		/// Only possible while the shutdown has had no irreversible effect: no CDP may have
+		/// been settled at the locked prices and the refund phase must not have been opened.
+		///
+		/// May only be called from `T::ShutdownOrigin`.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::resume_operations(
+			T::CollateralCurrencyIds::get().len() as u32
+		))]
+		pub fn resume_operations(origin: OriginFor<T>) -> DispatchResult {
+			T::ShutdownOrigin::ensure_origin(origin)?;
+			ensure!(IsShutdown::<T>::get(), Error::<T>::MustAfterShutdown);
+			ensure!(!CanRefund::<T>::get(), Error::<T>::RefundAlreadyOpened);
+			ensure!(
+				T::SettlementCounter::settlements_since_shutdown().is_zero(),
+				Error::<T>::AlreadySettled,
+			);
+
+			// Unlock both sides of the settlement rate, mirroring `do_shutdown`.
+			for currency_id in T::CollateralCurrencyIds::get()
+				.into_iter()
+				.chain(core::iter::once(T::GetStableCurrencyId::get()))
+			{
+				T::PriceSource::unlock_price(currency_id)?;
+			}
+
+			IsShutdown::<T>::kill();
+			ShutdownAt::<T>::kill();
+			Self::deposit_event(Event::<T>::Resumed {
+				block_number: frame_system::Pallet::<T>::block_number(),
+			});
+			Ok(())
+		}
+	}
+
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T> {
+		/// The exact collateral a holder receives for burning `amount` stable currency,
+		/// computed from the snapshots taken when the refund phase opened. Empty before
+		/// the refund phase opens.
+		pub fn claimable_collateral(amount: T::Balance) -> Vec<(T::CurrencyId, T::Balance)> {
+			if !CanRefund::<T>::get() {
+				return Vec::new()
+			}
+			Self::compute_refund(amount)
+		}
+
+		/// How close the system is to opening the refund phase, for operators watching the
+		/// wind-down after shutdown. `can_open_refund` mirrors the checks
+		/// [`Pallet::open_collateral_refund`] performs, so a failing
+		/// [`Error::ExistPotentialSurplus`] or [`Error::ExistUnhandledDebit`] can be read back
+		/// to the outstanding debit and auction totals. Purely informational; nothing is
+		/// written.
+		pub fn refund_readiness() -> RefundReadiness<T::Balance> {
+			let mut total_debit: T::Balance = Zero::zero();
+			let mut total_collateral_in_auction: T::Balance = Zero::zero();
+			for currency_id in T::CollateralCurrencyIds::get() {
+				total_debit
+					.saturating_accrue(pallet_loans::TotalPositions::<T>::get(currency_id).debit);
+				total_collateral_in_auction.saturating_accrue(
+					T::AuctionManagerHandler::get_total_collateral_in_auction(currency_id),
+				);
+			}
+			let can_open_refund = IsShutdown::<T>::get() &&
+				Self::refund_delay_elapsed() &&
+				T::AuctionManagerHandler::get_total_target_in_auction().is_zero() &&
+				total_collateral_in_auction.is_zero() &&
+				T::CDPTreasury::get_debit_pool() == T::CDPTreasury::get_surplus_pool();
+			RefundReadiness { total_debit, total_collateral_in_auction, can_open_refund }
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The collateral refunded for burning `amount` stable currency, a proportional share
+	/// of every snapshotted collateral holding. The ratio is held against the snapshots
+	/// taken when the refund phase opened, so the rate does not drift with later treasury
+	/// movements; payouts are still capped at what the treasury actually holds.
+	fn compute_refund(amount: T::Balance) -> Vec<(T::CurrencyId, T::Balance)> {
+		// The share of the snapshotted circulating stable currency being refunded. The
+		// surplus pool nets out against the debit pool, so the snapshot excluded it.
+		let refund_ratio =
+			Ratio::checked_from_rational(amount, RefundStableSnapshot::<T>::get())
+				.unwrap_or_default();
+
+		let mut refund_list = Vec::new();
+		for currency_id in T::CollateralCurrencyIds::get() {
+			let total = RefundCollateralSnapshot::<T>::get(currency_id);
+			let refund_amount = match T::RefundRounding::get() {
+				RefundRounding::Down => refund_ratio.saturating_mul_int(total),
+				RefundRounding::Nearest => multiply_by_rational_with_rounding(
+					total.saturated_into::<u128>(),
+					refund_ratio.into_inner(),
+					Ratio::DIV,
+					Rounding::NearestPrefDown,
+				)
+				.unwrap_or(u128::MAX)
+				.saturated_into(),
+			}
+			// Never pay out more than the treasury holds, whatever the rounding produced.
+			.min(T::CDPTreasury::get_total_collaterals(currency_id));
+			if !refund_amount.is_zero() {
+				refund_list.push((currency_id, refund_amount));
+			}
+		}
+		refund_list
+	}
+
+	/// Lock the prices of all collateral currencies and of the stable currency at their
+	/// current feed values and freeze the system.
+	fn do_shutdown() -> DispatchResult {
+		ensure!(!IsShutdown::<T>::get(), Error::<T>::AlreadyShutdown);
+
+		// Lock the stable currency as well: settlement prices are relative, so both sides
+		// of the rate must be frozen.
+		for currency_id in T::CollateralCurrencyIds::get()
+			.into_iter()
+			.chain(core::iter::once(T::GetStableCurrencyId::get()))
+		{
+			T::PriceSource::lock_price(currency_id)?;
+		}
+
+		IsShutdown::<T>::put(true);
+		let block_number = frame_system::Pallet::<T>::block_number();
+		ShutdownAt::<T>::put(block_number);
+		Self::deposit_event(Event::<T>::Shutdown { block_number });
+		Ok(())
+	}
+
+	/// Whether [`Config::RefundDelay`] blocks have passed since shutdown. A shutdown that
+	/// predates the delay's introduction has no recorded block and is exempt.
+	fn refund_delay_elapsed() -> bool {
+		ShutdownAt::<T>::get().map_or(true, |at| {
+			frame_system::Pallet::<T>::block_number() >= at.saturating_add(T::RefundDelay::get())
+		})
+	}
+}
+
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Config> Pallet<T> {
+	/// Ensure the correctness of the state of this pallet.
+	pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+		for (currency_id, refunded) in TotalRefunded::<T>::iter() {
+			ensure!(
+				refunded <= RefundCollateralSnapshot::<T>::get(currency_id),
+				"refunded collateral exceeds the refund snapshot"
+			);
+		}
+		Ok(())
+	}
+}
+
+impl<T: Config> EmergencyShutdown for Pallet<T> {
+	fn is_shutdown() -> bool {
+		IsShutdown::<T>::get()
+	}
+}
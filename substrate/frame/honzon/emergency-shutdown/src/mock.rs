@@ -0,0 +1,339 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocks for the emergency shutdown pallet.
+
+use super::*;
+use crate as pallet_emergency_shutdown;
+
+use frame_support::{derive_impl, parameter_types, traits::fungibles::Mutate, PalletId};
+use frame_system::EnsureRoot;
+use honzon_support::{Price, RiskManager, SettlementCounter};
+use sp_runtime::{BuildStorage, DispatchError};
+use std::collections::BTreeMap;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type Amount = i128;
+pub type CurrencyId = u32;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const TREASURY: AccountId = 100;
+
+pub const DOT: CurrencyId = 1;
+pub const BTC: CurrencyId = 2;
+pub const AUSD: CurrencyId = 9;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Assets: pallet_assets,
+		Loans: pallet_loans,
+		EmergencyShutdownModule: pallet_emergency_shutdown,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+#[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type CreateOrigin =
+		frame_support::traits::AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+	pub static LivePrices: BTreeMap<CurrencyId, Price> = BTreeMap::new();
+	pub static LockedPrices: BTreeMap<CurrencyId, Price> = BTreeMap::new();
+	pub static Auctions: Vec<(AccountId, CurrencyId, Balance, Balance)> = Vec::new();
+	pub static DebitPool: Balance = 0;
+	pub static SettledSinceShutdown: u32 = 0;
+}
+
+pub struct MockSettlementCounter;
+impl SettlementCounter for MockSettlementCounter {
+	fn settlements_since_shutdown() -> u32 {
+		SettledSinceShutdown::get()
+	}
+}
+
+pub struct MockPriceSource;
+impl LockablePrice<CurrencyId> for MockPriceSource {
+	fn lock_price(currency_id: CurrencyId) -> DispatchResult {
+		if let Some(price) = LivePrices::get().get(&currency_id).copied() {
+			let mut prices = LockedPrices::get();
+			prices.insert(currency_id, price);
+			LockedPrices::set(prices);
+		}
+		Ok(())
+	}
+
+	fn unlock_price(currency_id: CurrencyId) -> DispatchResult {
+		let mut prices = LockedPrices::get();
+		prices.remove(&currency_id);
+		LockedPrices::set(prices);
+		Ok(())
+	}
+}
+
+/// Records started auctions without running them.
+pub struct MockAuctionManager;
+impl AuctionManager<AccountId> for MockAuctionManager {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+	type AuctionId = u32;
+	type BlockNumber = u64;
+
+	fn new_collateral_auction(
+		refund_recipient: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+		target: Balance,
+	) -> DispatchResult {
+		let mut auctions = Auctions::get();
+		auctions.push((*refund_recipient, currency_id, amount, target));
+		Auctions::set(auctions);
+		Ok(())
+	}
+
+	fn new_forward_auction(
+		_currency_id: CurrencyId,
+		_amount: Balance,
+		_min_price_per_unit: Price,
+		_duration: u64,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn cancel_auction(_id: u32) -> DispatchResult {
+		Ok(())
+	}
+
+	fn get_total_collateral_in_auction(currency_id: CurrencyId) -> Balance {
+		Auctions::get()
+			.iter()
+			.filter(|(_, auction_currency, _, _)| *auction_currency == currency_id)
+			.map(|(_, _, amount, _)| amount)
+			.sum()
+	}
+
+	fn get_total_target_in_auction() -> Balance {
+		Auctions::get().iter().map(|(_, _, _, target)| target).sum()
+	}
+}
+
+/// Values the debit at half the debit unit balance and never rejects a position.
+pub struct MockRiskManager;
+impl RiskManager<AccountId, CurrencyId, Balance> for MockRiskManager {
+	fn get_debit_value(_currency_id: CurrencyId, debit_balance: Balance) -> Balance {
+		debit_balance / 2
+	}
+
+	fn check_position_valid(
+		_currency_id: CurrencyId,
+		_collateral_balance: Balance,
+		_debit_balance: Balance,
+		_check_required_ratio: bool,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn check_debit_cap(_currency_id: CurrencyId, total_debit_balance: Balance) -> DispatchResult {
+		if total_debit_balance <= 10_000 {
+			Ok(())
+		} else {
+			Err(DispatchError::Other("debit cap exceeded"))
+		}
+	}
+
+	fn minimum_collateral() -> Balance {
+		0
+	}
+}
+
+/// Issues and burns the stable asset directly and parks collateral on `TREASURY`.
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		Assets::balance(AUSD, TREASURY)
+	}
+
+	fn get_debit_pool() -> Balance {
+		DebitPool::get()
+	}
+
+	fn get_total_collaterals(currency_id: CurrencyId) -> Balance {
+		Assets::balance(currency_id, TREASURY)
+	}
+
+	fn on_system_debit(amount: Balance) -> DispatchResult {
+		DebitPool::mutate(|pool| *pool += amount);
+		Ok(())
+	}
+
+	fn on_system_surplus(amount: Balance) -> DispatchResult {
+		Assets::mint_into(AUSD, &TREASURY, amount).map(|_| ())
+	}
+
+	fn issue_debit(who: &AccountId, debit: Balance, _backed: bool) -> DispatchResult {
+		Assets::mint_into(AUSD, who, debit).map(|_| ())
+	}
+
+	fn burn_debit(who: &AccountId, debit: Balance) -> DispatchResult {
+		Assets::burn_from(
+			AUSD,
+			who,
+			debit,
+			frame_support::traits::tokens::Preservation::Expendable,
+			frame_support::traits::tokens::Precision::Exact,
+			frame_support::traits::tokens::Fortitude::Polite,
+		)
+		.map(|_| ())
+	}
+
+	fn deposit_surplus(from: &AccountId, surplus: Balance) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(
+			AUSD,
+			from,
+			&TREASURY,
+			surplus,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+
+	fn deposit_collateral(
+		from: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(
+			currency_id,
+			from,
+			&TREASURY,
+			amount,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+
+	fn withdraw_collateral(
+		to: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(
+			currency_id,
+			&TREASURY,
+			to,
+			amount,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)
+		.map(|_| ())
+	}
+}
+
+parameter_types! {
+	pub const LoansPalletId: PalletId = PalletId(*b"py/loans");
+	pub const MaxPositionsPageSize: u32 = 100;
+}
+
+impl pallet_loans::Config for Test {
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+	type Amount = Amount;
+	type Currency = Assets;
+	type RiskManager = MockRiskManager;
+	type CDPTreasury = MockCDPTreasury;
+	type OnUpdateLoan = ();
+	type PalletId = LoansPalletId;
+	type MaxPositionsPageSize = MaxPositionsPageSize;
+}
+
+parameter_types! {
+	pub CollateralCurrencies: Vec<CurrencyId> = vec![DOT, BTC];
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	pub static RefundRoundingMode: RefundRounding = RefundRounding::Down;
+	pub static RefundDelay: u64 = 0;
+}
+
+impl Config for Test {
+	type ShutdownOrigin = EnsureRoot<AccountId>;
+	type CollateralCurrencyIds = CollateralCurrencies;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type PriceSource = MockPriceSource;
+	type AuctionManagerHandler = MockAuctionManager;
+	type SettlementCounter = MockSettlementCounter;
+	type RefundDelay = RefundDelay;
+	type RefundRounding = RefundRoundingMode;
+	type WeightInfo = ();
+}
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		LivePrices::set(BTreeMap::from([
+			(DOT, Price::saturating_from_integer(1)),
+			(BTC, Price::saturating_from_integer(2)),
+			(AUSD, Price::saturating_from_integer(1)),
+		]));
+		LockedPrices::set(BTreeMap::new());
+		Auctions::set(Vec::new());
+		DebitPool::set(0);
+		SettledSinceShutdown::set(0);
+		RefundRoundingMode::set(RefundRounding::Down);
+		RefundDelay::set(0);
+
+		let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			for currency_id in [DOT, BTC, AUSD] {
+				assert_eq!(
+					Assets::force_create(RuntimeOrigin::root(), currency_id, TREASURY, true, 1),
+					Ok(())
+				);
+			}
+		});
+		ext
+	}
+}
@@ -0,0 +1,431 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the emergency shutdown pallet.
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::fungibles::Mutate};
+use honzon_support::Price;
+use mock::*;
+use sp_runtime::traits::One;
+
+#[test]
+fn emergency_shutdown_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::signed(ALICE)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert!(!EmergencyShutdownModule::is_shutdown());
+
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert!(EmergencyShutdownModule::is_shutdown());
+		// The prices of both collaterals and of the stable currency are locked.
+		assert_eq!(LockedPrices::get().get(&DOT), Some(&Price::one()));
+		assert_eq!(
+			LockedPrices::get().get(&BTC),
+			Some(&Price::saturating_from_integer(2))
+		);
+		assert_eq!(LockedPrices::get().get(&AUSD), Some(&Price::one()));
+		System::assert_last_event(Event::<Test>::Shutdown { block_number: 1 }.into());
+
+		assert_noop!(
+			EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn scheduled_shutdown_activates_at_the_announced_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EmergencyShutdownModule::schedule_shutdown(RuntimeOrigin::signed(ALICE), 10),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(EmergencyShutdownModule::schedule_shutdown(RuntimeOrigin::root(), 10));
+		assert_eq!(PendingShutdown::<Test>::get(), Some(11));
+		System::assert_last_event(Event::<Test>::ShutdownScheduled { effective_at: 11 }.into());
+
+		// Re-scheduling moves the activation block.
+		assert_ok!(EmergencyShutdownModule::schedule_shutdown(RuntimeOrigin::root(), 5));
+		assert_eq!(PendingShutdown::<Test>::get(), Some(6));
+
+		// Nothing happens before the activation block; users can still adjust positions.
+		System::set_block_number(5);
+		EmergencyShutdownModule::on_initialize(5);
+		assert!(!EmergencyShutdownModule::is_shutdown());
+		assert!(LockedPrices::get().is_empty());
+
+		System::set_block_number(6);
+		EmergencyShutdownModule::on_initialize(6);
+		assert!(EmergencyShutdownModule::is_shutdown());
+		assert_eq!(LockedPrices::get().get(&DOT), Some(&Price::one()));
+		assert!(PendingShutdown::<Test>::get().is_none());
+		System::assert_last_event(Event::<Test>::Shutdown { block_number: 6 }.into());
+
+		assert_noop!(
+			EmergencyShutdownModule::schedule_shutdown(RuntimeOrigin::root(), 10),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn scheduled_shutdown_can_be_cancelled() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EmergencyShutdownModule::cancel_scheduled_shutdown(RuntimeOrigin::root()),
+			Error::<Test>::NoScheduledShutdown
+		);
+		assert_ok!(EmergencyShutdownModule::schedule_shutdown(RuntimeOrigin::root(), 5));
+		assert_noop!(
+			EmergencyShutdownModule::cancel_scheduled_shutdown(RuntimeOrigin::signed(ALICE)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(EmergencyShutdownModule::cancel_scheduled_shutdown(RuntimeOrigin::root()));
+		assert!(PendingShutdown::<Test>::get().is_none());
+		System::assert_last_event(Event::<Test>::ShutdownCancelled { effective_at: 6 }.into());
+
+		// The false alarm passed: nothing activates.
+		System::set_block_number(6);
+		EmergencyShutdownModule::on_initialize(6);
+		assert!(!EmergencyShutdownModule::is_shutdown());
+
+		// An immediate shutdown supersedes a pending schedule.
+		assert_ok!(EmergencyShutdownModule::schedule_shutdown(RuntimeOrigin::root(), 5));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert!(EmergencyShutdownModule::is_shutdown());
+		assert!(PendingShutdown::<Test>::get().is_none());
+	});
+}
+
+#[test]
+fn open_collateral_refund_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()),
+			Error::<Test>::MustAfterShutdown
+		);
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+
+		// A running collateral auction means the treasury's final holdings are unknown.
+		assert_ok!(MockAuctionManager::new_collateral_auction(&ALICE, DOT, 100, 50));
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()),
+			Error::<Test>::ExistPotentialSurplus
+		);
+		Auctions::set(Vec::new());
+
+		// System debit not covered by the surplus pool.
+		DebitPool::set(5);
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()),
+			Error::<Test>::ExistUnhandledDebit
+		);
+		DebitPool::set(0);
+
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+		assert!(CanRefund::<Test>::get());
+		System::assert_last_event(Event::<Test>::OpenRefund { block_number: 1 }.into());
+	});
+}
+
+#[test]
+fn refund_delay_gates_the_refund_phase() {
+	ExtBuilder::default().build().execute_with(|| {
+		RefundDelay::set(10);
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert_eq!(ShutdownAt::<Test>::get(), Some(1));
+
+		// Nothing else blocks the refund phase, but the delay has not elapsed.
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()),
+			Error::<Test>::RefundDelayNotElapsed
+		);
+		assert!(!EmergencyShutdownModule::refund_readiness().can_open_refund);
+
+		System::set_block_number(10);
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()),
+			Error::<Test>::RefundDelayNotElapsed
+		);
+
+		System::set_block_number(11);
+		assert!(EmergencyShutdownModule::refund_readiness().can_open_refund);
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+	});
+}
+
+#[test]
+fn refund_collaterals_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// 400 stable currency in circulation, of which ALICE holds 100; the treasury holds
+		// 200 DOT and 100 BTC.
+		assert_ok!(Assets::mint_into(AUSD, &ALICE, 100));
+		assert_ok!(Assets::mint_into(AUSD, &BOB, 300));
+		assert_ok!(Assets::mint_into(DOT, &TREASURY, 200));
+		assert_ok!(Assets::mint_into(BTC, &TREASURY, 100));
+
+		assert_noop!(
+			EmergencyShutdownModule::refund_collaterals(RuntimeOrigin::signed(ALICE), 100, 0),
+			Error::<Test>::CanNotRefund
+		);
+
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+
+		// Burning a quarter of the circulating stable currency refunds a quarter of every
+		// collateral.
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(
+			RuntimeOrigin::signed(ALICE),
+			100,
+			0
+		));
+		assert_eq!(Assets::balance(AUSD, ALICE), 0);
+		assert_eq!(Assets::balance(DOT, ALICE), 50);
+		assert_eq!(Assets::balance(BTC, ALICE), 25);
+		assert_eq!(Assets::balance(DOT, TREASURY), 150);
+		assert_eq!(Assets::balance(BTC, TREASURY), 75);
+		System::assert_last_event(
+			Event::<Test>::Refund {
+				who: ALICE,
+				stable_coin_amount: 100,
+				refund_list: vec![(DOT, 50), (BTC, 25)],
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn refund_terms_are_snapshotted_when_the_phase_opens() {
+	ExtBuilder::default().build().execute_with(|| {
+		// 400 stable currency in circulation against 200 DOT and 100 BTC in the treasury.
+		assert_ok!(Assets::mint_into(AUSD, &ALICE, 100));
+		assert_ok!(Assets::mint_into(AUSD, &BOB, 300));
+		assert_ok!(Assets::mint_into(DOT, &TREASURY, 200));
+		assert_ok!(Assets::mint_into(BTC, &TREASURY, 100));
+
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert!(EmergencyShutdownModule::claimable_collateral(100).is_empty());
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+		assert_eq!(RefundStableSnapshot::<Test>::get(), 400);
+		assert_eq!(RefundCollateralSnapshot::<Test>::get(DOT), 200);
+		assert_eq!(RefundCollateralSnapshot::<Test>::get(BTC), 100);
+
+		// The view reports the exact entitlement for burning a quarter of the issuance.
+		assert_eq!(EmergencyShutdownModule::claimable_collateral(100), vec![(DOT, 50), (BTC, 25)]);
+
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(RuntimeOrigin::signed(ALICE), 100, 0));
+		assert_eq!(Assets::balance(DOT, ALICE), 50);
+		assert_eq!(Assets::balance(BTC, ALICE), 25);
+		assert_eq!(TotalRefunded::<Test>::get(DOT), 50);
+		assert_eq!(TotalRefunded::<Test>::get(BTC), 25);
+
+		// Surplus trickles into the treasury after refunds opened; the live holdings move
+		// but the refund rate must not.
+		System::set_block_number(5);
+		assert_ok!(Assets::mint_into(DOT, &TREASURY, 40));
+
+		// BOB burns the same amount blocks later and receives exactly what ALICE did.
+		assert_eq!(EmergencyShutdownModule::claimable_collateral(100), vec![(DOT, 50), (BTC, 25)]);
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(RuntimeOrigin::signed(BOB), 100, 0));
+		assert_eq!(Assets::balance(DOT, BOB), 50);
+		assert_eq!(Assets::balance(BTC, BOB), 25);
+		assert_eq!(TotalRefunded::<Test>::get(DOT), 100);
+		assert_eq!(TotalRefunded::<Test>::get(BTC), 50);
+
+		// The refunded totals stay within the snapshot.
+		assert_ok!(EmergencyShutdownModule::do_try_state());
+	});
+}
+
+#[test]
+fn refund_rounding_modes_compare() {
+	// A refund of 1 out of 16 circulating against 10 DOT held is worth 0.625 DOT.
+	let setup = || {
+		assert_ok!(Assets::mint_into(AUSD, &ALICE, 8));
+		assert_ok!(Assets::mint_into(AUSD, &BOB, 8));
+		assert_ok!(Assets::mint_into(DOT, &TREASURY, 10));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(RuntimeOrigin::signed(ALICE), 1, 0));
+	};
+
+	// Rounding down keeps the fractional share in the treasury.
+	ExtBuilder::default().build().execute_with(|| {
+		setup();
+		assert_eq!(Assets::balance(DOT, ALICE), 0);
+		assert_eq!(Assets::balance(DOT, TREASURY), 10);
+	});
+
+	// Nearest rounding pays it out.
+	ExtBuilder::default().build().execute_with(|| {
+		RefundRoundingMode::set(RefundRounding::Nearest);
+		setup();
+		assert_eq!(Assets::balance(DOT, ALICE), 1);
+		assert_eq!(Assets::balance(DOT, TREASURY), 9);
+	});
+}
+
+#[test]
+fn nearest_rounding_never_over_withdraws() {
+	ExtBuilder::default().build().execute_with(|| {
+		RefundRoundingMode::set(RefundRounding::Nearest);
+		assert_ok!(Assets::mint_into(AUSD, &ALICE, 15));
+		assert_ok!(Assets::mint_into(DOT, &TREASURY, 10));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+
+		// Redeem unit by unit: the repeated round-ups must never outrun the treasury, and
+		// full redemption must drain it exactly.
+		for _ in 0..15 {
+			assert_ok!(EmergencyShutdownModule::refund_collaterals(
+				RuntimeOrigin::signed(ALICE),
+				1,
+				0
+			));
+		}
+		assert_eq!(Assets::balance(AUSD, ALICE), 0);
+		assert_eq!(Assets::balance(DOT, ALICE), 10);
+		assert_eq!(Assets::balance(DOT, TREASURY), 0);
+	});
+}
+
+#[test]
+fn resume_operations_reverts_a_fresh_shutdown() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EmergencyShutdownModule::resume_operations(RuntimeOrigin::root()),
+			Error::<Test>::MustAfterShutdown
+		);
+
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert!(!LockedPrices::get().is_empty());
+
+		assert_noop!(
+			EmergencyShutdownModule::resume_operations(RuntimeOrigin::signed(ALICE)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		System::set_block_number(3);
+		assert_ok!(EmergencyShutdownModule::resume_operations(RuntimeOrigin::root()));
+		assert!(!EmergencyShutdownModule::is_shutdown());
+		assert!(ShutdownAt::<Test>::get().is_none());
+		// The prices of both collaterals and of the stable currency are unlocked again.
+		assert!(LockedPrices::get().is_empty());
+		System::assert_last_event(Event::<Test>::Resumed { block_number: 3 }.into());
+
+		// The system is fully operational: it can even be shut down again.
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+	});
+}
+
+#[test]
+fn resume_operations_is_rejected_once_the_shutdown_took_effect() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+
+		// Settled CDPs cannot be restored, so resumption is off the table.
+		SettledSinceShutdown::set(1);
+		assert_noop!(
+			EmergencyShutdownModule::resume_operations(RuntimeOrigin::root()),
+			Error::<Test>::AlreadySettled
+		);
+		SettledSinceShutdown::set(0);
+
+		// Likewise once the refund phase has opened.
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+		assert_noop!(
+			EmergencyShutdownModule::resume_operations(RuntimeOrigin::root()),
+			Error::<Test>::RefundAlreadyOpened
+		);
+	});
+}
+
+#[test]
+fn refund_readiness_reports_wind_down_progress() {
+	ExtBuilder::default().build().execute_with(|| {
+		// Nothing outstanding, but the system has not been shut down.
+		assert_eq!(
+			EmergencyShutdownModule::refund_readiness(),
+			RefundReadiness {
+				total_debit: 0,
+				total_collateral_in_auction: 0,
+				can_open_refund: false,
+			}
+		);
+
+		assert_ok!(Assets::mint_into(DOT, &ALICE, 1_000));
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 100, 40));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert_ok!(MockAuctionManager::new_collateral_auction(&ALICE, DOT, 100, 50));
+		DebitPool::set(5);
+
+		// Open loans and running auctions show up in the totals; the auctions and the
+		// uncovered debit pool both block the refund phase.
+		assert_eq!(
+			EmergencyShutdownModule::refund_readiness(),
+			RefundReadiness {
+				total_debit: 40,
+				total_collateral_in_auction: 100,
+				can_open_refund: false,
+			}
+		);
+
+		// Concluding the auctions is not enough while the debit pool is uncovered.
+		Auctions::set(Vec::new());
+		assert!(!EmergencyShutdownModule::refund_readiness().can_open_refund);
+
+		// Once it is covered, readiness matches what `open_collateral_refund` accepts.
+		DebitPool::set(0);
+		assert!(EmergencyShutdownModule::refund_readiness().can_open_refund);
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+	});
+}
+
+#[test]
+fn refund_collaterals_enforces_the_minimum_collateral_out() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Assets::mint_into(AUSD, &ALICE, 100));
+		assert_ok!(Assets::mint_into(AUSD, &BOB, 300));
+		assert_ok!(Assets::mint_into(DOT, &TREASURY, 200));
+		assert_ok!(Assets::mint_into(BTC, &TREASURY, 100));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(RuntimeOrigin::root()));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(RuntimeOrigin::root()));
+
+		// ALICE's quarter share is 50 DOT + 25 BTC = 75 collateral units. A minimum just
+		// above that rejects the refund before anything is burnt or withdrawn.
+		assert_noop!(
+			EmergencyShutdownModule::refund_collaterals(RuntimeOrigin::signed(ALICE), 100, 76),
+			Error::<Test>::RefundBelowMinimum
+		);
+		assert_eq!(Assets::balance(AUSD, ALICE), 100);
+		assert_eq!(Assets::balance(DOT, TREASURY), 200);
+
+		// A minimum exactly at the payout passes.
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(
+			RuntimeOrigin::signed(ALICE),
+			100,
+			75
+		));
+		assert_eq!(Assets::balance(AUSD, ALICE), 0);
+		assert_eq!(Assets::balance(DOT, ALICE), 50);
+		assert_eq!(Assets::balance(BTC, ALICE), 25);
+	});
+}
@@ -0,0 +1,693 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Honzon Pallet
+//!
+//! The user-facing front end of the Honzon protocol.
+//!
+//! ## Overview
+//!
+//! The loans pallet exposes no extrinsics of its own; this pallet is how end users adjust
+//! their collateralized debit positions. `adjust_loan` is the plain signed entry point,
+//! taking the debit delta in internal debit units. `adjust_loan_by_debit_value` takes it in
+//! stable currency value instead — MakerDAO-style "draw/repay X aUSD" semantics — with the
+//! unit conversion done by the CDP engine at the current debit exchange rate.
+//!
+//! `adjust_loan_signed` adds meta-transaction support for users who hold no native fee token:
+//! the position owner signs an [`AdjustLoanPayload`] offline and any relayer submits it as the
+//! signed origin, paying the transaction fees. The payload is bound to the owner by a
+//! signature over its SCALE encoding (any scheme the configured [`Config::Signature`] type
+//! accepts, e.g. sr25519, ed25519 or ecdsa via `MultiSignature`), to a point in time by a
+//! deadline block, and to a single execution by the owner's [`MetaNonce`]. The payload may
+//! also authorize a stable currency reimbursement from the owner to the relayer, compensating
+//! the relayer without the owner ever touching the native token.
+//!
+//! An owner can also delegate position management: `authorize` lets a named delegate adjust
+//! all of the owner's positions through `adjust_loan_for`, with a deposit held per delegate
+//! to discourage unbounded authorization lists. The deposit is released again on
+//! `unauthorize` (or `unauthorize_all`). Whole positions can also move between accounts
+//! without closing and reopening: an authorized delegate can pull an owner's position onto its
+//! own account with `transfer_loan_from`, e.g. to migrate a loan onto a proxy or multisig, and
+//! an owner can hand its position over directly with `transfer_loan`.
+//!
+//! All position adjustments are blocked after emergency shutdown; positions are then settled
+//! by the CDP engine instead.
+//!
+//! Accounts can opt into an on-chain position journal with `enable_journal`: every
+//! adjustment, liquidation and settlement touching an opted-in account's positions appends a
+//! [`JournalEntry`] to a bounded per-account ring buffer, letting light clients reconstruct a
+//! position's lifecycle from state alone instead of replaying historical events. Accounts
+//! that never opt in pay nothing; the journal is only written for accounts with an entry in
+//! [`PositionJournal`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{
+		fungible::MutateHold,
+		fungibles::Mutate,
+		tokens::{Precision, Preservation},
+	},
+};
+use frame_system::pallet_prelude::*;
+use honzon_support::{EmergencyShutdown, OnLiquidation, Ratio};
+use pallet_loans::Position;
+use sp_runtime::{
+	traits::{IdentifyAccount, Saturating, Verify, Zero},
+	DispatchResult, RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// A loan adjustment authorized by the position owner's signature rather than by a signed
+/// origin, so a relayer can submit (and pay for) it on the owner's behalf.
+#[derive(Encode, Decode, DecodeWithMemTracking, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct AdjustLoanPayload<CurrencyId, Amount, Balance, BlockNumber> {
+	/// The collateral currency of the position to adjust.
+	pub currency_id: CurrencyId,
+	/// The change in locked collateral.
+	pub collateral_adjustment: Amount,
+	/// The change in issued debit, in debit units.
+	pub debit_adjustment: Amount,
+	/// Must equal the owner's current [`MetaNonce`]; executing the payload bumps it, so each
+	/// payload runs at most once.
+	pub nonce: u32,
+	/// The last block at which the payload may execute.
+	pub deadline_block: BlockNumber,
+	/// Stable currency transferred from the owner to the relayer as fee compensation. Zero
+	/// authorizes no reimbursement.
+	pub fee_reimbursement: Balance,
+}
+
+/// The [`AdjustLoanPayload`] type of a runtime.
+pub type AdjustLoanPayloadOf<T> = AdjustLoanPayload<
+	<T as pallet_loans::Config>::CurrencyId,
+	<T as pallet_loans::Config>::Amount,
+	<T as pallet_loans::Config>::Balance,
+	BlockNumberFor<T>,
+>;
+
+/// The kind tags recorded on [`JournalEntry`] values.
+pub mod journal_kind {
+	/// A voluntary position adjustment by the owner or a delegate.
+	pub const ADJUSTMENT: u8 = 0;
+	/// Collateral and debit confiscated by an unsafe-CDP liquidation.
+	pub const LIQUIDATION: u8 = 1;
+	/// Collateral and debit confiscated by settlement after emergency shutdown.
+	pub const SETTLEMENT: u8 = 2;
+}
+
+/// One position-mutating operation recorded in an opted-in account's journal. Confiscations
+/// (liquidation and settlement) are recorded with negative deltas, mirroring how the same
+/// operation would look as an adjustment.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct JournalEntry<Amount, BlockNumber> {
+	/// The block in which the operation executed.
+	pub block: BlockNumber,
+	/// What kind of operation this was; one of the [`journal_kind`] constants.
+	pub kind: u8,
+	/// The change in locked collateral.
+	pub collateral_delta: Amount,
+	/// The change in issued debit, in debit units.
+	pub debit_delta: Amount,
+}
+
+/// An account's journal: the most recent position-mutating operations, oldest first. Once
+/// full, each new entry evicts the oldest.
+pub type JournalOf<T> = BoundedVec<
+	JournalEntry<<T as pallet_loans::Config>::Amount, BlockNumberFor<T>>,
+	ConstU32<64>,
+>;
+
+/// The coarse health classification of a position returned by
+/// [`Pallet::position_health_band`], so front ends can colour positions without replicating
+/// the collateral ratio math or the configured bands.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum HealthBand {
+	/// The collateral ratio is comfortably above the liquidation ratio, or the position
+	/// carries no debit at all.
+	Healthy,
+	/// The collateral ratio has dropped below [`Config::WarningRatioMultiple`] times the
+	/// liquidation ratio.
+	Warning,
+	/// The collateral ratio has dropped below [`Config::DangerRatioMultiple`] times the
+	/// liquidation ratio; liquidation is imminent or already possible.
+	Danger,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_cdp_engine::Config {
+		/// The signature type accepted on meta-transaction payloads. `MultiSignature` in a
+		/// production runtime, covering sr25519, ed25519 and ecdsa.
+		type Signature: Verify<Signer = Self::Public> + Parameter + DecodeWithMemTracking;
+
+		/// The public key type matching [`Config::Signature`], identifying the signing
+		/// account.
+		type Public: IdentifyAccount<AccountId = Self::AccountId> + Parameter;
+
+		/// The native currency, in which authorization deposits are held.
+		type NativeBalance: MutateHold<
+			Self::AccountId,
+			Reason = Self::RuntimeHoldReason,
+			Balance = Self::Balance,
+		>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+
+		/// The deposit held per authorized delegate, discouraging unbounded authorization
+		/// lists.
+		#[pallet::constant]
+		type DepositPerAuthorization: Get<Self::Balance>;
+
+		/// The emergency shutdown state of the system.
+		type EmergencyShutdown: EmergencyShutdown;
+
+		/// The multiple of the liquidation ratio below which a position's collateral ratio
+		/// classifies it as [`HealthBand::Warning`].
+		#[pallet::constant]
+		type WarningRatioMultiple: Get<Ratio>;
+
+		/// The multiple of the liquidation ratio below which a position's collateral ratio
+		/// classifies it as [`HealthBand::Danger`]. Should not exceed
+		/// [`Config::WarningRatioMultiple`].
+		#[pallet::constant]
+		type DangerRatioMultiple: Get<Ratio>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The operation is no longer available after emergency shutdown.
+		AlreadyShutdown,
+		/// The payload signature does not verify against the owner's account.
+		InvalidSignature,
+		/// The payload nonce does not match the owner's current meta nonce.
+		InvalidMetaNonce,
+		/// The payload's deadline block has passed.
+		PayloadExpired,
+		/// The delegate is already authorized.
+		AlreadyAuthorized,
+		/// The delegate is not authorized.
+		AuthorizationNotExists,
+		/// The caller is not authorized to adjust the owner's positions.
+		NoPermission,
+		/// The adjustment would change neither collateral nor debit.
+		NoAdjustment,
+		/// The caller has already enabled its position journal.
+		JournalAlreadyEnabled,
+		/// The caller has not enabled its position journal.
+		JournalNotEnabled,
+	}
+
+	/// A reason for the honzon pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The deposit held per delegate authorized to manage the owner's positions.
+		#[codec(index = 0)]
+		Authorization,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A signed payload has been executed on behalf of its owner.
+		MetaAdjustLoanExecuted { owner: T::AccountId, relayer: T::AccountId, nonce: u32 },
+		/// A relayer has been reimbursed in stable currency for submitting a signed payload.
+		MetaFeeReimbursed { owner: T::AccountId, relayer: T::AccountId, amount: T::Balance },
+		/// An owner has authorized a delegate to adjust all of its positions.
+		Authorized { owner: T::AccountId, delegate: T::AccountId },
+		/// An owner has revoked a delegate's authorization.
+		Unauthorized { owner: T::AccountId, delegate: T::AccountId },
+		/// An owner has revoked all of its delegate authorizations.
+		UnauthorizedAll { owner: T::AccountId },
+		/// A whole position has been transferred between accounts.
+		LoanTransferred { from: T::AccountId, to: T::AccountId, currency_id: T::CurrencyId },
+		/// An account has opted into position journaling.
+		JournalEnabled { who: T::AccountId },
+		/// An account has purged its position journal and opted out of journaling.
+		JournalPurged { who: T::AccountId },
+	}
+
+	/// The next expected payload nonce per owner, preventing meta-transaction replay.
+	#[pallet::storage]
+	pub type MetaNonce<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The delegates authorized to adjust an owner's positions, with the deposit held when
+	/// the authorization was granted.
+	#[pallet::storage]
+	pub type Authorization<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		T::Balance,
+		OptionQuery,
+	>;
+
+	/// The position journals of opted-in accounts. Presence of a (possibly empty) journal is
+	/// what marks an account as opted in; no entries are ever written for absent keys.
+	#[pallet::storage]
+	pub type PositionJournal<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, JournalOf<T>, OptionQuery>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Adjust the caller's position for `currency_id` by the given collateral and debit
+		/// deltas.
+		///
+		/// Not available after emergency shutdown.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::adjust_loan())]
+		pub fn adjust_loan(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			collateral_adjustment: T::Amount,
+			debit_adjustment: T::Amount,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_adjust_loan(&who, currency_id, collateral_adjustment, debit_adjustment)
+		}
+
+		/// Execute a loan adjustment signed offline by `owner`, with the caller acting as
+		/// relayer and paying the transaction fees.
+		///
+		/// `signature` must verify over the SCALE encoding of `payload` against `owner`, the
+		/// payload nonce must match the owner's [`MetaNonce`] and the deadline block must not
+		/// have passed. The adjustment itself executes exactly as if `owner` had called
+		/// [`Call::adjust_loan`]. A non-zero `fee_reimbursement` is paid from the owner to the
+		/// relayer afterwards; if the owner cannot afford it the whole call fails.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::adjust_loan_signed())]
+		pub fn adjust_loan_signed(
+			origin: OriginFor<T>,
+			owner: T::AccountId,
+			payload: AdjustLoanPayloadOf<T>,
+			signature: T::Signature,
+		) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= payload.deadline_block,
+				Error::<T>::PayloadExpired,
+			);
+			ensure!(payload.nonce == MetaNonce::<T>::get(&owner), Error::<T>::InvalidMetaNonce);
+			ensure!(
+				payload.using_encoded(|encoded| signature.verify(encoded, &owner)),
+				Error::<T>::InvalidSignature,
+			);
+
+			MetaNonce::<T>::insert(&owner, payload.nonce.saturating_add(1));
+			Self::do_adjust_loan(
+				&owner,
+				payload.currency_id,
+				payload.collateral_adjustment,
+				payload.debit_adjustment,
+			)?;
+			Self::deposit_event(Event::<T>::MetaAdjustLoanExecuted {
+				owner: owner.clone(),
+				relayer: relayer.clone(),
+				nonce: payload.nonce,
+			});
+
+			if !payload.fee_reimbursement.is_zero() {
+				T::Currency::transfer(
+					T::GetStableCurrencyId::get(),
+					&owner,
+					&relayer,
+					payload.fee_reimbursement,
+					Preservation::Expendable,
+				)?;
+				Self::deposit_event(Event::<T>::MetaFeeReimbursed {
+					owner,
+					relayer,
+					amount: payload.fee_reimbursement,
+				});
+			}
+			Ok(())
+		}
+
+		/// Authorize `delegate` to adjust all of the caller's positions via
+		/// [`Call::adjust_loan_for`], holding `DepositPerAuthorization` from the caller.
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::authorize())]
+		pub fn authorize(origin: OriginFor<T>, delegate: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				!Authorization::<T>::contains_key(&who, &delegate),
+				Error::<T>::AlreadyAuthorized
+			);
+
+			// The deposit is recorded with the authorization so exactly the held amount is
+			// released later, even if the configured deposit changes in between.
+			let deposit = T::DepositPerAuthorization::get();
+			T::NativeBalance::hold(&HoldReason::Authorization.into(), &who, deposit)?;
+			Authorization::<T>::insert(&who, &delegate, deposit);
+			Self::deposit_event(Event::<T>::Authorized { owner: who, delegate });
+			Ok(())
+		}
+
+		/// Revoke the authorization of `delegate`, releasing its deposit.
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::unauthorize())]
+		pub fn unauthorize(origin: OriginFor<T>, delegate: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let deposit = Authorization::<T>::take(&who, &delegate)
+				.ok_or(Error::<T>::AuthorizationNotExists)?;
+			T::NativeBalance::release(
+				&HoldReason::Authorization.into(),
+				&who,
+				deposit,
+				Precision::BestEffort,
+			)?;
+			Self::deposit_event(Event::<T>::Unauthorized { owner: who, delegate });
+			Ok(())
+		}
+
+		/// Revoke all of the caller's delegate authorizations, releasing their deposits.
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::unauthorize_all())]
+		pub fn unauthorize_all(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut total: T::Balance = Zero::zero();
+			for (_, deposit) in Authorization::<T>::drain_prefix(&who) {
+				total.saturating_accrue(deposit);
+			}
+			T::NativeBalance::release(
+				&HoldReason::Authorization.into(),
+				&who,
+				total,
+				Precision::BestEffort,
+			)?;
+			Self::deposit_event(Event::<T>::UnauthorizedAll { owner: who });
+			Ok(())
+		}
+
+		/// Adjust the position of `owner` for `currency_id` as an authorized delegate.
+		///
+		/// Not available after emergency shutdown.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::adjust_loan_for())]
+		pub fn adjust_loan_for(
+			origin: OriginFor<T>,
+			owner: T::AccountId,
+			currency_id: T::CurrencyId,
+			collateral_adjustment: T::Amount,
+			debit_adjustment: T::Amount,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Authorization::<T>::contains_key(&owner, &who), Error::<T>::NoPermission);
+			Self::do_adjust_loan(&owner, currency_id, collateral_adjustment, debit_adjustment)
+		}
+
+		/// Adjust the caller's position for `currency_id`, with the debit delta expressed in
+		/// stable currency value rather than debit units.
+		///
+		/// A positive `debit_value_adjustment` draws new debit worth that value; a negative
+		/// one repays, clamped at the outstanding debt so overpaying just closes the
+		/// position's debt. Adjustments where both deltas are zero are rejected.
+		///
+		/// Not available after emergency shutdown.
+		#[pallet::call_index(6)]
+		#[pallet::weight(if debit_value_adjustment.is_zero() {
+			<T as Config>::WeightInfo::adjust_loan_by_debit_value_collateral_only()
+		} else {
+			<T as Config>::WeightInfo::adjust_loan_by_debit_value()
+		})]
+		pub fn adjust_loan_by_debit_value(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			collateral_adjustment: T::Amount,
+			debit_value_adjustment: T::Amount,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				!collateral_adjustment.is_zero() || !debit_value_adjustment.is_zero(),
+				Error::<T>::NoAdjustment
+			);
+			ensure!(!<T as Config>::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			pallet_cdp_engine::Pallet::<T>::adjust_position_by_debit_value(
+				&who,
+				currency_id,
+				collateral_adjustment,
+				debit_value_adjustment,
+			)
+		}
+
+		/// Move the whole `currency_id` position of `from` onto the caller's position.
+		///
+		/// The caller is the receiving account and must have been authorized by `from` via
+		/// [`Call::authorize`], so position migration (e.g. onto a proxy or multisig) does not
+		/// require closing and reopening the loan. The merged position is validated by the
+		/// risk manager.
+		///
+		/// Not available after emergency shutdown.
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_loan_from())]
+		pub fn transfer_loan_from(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			from: T::AccountId,
+		) -> DispatchResult {
+			let to = ensure_signed(origin)?;
+			ensure!(Authorization::<T>::contains_key(&from, &to), Error::<T>::NoPermission);
+			ensure!(!<T as Config>::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			pallet_loans::Pallet::<T>::transfer_loan(&from, &to, currency_id)?;
+			Self::deposit_event(Event::<T>::LoanTransferred { from, to, currency_id });
+			Ok(())
+		}
+
+		/// Move the caller's whole `currency_id` position onto the account of `to`.
+		///
+		/// The owner-initiated counterpart of [`Call::transfer_loan_from`], e.g. for custody
+		/// migration or handing a position over to its buyer. No authorization is needed to
+		/// give a position away; the merged position of `to` is validated by the risk
+		/// manager.
+		///
+		/// Not available after emergency shutdown.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_loan())]
+		pub fn transfer_loan(
+			origin: OriginFor<T>,
+			currency_id: T::CurrencyId,
+			to: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!<T as Config>::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			pallet_loans::Pallet::<T>::transfer_loan(&who, &to, currency_id)?;
+			Self::deposit_event(Event::<T>::LoanTransferred { from: who, to, currency_id });
+			Ok(())
+		}
+
+		/// Opt the caller into position journaling. From here on, every adjustment,
+		/// liquidation and settlement touching the caller's positions is recorded in
+		/// [`PositionJournal`]; once the journal is full, each new entry evicts the oldest.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::enable_journal())]
+		pub fn enable_journal(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				!PositionJournal::<T>::contains_key(&who),
+				Error::<T>::JournalAlreadyEnabled
+			);
+			PositionJournal::<T>::insert(&who, JournalOf::<T>::default());
+			Self::deposit_event(Event::<T>::JournalEnabled { who });
+			Ok(())
+		}
+
+		/// Delete the caller's position journal and opt out of journaling.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::purge_journal())]
+		pub fn purge_journal(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(PositionJournal::<T>::contains_key(&who), Error::<T>::JournalNotEnabled);
+			PositionJournal::<T>::remove(&who);
+			Self::deposit_event(Event::<T>::JournalPurged { who });
+			Ok(())
+		}
+	}
+
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T> {
+		/// The position journal of `who`, oldest entry first. `None` for accounts that have
+		/// not opted in.
+		pub fn position_journal(who: T::AccountId) -> Option<JournalOf<T>> {
+			PositionJournal::<T>::get(who)
+		}
+
+		/// The health classification of the `currency_id` position of `who`.
+		///
+		/// The current collateral ratio is compared against the liquidation ratio scaled by
+		/// the configured multiples: below [`Config::DangerRatioMultiple`] times the
+		/// liquidation ratio is [`HealthBand::Danger`], below
+		/// [`Config::WarningRatioMultiple`] times is [`HealthBand::Warning`], anything above
+		/// is [`HealthBand::Healthy`]. A position without debit carries no liquidation risk
+		/// and is always healthy; `None` is returned when no live price is available to value
+		/// the collateral.
+		pub fn position_health_band(
+			currency_id: T::CurrencyId,
+			who: T::AccountId,
+		) -> Option<HealthBand> {
+			let Position { debit, .. } = pallet_loans::Positions::<T>::get(currency_id, &who);
+			if debit.is_zero() {
+				return Some(HealthBand::Healthy)
+			}
+			let ratio = pallet_cdp_engine::Pallet::<T>::collateral_ratio(currency_id, who)?;
+			let liquidation_ratio =
+				pallet_cdp_engine::Pallet::<T>::get_liquidation_ratio(currency_id);
+			let band = if ratio < liquidation_ratio.saturating_mul(T::DangerRatioMultiple::get())
+			{
+				HealthBand::Danger
+			} else if ratio <
+				liquidation_ratio.saturating_mul(T::WarningRatioMultiple::get())
+			{
+				HealthBand::Warning
+			} else {
+				HealthBand::Healthy
+			};
+			Some(band)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Adjust the position of `who`, refusing once the system has been shut down.
+	fn do_adjust_loan(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		collateral_adjustment: T::Amount,
+		debit_adjustment: T::Amount,
+	) -> DispatchResult {
+		ensure!(!<T as Config>::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+		pallet_loans::Pallet::<T>::adjust_position(
+			who,
+			currency_id,
+			collateral_adjustment,
+			debit_adjustment,
+		)?;
+		Self::note_journal_entry(
+			who,
+			journal_kind::ADJUSTMENT,
+			collateral_adjustment,
+			debit_adjustment,
+		);
+		Ok(())
+	}
+
+	/// Append an entry to the journal of `who`, evicting the oldest entry when full. A no-op
+	/// for accounts that have not opted in.
+	fn note_journal_entry(
+		who: &T::AccountId,
+		kind: u8,
+		collateral_delta: T::Amount,
+		debit_delta: T::Amount,
+	) {
+		PositionJournal::<T>::mutate(who, |maybe_journal| {
+			if let Some(journal) = maybe_journal {
+				let entry = JournalEntry {
+					block: frame_system::Pallet::<T>::block_number(),
+					kind,
+					collateral_delta,
+					debit_delta,
+				};
+				if journal.try_push(entry).is_err() {
+					journal.remove(0);
+					journal.force_push(entry);
+				}
+			}
+		});
+	}
+
+	/// Record a confiscation of `collateral` and `debit` in the journal of `who`, as the
+	/// negative deltas the same operation would have as an adjustment.
+	fn note_confiscation(who: &T::AccountId, kind: u8, collateral: T::Balance, debit: T::Balance) {
+		if !PositionJournal::<T>::contains_key(who) {
+			return
+		}
+		let (Ok(collateral_delta), Ok(debit_delta)) =
+			(T::Amount::try_from(collateral), T::Amount::try_from(debit))
+		else {
+			// Confiscated amounts come out of a live position, whose deltas all fitted.
+			frame_support::defensive!("confiscated amount not representable as an Amount");
+			return
+		};
+		Self::note_journal_entry(who, kind, -collateral_delta, -debit_delta);
+	}
+}
+
+impl<T: Config> OnLiquidation<T::AccountId, T::CurrencyId, T::Balance> for Pallet<T> {
+	fn on_liquidation(
+		who: &T::AccountId,
+		_currency_id: T::CurrencyId,
+		collateral: T::Balance,
+		debit: T::Balance,
+	) {
+		Self::note_confiscation(who, journal_kind::LIQUIDATION, collateral, debit);
+	}
+
+	fn on_settlement(
+		who: &T::AccountId,
+		_currency_id: T::CurrencyId,
+		collateral: T::Balance,
+		debit: T::Balance,
+	) {
+		Self::note_confiscation(who, journal_kind::SETTLEMENT, collateral, debit);
+	}
+}
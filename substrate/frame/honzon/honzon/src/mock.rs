@@ -0,0 +1,424 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocks for the honzon pallet.
+
+use super::*;
+use crate as pallet_honzon;
+
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{fungibles::Mutate, FindAuthor},
+	PalletId,
+};
+use frame_system::EnsureRoot;
+use honzon_support::{
+	AuctionManager, CDPTreasury, CDPTreasuryExtended, ExchangeRate, LockablePrice,
+	LockedPriceProvider, Price, PriceProvider, Rate, Ratio, RiskManager,
+};
+use sp_runtime::{
+	testing::TestXt,
+	traits::{IdentityLookup, One},
+	transaction_validity::TransactionPriority,
+	AccountId32, BuildStorage, ConsensusEngineId, DispatchError, FixedPointNumber, MultiSignature,
+	MultiSigner,
+};
+
+// Real signatures are verified in the tests, so accounts are 32-byte ids derived from
+// public keys rather than the usual small integers.
+pub type AccountId = AccountId32;
+pub type Balance = u64;
+pub type Amount = i128;
+pub type CurrencyId = u32;
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+pub const TREASURY: AccountId = AccountId32::new([100u8; 32]);
+pub const RELAYER: AccountId = AccountId32::new([101u8; 32]);
+
+pub const DOT: CurrencyId = 1;
+pub const AUSD: CurrencyId = 9;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Assets: pallet_assets,
+		Loans: pallet_loans,
+		CDPEngine: pallet_cdp_engine,
+		Honzon: pallet_honzon,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+	type RuntimeHoldReason = RuntimeHoldReason;
+}
+
+#[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type CreateOrigin =
+		frame_support::traits::AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+impl<LocalCall> frame_system::offchain::CreateTransactionBase<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type RuntimeCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateBare<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_bare(call: Self::RuntimeCall) -> Self::Extrinsic {
+		Extrinsic::new_bare(call)
+	}
+}
+
+parameter_types! {
+	pub static DebitPool: Balance = 0;
+	pub static IsShutdownFlag: bool = false;
+	/// The live price returned for collateral currencies; `None` simulates a missing price
+	/// feed. The stable currency is always priced at one.
+	pub static LivePrice: Option<Price> = Some(Price::one());
+}
+
+/// Prices collateral at [`LivePrice`] (one by default) and the stable currency at one.
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_price(currency_id: CurrencyId) -> Option<Price> {
+		if currency_id == AUSD {
+			Some(Price::one())
+		} else {
+			LivePrice::get()
+		}
+	}
+}
+impl LockablePrice<CurrencyId> for MockPriceSource {
+	fn lock_price(_currency_id: CurrencyId) -> DispatchResult {
+		Ok(())
+	}
+
+	fn unlock_price(_currency_id: CurrencyId) -> DispatchResult {
+		Ok(())
+	}
+}
+impl LockedPriceProvider<CurrencyId> for MockPriceSource {
+	fn locked_price(_currency_id: CurrencyId) -> Option<Price> {
+		None
+	}
+}
+
+/// Accepts every auction request without running anything.
+pub struct MockAuctionManager;
+impl AuctionManager<AccountId> for MockAuctionManager {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+	type AuctionId = u32;
+	type BlockNumber = u64;
+
+	fn new_collateral_auction(
+		_refund_recipient: &AccountId,
+		_currency_id: CurrencyId,
+		_amount: Balance,
+		_target: Balance,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn new_forward_auction(
+		_currency_id: CurrencyId,
+		_amount: Balance,
+		_min_price_per_unit: Price,
+		_duration: u64,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn cancel_auction(_id: u32) -> DispatchResult {
+		Ok(())
+	}
+
+	fn get_total_collateral_in_auction(_currency_id: CurrencyId) -> Balance {
+		0
+	}
+
+	fn get_total_target_in_auction() -> Balance {
+		0
+	}
+}
+
+/// Never finds a block author.
+pub struct MockFindAuthor;
+impl FindAuthor<AccountId> for MockFindAuthor {
+	fn find_author<'a, I>(_digests: I) -> Option<AccountId>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		None
+	}
+}
+
+pub fn set_shutdown(shutdown: bool) {
+	IsShutdownFlag::set(shutdown);
+}
+
+pub struct MockEmergencyShutdown;
+impl EmergencyShutdown for MockEmergencyShutdown {
+	fn is_shutdown() -> bool {
+		IsShutdownFlag::get()
+	}
+}
+
+/// Values the debit at half the debit unit balance, like a debit exchange rate of 1/2.
+pub struct MockRiskManager;
+impl RiskManager<AccountId, CurrencyId, Balance> for MockRiskManager {
+	fn get_debit_value(_currency_id: CurrencyId, debit_balance: Balance) -> Balance {
+		debit_balance / 2
+	}
+
+	fn check_position_valid(
+		_currency_id: CurrencyId,
+		_collateral_balance: Balance,
+		_debit_balance: Balance,
+		_check_required_ratio: bool,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn check_debit_cap(_currency_id: CurrencyId, total_debit_balance: Balance) -> DispatchResult {
+		if total_debit_balance <= 10_000 {
+			Ok(())
+		} else {
+			Err(DispatchError::Other("debit cap exceeded"))
+		}
+	}
+
+	fn minimum_collateral() -> Balance {
+		0
+	}
+}
+
+/// Issues and burns the stable asset directly and parks collateral on `TREASURY`.
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		Assets::balance(AUSD, TREASURY)
+	}
+
+	fn get_debit_pool() -> Balance {
+		DebitPool::get()
+	}
+
+	fn get_total_collaterals(currency_id: CurrencyId) -> Balance {
+		Assets::balance(currency_id, TREASURY)
+	}
+
+	fn on_system_debit(amount: Balance) -> DispatchResult {
+		DebitPool::mutate(|pool| *pool += amount);
+		Ok(())
+	}
+
+	fn on_system_surplus(amount: Balance) -> DispatchResult {
+		Assets::mint_into(AUSD, &TREASURY, amount).map(|_| ())
+	}
+
+	fn issue_debit(who: &AccountId, debit: Balance, _backed: bool) -> DispatchResult {
+		Assets::mint_into(AUSD, who, debit).map(|_| ())
+	}
+
+	fn burn_debit(who: &AccountId, debit: Balance) -> DispatchResult {
+		Assets::burn_from(
+			AUSD,
+			who,
+			debit,
+			Preservation::Expendable,
+			frame_support::traits::tokens::Precision::Exact,
+			frame_support::traits::tokens::Fortitude::Polite,
+		)
+		.map(|_| ())
+	}
+
+	fn deposit_surplus(from: &AccountId, surplus: Balance) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(AUSD, from, &TREASURY, surplus, Preservation::Expendable)
+			.map(|_| ())
+	}
+
+	fn deposit_collateral(
+		from: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(currency_id, from, &TREASURY, amount, Preservation::Expendable)
+			.map(|_| ())
+	}
+
+	fn withdraw_collateral(
+		to: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(currency_id, &TREASURY, to, amount, Preservation::Expendable)
+			.map(|_| ())
+	}
+}
+
+impl CDPTreasuryExtended<AccountId> for MockCDPTreasury {
+	fn create_debt_auction(_amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn create_surplus_auction(_amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const LoansPalletId: PalletId = PalletId(*b"py/loans");
+	pub const MaxPositionsPageSize: u32 = 100;
+}
+
+impl pallet_loans::Config for Test {
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+	type Amount = Amount;
+	type Currency = Assets;
+	type RiskManager = MockRiskManager;
+	type CDPTreasury = MockCDPTreasury;
+	type OnUpdateLoan = ();
+	type PalletId = LoansPalletId;
+	type MaxPositionsPageSize = MaxPositionsPageSize;
+}
+
+parameter_types! {
+	pub CollateralCurrencies: Vec<CurrencyId> = vec![DOT];
+	pub DefaultLiquidationRatio: Ratio = Ratio::saturating_from_rational(3, 2);
+	pub DefaultDebitExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(1, 2);
+	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(1, 10);
+	pub const MinimumDebitValue: Balance = 2;
+	pub const MinimumCollateralAmount: Balance = 10;
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	pub const MaxRiskBucketSize: u32 = 4;
+	pub CriticalRatioThreshold: Ratio = Ratio::saturating_from_rational(2, 3);
+	pub HysteresisBand: Ratio = Ratio::zero();
+	pub const DebtAuctionThreshold: Balance = 100;
+	pub const SurplusBufferSize: Balance = 200;
+	pub const MaxSettlementBatch: u32 = 10;
+	pub const SettlementProgressInterval: u32 = 1;
+	pub const OffchainLiquidationBatchSize: u32 = 1;
+	pub const CdpEngineUnsignedPriority: TransactionPriority = 1 << 20;
+	pub static LiquidationInclusionReward: Balance = 0;
+	pub const MaxLiquidationValue: Balance = 0;
+}
+
+impl pallet_cdp_engine::Config for Test {
+	type UpdateOrigin = EnsureRoot<AccountId>;
+	type CollateralCurrencyIds = CollateralCurrencies;
+	type DefaultLiquidationRatio = DefaultLiquidationRatio;
+	type DefaultDebitExchangeRate = DefaultDebitExchangeRate;
+	type DefaultLiquidationPenalty = DefaultLiquidationPenalty;
+	type MinimumDebitValue = MinimumDebitValue;
+	type MinimumCollateralAmount = MinimumCollateralAmount;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type PriceSource = MockPriceSource;
+	type EmergencyShutdown = MockEmergencyShutdown;
+	type OnLiquidation = Honzon;
+	type LiquidationGuard = ();
+	type MinBlocksSinceLastOracleUpdate = frame_support::traits::ConstU32<0>;
+	type AuctionManagerHandler = MockAuctionManager;
+	type CDPTreasuryHandler = MockCDPTreasury;
+	type LiquidationInclusionReward = LiquidationInclusionReward;
+	type SettlementIncentive = frame_support::traits::ConstU64<0>;
+	type MaxRiskBucketSize = MaxRiskBucketSize;
+	type CriticalRatioThreshold = CriticalRatioThreshold;
+	type HysteresisBand = HysteresisBand;
+	type MaxLiquidationValue = MaxLiquidationValue;
+	type FindAuthor = MockFindAuthor;
+	type DebtAuctionThreshold = DebtAuctionThreshold;
+	type SurplusBufferSize = SurplusBufferSize;
+	type MaxSettlementBatch = MaxSettlementBatch;
+	type SettlementProgressInterval = SettlementProgressInterval;
+	type OffchainLiquidationBatchSize = OffchainLiquidationBatchSize;
+	type UnsignedPriority = CdpEngineUnsignedPriority;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const DepositPerAuthorization: Balance = 100;
+	pub WarningRatioMultiple: Ratio = Ratio::saturating_from_rational(13, 10);
+	pub DangerRatioMultiple: Ratio = Ratio::saturating_from_rational(11, 10);
+}
+
+impl Config for Test {
+	type Signature = MultiSignature;
+	type Public = MultiSigner;
+	type NativeBalance = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type DepositPerAuthorization = DepositPerAuthorization;
+	type EmergencyShutdown = MockEmergencyShutdown;
+	type WarningRatioMultiple = WarningRatioMultiple;
+	type DangerRatioMultiple = DangerRatioMultiple;
+	type WeightInfo = ();
+}
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		DebitPool::set(0);
+		IsShutdownFlag::set(false);
+		LivePrice::set(Some(Price::one()));
+
+		let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			for currency_id in [DOT, AUSD] {
+				assert_eq!(
+					Assets::force_create(RuntimeOrigin::root(), currency_id, TREASURY, true, 1),
+					Ok(())
+				);
+			}
+		});
+		ext
+	}
+}
@@ -0,0 +1,725 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the honzon pallet.
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::fungibles::Mutate};
+use honzon_support::Price;
+use mock::*;
+use pallet_loans::Position;
+use sp_core::Pair;
+use sp_runtime::{FixedPointNumber, MultiSignature, MultiSigner};
+
+fn account(signer: impl Into<MultiSigner>) -> AccountId {
+	signer.into().into_account()
+}
+
+fn payload(nonce: u32, deadline_block: u64, fee_reimbursement: Balance) -> AdjustLoanPayloadOf<Test> {
+	AdjustLoanPayload {
+		currency_id: DOT,
+		collateral_adjustment: 500,
+		debit_adjustment: 200,
+		nonce,
+		deadline_block,
+		fee_reimbursement,
+	}
+}
+
+#[test]
+fn adjust_loan_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 500, 200));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, &alice),
+			Position { collateral: 500, debit: 200 }
+		);
+		// Debit 200 is worth 100 at the mock debit exchange rate of 1/2.
+		assert_eq!(Assets::balance(AUSD, &alice), 100);
+
+		set_shutdown(true);
+		assert_noop!(
+			Honzon::adjust_loan(RuntimeOrigin::signed(alice), DOT, 100, 0),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn adjust_loan_by_debit_value_draws_debit() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+
+		assert_noop!(
+			Honzon::adjust_loan_by_debit_value(RuntimeOrigin::signed(alice.clone()), DOT, 0, 0),
+			Error::<Test>::NoAdjustment
+		);
+
+		// Drawing 50 stable at the debit exchange rate of 1/2 records 100 debit units.
+		assert_ok!(Honzon::adjust_loan_by_debit_value(
+			RuntimeOrigin::signed(alice.clone()),
+			DOT,
+			500,
+			50
+		));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, &alice),
+			Position { collateral: 500, debit: 100 }
+		);
+		assert_eq!(Assets::balance(AUSD, &alice), 50);
+
+		set_shutdown(true);
+		assert_noop!(
+			Honzon::adjust_loan_by_debit_value(RuntimeOrigin::signed(alice), DOT, 100, 0),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn adjust_loan_by_debit_value_clamps_repayment_to_outstanding_debit() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+		assert_ok!(Honzon::adjust_loan_by_debit_value(
+			RuntimeOrigin::signed(alice.clone()),
+			DOT,
+			500,
+			50
+		));
+
+		// Only the 50 stable actually owed is burned; the excess stays with the owner.
+		assert_ok!(Honzon::adjust_loan_by_debit_value(
+			RuntimeOrigin::signed(alice.clone()),
+			DOT,
+			0,
+			-80
+		));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, &alice),
+			Position { collateral: 500, debit: 0 }
+		);
+		assert_eq!(Assets::balance(AUSD, &alice), 0);
+	});
+}
+
+#[test]
+fn adjust_loan_by_debit_value_handles_mixed_adjustments() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+		assert_ok!(Honzon::adjust_loan_by_debit_value(
+			RuntimeOrigin::signed(alice.clone()),
+			DOT,
+			500,
+			50
+		));
+
+		// Withdraw 200 collateral while repaying 20 stable, i.e. 40 debit units.
+		assert_ok!(Honzon::adjust_loan_by_debit_value(
+			RuntimeOrigin::signed(alice.clone()),
+			DOT,
+			-200,
+			-20
+		));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, &alice),
+			Position { collateral: 300, debit: 60 }
+		);
+		assert_eq!(Assets::balance(AUSD, &alice), 30);
+		assert_eq!(Assets::balance(DOT, &alice), 700);
+	});
+}
+
+#[test]
+fn adjust_loan_signed_verifies_all_signature_schemes() {
+	ExtBuilder::default().build().execute_with(|| {
+		let sr25519_pair = sp_core::sr25519::Pair::from_seed(&[1u8; 32]);
+		let ed25519_pair = sp_core::ed25519::Pair::from_seed(&[2u8; 32]);
+		let ecdsa_pair = sp_core::ecdsa::Pair::from_seed(&[3u8; 32]);
+
+		let owners_and_signatures: Vec<(AccountId, MultiSignature)> = {
+			let encoded = payload(0, 10, 0).encode();
+			vec![
+				(account(sr25519_pair.public()), sr25519_pair.sign(&encoded).into()),
+				(account(ed25519_pair.public()), ed25519_pair.sign(&encoded).into()),
+				(account(ecdsa_pair.public()), ecdsa_pair.sign(&encoded).into()),
+			]
+		};
+
+		for (owner, signature) in owners_and_signatures {
+			assert_ok!(Assets::mint_into(DOT, &owner, 1_000));
+			assert_ok!(Honzon::adjust_loan_signed(
+				RuntimeOrigin::signed(RELAYER),
+				owner.clone(),
+				payload(0, 10, 0),
+				signature,
+			));
+			assert_eq!(
+				pallet_loans::Positions::<Test>::get(DOT, &owner),
+				Position { collateral: 500, debit: 200 }
+			);
+			assert_eq!(MetaNonce::<Test>::get(&owner), 1);
+			System::assert_has_event(
+				Event::<Test>::MetaAdjustLoanExecuted {
+					owner,
+					relayer: RELAYER,
+					nonce: 0,
+				}
+				.into(),
+			);
+		}
+	});
+}
+
+#[test]
+fn adjust_loan_signed_rejects_bad_signatures() {
+	ExtBuilder::default().build().execute_with(|| {
+		let owner_pair = sp_core::sr25519::Pair::from_seed(&[1u8; 32]);
+		let owner = account(owner_pair.public());
+		assert_ok!(Assets::mint_into(DOT, &owner, 1_000));
+
+		// Signed by somebody else.
+		let other_pair = sp_core::sr25519::Pair::from_seed(&[2u8; 32]);
+		let signature: MultiSignature = other_pair.sign(&payload(0, 10, 0).encode()).into();
+		assert_noop!(
+			Honzon::adjust_loan_signed(
+				RuntimeOrigin::signed(RELAYER),
+				owner.clone(),
+				payload(0, 10, 0),
+				signature,
+			),
+			Error::<Test>::InvalidSignature
+		);
+
+		// A valid signature over a different payload than the one submitted.
+		let signature: MultiSignature = owner_pair.sign(&payload(0, 10, 0).encode()).into();
+		let mut tampered = payload(0, 10, 0);
+		tampered.debit_adjustment = 400;
+		assert_noop!(
+			Honzon::adjust_loan_signed(
+				RuntimeOrigin::signed(RELAYER),
+				owner.clone(),
+				tampered,
+				signature,
+			),
+			Error::<Test>::InvalidSignature
+		);
+		assert_eq!(MetaNonce::<Test>::get(&owner), 0);
+	});
+}
+
+#[test]
+fn adjust_loan_signed_prevents_replay() {
+	ExtBuilder::default().build().execute_with(|| {
+		let owner_pair = sp_core::sr25519::Pair::from_seed(&[1u8; 32]);
+		let owner = account(owner_pair.public());
+		assert_ok!(Assets::mint_into(DOT, &owner, 1_000));
+
+		// A payload with a future nonce is not yet valid.
+		let early: MultiSignature = owner_pair.sign(&payload(1, 10, 0).encode()).into();
+		assert_noop!(
+			Honzon::adjust_loan_signed(
+				RuntimeOrigin::signed(RELAYER),
+				owner.clone(),
+				payload(1, 10, 0),
+				early,
+			),
+			Error::<Test>::InvalidMetaNonce
+		);
+
+		let signature: MultiSignature = owner_pair.sign(&payload(0, 10, 0).encode()).into();
+		assert_ok!(Honzon::adjust_loan_signed(
+			RuntimeOrigin::signed(RELAYER),
+			owner.clone(),
+			payload(0, 10, 0),
+			signature.clone(),
+		));
+
+		// Submitting the very same payload again must fail: the nonce has moved on.
+		assert_noop!(
+			Honzon::adjust_loan_signed(
+				RuntimeOrigin::signed(RELAYER),
+				owner.clone(),
+				payload(0, 10, 0),
+				signature,
+			),
+			Error::<Test>::InvalidMetaNonce
+		);
+	});
+}
+
+#[test]
+fn adjust_loan_signed_enforces_deadline() {
+	ExtBuilder::default().build().execute_with(|| {
+		let owner_pair = sp_core::sr25519::Pair::from_seed(&[1u8; 32]);
+		let owner = account(owner_pair.public());
+		assert_ok!(Assets::mint_into(DOT, &owner, 1_000));
+
+		let signature: MultiSignature = owner_pair.sign(&payload(0, 1, 0).encode()).into();
+		System::set_block_number(2);
+		assert_noop!(
+			Honzon::adjust_loan_signed(
+				RuntimeOrigin::signed(RELAYER),
+				owner.clone(),
+				payload(0, 1, 0),
+				signature.clone(),
+			),
+			Error::<Test>::PayloadExpired
+		);
+
+		// The deadline block itself is still valid.
+		System::set_block_number(1);
+		assert_ok!(Honzon::adjust_loan_signed(
+			RuntimeOrigin::signed(RELAYER),
+			owner,
+			payload(0, 1, 0),
+			signature,
+		));
+	});
+}
+
+#[test]
+fn adjust_loan_signed_reimburses_relayer() {
+	ExtBuilder::default().build().execute_with(|| {
+		let owner_pair = sp_core::sr25519::Pair::from_seed(&[1u8; 32]);
+		let owner = account(owner_pair.public());
+		assert_ok!(Assets::mint_into(DOT, &owner, 1_000));
+
+		// Drawing debit 200 mints the owner 100 stable currency, of which 30 goes to the
+		// relayer as authorized by the payload.
+		let signature: MultiSignature = owner_pair.sign(&payload(0, 10, 30).encode()).into();
+		assert_ok!(Honzon::adjust_loan_signed(
+			RuntimeOrigin::signed(RELAYER),
+			owner.clone(),
+			payload(0, 10, 30),
+			signature,
+		));
+		assert_eq!(Assets::balance(AUSD, &owner), 70);
+		assert_eq!(Assets::balance(AUSD, &RELAYER), 30);
+		System::assert_has_event(
+			Event::<Test>::MetaFeeReimbursed { owner: owner.clone(), relayer: RELAYER, amount: 30 }
+				.into(),
+		);
+
+		// A reimbursement the owner cannot afford fails the whole call, nonce included.
+		let signature: MultiSignature = owner_pair.sign(&payload(1, 10, 1_000).encode()).into();
+		assert_noop!(
+			Honzon::adjust_loan_signed(
+				RuntimeOrigin::signed(RELAYER),
+				owner.clone(),
+				payload(1, 10, 1_000),
+				signature,
+			),
+			sp_runtime::ArithmeticError::Underflow
+		);
+		assert_eq!(MetaNonce::<Test>::get(&owner), 1);
+	});
+}
+
+#[test]
+fn adjust_loan_signed_blocked_after_shutdown() {
+	ExtBuilder::default().build().execute_with(|| {
+		let owner_pair = sp_core::sr25519::Pair::from_seed(&[1u8; 32]);
+		let owner = account(owner_pair.public());
+		assert_ok!(Assets::mint_into(DOT, &owner, 1_000));
+
+		set_shutdown(true);
+		let signature: MultiSignature = owner_pair.sign(&payload(0, 10, 0).encode()).into();
+		assert_noop!(
+			Honzon::adjust_loan_signed(
+				RuntimeOrigin::signed(RELAYER),
+				owner,
+				payload(0, 10, 0),
+				signature,
+			),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn authorize_holds_a_deposit_per_delegate() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		let bob = AccountId::new([2u8; 32]);
+		let carol = AccountId::new([3u8; 32]);
+
+		// Without free native balance the deposit cannot be held.
+		assert_noop!(
+			Honzon::authorize(RuntimeOrigin::signed(alice.clone()), bob.clone()),
+			sp_runtime::TokenError::FundsUnavailable
+		);
+
+		assert_ok!(
+			<Balances as frame_support::traits::fungible::Mutate<AccountId>>::mint_into(
+				&alice, 1_000
+			)
+		);
+		assert_ok!(Honzon::authorize(RuntimeOrigin::signed(alice.clone()), bob.clone()));
+		System::assert_last_event(
+			Event::<Test>::Authorized { owner: alice.clone(), delegate: bob.clone() }.into(),
+		);
+		assert_eq!(Authorization::<Test>::get(&alice, &bob), Some(100));
+		assert_eq!(Balances::free_balance(&alice), 900);
+		assert_noop!(
+			Honzon::authorize(RuntimeOrigin::signed(alice.clone()), bob.clone()),
+			Error::<Test>::AlreadyAuthorized
+		);
+
+		// Each delegate holds its own deposit.
+		assert_ok!(Honzon::authorize(RuntimeOrigin::signed(alice.clone()), carol.clone()));
+		assert_eq!(Balances::free_balance(&alice), 800);
+
+		// Revoking refunds exactly the deposit held for that delegate.
+		assert_ok!(Honzon::unauthorize(RuntimeOrigin::signed(alice.clone()), bob.clone()));
+		System::assert_last_event(
+			Event::<Test>::Unauthorized { owner: alice.clone(), delegate: bob.clone() }.into(),
+		);
+		assert_eq!(Authorization::<Test>::get(&alice, &bob), None);
+		assert_eq!(Balances::free_balance(&alice), 900);
+		assert_noop!(
+			Honzon::unauthorize(RuntimeOrigin::signed(alice.clone()), bob),
+			Error::<Test>::AuthorizationNotExists
+		);
+
+		assert_ok!(Honzon::unauthorize_all(RuntimeOrigin::signed(alice.clone())));
+		System::assert_last_event(Event::<Test>::UnauthorizedAll { owner: alice.clone() }.into());
+		assert_eq!(Authorization::<Test>::get(&alice, &carol), None);
+		assert_eq!(Balances::free_balance(&alice), 1_000);
+	});
+}
+
+#[test]
+fn adjust_loan_for_requires_authorization() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		let bob = AccountId::new([2u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+		assert_ok!(
+			<Balances as frame_support::traits::fungible::Mutate<AccountId>>::mint_into(
+				&alice, 1_000
+			)
+		);
+
+		assert_noop!(
+			Honzon::adjust_loan_for(
+				RuntimeOrigin::signed(bob.clone()),
+				alice.clone(),
+				DOT,
+				500,
+				200
+			),
+			Error::<Test>::NoPermission
+		);
+
+		// Authorization runs one way: ALICE authorizing BOB does not let ALICE adjust
+		// BOB's positions.
+		assert_ok!(Honzon::authorize(RuntimeOrigin::signed(alice.clone()), bob.clone()));
+		assert_noop!(
+			Honzon::adjust_loan_for(
+				RuntimeOrigin::signed(alice.clone()),
+				bob.clone(),
+				DOT,
+				500,
+				200
+			),
+			Error::<Test>::NoPermission
+		);
+
+		assert_ok!(Honzon::adjust_loan_for(
+			RuntimeOrigin::signed(bob.clone()),
+			alice.clone(),
+			DOT,
+			500,
+			200
+		));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, &alice),
+			Position { collateral: 500, debit: 200 }
+		);
+		// The adjustment runs on the owner's funds; the minted stable goes to the owner.
+		assert_eq!(Assets::balance(AUSD, &alice), 100);
+		assert_eq!(Assets::balance(AUSD, &bob), 0);
+
+		set_shutdown(true);
+		assert_noop!(
+			Honzon::adjust_loan_for(RuntimeOrigin::signed(bob), alice, DOT, 100, 0),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn transfer_loan_from_requires_authorization() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		let bob = AccountId::new([2u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+		assert_ok!(
+			<Balances as frame_support::traits::fungible::Mutate<AccountId>>::mint_into(
+				&alice, 1_000
+			)
+		);
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 500, 200));
+
+		assert_noop!(
+			Honzon::transfer_loan_from(RuntimeOrigin::signed(bob.clone()), DOT, alice.clone()),
+			Error::<Test>::NoPermission
+		);
+
+		// Authorization runs one way: BOB authorizing ALICE does not let ALICE's delegates
+		// pull from BOB, nor BOB pull from ALICE.
+		assert_ok!(
+			<Balances as frame_support::traits::fungible::Mutate<AccountId>>::mint_into(
+				&bob, 1_000
+			)
+		);
+		assert_ok!(Honzon::authorize(RuntimeOrigin::signed(bob.clone()), alice.clone()));
+		assert_noop!(
+			Honzon::transfer_loan_from(RuntimeOrigin::signed(bob), DOT, alice),
+			Error::<Test>::NoPermission
+		);
+	});
+}
+
+#[test]
+fn transfer_loan_from_merges_position_into_receiver() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		let bob = AccountId::new([2u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+		assert_ok!(Assets::mint_into(DOT, &bob, 1_000));
+		assert_ok!(
+			<Balances as frame_support::traits::fungible::Mutate<AccountId>>::mint_into(
+				&alice, 1_000
+			)
+		);
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 500, 200));
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(bob.clone()), DOT, 300, 100));
+
+		assert_ok!(Honzon::authorize(RuntimeOrigin::signed(alice.clone()), bob.clone()));
+		assert_ok!(Honzon::transfer_loan_from(
+			RuntimeOrigin::signed(bob.clone()),
+			DOT,
+			alice.clone()
+		));
+		System::assert_last_event(
+			Event::<Test>::LoanTransferred {
+				from: alice.clone(),
+				to: bob.clone(),
+				currency_id: DOT,
+			}
+			.into(),
+		);
+
+		// The source entry is gone and the receiver holds the combined position.
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, &alice));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, &bob),
+			Position { collateral: 800, debit: 300 }
+		);
+
+		set_shutdown(true);
+		assert_noop!(
+			Honzon::transfer_loan_from(RuntimeOrigin::signed(bob), DOT, alice),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn transfer_loan_moves_position_to_destination() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		let bob = AccountId::new([2u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+		assert_ok!(Assets::mint_into(DOT, &bob, 1_000));
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 500, 200));
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(bob.clone()), DOT, 300, 100));
+
+		// No authorization is needed to give a position away.
+		assert_ok!(Honzon::transfer_loan(
+			RuntimeOrigin::signed(alice.clone()),
+			DOT,
+			bob.clone()
+		));
+		System::assert_last_event(
+			Event::<Test>::LoanTransferred {
+				from: alice.clone(),
+				to: bob.clone(),
+				currency_id: DOT,
+			}
+			.into(),
+		);
+		assert!(!pallet_loans::Positions::<Test>::contains_key(DOT, &alice));
+		assert_eq!(
+			pallet_loans::Positions::<Test>::get(DOT, &bob),
+			Position { collateral: 800, debit: 300 }
+		);
+
+		set_shutdown(true);
+		assert_noop!(
+			Honzon::transfer_loan(RuntimeOrigin::signed(bob), DOT, alice),
+			Error::<Test>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn journal_is_only_written_for_opted_in_accounts() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+
+		// No journal before opting in, no matter how often positions change.
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 500, 200));
+		assert_eq!(Honzon::position_journal(alice.clone()), None);
+
+		assert_noop!(
+			Honzon::purge_journal(RuntimeOrigin::signed(alice.clone())),
+			Error::<Test>::JournalNotEnabled
+		);
+		assert_ok!(Honzon::enable_journal(RuntimeOrigin::signed(alice.clone())));
+		System::assert_last_event(Event::<Test>::JournalEnabled { who: alice.clone() }.into());
+		assert_noop!(
+			Honzon::enable_journal(RuntimeOrigin::signed(alice.clone())),
+			Error::<Test>::JournalAlreadyEnabled
+		);
+
+		// Only adjustments after opting in are journaled.
+		System::set_block_number(3);
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 100, -50));
+		assert_eq!(
+			Honzon::position_journal(alice.clone()).unwrap().into_inner(),
+			vec![JournalEntry {
+				block: 3,
+				kind: journal_kind::ADJUSTMENT,
+				collateral_delta: 100,
+				debit_delta: -50,
+			}]
+		);
+
+		// Purging deletes the journal and opts out again.
+		assert_ok!(Honzon::purge_journal(RuntimeOrigin::signed(alice.clone())));
+		System::assert_last_event(Event::<Test>::JournalPurged { who: alice.clone() }.into());
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 100, 0));
+		assert_eq!(Honzon::position_journal(alice), None);
+	});
+}
+
+#[test]
+fn journal_evicts_the_oldest_entry_when_full() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+		assert_ok!(Honzon::enable_journal(RuntimeOrigin::signed(alice.clone())));
+
+		for block in 1..=65u64 {
+			System::set_block_number(block);
+			assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 10, 0));
+		}
+
+		// The 65th entry evicted the first; the remaining 64 stay ordered oldest first.
+		let journal = Honzon::position_journal(alice).unwrap();
+		assert_eq!(journal.len(), 64);
+		assert_eq!(journal.first().unwrap().block, 2);
+		assert_eq!(journal.last().unwrap().block, 65);
+	});
+}
+
+#[test]
+fn liquidation_journals_the_confiscated_deltas() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+		assert_ok!(Honzon::enable_journal(RuntimeOrigin::signed(alice.clone())));
+
+		// Collateral 100 against debit value 100 sits below the liquidation ratio of 3/2.
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 100, 200));
+		System::set_block_number(5);
+		assert_ok!(CDPEngine::liquidate_unsafe_cdp(alice.clone(), DOT));
+
+		assert_eq!(
+			Honzon::position_journal(alice).unwrap().into_inner(),
+			vec![
+				JournalEntry {
+					block: 1,
+					kind: journal_kind::ADJUSTMENT,
+					collateral_delta: 100,
+					debit_delta: 200,
+				},
+				JournalEntry {
+					block: 5,
+					kind: journal_kind::LIQUIDATION,
+					collateral_delta: -100,
+					debit_delta: -200,
+				},
+			]
+		);
+	});
+}
+
+#[test]
+fn position_health_band_classifies_by_collateral_ratio() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice = AccountId::new([1u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &alice, 1_000));
+
+		// Collateral 500 against debit value 100: the ratio is five times the price. With
+		// the liquidation ratio of 3/2, the warning band starts below a ratio of 1.95 and
+		// the danger band below 1.65.
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(alice.clone()), DOT, 500, 200));
+		assert_eq!(Honzon::position_health_band(DOT, alice.clone()), Some(HealthBand::Healthy));
+
+		// Exactly on the warning threshold is still healthy; just below it is not.
+		LivePrice::set(Some(Price::saturating_from_rational(39, 100)));
+		assert_eq!(Honzon::position_health_band(DOT, alice.clone()), Some(HealthBand::Healthy));
+		LivePrice::set(Some(Price::saturating_from_rational(38, 100)));
+		assert_eq!(Honzon::position_health_band(DOT, alice.clone()), Some(HealthBand::Warning));
+
+		// Likewise on the danger threshold.
+		LivePrice::set(Some(Price::saturating_from_rational(33, 100)));
+		assert_eq!(Honzon::position_health_band(DOT, alice.clone()), Some(HealthBand::Warning));
+		LivePrice::set(Some(Price::saturating_from_rational(32, 100)));
+		assert_eq!(Honzon::position_health_band(DOT, alice.clone()), Some(HealthBand::Danger));
+
+		// Without a price the ratio cannot be computed at all.
+		LivePrice::set(None);
+		assert_eq!(Honzon::position_health_band(DOT, alice), None);
+	});
+}
+
+#[test]
+fn positions_without_debit_are_always_healthy() {
+	ExtBuilder::default().build().execute_with(|| {
+		let bob = AccountId::new([2u8; 32]);
+		assert_ok!(Assets::mint_into(DOT, &bob, 1_000));
+		assert_ok!(Honzon::adjust_loan(RuntimeOrigin::signed(bob.clone()), DOT, 500, 0));
+
+		// No debit means no liquidation risk, even when no price is available; the same
+		// holds for accounts without any position.
+		LivePrice::set(None);
+		assert_eq!(Honzon::position_health_band(DOT, bob), Some(HealthBand::Healthy));
+		let carol = AccountId::new([3u8; 32]);
+		assert_eq!(Honzon::position_health_band(DOT, carol), Some(HealthBand::Healthy));
+	});
+}
@@ -0,0 +1,719 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Loans Pallet
+//!
+//! Manages the debit and collateral bookkeeping of CDPs for the Honzon protocol.
+//!
+//! ## Overview
+//!
+//! Each account can hold one position per collateral currency, consisting of a collateral
+//! balance (held in this pallet's account) and a debit balance (an internal unit which the
+//! risk manager converts into stable currency value via the debit exchange rate). Per-currency
+//! totals are maintained alongside the individual positions.
+//!
+//! This pallet exposes no user-facing extrinsics. It is driven by higher-level pallets: the
+//! CDP engine adjusts and confiscates positions during liquidation and settlement, and the
+//! honzon front-end pallet adjusts positions on behalf of users. Every adjustment is validated
+//! by the configured [`RiskManager`] and stable currency issuance is routed through the
+//! configured [`CDPTreasury`]. The only call is a root-only repair that rebuilds the
+//! per-currency totals from the individual positions in bounded chunks, backing the
+//! try-runtime invariant that the two never drift apart.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use frame_support::{
+	pallet_prelude::*,
+	storage::with_storage_layer,
+	traits::{
+		fungibles::Mutate,
+		tokens::{Balance, Preservation},
+	},
+	PalletId,
+};
+use frame_system::pallet_prelude::*;
+use honzon_support::{CDPTreasury, ExchangeRate, OnUpdateLoan, Price, Ratio, RiskManager};
+use sp_arithmetic::traits::Signed;
+use sp_runtime::{
+	traits::{AccountIdConversion, CheckedAdd, CheckedSub, Saturating, Zero},
+	DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug,
+};
+
+pub use pallet::*;
+
+const LOG_TARGET: &str = "runtime::loans";
+
+/// A CDP: some collateral locked against some debit issued.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct Position<Balance> {
+	/// The amount of collateral locked.
+	pub collateral: Balance,
+	/// The amount of debit issued, in debit units.
+	pub debit: Balance,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The currency id type, shared by collateral currencies and the stable currency.
+		type CurrencyId: Parameter + Member + Copy + MaxEncodedLen + Ord;
+
+		/// The balance type.
+		type Balance: Balance + sp_runtime::FixedPointOperand;
+
+		/// The signed counterpart of [`Config::Balance`], used for position adjustments.
+		type Amount: Parameter
+			+ Member
+			+ Copy
+			+ MaxEncodedLen
+			+ Default
+			+ Ord
+			+ Signed
+			+ TryInto<Self::Balance>
+			+ TryFrom<Self::Balance>;
+
+		/// The assets in which collateral is held. Collateral is kept in this pallet's account
+		/// while a position is open.
+		type Currency: Mutate<
+			Self::AccountId,
+			AssetId = Self::CurrencyId,
+			Balance = Self::Balance,
+		>;
+
+		/// Risk management logic validating every position change. Normally the CDP engine.
+		type RiskManager: RiskManager<Self::AccountId, Self::CurrencyId, Self::Balance>;
+
+		/// The CDP treasury, through which stable currency is issued and burned and which
+		/// custodies confiscated collateral.
+		type CDPTreasury: CDPTreasury<
+			Self::AccountId,
+			Balance = Self::Balance,
+			CurrencyId = Self::CurrencyId,
+		>;
+
+		/// A handler notified of the new position values after every position change. Used by
+		/// the CDP engine to maintain its risk bucket index.
+		type OnUpdateLoan: OnUpdateLoan<Self::AccountId, Self::CurrencyId, Self::Balance>;
+
+		/// The loans pallet id, used for deriving the account holding all collateral.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The most position entries one `positions_paged` view function call returns,
+		/// bounding its execution time.
+		#[pallet::constant]
+		type MaxPositionsPageSize: Get<u32>;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The position has less collateral than the requested decrease.
+		CollateralTooLow,
+		/// The position has less debit than the requested decrease.
+		DebitTooLow,
+		/// Failed to convert between the balance and amount types.
+		AmountConvertFailed,
+		/// The target collateral ratio or the debit exchange rate is zero.
+		InvalidTargetRatio,
+		/// A balance overflowed when applying the adjustment.
+		Overflow,
+		/// A totals rebuild step must be allowed to visit at least one position.
+		ZeroLimit,
+		/// The adjustment would leave a debit-free position with dust collateral; withdraw the
+		/// collateral in full instead.
+		CollateralAmountBelowMinimum,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A position has been adjusted.
+		PositionUpdated {
+			owner: T::AccountId,
+			collateral_type: T::CurrencyId,
+			collateral_adjustment: T::Amount,
+			debit_adjustment: T::Amount,
+		},
+		/// Collateral and debit have been confiscated from a position into the CDP treasury.
+		/// `collateral_dust` is the part of the requested confiscation that exceeded the
+		/// collateral actually held by the position and was forgiven.
+		ConfiscateCollateralAndDebit {
+			owner: T::AccountId,
+			collateral_type: T::CurrencyId,
+			confiscated_collateral_amount: T::Balance,
+			deduct_debit_amount: T::Balance,
+			collateral_dust: T::Balance,
+		},
+		/// A position has been transferred between accounts.
+		TransferLoan { from: T::AccountId, to: T::AccountId, currency_id: T::CurrencyId },
+		/// The per-currency totals have been rebuilt from the individual positions.
+		TotalsRebuilt { currencies: u32 },
+		/// A confiscation covered only part of the position's debit, leaving uncovered bad
+		/// debt behind.
+		ResidualBadDebt {
+			owner: T::AccountId,
+			collateral_type: T::CurrencyId,
+			remaining_collateral: T::Balance,
+			remaining_debit: T::Balance,
+			bad_debt_value: T::Balance,
+		},
+	}
+
+	/// The collateralized debit positions, keyed by collateral currency and owner.
+	#[pallet::storage]
+	pub type Positions<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CurrencyId,
+		Twox64Concat,
+		T::AccountId,
+		Position<T::Balance>,
+		ValueQuery,
+	>;
+
+	/// The block at which each position was last adjusted by its owner, via
+	/// [`Pallet::adjust_position`] or [`Pallet::transfer_loan`]. Confiscations and
+	/// settlements do not count. Read by the CDP engine to defer the liquidation of
+	/// positions touched in the current block.
+	#[pallet::storage]
+	pub type LastAdjustment<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CurrencyId,
+		Twox64Concat,
+		T::AccountId,
+		BlockNumberFor<T>,
+		OptionQuery,
+	>;
+
+	/// The total collateral and debit of all positions, per collateral currency.
+	#[pallet::storage]
+	pub type TotalPositions<T: Config> =
+		StorageMap<_, Twox64Concat, T::CurrencyId, Position<T::Balance>, ValueQuery>;
+
+	/// The last position visited by an in-progress totals rebuild; the next
+	/// [`Pallet::rebuild_totals`] call resumes after it. `None` when no rebuild is running.
+	#[pallet::storage]
+	pub type RebuildCursor<T: Config> = StorageValue<_, (T::CurrencyId, T::AccountId), OptionQuery>;
+
+	/// The partial per-currency sums accumulated by an in-progress totals rebuild. Drained
+	/// into [`TotalPositions`] when the rebuild completes.
+	#[pallet::storage]
+	pub type RebuildingTotals<T: Config> =
+		StorageMap<_, Twox64Concat, T::CurrencyId, Position<T::Balance>, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Recompute [`TotalPositions`] from the individual [`Positions`], visiting at most
+		/// `max_positions` entries per call.
+		///
+		/// The totals are maintained incrementally on every position change and should never
+		/// diverge from the positions; this call is the repair path should they ever do, e.g.
+		/// after a faulty migration. The first call starts a rebuild and further calls resume
+		/// it from the stored cursor; once the last position has been visited the accumulated
+		/// sums replace the recorded totals wholesale — stale entries of currencies without
+		/// any open position are dropped — and [`Event::TotalsRebuilt`] is emitted. A position
+		/// change made while a rebuild is running is missed if its entry was already visited,
+		/// so on a live chain the rebuild may need a second pass to converge.
+		///
+		/// May only be called from root.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(
+			u64::from(*max_positions).saturating_add(2),
+			u64::from(*max_positions).saturating_add(2),
+		))]
+		pub fn rebuild_totals(origin: OriginFor<T>, max_positions: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!max_positions.is_zero(), Error::<T>::ZeroLimit);
+
+			let mut iter = match RebuildCursor::<T>::get() {
+				Some((currency_id, who)) =>
+					Positions::<T>::iter_from(Positions::<T>::hashed_key_for(currency_id, &who)),
+				None => Positions::<T>::iter(),
+			};
+
+			let mut visited = 0u32;
+			let mut cursor = None;
+			for (currency_id, who, Position { collateral, debit }) in &mut iter {
+				RebuildingTotals::<T>::mutate(currency_id, |total| {
+					total.collateral = total.collateral.saturating_add(collateral);
+					total.debit = total.debit.saturating_add(debit);
+				});
+				visited.saturating_inc();
+				if visited == max_positions {
+					cursor = Some((currency_id, who));
+					break
+				}
+			}
+
+			match cursor {
+				// Only pause when another entry actually follows; otherwise the chunk that
+				// exactly exhausts the positions would take one extra call to finish.
+				Some(cursor) if iter.next().is_some() => RebuildCursor::<T>::put(cursor),
+				_ => {
+					let _ = TotalPositions::<T>::clear(u32::MAX, None);
+					let mut currencies = 0u32;
+					for (currency_id, total) in RebuildingTotals::<T>::drain() {
+						TotalPositions::<T>::insert(currency_id, total);
+						currencies.saturating_inc();
+					}
+					RebuildCursor::<T>::kill();
+					Self::deposit_event(Event::<T>::TotalsRebuilt { currencies });
+				},
+			}
+			Ok(())
+		}
+	}
+
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T> {
+		/// Up to `limit` open positions of `currency_id` after `start_key`, together with the
+		/// key to continue from when more entries remain.
+		///
+		/// `limit` is clamped to [`Config::MaxPositionsPageSize`]. Entries come in storage
+		/// hash order, and the continuation key resumes from its raw storage position, so
+		/// paging misses no entry and repeats none even when positions open or close between
+		/// calls (the continuation account itself may close without invalidating the key).
+		pub fn positions_paged(
+			currency_id: T::CurrencyId,
+			start_key: Option<T::AccountId>,
+			limit: u32,
+		) -> (Vec<(T::AccountId, Position<T::Balance>)>, Option<T::AccountId>) {
+			let limit = limit.min(T::MaxPositionsPageSize::get()) as usize;
+			if limit == 0 {
+				return (Vec::new(), start_key)
+			}
+			let mut iter = match &start_key {
+				Some(who) => Positions::<T>::iter_prefix_from(
+					currency_id,
+					Positions::<T>::hashed_key_for(currency_id, who),
+				),
+				None => Positions::<T>::iter_prefix(currency_id),
+			};
+			let mut page = Vec::with_capacity(limit);
+			for entry in &mut iter {
+				page.push(entry);
+				if page.len() == limit {
+					break
+				}
+			}
+			// Only hand out a continuation key when another entry actually follows, so
+			// callers can treat `None` as the end of the set.
+			let continuation = if page.len() == limit && iter.next().is_some() {
+				page.last().map(|(who, _)| who.clone())
+			} else {
+				None
+			};
+			(page, continuation)
+		}
+
+		/// The total of all positions for `currency_id`.
+		pub fn total_positions(currency_id: T::CurrencyId) -> Position<T::Balance> {
+			TotalPositions::<T>::get(currency_id)
+		}
+	}
+}
+
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Config> Pallet<T> {
+	/// Ensure the correctness of the state of this pallet.
+	pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+		use alloc::collections::BTreeMap;
+
+		// While a rebuild is running the totals are known-bad; that is the point of running
+		// one. The invariants apply again once it completes.
+		if RebuildCursor::<T>::get().is_some() {
+			return Ok(())
+		}
+		ensure!(
+			RebuildingTotals::<T>::iter().next().is_none(),
+			"staged rebuild sums exist without a rebuild in progress"
+		);
+
+		let mut sums = BTreeMap::<T::CurrencyId, Position<T::Balance>>::new();
+		for (currency_id, _, position) in Positions::<T>::iter() {
+			ensure!(
+				!position.collateral.is_zero() || !position.debit.is_zero(),
+				"an empty position was left in storage"
+			);
+			let sum = sums.entry(currency_id).or_default();
+			sum.collateral = sum.collateral.saturating_add(position.collateral);
+			sum.debit = sum.debit.saturating_add(position.debit);
+		}
+		for (currency_id, total) in TotalPositions::<T>::iter() {
+			ensure!(
+				sums.remove(&currency_id).unwrap_or_default() == total,
+				"the recorded totals diverge from the sum of the positions"
+			);
+		}
+		ensure!(sums.is_empty(), "positions exist for a currency without recorded totals");
+		Ok(())
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account that holds the collateral of all open positions.
+	///
+	/// This actually does computation. If you need to keep using it, then make sure you cache
+	/// the value and only call this once.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
+	/// The position of `who` for `currency_id`.
+	pub fn positions(currency_id: T::CurrencyId, who: &T::AccountId) -> Position<T::Balance> {
+		Positions::<T>::get(currency_id, who)
+	}
+
+	/// Adjust the position of `who`, moving collateral between `who` and the pallet account
+	/// and issuing or burning stable currency through the CDP treasury.
+	///
+	/// The updated position and the new total debit are validated by the risk manager. The
+	/// whole adjustment happens in a storage layer: a failed check rolls everything back.
+	pub fn adjust_position(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		collateral_adjustment: T::Amount,
+		debit_adjustment: T::Amount,
+	) -> DispatchResult {
+		with_storage_layer(|| {
+			Self::do_adjust_position(who, currency_id, collateral_adjustment, debit_adjustment)
+		})
+	}
+
+	fn do_adjust_position(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		collateral_adjustment: T::Amount,
+		debit_adjustment: T::Amount,
+	) -> DispatchResult {
+		// Mutate the storage first, so the checks below run against the updated position.
+		Self::update_loan(who, currency_id, collateral_adjustment, debit_adjustment)?;
+		Self::note_adjustment(who, currency_id);
+
+		let collateral_balance = Self::balance_try_from_amount_abs(collateral_adjustment)?;
+		let debit_balance = Self::balance_try_from_amount_abs(debit_adjustment)?;
+		let pallet_account = Self::account_id();
+
+		if collateral_adjustment.is_positive() {
+			T::Currency::transfer(
+				currency_id,
+				who,
+				&pallet_account,
+				collateral_balance,
+				Preservation::Expendable,
+			)?;
+		} else if collateral_adjustment.is_negative() {
+			T::Currency::transfer(
+				currency_id,
+				&pallet_account,
+				who,
+				collateral_balance,
+				Preservation::Expendable,
+			)?;
+		}
+
+		if debit_adjustment.is_positive() {
+			// Check the new total against the debit hard cap before issuing.
+			T::RiskManager::check_debit_cap(
+				currency_id,
+				TotalPositions::<T>::get(currency_id).debit,
+			)?;
+			T::CDPTreasury::issue_debit(
+				who,
+				T::RiskManager::get_debit_value(currency_id, debit_balance),
+				true,
+			)?;
+		} else if debit_adjustment.is_negative() {
+			T::CDPTreasury::burn_debit(
+				who,
+				T::RiskManager::get_debit_value(currency_id, debit_balance),
+			)?;
+		}
+
+		// Risky adjustments (more debit or less collateral) must leave a valid position.
+		if debit_adjustment.is_positive() || collateral_adjustment.is_negative() {
+			let Position { collateral, debit } = Positions::<T>::get(currency_id, who);
+			T::RiskManager::check_position_valid(currency_id, collateral, debit, true)?;
+		}
+
+		// A debit-free position must keep a meaningful amount of collateral or none at all;
+		// dust remainders are not worth settling and clutter the books.
+		let Position { collateral, debit } = Positions::<T>::get(currency_id, who);
+		if debit.is_zero() && !collateral.is_zero() {
+			ensure!(
+				collateral >= T::RiskManager::minimum_collateral(),
+				Error::<T>::CollateralAmountBelowMinimum,
+			);
+		}
+
+		Self::deposit_event(Event::<T>::PositionUpdated {
+			owner: who.clone(),
+			collateral_type: currency_id,
+			collateral_adjustment,
+			debit_adjustment,
+		});
+		Ok(())
+	}
+
+	/// Confiscate some collateral and deduct some debit from the position of `who`, moving the
+	/// collateral into the CDP treasury and recording the deducted debit value as system
+	/// debit. Used during liquidation and post-shutdown settlement.
+	pub fn confiscate_collateral_and_debit(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		collateral_confiscate: T::Balance,
+		debit_decrease: T::Balance,
+	) -> DispatchResult {
+		// A confiscation amount derived upstream from a ratio can overshoot the collateral the
+		// position actually holds by a rounding unit; cap at the held amount and forgive the
+		// dust rather than failing the whole liquidation.
+		let held_collateral = Positions::<T>::get(currency_id, who).collateral;
+		let collateral_dust = collateral_confiscate.saturating_sub(held_collateral);
+		let collateral_confiscate = collateral_confiscate.min(held_collateral);
+		if !collateral_dust.is_zero() {
+			log::debug!(
+				target: LOG_TARGET,
+				"confiscation from {:?} capped at held {:?} {:?} collateral, forgiving {:?} dust",
+				who, held_collateral, currency_id, collateral_dust,
+			);
+		}
+
+		// Convert up front so the adjustment cannot fail after funds have moved.
+		let collateral_adjustment = Self::amount_try_from_balance(collateral_confiscate)?;
+		let debit_adjustment = Self::amount_try_from_balance(debit_decrease)?;
+
+		T::CDPTreasury::deposit_collateral(
+			&Self::account_id(),
+			currency_id,
+			collateral_confiscate,
+		)?;
+		let bad_debt_value = T::RiskManager::get_debit_value(currency_id, debit_decrease);
+		T::CDPTreasury::on_system_debit(bad_debt_value)?;
+
+		Self::update_loan(who, currency_id, -collateral_adjustment, -debit_adjustment)?;
+
+		Self::deposit_event(Event::<T>::ConfiscateCollateralAndDebit {
+			owner: who.clone(),
+			collateral_type: currency_id,
+			confiscated_collateral_amount: collateral_confiscate,
+			deduct_debit_amount: debit_decrease,
+			collateral_dust,
+		});
+
+		// Surface partial confiscations: whatever debit the liquidation strategy could not
+		// cover remains on the position and needs operator attention.
+		let Position { collateral: remaining_collateral, debit: remaining_debit } =
+			Positions::<T>::get(currency_id, who);
+		if !remaining_debit.is_zero() {
+			Self::deposit_event(Event::<T>::ResidualBadDebt {
+				owner: who.clone(),
+				collateral_type: currency_id,
+				remaining_collateral,
+				remaining_debit,
+				bad_debt_value,
+			});
+		}
+		Ok(())
+	}
+
+	/// Transfer the whole position of `from` for `currency_id` to `to`.
+	///
+	/// The merged position of `to` is validated by the risk manager; a failed check rolls the
+	/// transfer back.
+	pub fn transfer_loan(
+		from: &T::AccountId,
+		to: &T::AccountId,
+		currency_id: T::CurrencyId,
+	) -> DispatchResult {
+		with_storage_layer(|| Self::do_transfer_loan(from, to, currency_id))
+	}
+
+	fn do_transfer_loan(
+		from: &T::AccountId,
+		to: &T::AccountId,
+		currency_id: T::CurrencyId,
+	) -> DispatchResult {
+		let Position { collateral, debit } = Positions::<T>::get(currency_id, from);
+		let collateral_adjustment = Self::amount_try_from_balance(collateral)?;
+		let debit_adjustment = Self::amount_try_from_balance(debit)?;
+
+		Self::update_loan(from, currency_id, -collateral_adjustment, -debit_adjustment)?;
+		Self::update_loan(to, currency_id, collateral_adjustment, debit_adjustment)?;
+		Self::note_adjustment(from, currency_id);
+		Self::note_adjustment(to, currency_id);
+
+		let Position { collateral, debit } = Positions::<T>::get(currency_id, to);
+		T::RiskManager::check_position_valid(currency_id, collateral, debit, true)?;
+
+		Self::deposit_event(Event::<T>::TransferLoan {
+			from: from.clone(),
+			to: to.clone(),
+			currency_id,
+		});
+		Ok(())
+	}
+
+	/// Adjust the debit of `who`'s position for `currency_id` so that its collateral ratio
+	/// reaches `target_ratio`, keeping the collateral fixed: debit is borrowed when the
+	/// position sits above the target and repaid when it sits below. Returns the applied
+	/// debit adjustment in debit units, positive for borrowed debit.
+	///
+	/// The collateral is valued at `price` and debit units at `debit_exchange_rate`, both
+	/// supplied by the caller since this pallet holds no price source. A target below the
+	/// liquidation ratio borrows debit and is rejected by the risk manager's position
+	/// check, like any other risky adjustment.
+	pub fn adjust_to_target_ratio(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		target_ratio: Ratio,
+		price: Price,
+		debit_exchange_rate: ExchangeRate,
+	) -> Result<T::Amount, DispatchError> {
+		let collateral_value =
+			price.saturating_mul_int(Positions::<T>::get(currency_id, who).collateral);
+		let target_debit_value = target_ratio
+			.reciprocal()
+			.ok_or(Error::<T>::InvalidTargetRatio)?
+			.saturating_mul_int(collateral_value);
+		let target_debit = debit_exchange_rate
+			.reciprocal()
+			.ok_or(Error::<T>::InvalidTargetRatio)?
+			.saturating_mul_int(target_debit_value);
+
+		let debit = Positions::<T>::get(currency_id, who).debit;
+		let debit_adjustment = if target_debit >= debit {
+			Self::amount_try_from_balance(target_debit.saturating_sub(debit))?
+		} else {
+			-Self::amount_try_from_balance(debit.saturating_sub(target_debit))?
+		};
+		if !debit_adjustment.is_zero() {
+			Self::adjust_position(who, currency_id, T::Amount::default(), debit_adjustment)?;
+		}
+		Ok(debit_adjustment)
+	}
+
+	/// Mutate the position of `who` and the currency totals without moving any funds or
+	/// validating the result. Prefer [`Self::adjust_position`] unless the caller moves funds
+	/// and validates itself.
+	pub fn update_loan(
+		who: &T::AccountId,
+		currency_id: T::CurrencyId,
+		collateral_adjustment: T::Amount,
+		debit_adjustment: T::Amount,
+	) -> DispatchResult {
+		let collateral_balance = Self::balance_try_from_amount_abs(collateral_adjustment)?;
+		let debit_balance = Self::balance_try_from_amount_abs(debit_adjustment)?;
+
+		let mutate = |position: &mut Position<T::Balance>,
+		              collateral_low: Error<T>,
+		              debit_low: Error<T>|
+		 -> DispatchResult {
+			position.collateral = if collateral_adjustment.is_negative() {
+				position.collateral.checked_sub(&collateral_balance).ok_or(collateral_low)?
+			} else {
+				position.collateral.checked_add(&collateral_balance).ok_or(Error::<T>::Overflow)?
+			};
+			position.debit = if debit_adjustment.is_negative() {
+				position.debit.checked_sub(&debit_balance).ok_or(debit_low)?
+			} else {
+				position.debit.checked_add(&debit_balance).ok_or(Error::<T>::Overflow)?
+			};
+			Ok(())
+		};
+
+		Positions::<T>::try_mutate_exists(currency_id, who, |maybe_position| -> DispatchResult {
+			let mut position = maybe_position.take().unwrap_or_default();
+			mutate(&mut position, Error::<T>::CollateralTooLow, Error::<T>::DebitTooLow)?;
+			*maybe_position =
+				if position.collateral.is_zero() && position.debit.is_zero() {
+					None
+				} else {
+					Some(position)
+				};
+			Ok(())
+		})?;
+
+		TotalPositions::<T>::try_mutate(currency_id, |total| {
+			mutate(total, Error::<T>::CollateralTooLow, Error::<T>::DebitTooLow)
+		})?;
+
+		let Position { collateral, debit } = Positions::<T>::get(currency_id, who);
+		T::OnUpdateLoan::on_update_loan(who, currency_id, collateral, debit);
+		Ok(())
+	}
+
+	/// Stamp the owner-adjustment marker of a position, or clear it if the position was
+	/// emptied.
+	fn note_adjustment(who: &T::AccountId, currency_id: T::CurrencyId) {
+		if Positions::<T>::contains_key(currency_id, who) {
+			LastAdjustment::<T>::insert(
+				currency_id,
+				who,
+				frame_system::Pallet::<T>::block_number(),
+			);
+		} else {
+			LastAdjustment::<T>::remove(currency_id, who);
+		}
+	}
+
+	/// Convert an amount to a balance by absolute value.
+	fn balance_try_from_amount_abs(amount: T::Amount) -> Result<T::Balance, Error<T>> {
+		amount.abs().try_into().map_err(|_| Error::<T>::AmountConvertFailed)
+	}
+
+	/// Convert a balance to a (positive) amount.
+	fn amount_try_from_balance(balance: T::Balance) -> Result<T::Amount, Error<T>> {
+		balance.try_into().map_err(|_| Error::<T>::AmountConvertFailed)
+	}
+}
@@ -0,0 +1,240 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocks for the loans pallet.
+
+use super::*;
+use crate as pallet_loans;
+
+use frame_support::{derive_impl, parameter_types, traits::fungibles::Mutate, PalletId};
+use sp_runtime::{traits::Bounded, BuildStorage, DispatchError, FixedPointNumber};
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type Amount = i128;
+pub type CurrencyId = u32;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const TREASURY: AccountId = 100;
+
+pub const DOT: CurrencyId = 1;
+pub const BTC: CurrencyId = 2;
+pub const AUSD: CurrencyId = 9;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Assets: pallet_assets,
+		Loans: pallet_loans,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+#[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type CreateOrigin =
+		frame_support::traits::AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+	pub static RiskValid: bool = true;
+	pub static DebitCap: Balance = 10_000;
+	pub static DebitPool: Balance = 0;
+	pub static LiquidationRatio: Option<Ratio> = None;
+	pub static MinimumCollateral: Balance = 0;
+}
+
+/// Values the debit at half the debit unit balance, like a debit exchange rate of 1/2.
+pub struct MockRiskManager;
+impl RiskManager<AccountId, CurrencyId, Balance> for MockRiskManager {
+	fn get_debit_value(_currency_id: CurrencyId, debit_balance: Balance) -> Balance {
+		debit_balance / 2
+	}
+
+	fn check_position_valid(
+		currency_id: CurrencyId,
+		collateral_balance: Balance,
+		debit_balance: Balance,
+		_check_required_ratio: bool,
+	) -> DispatchResult {
+		if !RiskValid::get() {
+			return Err(DispatchError::Other("position invalid"));
+		}
+		// Values the collateral at a price of 1.
+		if let Some(liquidation_ratio) = LiquidationRatio::get() {
+			let debit_value = Self::get_debit_value(currency_id, debit_balance);
+			let ratio = Ratio::checked_from_rational(collateral_balance, debit_value)
+				.unwrap_or_else(Ratio::max_value);
+			if ratio < liquidation_ratio {
+				return Err(DispatchError::Other("below liquidation ratio"));
+			}
+		}
+		Ok(())
+	}
+
+	fn check_debit_cap(_currency_id: CurrencyId, total_debit_balance: Balance) -> DispatchResult {
+		if total_debit_balance <= DebitCap::get() {
+			Ok(())
+		} else {
+			Err(DispatchError::Other("debit cap exceeded"))
+		}
+	}
+
+	fn minimum_collateral() -> Balance {
+		MinimumCollateral::get()
+	}
+}
+
+/// Issues and burns the stable asset directly and parks collateral on `TREASURY`.
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		Assets::balance(AUSD, TREASURY)
+	}
+
+	fn get_debit_pool() -> Balance {
+		DebitPool::get()
+	}
+
+	fn get_total_collaterals(currency_id: CurrencyId) -> Balance {
+		Assets::balance(currency_id, TREASURY)
+	}
+
+	fn on_system_debit(amount: Balance) -> DispatchResult {
+		DebitPool::mutate(|pool| *pool += amount);
+		Ok(())
+	}
+
+	fn on_system_surplus(amount: Balance) -> DispatchResult {
+		Assets::mint_into(AUSD, &TREASURY, amount).map(|_| ())
+	}
+
+	fn issue_debit(who: &AccountId, debit: Balance, _backed: bool) -> DispatchResult {
+		Assets::mint_into(AUSD, who, debit).map(|_| ())
+	}
+
+	fn burn_debit(who: &AccountId, debit: Balance) -> DispatchResult {
+		Assets::burn_from(
+			AUSD,
+			who,
+			debit,
+			Preservation::Expendable,
+			frame_support::traits::tokens::Precision::Exact,
+			frame_support::traits::tokens::Fortitude::Polite,
+		)
+		.map(|_| ())
+	}
+
+	fn deposit_surplus(from: &AccountId, surplus: Balance) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(AUSD, from, &TREASURY, surplus, Preservation::Expendable).map(|_| ())
+	}
+
+	fn deposit_collateral(
+		from: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(currency_id, from, &TREASURY, amount, Preservation::Expendable)
+			.map(|_| ())
+	}
+
+	fn withdraw_collateral(
+		to: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		<Assets as Mutate<AccountId>>::transfer(currency_id, &TREASURY, to, amount, Preservation::Expendable).map(|_| ())
+	}
+}
+
+parameter_types! {
+	pub const LoansPalletId: PalletId = PalletId(*b"py/loans");
+	pub const MaxPositionsPageSize: u32 = 5;
+}
+
+impl Config for Test {
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+	type Amount = Amount;
+	type Currency = Assets;
+	type RiskManager = MockRiskManager;
+	type CDPTreasury = MockCDPTreasury;
+	type OnUpdateLoan = ();
+	type PalletId = LoansPalletId;
+	type MaxPositionsPageSize = MaxPositionsPageSize;
+}
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		RiskValid::set(true);
+		DebitPool::set(0);
+		LiquidationRatio::set(None);
+		MinimumCollateral::set(0);
+
+		let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			for currency_id in [DOT, BTC, AUSD] {
+				assert_eq!(
+					Assets::force_create(
+						RuntimeOrigin::root(),
+						currency_id,
+						TREASURY,
+						true,
+						1
+					),
+					Ok(())
+				);
+			}
+			for (who, currency_id, balance) in
+				[(ALICE, DOT, 1000), (ALICE, BTC, 1000), (BOB, DOT, 1000)]
+			{
+				assert_eq!(Assets::mint_into(currency_id, &who, balance), Ok(balance));
+			}
+		});
+		ext
+	}
+}
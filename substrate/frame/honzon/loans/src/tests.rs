@@ -0,0 +1,478 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the loans pallet.
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::*;
+
+#[test]
+fn adjust_position_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+
+		// Collateral moved into the pallet account.
+		assert_eq!(Assets::balance(DOT, ALICE), 500);
+		assert_eq!(Assets::balance(DOT, Loans::account_id()), 500);
+		// Stable currency issued at the debit value (300 / 2).
+		assert_eq!(Assets::balance(AUSD, ALICE), 150);
+
+		assert_eq!(
+			Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 500, debit: 300 }
+		);
+		assert_eq!(
+			TotalPositions::<Test>::get(DOT),
+			Position { collateral: 500, debit: 300 }
+		);
+		System::assert_last_event(
+			Event::<Test>::PositionUpdated {
+				owner: ALICE,
+				collateral_type: DOT,
+				collateral_adjustment: 500,
+				debit_adjustment: 300,
+			}
+			.into(),
+		);
+
+		// Pay the debit back and withdraw the collateral again.
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, -500, -300));
+		assert_eq!(Assets::balance(DOT, ALICE), 1000);
+		assert_eq!(Assets::balance(AUSD, ALICE), 0);
+		assert!(!Positions::<Test>::contains_key(DOT, ALICE));
+	});
+}
+
+#[test]
+fn adjust_position_checks_risk_manager() {
+	ExtBuilder::default().build().execute_with(|| {
+		RiskValid::set(false);
+		// Opening a position must leave it valid according to the risk manager.
+		assert_noop!(
+			Loans::adjust_position(&ALICE, DOT, 500, 300),
+			sp_runtime::DispatchError::Other("position invalid")
+		);
+
+		RiskValid::set(true);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+
+		// Pure collateral increases are never risky and skip the validity check.
+		RiskValid::set(false);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 100, 0));
+		// But collateral decreases are checked.
+		assert_noop!(
+			Loans::adjust_position(&ALICE, DOT, -100, 0),
+			sp_runtime::DispatchError::Other("position invalid")
+		);
+	});
+}
+
+#[test]
+fn adjust_position_enforces_debit_cap() {
+	ExtBuilder::default().build().execute_with(|| {
+		DebitCap::set(200);
+		assert_noop!(
+			Loans::adjust_position(&ALICE, DOT, 500, 300),
+			sp_runtime::DispatchError::Other("debit cap exceeded")
+		);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 200));
+	});
+}
+
+#[test]
+fn adjust_position_rejects_dust_collateral_remainders() {
+	ExtBuilder::default().build().execute_with(|| {
+		MinimumCollateral::set(10);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+
+		// Repaying all debit while keeping only dust collateral is rejected; the remainder
+		// must be withdrawn in full.
+		assert_noop!(
+			Loans::adjust_position(&ALICE, DOT, -495, -300),
+			Error::<Test>::CollateralAmountBelowMinimum
+		);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, -500, -300));
+		assert!(!Positions::<Test>::contains_key(DOT, ALICE));
+
+		// A debit-free position cannot be opened with dust collateral either.
+		assert_noop!(
+			Loans::adjust_position(&ALICE, DOT, 5, 0),
+			Error::<Test>::CollateralAmountBelowMinimum
+		);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 10, 0));
+	});
+}
+
+#[test]
+fn update_loan_validates_balances() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::update_loan(&ALICE, DOT, 500, 300));
+		assert_noop!(Loans::update_loan(&ALICE, DOT, -600, 0), Error::<Test>::CollateralTooLow);
+		assert_noop!(Loans::update_loan(&ALICE, DOT, 0, -400), Error::<Test>::DebitTooLow);
+		assert_ok!(Loans::update_loan(&ALICE, DOT, -500, -300));
+		assert!(!Positions::<Test>::contains_key(DOT, ALICE));
+		assert_eq!(TotalPositions::<Test>::get(DOT), Position::default());
+	});
+}
+
+#[test]
+fn confiscate_collateral_and_debit_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+
+		assert_ok!(Loans::confiscate_collateral_and_debit(&ALICE, DOT, 400, 200));
+		// Confiscated collateral is custodied by the CDP treasury.
+		assert_eq!(Assets::balance(DOT, TREASURY), 400);
+		assert_eq!(Assets::balance(DOT, Loans::account_id()), 100);
+		// The deducted debit value (200 / 2) is recorded as system debit.
+		assert_eq!(MockCDPTreasury::get_debit_pool(), 100);
+		assert_eq!(
+			Positions::<Test>::get(DOT, ALICE),
+			Position { collateral: 100, debit: 100 }
+		);
+		System::assert_has_event(
+			Event::<Test>::ConfiscateCollateralAndDebit {
+				owner: ALICE,
+				collateral_type: DOT,
+				confiscated_collateral_amount: 400,
+				deduct_debit_amount: 200,
+				collateral_dust: 0,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn partial_confiscation_reports_residual_bad_debt() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+
+		// Only two thirds of the debit are covered; the rest stays on the position.
+		assert_ok!(Loans::confiscate_collateral_and_debit(&ALICE, DOT, 400, 200));
+		System::assert_last_event(
+			Event::<Test>::ResidualBadDebt {
+				owner: ALICE,
+				collateral_type: DOT,
+				remaining_collateral: 100,
+				remaining_debit: 100,
+				// The value of the deducted debit (200 / 2), recorded as system debit.
+				bad_debt_value: 100,
+			}
+			.into(),
+		);
+
+		// Confiscating the rest clears the position; no residual is reported.
+		assert_ok!(Loans::confiscate_collateral_and_debit(&ALICE, DOT, 100, 100));
+		System::assert_last_event(
+			Event::<Test>::ConfiscateCollateralAndDebit {
+				owner: ALICE,
+				collateral_type: DOT,
+				confiscated_collateral_amount: 100,
+				deduct_debit_amount: 100,
+				collateral_dust: 0,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn confiscation_forgives_collateral_dust_overshoot() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+
+		// A ratio-derived liquidation amount overshoots the held collateral by one unit; the
+		// confiscation is capped at the held amount instead of failing.
+		assert_ok!(Loans::confiscate_collateral_and_debit(&ALICE, DOT, 501, 300));
+		assert_eq!(Assets::balance(DOT, TREASURY), 500);
+		assert_eq!(Assets::balance(DOT, Loans::account_id()), 0);
+		assert_eq!(MockCDPTreasury::get_debit_pool(), 150);
+		assert!(!Positions::<Test>::contains_key(DOT, ALICE));
+		System::assert_last_event(
+			Event::<Test>::ConfiscateCollateralAndDebit {
+				owner: ALICE,
+				collateral_type: DOT,
+				confiscated_collateral_amount: 500,
+				deduct_debit_amount: 300,
+				collateral_dust: 1,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn transfer_loan_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 200, 100));
+
+		assert_ok!(Loans::transfer_loan(&ALICE, &BOB, DOT));
+		assert!(!Positions::<Test>::contains_key(DOT, ALICE));
+		assert_eq!(
+			Positions::<Test>::get(DOT, BOB),
+			Position { collateral: 700, debit: 400 }
+		);
+		assert_eq!(
+			TotalPositions::<Test>::get(DOT),
+			Position { collateral: 700, debit: 400 }
+		);
+		System::assert_last_event(
+			Event::<Test>::TransferLoan { from: ALICE, to: BOB, currency_id: DOT }.into(),
+		);
+
+		// The merged position must be valid.
+		RiskValid::set(false);
+		assert_noop!(
+			Loans::transfer_loan(&BOB, &ALICE, DOT),
+			sp_runtime::DispatchError::Other("position invalid")
+		);
+	});
+}
+
+#[test]
+fn positions_are_tracked_per_currency() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+		assert_ok!(Loans::adjust_position(&ALICE, BTC, 200, 100));
+
+		assert_eq!(Loans::positions(DOT, &ALICE).collateral, 500);
+		assert_eq!(Loans::positions(BTC, &ALICE).collateral, 200);
+		assert_eq!(Loans::total_positions(DOT).debit, 300);
+		assert_eq!(Loans::total_positions(BTC).debit, 100);
+	});
+}
+
+#[test]
+fn positions_paged_enumerates_all_positions() {
+	ExtBuilder::default().build().execute_with(|| {
+		for who in 1u64..=5 {
+			assert_ok!(Loans::update_loan(&who, DOT, 100, 50));
+		}
+		// Another currency's positions must not leak into the page.
+		assert_ok!(Loans::update_loan(&ALICE, BTC, 100, 50));
+
+		// The limit is clamped to `MaxPositionsPageSize` (5 in the mock), so a single
+		// oversized request cannot run unbounded; with nothing left over there is no
+		// continuation key.
+		let (page, continuation) = Loans::positions_paged(DOT, None, u32::MAX);
+		assert_eq!(page.len(), 5);
+		assert_eq!(continuation, None);
+		assert!(page.iter().all(|(_, position)| position.debit == 50));
+
+		// Paging with limit 2 walks the same set in three pages.
+		let mut seen = Vec::new();
+		let (page, mut continuation) = Loans::positions_paged(DOT, None, 2);
+		assert_eq!(page.len(), 2);
+		seen.extend(page.iter().map(|(who, _)| *who));
+
+		// Closing the continuation account's position between calls neither invalidates
+		// the key nor skips the entries behind it.
+		let closed = continuation.unwrap();
+		assert_ok!(Loans::update_loan(&closed, DOT, -100, -50));
+
+		while let Some(start) = continuation {
+			let (page, next) = Loans::positions_paged(DOT, Some(start), 2);
+			seen.extend(page.iter().map(|(who, _)| *who));
+			continuation = next;
+		}
+		seen.sort_unstable();
+		assert_eq!(seen, vec![1, 2, 3, 4, 5]);
+	});
+}
+
+#[test]
+fn adjust_to_target_ratio_reaches_the_target() {
+	ExtBuilder::default().build().execute_with(|| {
+		LiquidationRatio::set(Some(Ratio::saturating_from_integer(2)));
+		let price = Price::saturating_from_integer(1);
+		let exchange_rate = ExchangeRate::saturating_from_rational(1, 2);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 100, 40));
+
+		// Collateral value 100 at a target ratio of 2 supports a debit value of 50, i.e.
+		// 100 debit units: exactly the liquidation boundary, which still passes.
+		assert_eq!(
+			Loans::adjust_to_target_ratio(
+				&ALICE,
+				DOT,
+				Ratio::saturating_from_integer(2),
+				price,
+				exchange_rate,
+			),
+			Ok(60)
+		);
+		assert_eq!(Positions::<Test>::get(DOT, ALICE).debit, 100);
+		assert_eq!(Assets::balance(AUSD, ALICE), 50);
+
+		// A position already at its target needs no adjustment.
+		assert_eq!(
+			Loans::adjust_to_target_ratio(
+				&ALICE,
+				DOT,
+				Ratio::saturating_from_integer(2),
+				price,
+				exchange_rate,
+			),
+			Ok(0)
+		);
+
+		// Raising the target repays debit, keeping the collateral fixed.
+		assert_eq!(
+			Loans::adjust_to_target_ratio(
+				&ALICE,
+				DOT,
+				Ratio::saturating_from_integer(5),
+				price,
+				exchange_rate,
+			),
+			Ok(-60)
+		);
+		assert_eq!(Positions::<Test>::get(DOT, ALICE).debit, 40);
+		assert_eq!(Assets::balance(AUSD, ALICE), 20);
+	});
+}
+
+#[test]
+fn adjust_to_target_ratio_rejects_unsafe_targets() {
+	ExtBuilder::default().build().execute_with(|| {
+		LiquidationRatio::set(Some(Ratio::saturating_from_integer(2)));
+		let price = Price::saturating_from_integer(1);
+		let exchange_rate = ExchangeRate::saturating_from_rational(1, 2);
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 100, 40));
+
+		// A target below the liquidation ratio would borrow into unsafe territory; the
+		// risk manager rejects it and the adjustment rolls back.
+		assert_noop!(
+			Loans::adjust_to_target_ratio(
+				&ALICE,
+				DOT,
+				Ratio::saturating_from_rational(3, 2),
+				price,
+				exchange_rate,
+			),
+			sp_runtime::DispatchError::Other("below liquidation ratio")
+		);
+		assert_eq!(Positions::<Test>::get(DOT, ALICE).debit, 40);
+		assert_eq!(Assets::balance(AUSD, ALICE), 20);
+
+		// A zero target ratio or exchange rate cannot be inverted.
+		assert_noop!(
+			Loans::adjust_to_target_ratio(&ALICE, DOT, Ratio::zero(), price, exchange_rate),
+			Error::<Test>::InvalidTargetRatio
+		);
+		assert_noop!(
+			Loans::adjust_to_target_ratio(
+				&ALICE,
+				DOT,
+				Ratio::saturating_from_integer(2),
+				price,
+				ExchangeRate::zero(),
+			),
+			Error::<Test>::InvalidTargetRatio
+		);
+	});
+}
+
+#[test]
+fn try_state_catches_total_drift() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 200, 100));
+		assert_ok!(Loans::do_try_state());
+
+		// Overwrite the recorded totals behind the pallet's back.
+		TotalPositions::<Test>::insert(DOT, Position { collateral: 500, debit: 300 });
+		assert!(Loans::do_try_state().is_err());
+
+		TotalPositions::<Test>::insert(DOT, Position { collateral: 700, debit: 400 });
+		assert_ok!(Loans::do_try_state());
+
+		// A stale total for a currency without any open position is a failure too.
+		TotalPositions::<Test>::insert(BTC, Position { collateral: 1, debit: 0 });
+		assert!(Loans::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn rebuild_totals_repairs_drifted_totals() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+		assert_ok!(Loans::adjust_position(&ALICE, BTC, 400, 200));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 200, 100));
+
+		// Simulate drift: a wrong DOT total and a stale entry for a currency whose last
+		// position has long closed.
+		TotalPositions::<Test>::insert(DOT, Position { collateral: 1, debit: 1 });
+		TotalPositions::<Test>::insert(AUSD, Position { collateral: 9, debit: 9 });
+		assert!(Loans::do_try_state().is_err());
+
+		assert_noop!(
+			Loans::rebuild_totals(RuntimeOrigin::signed(ALICE), 1),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Loans::rebuild_totals(RuntimeOrigin::root(), 0),
+			Error::<Test>::ZeroLimit
+		);
+
+		// One position per call: the first two calls pause with a cursor, the third
+		// visits the last position and swaps the rebuilt sums in.
+		assert_ok!(Loans::rebuild_totals(RuntimeOrigin::root(), 1));
+		assert!(RebuildCursor::<Test>::get().is_some());
+		assert_ok!(Loans::rebuild_totals(RuntimeOrigin::root(), 1));
+		assert!(RebuildCursor::<Test>::get().is_some());
+		// Mid-rebuild the totals are still the drifted ones.
+		assert_eq!(TotalPositions::<Test>::get(DOT), Position { collateral: 1, debit: 1 });
+		assert_ok!(Loans::rebuild_totals(RuntimeOrigin::root(), 1));
+
+		assert!(RebuildCursor::<Test>::get().is_none());
+		assert!(RebuildingTotals::<Test>::iter().next().is_none());
+		assert_eq!(
+			TotalPositions::<Test>::get(DOT),
+			Position { collateral: 700, debit: 400 }
+		);
+		assert_eq!(
+			TotalPositions::<Test>::get(BTC),
+			Position { collateral: 400, debit: 200 }
+		);
+		assert!(!TotalPositions::<Test>::contains_key(AUSD));
+		System::assert_last_event(Event::<Test>::TotalsRebuilt { currencies: 2 }.into());
+		assert_ok!(Loans::do_try_state());
+	});
+}
+
+#[test]
+fn rebuild_totals_finishes_in_one_exact_chunk() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Loans::adjust_position(&ALICE, DOT, 500, 300));
+		assert_ok!(Loans::adjust_position(&BOB, DOT, 200, 100));
+		TotalPositions::<Test>::insert(DOT, Position { collateral: 1, debit: 1 });
+
+		// A chunk that exactly exhausts the positions completes immediately instead of
+		// leaving a cursor behind.
+		assert_ok!(Loans::rebuild_totals(RuntimeOrigin::root(), 2));
+		assert!(RebuildCursor::<Test>::get().is_none());
+		assert_eq!(
+			TotalPositions::<Test>::get(DOT),
+			Position { collateral: 700, debit: 400 }
+		);
+		System::assert_last_event(Event::<Test>::TotalsRebuilt { currencies: 1 }.into());
+		assert_ok!(Loans::do_try_state());
+	});
+}
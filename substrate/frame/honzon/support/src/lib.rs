@@ -0,0 +1,440 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2025 Acala Foundation.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Honzon Support
+//!
+//! Shared types and traits for the Honzon (CDP) family of pallets.
+//!
+//! The Honzon protocol is split across several pallets - loans (position bookkeeping), the CDP
+//! engine (risk management and liquidation), the CDP treasury (system surplus and debit pools),
+//! the auction manager and emergency shutdown. This crate holds the fixed-point types and the
+//! interfaces these pallets use to talk to each other, so each of them only depends on the
+//! abstractions and runtimes are free to mix implementations.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, DecodeWithMemTracking, Encode, FullCodec, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Bounded, CheckedDiv, Member},
+	DispatchError, DispatchResult, FixedU128, RuntimeDebug,
+};
+
+/// A price, as a fixed point number.
+pub type Price = FixedU128;
+
+/// An exchange rate between two balances (e.g. debit units to debit value), as a fixed point
+/// number.
+pub type ExchangeRate = FixedU128;
+
+/// A ratio between two balances (e.g. collateral value to debit value), as a fixed point
+/// number.
+pub type Ratio = FixedU128;
+
+/// A per-period rate (e.g. a stability fee), as a fixed point number.
+pub type Rate = FixedU128;
+
+/// An intended change to a stored parameter: either keep the current value or set a new one.
+///
+/// Used by governance calls that update several parameters at once, so each parameter can be
+/// changed independently.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum Change<Value> {
+	/// No change.
+	NoChange,
+	/// Changed to a new value.
+	NewValue(Value),
+}
+
+/// A source of prices, quoted in some common unit of account (typically USD).
+pub trait PriceProvider<CurrencyId> {
+	/// The current price of `currency_id`, if available.
+	fn get_price(currency_id: CurrencyId) -> Option<Price>;
+
+	/// The current price of `base` denominated in `quote`, if both prices are available.
+	fn get_relative_price(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
+		match (Self::get_price(base), Self::get_price(quote)) {
+			(Some(base_price), Some(quote_price)) => base_price.checked_div(&quote_price),
+			_ => None,
+		}
+	}
+
+	/// How many blocks ago the price of `currency_id` was last published, if the feed
+	/// tracks publication times. `None` means the age is unknown.
+	fn price_age(_currency_id: CurrencyId) -> Option<u32> {
+		None
+	}
+}
+
+/// Means of locking the price of a currency at its current value and releasing the lock again,
+/// used by emergency shutdown to fix the rates all settlements happen at.
+pub trait LockablePrice<CurrencyId> {
+	/// Record the current price of `currency_id` as the locked price.
+	fn lock_price(currency_id: CurrencyId) -> DispatchResult;
+	/// Remove the locked price of `currency_id`.
+	fn unlock_price(currency_id: CurrencyId) -> DispatchResult;
+}
+
+/// A price provider that is aware of prices locked via [`LockablePrice`].
+///
+/// Consumers that must not follow the live market after emergency shutdown - such as CDP
+/// settlement - read [`Self::locked_price`] and treat its absence as an error rather than
+/// falling back to [`PriceProvider::get_price`].
+pub trait LockedPriceProvider<CurrencyId>: PriceProvider<CurrencyId> {
+	/// The price of `currency_id` locked at emergency shutdown, if any.
+	fn locked_price(currency_id: CurrencyId) -> Option<Price>;
+}
+
+/// An abstraction of risk management logic for CDPs, implemented by the CDP engine and
+/// consumed by the loans pallet whenever a position changes.
+pub trait RiskManager<AccountId, CurrencyId, Balance> {
+	/// The stable currency value of `debit_balance` debit units of `currency_id` CDPs.
+	fn get_debit_value(currency_id: CurrencyId, debit_balance: Balance) -> Balance;
+
+	/// Check that a position with the given collateral and debit is allowed to exist.
+	///
+	/// `check_required_ratio` determines whether the position is held to the required
+	/// collateral ratio on top of the liquidation ratio. Callers pass `false` for
+	/// operations that can only improve a position, such as debt repayments; the
+	/// implementation may still enforce the required ratio unconditionally.
+	fn check_position_valid(
+		currency_id: CurrencyId,
+		collateral_balance: Balance,
+		debit_balance: Balance,
+		check_required_ratio: bool,
+	) -> DispatchResult;
+
+	/// Check that the total debit of `currency_id` CDPs does not exceed its hard cap.
+	fn check_debit_cap(currency_id: CurrencyId, total_debit_balance: Balance) -> DispatchResult;
+
+	/// The smallest collateral amount a debit-free position may keep. Positions below it must
+	/// withdraw their collateral in full instead of leaving dust behind.
+	fn minimum_collateral() -> Balance;
+}
+
+/// A handler notified whenever a loan position changes, e.g. to maintain an index over
+/// positions.
+pub trait OnUpdateLoan<AccountId, CurrencyId, Balance> {
+	/// The position of `who` for `currency_id` now holds `collateral` against `debit`.
+	fn on_update_loan(
+		who: &AccountId,
+		currency_id: CurrencyId,
+		collateral: Balance,
+		debit: Balance,
+	);
+}
+
+impl<AccountId, CurrencyId, Balance> OnUpdateLoan<AccountId, CurrencyId, Balance> for () {
+	fn on_update_loan(
+		_who: &AccountId,
+		_currency_id: CurrencyId,
+		_collateral: Balance,
+		_debit: Balance,
+	) {
+	}
+}
+
+/// An observer of position liquidations and settlements, e.g. to journal position history.
+pub trait OnLiquidation<AccountId, CurrencyId, Balance> {
+	/// The position of `who` for `currency_id` was liquidated, confiscating `collateral`
+	/// and `debit`.
+	fn on_liquidation(who: &AccountId, currency_id: CurrencyId, collateral: Balance, debit: Balance);
+
+	/// The position of `who` for `currency_id` was settled after emergency shutdown,
+	/// confiscating `collateral` and `debit`.
+	fn on_settlement(who: &AccountId, currency_id: CurrencyId, collateral: Balance, debit: Balance);
+}
+
+/// An external risk manager that can veto the liquidation of specific positions, e.g. a
+/// governance-granted grace period.
+pub trait LiquidationGuard<AccountId> {
+	/// Whether the positions of `who` may currently be liquidated.
+	fn can_liquidate(who: &AccountId) -> bool;
+}
+
+impl<AccountId> LiquidationGuard<AccountId> for () {
+	fn can_liquidate(_who: &AccountId) -> bool {
+		true
+	}
+}
+
+impl<AccountId, CurrencyId, Balance> OnLiquidation<AccountId, CurrencyId, Balance> for () {
+	fn on_liquidation(
+		_who: &AccountId,
+		_currency_id: CurrencyId,
+		_collateral: Balance,
+		_debit: Balance,
+	) {
+	}
+
+	fn on_settlement(
+		_who: &AccountId,
+		_currency_id: CurrencyId,
+		_collateral: Balance,
+		_debit: Balance,
+	) {
+	}
+}
+
+/// An abstraction of the CDP treasury, which manages the system's surplus and debit pools and
+/// custodies confiscated collateral.
+pub trait CDPTreasury<AccountId> {
+	type Balance;
+	type CurrencyId;
+
+	/// The current system surplus pool, in the stable currency.
+	fn get_surplus_pool() -> Self::Balance;
+
+	/// The current system debit pool, in the stable currency.
+	fn get_debit_pool() -> Self::Balance;
+
+	/// The amount of `currency_id` collateral held by the treasury.
+	fn get_total_collaterals(currency_id: Self::CurrencyId) -> Self::Balance;
+
+	/// Record `amount` of system debit (unbacked stable currency).
+	fn on_system_debit(amount: Self::Balance) -> DispatchResult;
+
+	/// Record `amount` of system surplus.
+	fn on_system_surplus(amount: Self::Balance) -> DispatchResult;
+
+	/// Issue `debit` amount of stable currency to `who`. If `backed` is false the issuance is
+	/// recorded as system debit.
+	fn issue_debit(who: &AccountId, debit: Self::Balance, backed: bool) -> DispatchResult;
+
+	/// Burn `debit` amount of stable currency from `who`.
+	fn burn_debit(who: &AccountId, debit: Self::Balance) -> DispatchResult;
+
+	/// Transfer `surplus` amount of stable currency from `from` into the surplus pool.
+	fn deposit_surplus(from: &AccountId, surplus: Self::Balance) -> DispatchResult;
+
+	/// Transfer `amount` of `currency_id` collateral from `from` into the treasury.
+	fn deposit_collateral(
+		from: &AccountId,
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+	) -> DispatchResult;
+
+	/// Transfer `amount` of `currency_id` collateral from the treasury to `to`.
+	fn withdraw_collateral(
+		to: &AccountId,
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+	) -> DispatchResult;
+}
+
+/// Extension of [`CDPTreasury`] with the ability to recapitalize the system through debt and
+/// surplus auctions.
+pub trait CDPTreasuryExtended<AccountId>: CDPTreasury<AccountId> {
+	/// Start an auction selling newly minted native tokens for `amount` of stable currency,
+	/// to cover bad debt.
+	fn create_debt_auction(amount: Self::Balance) -> DispatchResult;
+
+	/// Start an auction selling `amount` of surplus stable currency for native tokens to be
+	/// burned.
+	fn create_surplus_auction(amount: Self::Balance) -> DispatchResult;
+}
+
+/// The information of an auction.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct AuctionInfo<AccountId, Balance, BlockNumber> {
+	/// The current winning bid, if any.
+	pub bid: Option<(AccountId, Balance)>,
+	/// The block the auction started at.
+	pub start: BlockNumber,
+	/// The block the auction ends at, if it has a deadline.
+	pub end: Option<BlockNumber>,
+}
+
+/// An abstraction of a simple auction system: auctions are created and removed by other
+/// pallets, while bids arrive as extrinsics and are judged by an [`AuctionHandler`].
+pub trait Auction<AccountId, BlockNumber> {
+	/// The id of an auction.
+	type AuctionId: AtLeast32BitUnsigned
+		+ Bounded
+		+ Member
+		+ FullCodec
+		+ DecodeWithMemTracking
+		+ Copy
+		+ MaxEncodedLen
+		+ TypeInfo;
+	/// The price of a bid.
+	type Balance: AtLeast32BitUnsigned + Member + FullCodec + Copy + MaxEncodedLen + TypeInfo;
+
+	/// The information of `id`, if it exists.
+	fn auction_info(
+		id: Self::AuctionId,
+	) -> Option<AuctionInfo<AccountId, Self::Balance, BlockNumber>>;
+
+	/// Replace the information of `id` with `info`.
+	fn update_auction(
+		id: Self::AuctionId,
+		info: AuctionInfo<AccountId, Self::Balance, BlockNumber>,
+	) -> DispatchResult;
+
+	/// Create a new auction running from `start` until `end` (forever if `None`).
+	fn new_auction(
+		start: BlockNumber,
+		end: Option<BlockNumber>,
+	) -> Result<Self::AuctionId, DispatchError>;
+
+	/// Remove `id` without triggering the end handling.
+	fn remove_auction(id: Self::AuctionId);
+}
+
+/// The result of the auction handler judging a new bid.
+pub struct OnNewBidResult<BlockNumber> {
+	/// Whether the bid is accepted.
+	pub accept_bid: bool,
+	/// An intended change of the auction end time, e.g. to extend the auction on late bids.
+	pub auction_end_change: Change<Option<BlockNumber>>,
+	/// The all-in actual weight of the bid dispatch on the path the handler took, replacing
+	/// the extrinsic's worst-case weight post-dispatch so cheaper paths are refunded.
+	/// `None` charges the pre-dispatch weight.
+	pub actual_weight: Option<sp_weights::Weight>,
+}
+
+/// The business logic behind an [`Auction`] implementation. Moving the funds bids are made
+/// with is entirely the handler's responsibility; the auction pallet only keeps the schedule.
+pub trait AuctionHandler<AccountId, Balance, BlockNumber, AuctionId> {
+	/// Judge the new bid `new_bid` against the current winning bid `last_bid`, moving funds
+	/// accordingly if it is accepted.
+	fn on_new_bid(
+		now: BlockNumber,
+		id: AuctionId,
+		new_bid: (AccountId, Balance),
+		last_bid: Option<(AccountId, Balance)>,
+	) -> OnNewBidResult<BlockNumber>;
+
+	/// An absolute floor below which bids on `id` are rejected before [`Self::on_new_bid`]
+	/// is consulted, e.g. a starting price. `None` imposes no floor.
+	fn minimum_bid(_id: AuctionId) -> Option<Balance> {
+		None
+	}
+
+	/// The current winning bidder asks to withdraw their bid of `amount`. Return
+	/// `Some(penalty)` to allow the cancellation and refund the bidder minus `penalty`, or
+	/// `None` to forbid it.
+	fn on_bid_cancelled(id: AuctionId, bidder: &AccountId, amount: Balance) -> Option<Balance>;
+
+	/// An auction ended with `winner` as the winning bid, if any.
+	fn on_auction_ended(id: AuctionId, winner: Option<(AccountId, Balance)>);
+}
+
+/// A secondary observer of auction completion, for consumers beyond the single configured
+/// [`AuctionHandler`] - analytics, bidder incentives, readiness trackers. Observers run after
+/// the primary handler in the closing path and must be infallible and cheap: closing happens
+/// in `on_initialize`, and [`Self::weight_hint`] is charged for every closed auction.
+pub trait OnAuctionEnded<AccountId, Balance, AuctionId> {
+	/// An auction ended with `winner` as the winning bid, if any. The primary handler has
+	/// already acted on the outcome.
+	fn on_auction_ended(id: AuctionId, winner: Option<(AccountId, Balance)>);
+
+	/// An upper bound on the weight of one `on_auction_ended` invocation.
+	fn weight_hint() -> sp_weights::Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId: Clone, Balance: Clone, AuctionId: Copy>
+	OnAuctionEnded<AccountId, Balance, AuctionId> for Tuple
+{
+	fn on_auction_ended(id: AuctionId, winner: Option<(AccountId, Balance)>) {
+		for_tuples!( #( Tuple::on_auction_ended(id, winner.clone()); )* );
+	}
+
+	fn weight_hint() -> sp_weights::Weight {
+		let mut weight = sp_weights::Weight::zero();
+		for_tuples!( #( weight = weight.saturating_add(Tuple::weight_hint()); )* );
+		weight
+	}
+}
+
+/// An abstraction of the auction manager, which turns confiscated collateral back into stable
+/// currency.
+pub trait AuctionManager<AccountId> {
+	type Balance;
+	type CurrencyId;
+	type AuctionId;
+	type BlockNumber;
+
+	/// Start a new collateral auction selling `amount` of `currency_id` for a `target` amount
+	/// of stable currency. Collateral refunds beyond the target go to `refund_recipient`.
+	fn new_collateral_auction(
+		refund_recipient: &AccountId,
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+		target: Self::Balance,
+	) -> DispatchResult;
+
+	/// Start an always-forward auction selling `amount` of `currency_id` without a target:
+	/// every bid wins an ever-increasing price and there is no reverse stage. Bids implying
+	/// a unit price below `min_price_per_unit` are rejected. The auction closes after
+	/// `duration` blocks. Used for treasury-initiated collateral sales.
+	fn new_forward_auction(
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+		min_price_per_unit: Price,
+		duration: Self::BlockNumber,
+	) -> DispatchResult;
+
+	/// Cancel an in-progress auction.
+	fn cancel_auction(id: Self::AuctionId) -> DispatchResult;
+
+	/// The total amount of `currency_id` collateral currently locked in auctions.
+	fn get_total_collateral_in_auction(currency_id: Self::CurrencyId) -> Self::Balance;
+
+	/// The total stable currency target of all in-progress collateral auctions.
+	fn get_total_target_in_auction() -> Self::Balance;
+}
+
+/// An abstraction of the emergency shutdown state.
+pub trait EmergencyShutdown {
+	/// Whether emergency shutdown has been triggered.
+	fn is_shutdown() -> bool;
+}
+
+/// Exposes how far post-shutdown settlement has progressed, gating whether a shutdown can
+/// still be reverted.
+pub trait SettlementCounter {
+	/// The number of CDPs settled since emergency shutdown was triggered.
+	fn settlements_since_shutdown() -> u32;
+}
+
+impl SettlementCounter for () {
+	fn settlements_since_shutdown() -> u32 {
+		0
+	}
+}
@@ -0,0 +1,3390 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Multi-Asset Bounties Pallet ( pallet-multi-asset-bounties )
+//!
+//! ## Bounty
+//!
+//! A bounty is a reward for a specified body of work - or specified set of objectives - that is
+//! paid out in an arbitrary [`Config::AssetKind`] once the work is completed and accepted by a
+//! curator. Unlike `pallet-bounties`, bounty funds do not have to live in the native currency:
+//! each bounty nominates the asset it is denominated in and all value movements happen through
+//! an asynchronous [`PayWithSource`] paymaster (e.g. an XCM transfer from the treasury's
+//! sovereign account on an asset hub).
+//!
+//! Because payments may settle off-chain (or on another chain), funding, payouts and refunds
+//! are tracked with an explicit [`PaymentState`] and progressed by permissionless
+//! `check_payment_status` calls, in the same fashion as `pallet-treasury` spends.
+//!
+//! Governance (the `RejectOrigin`) may restrict which asset kinds bounties can be funded with
+//! by managing an allow-list; see [`AllowedAssetKinds`]. While the allow-list is empty, any
+//! asset kind convertible by the [`Config::BalanceConverter`] is accepted. Since child bounties
+//! are carved out of their parent's funds, the check on the parent at funding time implicitly
+//! covers all of its children.
+//!
+//! Proposer bonds and curator deposits remain in the native currency, as they protect against
+//! spam on this chain and are slashable via [`Config::OnSlash`].
+//!
+//! High-value bounties may be curated by a group instead of a single account. While a multisig
+//! account can always be made the curator, `propose_multi_curator` additionally records the
+//! member accounts and an approval threshold on chain: `accept_curator` and `award_bounty` then
+//! only take effect once [`Config::CuratorThreshold`] distinct members have called them, with
+//! pending approvals tracked in storage. The curator of record stays a single account (typically
+//! the multisig account composed of the members), holding the deposit and receiving the fee.
+//!
+//! When a bounty or child bounty is removed, only its primary [`Bounties`] (resp.
+//! [`ChildBounties`]) entry is deleted synchronously; the auxiliary maps (descriptions,
+//! curator sets, child accounting) are enqueued into [`PendingCleanup`] and deleted lazily in
+//! weight-metered batches by `on_idle` or the permissionless `purge_removed` call. Read paths
+//! treat auxiliary data whose primary entry is gone as already deleted.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! Bounty protocol:
+//!
+//! - `propose_bounty` - Propose a bounty denominated in some asset kind and stake the required
+//!   native deposit.
+//! - `approve_bounty` - Accept a bounty proposal, allowing it to be funded.
+//! - `fund_bounty` - Trigger payment of the bounty value from the funding source into the
+//!   bounty's sub-account.
+//! - `check_payment_status` - Progress a pending funding, payout or refund payment.
+//! - `propose_curator` - Assign an account to an funded bounty as candidate curator.
+//! - `propose_multi_curator` - Assign a multi-signature curator set to a funded bounty.
+//! - `accept_curator` - Accept a bounty assignment, staking a native curator deposit.
+//! - `award_bounty` - Close and begin payout of the bounty value for the completed work.
+//! - `claim_bounty` - Trigger the payout payments of an awarded bounty.
+//! - `unassign_curator` - Unassign an accepted curator from a specific bounty.
+//! - `close_bounty` - Cancel a bounty, refunding its funds to the funding source.
+//! - `extend_bounty_expiry` - Extend the expiry block number of the bounty and stay active.
+//! - `allow_asset_kind` / `disallow_asset_kind` - Manage the funding asset allow-list.
+//! - `purge_removed` - Delete the auxiliary storage of removed bounties from the cleanup
+//!   queue.
+//!
+//! Child bounty protocol:
+//!
+//! - `add_child_bounty` - Carve a child bounty out of an active parent bounty.
+//! - `propose_child_curator` - Assign a candidate curator to a child bounty.
+//! - `accept_child_curator` - Accept a child bounty assignment, staking a native deposit.
+//! - `award_child_bounty` - Award the child bounty to a beneficiary.
+//! - `claim_child_bounty` - Trigger the payout payments of an awarded child bounty.
+//! - `check_child_payment_status` - Progress a pending child bounty payout.
+//! - `close_child_bounty` - Cancel a child bounty, returning its value to the parent.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+extern crate alloc;
+
+use alloc::{boxed::Box, vec::Vec};
+
+use codec::FullCodec;
+
+use frame_support::{
+	dispatch::DispatchResultWithPostInfo,
+	pallet_prelude::*,
+	traits::{
+		fungible::{self, BalancedHold, Inspect, InspectHold, MutateHold},
+		tokens::{ConversionFromAssetBalance, PaymentStatus, Precision},
+		Currency, EnsureOrigin, Get, OnUnbalanced, ReservableCurrency,
+	},
+	PalletId,
+};
+use frame_system::pallet_prelude::{
+	ensure_signed, BlockNumberFor as SystemBlockNumberFor, OriginFor,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AccountIdConversion, BadOrigin, BlockNumberProvider, Saturating, StaticLookup, Zero},
+	DispatchResult, Permill, RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+type BalanceOf<T, I = ()> =
+	<<T as Config<I>>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The imbalance raised when a held deposit is slashed, routed to [`Config::OnSlash`].
+pub type CreditOf<T, I = ()> =
+	fungible::Credit<<T as frame_system::Config>::AccountId, <T as Config<I>>::Currency>;
+
+/// The balance type of the bounty asset, as measured by the paymaster.
+pub type AssetBalanceOf<T, I = ()> = <<T as Config<I>>::Paymaster as PayWithSource>::Balance;
+
+/// The payment identifier handed out by the paymaster.
+pub type PaymentIdOf<T, I = ()> = <<T as Config<I>>::Paymaster as PayWithSource>::Id;
+
+type BlockNumberFor<T, I = ()> =
+	<<T as Config<I>>::BlockNumberProvider as BlockNumberProvider>::BlockNumber;
+
+type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
+
+/// An index of a bounty. Just a `u32`.
+pub type BountyIndex = u32;
+
+/// Where the descriptions of bounties and child bounties are stored.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum DescriptionStorageMode {
+	/// The full description text is stored on chain, bounded by
+	/// [`Config::MaximumReasonLength`].
+	Full,
+	/// Only a 32 byte hash of the description is stored on chain; the text itself lives off
+	/// chain. Description arguments must then be exactly 32 bytes long.
+	HashOnly,
+}
+
+/// A description argument validated against the configured [`DescriptionStorageMode`], ready
+/// to be written to the matching storage map.
+enum PreparedDescription<Description, Hash> {
+	/// The full bounded description text.
+	Full(Description),
+	/// The 32 byte description hash.
+	HashOnly(Hash),
+}
+
+impl<Description: AsRef<[u8]>, Hash> PreparedDescription<Description, Hash> {
+	/// The number of bytes that will be stored, determining the data deposit.
+	fn len(&self) -> usize {
+		match self {
+			Self::Full(description) => description.as_ref().len(),
+			Self::HashOnly(_) => 32,
+		}
+	}
+}
+
+/// What a successful propose/approve/fund pipeline would produce for a prospective bounty,
+/// returned by the [`Pallet::validate_fund_bounty`] view function.
+#[derive(Encode, Decode, DecodeWithMemTracking, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct FundBountyPreview<AccountId, NativeBalance> {
+	/// The bounty value converted to the native currency, as compared against the spend
+	/// origin's budget and the bounty value minimum.
+	pub native_value: NativeBalance,
+	/// The account the bounty's funds would be paid into, derived for the next bounty index.
+	pub bounty_account: AccountId,
+	/// The curator deposit acceptance would require if the whole value were the curator fee.
+	/// The fee cannot exceed the value, so deposits for real fees are at most this.
+	pub max_curator_deposit: NativeBalance,
+}
+
+/// A removed bounty or child bounty whose auxiliary storage still awaits deferred deletion.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	MaxEncodedLen,
+	RuntimeDebug,
+	TypeInfo,
+)]
+pub enum PendingCleanupEntry {
+	/// The index of a removed bounty.
+	Bounty(BountyIndex),
+	/// The (globally unique) child index of a removed child bounty.
+	ChildBounty(BountyIndex),
+}
+
+type BountyOf<T, I> = Bounty<
+	<T as frame_system::Config>::AccountId,
+	<T as Config<I>>::AssetKind,
+	AssetBalanceOf<T, I>,
+	BalanceOf<T, I>,
+	BlockNumberFor<T, I>,
+	PaymentIdOf<T, I>,
+>;
+
+type ChildBountyOf<T, I> = ChildBounty<
+	<T as frame_system::Config>::AccountId,
+	AssetBalanceOf<T, I>,
+	BalanceOf<T, I>,
+	BlockNumberFor<T, I>,
+	PaymentIdOf<T, I>,
+>;
+
+type MultiCuratorSetOf<T, I> =
+	MultiCuratorSet<<T as frame_system::Config>::AccountId, <T as Config<I>>::MaxMultiCurators>;
+
+/// Means of making a payment from a known source account.
+///
+/// This mirrors [`frame_support::traits::tokens::Pay`], but payments carry an explicit source so
+/// a single implementation can both fund a bounty from the treasury pot and later pay a
+/// beneficiary out of the bounty's own sub-account.
+pub trait PayWithSource {
+	/// The type by which we measure units of the asset in which we make payments.
+	type Balance: frame_support::traits::tokens::Balance;
+	/// The type by which we identify payment sources and beneficiaries.
+	type AccountId;
+	/// The type for the kinds of asset that payments are made in.
+	type AssetKind;
+	/// An identifier given to an individual payment.
+	type Id: FullCodec + MaxEncodedLen + TypeInfo + Clone + Eq + PartialEq + core::fmt::Debug + Copy;
+	/// An error which could be returned by the paymaster.
+	type Error: core::fmt::Debug;
+
+	/// Make a payment and return an identifier for later evaluation of success in some off-chain
+	/// mechanism (likely an event, but possibly not on this chain).
+	fn pay(
+		source: &Self::AccountId,
+		beneficiary: &Self::AccountId,
+		asset_kind: Self::AssetKind,
+		amount: Self::Balance,
+	) -> Result<Self::Id, Self::Error>;
+
+	/// Check how a payment has proceeded. `id` must have been previously returned by `pay` for
+	/// the result of this call to be meaningful.
+	fn check_payment(id: Self::Id) -> PaymentStatus;
+
+	/// Ensure that a call to `pay` with the given parameters will be successful if done
+	/// immediately after this call. Used in benchmarking code.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn ensure_successful(
+		source: &Self::AccountId,
+		beneficiary: &Self::AccountId,
+		asset_kind: Self::AssetKind,
+		amount: Self::Balance,
+	);
+
+	/// Ensure that a call to `check_payment` with the given parameters will return either
+	/// `Success` or `Failure`.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn ensure_concluded(id: Self::Id);
+}
+
+/// The state of an asynchronous bounty payment.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	MaxEncodedLen,
+	RuntimeDebug,
+	TypeInfo,
+)]
+pub enum PaymentState<Id> {
+	/// Payment attempted with a payment identifier.
+	Attempted {
+		/// The payment identifier handed out by the paymaster.
+		id: Id,
+	},
+	/// Payment failed. It may be retried.
+	Failed,
+}
+
+/// A bounty denominated in some asset kind.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct Bounty<AccountId, AssetKind, AssetBalance, NativeBalance, BlockNumber, PaymentId> {
+	/// The account proposing it.
+	pub proposer: AccountId,
+	/// The kind of asset this bounty is rewarded in.
+	pub asset_kind: AssetKind,
+	/// The (total) amount of `asset_kind` that should be paid if the bounty is rewarded.
+	pub value: AssetBalance,
+	/// The curator fee in `asset_kind`. Included in value.
+	pub fee: AssetBalance,
+	/// The deposit of curator, in the native currency.
+	pub curator_deposit: NativeBalance,
+	/// The amount held on deposit (reserved) for making this proposal.
+	pub bond: NativeBalance,
+	/// The status of this bounty.
+	pub status: BountyStatus<AccountId, BlockNumber, PaymentId>,
+}
+
+/// The status of a bounty proposal.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum BountyStatus<AccountId, BlockNumber, PaymentId> {
+	/// The bounty is proposed and waiting for approval.
+	Proposed,
+	/// The bounty is approved and waiting to be funded.
+	Approved,
+	/// Payment of the bounty value into the bounty account has been attempted.
+	FundingAttempted {
+		/// The state of the funding payment.
+		payment_status: PaymentState<PaymentId>,
+	},
+	/// The bounty is funded and waiting for curator assignment.
+	Funded,
+	/// A curator has been proposed. Waiting for acceptance from the curator.
+	CuratorProposed {
+		/// The assigned curator of this bounty.
+		curator: AccountId,
+	},
+	/// The bounty is active and waiting to be awarded.
+	Active {
+		/// The curator of this bounty.
+		curator: AccountId,
+		/// An update from the curator is due by this block, else they are considered inactive.
+		update_due: BlockNumber,
+	},
+	/// The bounty is awarded and waiting to be claimed after a delay.
+	PendingPayout {
+		/// The curator of this bounty.
+		curator: AccountId,
+		/// The beneficiary of the bounty.
+		beneficiary: AccountId,
+		/// When the bounty can be claimed.
+		unlock_at: BlockNumber,
+	},
+	/// Payout of the bounty value to the curator and beneficiary has been attempted.
+	PayoutAttempted {
+		/// The curator of this bounty.
+		curator: AccountId,
+		/// The beneficiary of the bounty.
+		beneficiary: AccountId,
+		/// The state of the curator fee payment.
+		curator_payment: PaymentState<PaymentId>,
+		/// The state of the beneficiary payout payment.
+		beneficiary_payment: PaymentState<PaymentId>,
+	},
+	/// Refund of the bounty value back to the funding source has been attempted.
+	RefundAttempted {
+		/// The state of the refund payment.
+		payment_status: PaymentState<PaymentId>,
+	},
+}
+
+/// A child bounty, carved out of its parent bounty's funds.
+///
+/// The child is denominated in its parent's asset kind and is paid out of the parent's bounty
+/// account.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct ChildBounty<AccountId, AssetBalance, NativeBalance, BlockNumber, PaymentId> {
+	/// The parent bounty of this child bounty.
+	pub parent_bounty: BountyIndex,
+	/// The amount of the parent's asset kind that should be paid if the child bounty is
+	/// rewarded.
+	pub value: AssetBalance,
+	/// The child curator fee. Included in value.
+	pub fee: AssetBalance,
+	/// The deposit of the child curator, in the native currency.
+	pub curator_deposit: NativeBalance,
+	/// The status of this child bounty.
+	pub status: ChildBountyStatus<AccountId, BlockNumber, PaymentId>,
+}
+
+/// The status of a child bounty.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum ChildBountyStatus<AccountId, BlockNumber, PaymentId> {
+	/// The child bounty has been added and is waiting for a curator.
+	Added,
+	/// A curator has been proposed. Waiting for acceptance from the curator.
+	CuratorProposed {
+		/// The assigned child curator.
+		curator: AccountId,
+	},
+	/// The child bounty is active and waiting to be awarded.
+	Active {
+		/// The child curator.
+		curator: AccountId,
+		/// An update from the child curator is due by this block, else they are considered
+		/// inactive.
+		update_due: BlockNumber,
+	},
+	/// The child bounty is awarded and waiting to be claimed after a delay.
+	PendingPayout {
+		/// The child curator.
+		curator: AccountId,
+		/// The beneficiary of the child bounty.
+		beneficiary: AccountId,
+		/// When the child bounty can be claimed.
+		unlock_at: BlockNumber,
+	},
+	/// Payout of the child bounty value has been attempted.
+	PayoutAttempted {
+		/// The child curator.
+		curator: AccountId,
+		/// The beneficiary of the child bounty.
+		beneficiary: AccountId,
+		/// The state of the child curator fee payment.
+		curator_payment: PaymentState<PaymentId>,
+		/// The state of the beneficiary payout payment.
+		beneficiary_payment: PaymentState<PaymentId>,
+	},
+}
+
+/// A multi-signature curator set gating the curator actions of a bounty.
+///
+/// The curator of record in [`BountyStatus`] remains a single account holding the deposit and
+/// receiving the fee; this set only determines whose individual approvals count towards the
+/// threshold for `accept_curator` and `award_bounty`.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(S))]
+pub struct MultiCuratorSet<AccountId, S: Get<u32>> {
+	/// The member accounts whose approvals count towards the threshold.
+	pub curators: BoundedVec<AccountId, S>,
+	/// The number of member approvals required for a curator action.
+	pub threshold: u32,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T, I = ()>(_);
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The native currency, used for proposer bonds and curator deposits. Both are
+		/// taken as fungible holds, so they stay visible to hold-aware tooling instead of
+		/// pooling anonymously with other reserves.
+		type Currency: Inspect<Self::AccountId>
+			+ MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>
+			+ BalancedHold<Self::AccountId>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason<I>>;
+
+		/// The legacy reservable currency bonds and deposits were taken with before the
+		/// switch to fungible holds. Only used to lazily convert pre-existing reserves;
+		/// normally the same `pallet_balances` instance as [`Config::Currency`].
+		type OldCurrency: ReservableCurrency<Self::AccountId>
+			+ Currency<Self::AccountId, Balance = BalanceOf<Self, I>>;
+
+		/// Origin from which rejections and other administrative actions must come.
+		type RejectOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin required for approving bounties. The `Success` value is the maximum
+		/// amount in the native currency that this origin is allowed to approve, compared
+		/// against the bounty value converted via [`Config::BalanceConverter`].
+		type SpendOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = BalanceOf<Self, I>>;
+
+		/// Type parameter representing the asset kinds bounties may be denominated in.
+		type AssetKind: Parameter + MaxEncodedLen;
+
+		/// Type for making bounty payments between the funding source, bounty accounts and
+		/// beneficiaries.
+		type Paymaster: PayWithSource<AccountId = Self::AccountId, AssetKind = Self::AssetKind>;
+
+		/// Type for converting an amount of the bounty asset to the native balance, for the
+		/// purpose of asserting bounty values against [`Config::SpendOrigin`] limits and
+		/// computing native deposits.
+		type BalanceConverter: ConversionFromAssetBalance<
+			AssetBalanceOf<Self, I>,
+			Self::AssetKind,
+			BalanceOf<Self, I>,
+		>;
+
+		/// The account bounties are funded from, typically the treasury pot.
+		type FundingSource: Get<Self::AccountId>;
+
+		/// The amount held on deposit for placing a bounty proposal.
+		#[pallet::constant]
+		type BountyDepositBase: Get<BalanceOf<Self, I>>;
+
+		/// The delay period for which a bounty beneficiary needs to wait before claiming the
+		/// payout.
+		#[pallet::constant]
+		type BountyDepositPayoutDelay: Get<BlockNumberFor<Self, I>>;
+
+		/// The time limit for a curator to act before a bounty expires.
+		#[pallet::constant]
+		type BountyUpdatePeriod: Get<BlockNumberFor<Self, I>>;
+
+		/// How long a funding payment may stay inconclusive before the `RejectOrigin` may
+		/// abandon it via `abandon_payment`, counted from the funding attempt.
+		#[pallet::constant]
+		type PaymentInconclusiveTimeout: Get<BlockNumberFor<Self, I>>;
+
+		/// The curator deposit is calculated as a percentage of the curator fee, converted to
+		/// the native currency.
+		///
+		/// This deposit has optional upper and lower bounds with `CuratorDepositMax` and
+		/// `CuratorDepositMin`.
+		#[pallet::constant]
+		type CuratorDepositMultiplier: Get<Permill>;
+
+		/// Maximum amount of funds that should be placed in a deposit for accepting a curator
+		/// role.
+		#[pallet::constant]
+		type CuratorDepositMax: Get<Option<BalanceOf<Self, I>>>;
+
+		/// Minimum amount of funds that should be placed in a deposit for accepting a curator
+		/// role.
+		#[pallet::constant]
+		type CuratorDepositMin: Get<Option<BalanceOf<Self, I>>>;
+
+		/// The maximum curator fee as a fraction of the bounty (or child bounty) value.
+		///
+		/// Proposing a curator with a higher fee fails with [`Error::InvalidFee`]. Since
+		/// the fee is carved out of the value, this caps how much of a bounty can go to
+		/// its curator instead of the beneficiary.
+		#[pallet::constant]
+		type MaxCuratorFeeMultiplier: Get<Permill>;
+
+		/// The number of member approvals required for the curator actions of a
+		/// multi-signature curator set assigned via `propose_multi_curator`.
+		///
+		/// Recorded per bounty at proposal time, so changing this constant does not affect
+		/// sets already in flight. Values below one are treated as one.
+		#[pallet::constant]
+		type CuratorThreshold: Get<u32>;
+
+		/// Maximum number of members in a multi-signature curator set.
+		#[pallet::constant]
+		type MaxMultiCurators: Get<u32>;
+
+		/// Minimum value for a bounty, in the native currency (after conversion via
+		/// [`Config::BalanceConverter`]).
+		#[pallet::constant]
+		type BountyValueMinimum: Get<BalanceOf<Self, I>>;
+
+		/// The amount held on deposit per byte within the bounty description.
+		#[pallet::constant]
+		type DataDepositPerByte: Get<BalanceOf<Self, I>>;
+
+		/// Maximum acceptable reason length.
+		///
+		/// Benchmarks depend on this value, be sure to update weights file when changing this
+		/// value.
+		#[pallet::constant]
+		type MaximumReasonLength: Get<u32>;
+
+		/// How the descriptions of bounties and child bounties are stored.
+		///
+		/// With [`DescriptionStorageMode::HashOnly`], the description arguments of
+		/// `propose_bounty` and `add_child_bounty` must be the 32 byte hash of the off-chain
+		/// description text. Switching a live chain from [`DescriptionStorageMode::Full`]
+		/// requires running [`migrations::MigrateDescriptionsToHashOnly`].
+		type DescriptionMode: Get<DescriptionStorageMode>;
+
+		/// Maximum number of child bounties that can be added to a bounty.
+		#[pallet::constant]
+		type MaxActiveChildBountyCount: Get<u32>;
+
+		/// Maximum number of removed bounties and child bounties whose auxiliary storage may
+		/// await deferred cleanup in [`PendingCleanup`] at once.
+		///
+		/// When the backlog is full, further removals delete their auxiliary storage
+		/// synchronously instead, so nothing is ever leaked.
+		#[pallet::constant]
+		type MaxCleanupBacklog: Get<u32>;
+
+		/// The bounties' pallet id, used for deriving its sovereign account and the bounty
+		/// sub-accounts.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Handler for the unbalanced decrease when slashing for a rejected bounty.
+		type OnSlash: OnUnbalanced<CreditOf<Self, I>>;
+
+		/// Provider for the block number. Normally this is the `frame_system` pallet.
+		type BlockNumberProvider: BlockNumberProvider;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Reasons this pallet holds native funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason<I: 'static = ()> {
+		/// The curator's deposit while curating a bounty or child bounty.
+		#[codec(index = 0)]
+		CuratorDeposit,
+		/// The proposer's bond while their bounty proposal awaits funding.
+		#[codec(index = 1)]
+		ProposerBond,
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// Proposer's balance is too low.
+		InsufficientProposersBalance,
+		/// No bounty or child bounty at that index.
+		InvalidIndex,
+		/// The reason given is just too big.
+		ReasonTooBig,
+		/// The bounty status is unexpected.
+		UnexpectedStatus,
+		/// Require bounty curator.
+		RequireCurator,
+		/// Invalid bounty value.
+		InvalidValue,
+		/// Invalid bounty fee.
+		InvalidFee,
+		/// A bounty payout is pending.
+		/// To cancel the bounty, you must unassign and slash the curator.
+		PendingPayout,
+		/// The bounty cannot be claimed/closed because it's still in the countdown period.
+		Premature,
+		/// The bounty cannot be closed because it has active child bounties.
+		HasActiveChildBounty,
+		/// The balance of the bounty asset kind is not convertible to the native balance.
+		FailedToConvertBalance,
+		/// The bounty asset kind is not in the allow-list.
+		AssetNotAllowed,
+		/// The origin is not allowed to approve a bounty of this value.
+		InsufficientPermission,
+		/// There was some issue with the mechanism of payment.
+		PayoutError,
+		/// The payment has neither failed nor succeeded yet.
+		Inconclusive,
+		/// The parent bounty does not have enough unassigned funds for this child bounty.
+		InsufficientBountyBalance,
+		/// Number of child bounties exceeds limit `MaxActiveChildBountyCount`.
+		TooManyChildBounties,
+		/// In hash-only description mode, the description must be exactly a 32 byte hash.
+		InvalidDescriptionHash,
+		/// A multi-signature curator set has fewer members than the required threshold.
+		TooFewCurators,
+		/// Number of curator set members exceeds limit `MaxMultiCurators`.
+		TooManyCurators,
+		/// The same account appears twice in a multi-signature curator set.
+		DuplicateCurator,
+		/// The member has already approved this curator action.
+		AlreadyApproved,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// New bounty proposal.
+		BountyProposed { index: BountyIndex },
+		/// A bounty is approved.
+		BountyApproved { index: BountyIndex },
+		/// A bounty proposal was rejected; funds were slashed.
+		BountyRejected { index: BountyIndex, bond: BalanceOf<T, I> },
+		/// Payment of the bounty value into the bounty account has been attempted.
+		BountyFundingAttempted { index: BountyIndex, payment_id: PaymentIdOf<T, I> },
+		/// A bounty is funded and ready for curator assignment.
+		BountyFunded { index: BountyIndex },
+		/// A bounty payment failed and can be retried.
+		PaymentFailed { index: BountyIndex, payment_id: PaymentIdOf<T, I> },
+		/// A bounty curator is proposed.
+		CuratorProposed { bounty_id: BountyIndex, curator: T::AccountId },
+		/// A multi-signature curator set is proposed.
+		MultiCuratorProposed { bounty_id: BountyIndex, curator: T::AccountId, threshold: u32 },
+		/// A member approval towards accepting the curator role was recorded.
+		CuratorAcceptApproved { bounty_id: BountyIndex, approver: T::AccountId, approvals: u32 },
+		/// A member approval towards awarding the bounty was recorded.
+		BountyAwardApproved {
+			bounty_id: BountyIndex,
+			approver: T::AccountId,
+			beneficiary: T::AccountId,
+			approvals: u32,
+		},
+		/// A bounty curator is unassigned.
+		CuratorUnassigned { bounty_id: BountyIndex },
+		/// A bounty curator is accepted.
+		CuratorAccepted { bounty_id: BountyIndex, curator: T::AccountId },
+		/// A bounty is awarded to a beneficiary.
+		BountyAwarded { index: BountyIndex, beneficiary: T::AccountId },
+		/// Payout of a bounty has been attempted.
+		BountyClaimAttempted { index: BountyIndex, beneficiary: T::AccountId },
+		/// A bounty is claimed: `payout` went to the beneficiary and `curator_fee` to the
+		/// curator.
+		BountyClaimed {
+			index: BountyIndex,
+			payout: AssetBalanceOf<T, I>,
+			curator_fee: AssetBalanceOf<T, I>,
+			beneficiary: T::AccountId,
+		},
+		/// Refund of a bounty's funds has been attempted.
+		BountyRefundAttempted { index: BountyIndex, payment_id: PaymentIdOf<T, I> },
+		/// A bounty is cancelled.
+		BountyCanceled { index: BountyIndex },
+		/// A bounty expiry is extended.
+		BountyExtended { index: BountyIndex },
+		/// An asset kind was added to the funding allow-list.
+		AssetKindAllowed { asset_kind: T::AssetKind },
+		/// An asset kind was removed from the funding allow-list.
+		AssetKindDisallowed { asset_kind: T::AssetKind },
+		/// A child bounty is added.
+		ChildBountyAdded { index: BountyIndex, child_index: BountyIndex },
+		/// A child bounty is awarded to a beneficiary.
+		ChildBountyAwarded { index: BountyIndex, child_index: BountyIndex, beneficiary: T::AccountId },
+		/// A child bounty is claimed: `payout` went to the beneficiary and `curator_fee` to
+		/// the child curator.
+		ChildBountyClaimed {
+			index: BountyIndex,
+			child_index: BountyIndex,
+			payout: AssetBalanceOf<T, I>,
+			curator_fee: AssetBalanceOf<T, I>,
+			beneficiary: T::AccountId,
+		},
+		/// A child bounty is cancelled.
+		ChildBountyCanceled { index: BountyIndex, child_index: BountyIndex },
+		/// An inconclusive funding payment was abandoned by the `RejectOrigin`: an operator
+		/// assertion that the remote payment will never arrive. The funding state is now
+		/// failed, so the bounty can be closed or its funding retried.
+		PaymentAbandoned { index: BountyIndex, payment_id: PaymentIdOf<T, I> },
+		/// Recovery of an orphaned balance from a bounty account back to the funding source
+		/// has been attempted.
+		BountyAccountSwept { index: BountyIndex, payment_id: PaymentIdOf<T, I> },
+		/// Legacy full descriptions left behind by a switch to hash-only mode were pruned.
+		LegacyDescriptionsPruned { pruned: u32 },
+		/// The auxiliary storage of removed bounties was deleted from the cleanup queue.
+		RemovedBountiesPurged { purged: u32 },
+		/// An additional funding payment to increase a bounty's value has been attempted.
+		BountyTopUpAttempted {
+			index: BountyIndex,
+			additional_value: AssetBalanceOf<T, I>,
+			payment_id: PaymentIdOf<T, I>,
+		},
+		/// A bounty's value was increased after its top-up payment was confirmed.
+		BountyValueIncreased { index: BountyIndex, new_value: AssetBalanceOf<T, I> },
+		/// A child bounty's value was increased out of unassigned parent funds.
+		ChildBountyValueIncreased {
+			index: BountyIndex,
+			child_index: BountyIndex,
+			new_value: AssetBalanceOf<T, I>,
+		},
+	}
+
+	/// Number of bounty proposals that have been made.
+	#[pallet::storage]
+	pub type BountyCount<T: Config<I>, I: 'static = ()> = StorageValue<_, BountyIndex, ValueQuery>;
+
+	/// Bounties that have been made.
+	#[pallet::storage]
+	pub type Bounties<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, BountyOf<T, I>>;
+
+	/// The description of each bounty. Only populated in [`DescriptionStorageMode::Full`]
+	/// mode.
+	#[pallet::storage]
+	pub type BountyDescriptions<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, BoundedVec<u8, T::MaximumReasonLength>>;
+
+	/// The description hash of each bounty. Only populated in
+	/// [`DescriptionStorageMode::HashOnly`] mode.
+	#[pallet::storage]
+	pub type BountyDescriptionHashes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, T::Hash>;
+
+	/// Full bounty descriptions retained after migrating to hash-only mode, kept only until
+	/// governance prunes them via [`Pallet::prune_legacy_descriptions`].
+	#[pallet::storage]
+	pub type DeprecatedBountyDescriptions<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, BoundedVec<u8, T::MaximumReasonLength>>;
+
+	/// The block at which the funding payment of each bounty was last attempted, while that
+	/// payment is still pending. Gates [`Pallet::abandon_payment`] against
+	/// [`Config::PaymentInconclusiveTimeout`].
+	#[pallet::storage]
+	pub type LastFundingAttempt<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, BlockNumberFor<T, I>>;
+
+	/// The set of asset kinds that bounties may be funded with.
+	///
+	/// While this set is empty, any asset kind convertible by [`Config::BalanceConverter`] is
+	/// accepted. Once it is non-empty, it is exhaustive: funding a bounty denominated in any
+	/// other asset kind fails with [`Error::AssetNotAllowed`].
+	#[pallet::storage]
+	pub type AllowedAssetKinds<T: Config<I>, I: 'static = ()> =
+		CountedStorageMap<_, Blake2_128Concat, T::AssetKind, ()>;
+
+	/// Number of total child bounties, used to generate the next child bounty index.
+	#[pallet::storage]
+	pub type ChildBountyCount<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BountyIndex, ValueQuery>;
+
+	/// Number of child bounties per parent bounty that are not yet claimed or closed.
+	#[pallet::storage]
+	pub type ParentChildBounties<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, u32, ValueQuery>;
+
+	/// Child bounties that have been added.
+	#[pallet::storage]
+	pub type ChildBounties<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		BountyIndex,
+		Twox64Concat,
+		BountyIndex,
+		ChildBountyOf<T, I>,
+	>;
+
+	/// The description of each child bounty, keyed by the (globally unique) child index. Only
+	/// populated in [`DescriptionStorageMode::Full`] mode.
+	#[pallet::storage]
+	pub type ChildBountyDescriptions<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, BoundedVec<u8, T::MaximumReasonLength>>;
+
+	/// The description hash of each child bounty, keyed by the (globally unique) child index.
+	/// Only populated in [`DescriptionStorageMode::HashOnly`] mode.
+	#[pallet::storage]
+	pub type ChildBountyDescriptionHashes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, T::Hash>;
+
+	/// Full child bounty descriptions retained after migrating to hash-only mode, kept only
+	/// until governance prunes them via [`Pallet::prune_legacy_descriptions`].
+	#[pallet::storage]
+	pub type DeprecatedChildBountyDescriptions<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, BoundedVec<u8, T::MaximumReasonLength>>;
+
+	/// The cumulative value of claimed child bounties per parent bounty, already paid out of the
+	/// parent's bounty account.
+	#[pallet::storage]
+	pub type ChildrenClaimedValues<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, AssetBalanceOf<T, I>, ValueQuery>;
+
+	/// The cumulative fees of claimed child bounty curators per parent bounty. Subtracted from
+	/// the parent curator's fee when the parent bounty is claimed.
+	#[pallet::storage]
+	pub type ChildrenCuratorFees<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, AssetBalanceOf<T, I>, ValueQuery>;
+
+	/// The cumulative value of child bounties per parent bounty that are not yet claimed or
+	/// closed.
+	#[pallet::storage]
+	pub type ChildrenActiveValues<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, AssetBalanceOf<T, I>, ValueQuery>;
+
+	/// The multi-signature curator set of each bounty, where one was proposed via
+	/// `propose_multi_curator`.
+	#[pallet::storage]
+	pub type MultiCurators<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, BountyIndex, MultiCuratorSetOf<T, I>>;
+
+	/// Members that have approved accepting the curator role, per bounty.
+	#[pallet::storage]
+	pub type CuratorAcceptApprovals<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Twox64Concat,
+		BountyIndex,
+		BoundedVec<T::AccountId, T::MaxMultiCurators>,
+		ValueQuery,
+	>;
+
+	/// The pending award beneficiary and the members that have approved awarding to them, per
+	/// bounty.
+	#[pallet::storage]
+	pub type BountyAwardApprovals<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Twox64Concat,
+		BountyIndex,
+		(T::AccountId, BoundedVec<T::AccountId, T::MaxMultiCurators>),
+	>;
+
+	/// The pending value top-up of each bounty: the additional value and the state of its
+	/// funding payment. At most one top-up is in flight per bounty; a failed one may be
+	/// replaced by a fresh `increase_bounty_value` call.
+	#[pallet::storage]
+	pub type PendingTopUps<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Twox64Concat,
+		BountyIndex,
+		(AssetBalanceOf<T, I>, PaymentState<PaymentIdOf<T, I>>),
+	>;
+
+	/// Bounties and child bounties with an in-flight payment, keyed by the parent bounty
+	/// index and, for child bounty payouts, the child bounty index.
+	///
+	/// Swept in weight-metered batches by `on_idle`, which gives each entry the same
+	/// treatment a manual [`Pallet::check_payment_status`] call would. Concluding a payment
+	/// through any path removes the entry.
+	#[pallet::storage]
+	pub type PendingPayments<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, (BountyIndex, Option<BountyIndex>), ()>;
+
+	/// Removed bounties and child bounties whose auxiliary storage awaits deferred deletion.
+	///
+	/// Drained in weight-metered batches by `on_idle` and [`Pallet::purge_removed`].
+	#[pallet::storage]
+	pub type PendingCleanup<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BoundedVec<PendingCleanupEntry, T::MaxCleanupBacklog>, ValueQuery>;
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Propose a new bounty denominated in `asset_kind`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Payment: `BountyDepositBase` will be reserved from the origin account, as well as
+		/// `DataDepositPerByte` for each byte in `description`. It will be unreserved upon
+		/// funding, or slashed when rejected.
+		///
+		/// - `asset_kind`: The kind of asset the bounty is denominated in.
+		/// - `value`: The total payment amount of this bounty, curator fee included.
+		/// - `description`: The description of this bounty, or its 32 byte hash in
+		///   [`DescriptionStorageMode::HashOnly`] mode.
+		#[pallet::call_index(0)]
+		#[pallet::weight(match T::DescriptionMode::get() {
+			DescriptionStorageMode::Full =>
+				<T as Config<I>>::WeightInfo::propose_bounty(description.len() as u32),
+			DescriptionStorageMode::HashOnly =>
+				<T as Config<I>>::WeightInfo::propose_bounty_hash_only(),
+		})]
+		pub fn propose_bounty(
+			origin: OriginFor<T>,
+			asset_kind: Box<T::AssetKind>,
+			#[pallet::compact] value: AssetBalanceOf<T, I>,
+			description: Vec<u8>,
+		) -> DispatchResult {
+			let proposer = ensure_signed(origin)?;
+			Self::create_bounty(proposer, *asset_kind, value, description)?;
+			Ok(())
+		}
+
+		/// Approve a bounty proposal. At a later time, the bounty must be funded via
+		/// `fund_bounty`.
+		///
+		/// May only be called from `T::SpendOrigin` with a `Success` value of at least the
+		/// bounty value converted to the native balance.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::approve_bounty())]
+		pub fn approve_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let max_amount = T::SpendOrigin::ensure_origin(origin)?;
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+				ensure!(bounty.status == BountyStatus::Proposed, Error::<T, I>::UnexpectedStatus);
+
+				let native_value =
+					T::BalanceConverter::from_asset_balance(bounty.value, bounty.asset_kind.clone())
+						.map_err(|_| Error::<T, I>::FailedToConvertBalance)?;
+				Self::ensure_within_origin_budget(native_value, max_amount)?;
+
+				bounty.status = BountyStatus::Approved;
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T, I>::BountyApproved { index: bounty_id });
+			Ok(())
+		}
+
+		/// Trigger payment of an approved bounty's value from the funding source into the
+		/// bounty's sub-account.
+		///
+		/// The dispatch origin for this call must be _Signed_; anyone can trigger funding of an
+		/// approved bounty. The payment is made asynchronously via the paymaster and has to be
+		/// concluded with `check_payment_status`.
+		///
+		/// Fails with [`Error::AssetNotAllowed`] if the bounty's asset kind is not covered by
+		/// the (non-empty) allow-list; see [`AllowedAssetKinds`].
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::fund_bounty())]
+		pub fn fund_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+				match bounty.status {
+					BountyStatus::Approved |
+					BountyStatus::FundingAttempted {
+						payment_status: PaymentState::Failed,
+					} => {},
+					_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+				}
+
+				ensure!(
+					Self::is_asset_kind_allowed(&bounty.asset_kind),
+					Error::<T, I>::AssetNotAllowed
+				);
+
+				let bounty_account = Self::bounty_account_id(bounty_id);
+				let payment_id = T::Paymaster::pay(
+					&T::FundingSource::get(),
+					&bounty_account,
+					bounty.asset_kind.clone(),
+					bounty.value,
+				)
+				.map_err(|_| Error::<T, I>::PayoutError)?;
+
+				bounty.status = BountyStatus::FundingAttempted {
+					payment_status: PaymentState::Attempted { id: payment_id },
+				};
+				LastFundingAttempt::<T, I>::insert(bounty_id, Self::current_block_number());
+				PendingPayments::<T, I>::insert((bounty_id, None::<BountyIndex>), ());
+
+				Self::deposit_event(Event::<T, I>::BountyFundingAttempted {
+					index: bounty_id,
+					payment_id,
+				});
+				Ok(())
+			})
+		}
+
+		/// Check and progress the payment state of a bounty's pending funding, payout or refund.
+		///
+		/// The dispatch origin for this call must be _Signed_ (anyone).
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::check_payment_status())]
+		pub fn check_payment_status(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::do_check_payment_status(bounty_id)
+		}
+
+		/// Propose a curator to a funded bounty.
+		///
+		/// May only be called from `T::SpendOrigin`.
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::propose_curator())]
+		pub fn propose_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+			curator: AccountIdLookupOf<T>,
+			#[pallet::compact] fee: AssetBalanceOf<T, I>,
+		) -> DispatchResult {
+			let max_amount = T::SpendOrigin::ensure_origin(origin)?;
+
+			let curator = T::Lookup::lookup(curator)?;
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+				let native_value =
+					T::BalanceConverter::from_asset_balance(bounty.value, bounty.asset_kind.clone())
+						.map_err(|_| Error::<T, I>::FailedToConvertBalance)?;
+				Self::ensure_within_origin_budget(native_value, max_amount)?;
+
+				match bounty.status {
+					BountyStatus::Funded => {},
+					_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+				};
+
+				Self::ensure_valid_fee(fee, bounty.value)?;
+
+				bounty.status = BountyStatus::CuratorProposed { curator: curator.clone() };
+				bounty.fee = fee;
+
+				Self::deposit_event(Event::<T, I>::CuratorProposed { bounty_id, curator });
+
+				Ok(())
+			})
+		}
+
+		/// Unassign curator from a bounty.
+		///
+		/// This function can only be called by the `RejectOrigin` or a signed origin.
+		///
+		/// If this function is called by the `RejectOrigin`, we assume that the curator is
+		/// malicious or inactive. As a result, we will slash the curator when possible.
+		///
+		/// If the origin is the curator, we take this as a sign they are unable to do their job
+		/// and they willingly give up. We could slash them, but for now we allow them to
+		/// recover their deposit and exit without issue. (We may want to change this if it is
+		/// abused.)
+		///
+		/// Finally, the origin can be anyone if and only if the curator is "inactive". This
+		/// allows anyone in the community to call out that a curator is not doing their due
+		/// diligence, and we should pick a new curator. In this case the curator should also be
+		/// slashed.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::unassign_curator())]
+		pub fn unassign_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+				let slash_curator =
+					|curator: &T::AccountId, curator_deposit: &mut BalanceOf<T, I>| {
+						Self::slash_deposit(
+							curator,
+							&HoldReason::CuratorDeposit.into(),
+							*curator_deposit,
+						);
+						*curator_deposit = Zero::zero();
+					};
+
+				match bounty.status {
+					BountyStatus::Proposed |
+					BountyStatus::Approved |
+					BountyStatus::FundingAttempted { .. } |
+					BountyStatus::Funded |
+					BountyStatus::RefundAttempted { .. } => {
+						// No curator to unassign at this point.
+						return Err(Error::<T, I>::UnexpectedStatus.into())
+					},
+					BountyStatus::CuratorProposed { ref curator } => {
+						// A curator has been proposed, but not accepted yet.
+						// Either `RejectOrigin`, the proposed curator or any member of a
+						// multi-signature curator set can unassign the curator.
+						ensure!(
+							maybe_sender.map_or(true, |sender| sender == *curator ||
+								Self::is_multi_curator_member(bounty_id, &sender)),
+							BadOrigin
+						);
+					},
+					BountyStatus::Active { ref curator, ref update_due } => {
+						// The bounty is active.
+						match maybe_sender {
+							// If the `RejectOrigin` is calling this function, slash the curator.
+							None => {
+								slash_curator(curator, &mut bounty.curator_deposit);
+								// Continue to change bounty status below...
+							},
+							Some(sender) => {
+								// A member of a multi-signature curator set resigns on behalf
+								// of the whole set; the deposit returns to the curator of
+								// record either way.
+								if sender != *curator &&
+									!Self::is_multi_curator_member(bounty_id, &sender)
+								{
+									// If the sender is not the curator, and the curator is
+									// inactive, slash the curator.
+									let block_number = Self::current_block_number();
+									if *update_due < block_number {
+										slash_curator(curator, &mut bounty.curator_deposit);
+									// Continue to change bounty status below...
+									} else {
+										// Curator has more time to give an update.
+										return Err(Error::<T, I>::Premature.into())
+									}
+								} else {
+									// Else this is the curator, willingly giving up their role.
+									// Give back their deposit.
+									Self::release_deposit(
+										curator,
+										&HoldReason::CuratorDeposit.into(),
+										bounty.curator_deposit,
+									);
+									bounty.curator_deposit = Zero::zero();
+									// Continue to change bounty status below...
+								}
+							},
+						}
+					},
+					BountyStatus::PendingPayout { ref curator, .. } => {
+						// The bounty is pending payout, so only council can unassign a curator.
+						// By doing so, they are claiming the curator is acting maliciously, so
+						// we slash the curator.
+						ensure!(maybe_sender.is_none(), BadOrigin);
+						slash_curator(curator, &mut bounty.curator_deposit);
+						// Continue to change bounty status below...
+					},
+					BountyStatus::PayoutAttempted { .. } => {
+						// Payout is already underway, it is too late to unassign the curator.
+						return Err(Error::<T, I>::UnexpectedStatus.into())
+					},
+				};
+
+				bounty.status = BountyStatus::Funded;
+				Ok(())
+			})?;
+
+			// A fresh curator proposal starts from scratch, so drop any multi-signature
+			// curator set along with its pending approvals.
+			MultiCurators::<T, I>::remove(bounty_id);
+			CuratorAcceptApprovals::<T, I>::remove(bounty_id);
+			BountyAwardApprovals::<T, I>::remove(bounty_id);
+
+			Self::deposit_event(Event::<T, I>::CuratorUnassigned { bounty_id });
+			Ok(())
+		}
+
+		/// Accept the curator role for a bounty.
+		/// A deposit will be reserved from curator and refund upon successful payout.
+		///
+		/// May only be called from the curator. For a multi-signature curator set, each member
+		/// calls this individually; the role is only accepted (and the deposit reserved from
+		/// the curator of record) once the set's threshold of approvals is reached.
+		#[pallet::call_index(6)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::accept_curator())]
+		pub fn accept_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+				match bounty.status {
+					BountyStatus::CuratorProposed { ref curator } => {
+						let curator = curator.clone();
+						if let Some(set) = MultiCurators::<T, I>::get(bounty_id) {
+							ensure!(
+								set.curators.contains(&signer),
+								Error::<T, I>::RequireCurator
+							);
+							let mut approvals = CuratorAcceptApprovals::<T, I>::get(bounty_id);
+							ensure!(
+								!approvals.contains(&signer),
+								Error::<T, I>::AlreadyApproved
+							);
+							approvals
+								.try_push(signer.clone())
+								.map_err(|_| Error::<T, I>::TooManyCurators)?;
+							if (approvals.len() as u32) < set.threshold {
+								// Not enough approvals yet; record this one and wait.
+								Self::deposit_event(Event::<T, I>::CuratorAcceptApproved {
+									bounty_id,
+									approver: signer,
+									approvals: approvals.len() as u32,
+								});
+								CuratorAcceptApprovals::<T, I>::insert(bounty_id, approvals);
+								return Ok(())
+							}
+							CuratorAcceptApprovals::<T, I>::remove(bounty_id);
+						} else {
+							ensure!(signer == curator, Error::<T, I>::RequireCurator);
+						}
+
+						let deposit = Self::calculate_curator_deposit(
+							&bounty.fee,
+							bounty.asset_kind.clone(),
+						)?;
+						T::Currency::hold(&HoldReason::CuratorDeposit.into(), &curator, deposit)?;
+						bounty.curator_deposit = deposit;
+
+						let update_due = Self::current_block_number()
+							.saturating_add(T::BountyUpdatePeriod::get());
+						bounty.status =
+							BountyStatus::Active { curator: curator.clone(), update_due };
+
+						Self::deposit_event(Event::<T, I>::CuratorAccepted {
+							bounty_id,
+							curator,
+						});
+						Ok(())
+					},
+					_ => Err(Error::<T, I>::UnexpectedStatus.into()),
+				}
+			})
+		}
+
+		/// Award bounty to a beneficiary account. The beneficiary will be able to claim the
+		/// funds after a delay.
+		///
+		/// The dispatch origin for this call must be the curator of this bounty. For a
+		/// multi-signature curator set, each member calls this individually; the bounty is
+		/// only awarded once the set's threshold of approvals for the same beneficiary is
+		/// reached. An approval for a different beneficiary than the pending one restarts the
+		/// tally.
+		///
+		/// - `bounty_id`: Bounty ID to award.
+		/// - `beneficiary`: The beneficiary account whom will receive the payout.
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::award_bounty())]
+		pub fn award_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+			beneficiary: AccountIdLookupOf<T>,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			let awarded = Bounties::<T, I>::try_mutate_exists(
+				bounty_id,
+				|maybe_bounty| -> Result<bool, DispatchError> {
+					let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+					// Ensure no child bounties are in flight before processing the call.
+					ensure!(
+						ParentChildBounties::<T, I>::get(bounty_id) == 0,
+						Error::<T, I>::HasActiveChildBounty
+					);
+
+					let curator = match &bounty.status {
+						BountyStatus::Active { curator, .. } => curator.clone(),
+						_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+					};
+					if let Some(set) = MultiCurators::<T, I>::get(bounty_id) {
+						ensure!(set.curators.contains(&signer), Error::<T, I>::RequireCurator);
+						let mut approvals = match BountyAwardApprovals::<T, I>::get(bounty_id) {
+							Some((pending, approvals)) if pending == beneficiary => approvals,
+							// No pending tally, or one for a different beneficiary: restart.
+							_ => BoundedVec::default(),
+						};
+						ensure!(!approvals.contains(&signer), Error::<T, I>::AlreadyApproved);
+						approvals
+							.try_push(signer.clone())
+							.map_err(|_| Error::<T, I>::TooManyCurators)?;
+						if (approvals.len() as u32) < set.threshold {
+							Self::deposit_event(Event::<T, I>::BountyAwardApproved {
+								bounty_id,
+								approver: signer,
+								beneficiary: beneficiary.clone(),
+								approvals: approvals.len() as u32,
+							});
+							BountyAwardApprovals::<T, I>::insert(
+								bounty_id,
+								(beneficiary.clone(), approvals),
+							);
+							return Ok(false)
+						}
+						BountyAwardApprovals::<T, I>::remove(bounty_id);
+					} else {
+						ensure!(signer == curator, Error::<T, I>::RequireCurator);
+					}
+					bounty.status = BountyStatus::PendingPayout {
+						curator,
+						beneficiary: beneficiary.clone(),
+						unlock_at: Self::current_block_number() +
+							T::BountyDepositPayoutDelay::get(),
+					};
+
+					Ok(true)
+				},
+			)?;
+
+			if awarded {
+				Self::deposit_event(Event::<T, I>::BountyAwarded {
+					index: bounty_id,
+					beneficiary,
+				});
+			}
+			Ok(())
+		}
+
+		/// Trigger the payout payments of an awarded bounty after the payout delay.
+		///
+		/// The dispatch origin for this call must be _Signed_; anyone can trigger the claim.
+		/// The payments are made asynchronously via the paymaster and have to be concluded
+		/// with `check_payment_status`. If a payment failed, calling this again retries it.
+		///
+		/// - `bounty_id`: Bounty ID to claim.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::claim_bounty())]
+		pub fn claim_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+		) -> DispatchResult {
+			ensure_signed(origin)?; // anyone can trigger claim
+
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+				let (final_fee, payout) =
+					Self::calculate_curator_fee_and_payout(bounty_id, bounty.fee, bounty.value);
+				let bounty_account = Self::bounty_account_id(bounty_id);
+				let asset_kind = bounty.asset_kind.clone();
+
+				match &mut bounty.status {
+					BountyStatus::PendingPayout { curator, beneficiary, unlock_at } => {
+						ensure!(
+							Self::current_block_number() >= *unlock_at,
+							Error::<T, I>::Premature
+						);
+
+						let curator_payment = Self::attempt_payment(
+							&bounty_account,
+							curator,
+							asset_kind.clone(),
+							final_fee,
+						)?;
+						let beneficiary_payment = Self::attempt_payment(
+							&bounty_account,
+							beneficiary,
+							asset_kind,
+							payout,
+						)?;
+
+						let beneficiary = beneficiary.clone();
+						bounty.status = BountyStatus::PayoutAttempted {
+							curator: curator.clone(),
+							beneficiary: beneficiary.clone(),
+							curator_payment,
+							beneficiary_payment,
+						};
+						PendingPayments::<T, I>::insert((bounty_id, None::<BountyIndex>), ());
+
+						Self::deposit_event(Event::<T, I>::BountyClaimAttempted {
+							index: bounty_id,
+							beneficiary,
+						});
+						Ok(())
+					},
+					BountyStatus::PayoutAttempted {
+						curator,
+						beneficiary,
+						curator_payment,
+						beneficiary_payment,
+					} => {
+						// Retry any failed payment legs.
+						let mut retried = false;
+						if matches!(curator_payment, PaymentState::Failed) {
+							*curator_payment = Self::attempt_payment(
+								&bounty_account,
+								curator,
+								asset_kind.clone(),
+								final_fee,
+							)?;
+							retried = true;
+						}
+						if matches!(beneficiary_payment, PaymentState::Failed) {
+							*beneficiary_payment = Self::attempt_payment(
+								&bounty_account,
+								beneficiary,
+								asset_kind,
+								payout,
+							)?;
+							retried = true;
+						}
+						ensure!(retried, Error::<T, I>::UnexpectedStatus);
+						Ok(())
+					},
+					_ => Err(Error::<T, I>::UnexpectedStatus.into()),
+				}
+			})
+		}
+
+		/// Cancel a bounty and refund any paid-out funds back to the funding source.
+		///
+		/// Only `T::RejectOrigin` is able to cancel a bounty.
+		///
+		/// - `bounty_id`: Bounty ID to cancel.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::close_bounty())]
+		pub fn close_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+		) -> DispatchResult {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+				// Ensure no child bounties are in flight before processing the call.
+				ensure!(
+					ParentChildBounties::<T, I>::get(bounty_id) == 0,
+					Error::<T, I>::HasActiveChildBounty
+				);
+
+				match &bounty.status {
+					BountyStatus::Proposed => {
+						// The reject origin cancels an unapproved bounty, slashing the deposit.
+						let value = bounty.bond;
+						Self::slash_deposit(
+							&bounty.proposer,
+							&HoldReason::ProposerBond.into(),
+							value,
+						);
+						*maybe_bounty = None;
+						Self::remove_bounty_records(bounty_id);
+
+						Self::deposit_event(Event::<T, I>::BountyRejected {
+							index: bounty_id,
+							bond: value,
+						});
+						return Ok(())
+					},
+					BountyStatus::Approved |
+					BountyStatus::FundingAttempted { payment_status: PaymentState::Failed } => {
+						// No funds were moved yet; return the proposer's bond and remove.
+						Self::release_deposit(
+							&bounty.proposer,
+							&HoldReason::ProposerBond.into(),
+							bounty.bond,
+						);
+						*maybe_bounty = None;
+						Self::remove_bounty_records(bounty_id);
+
+						Self::deposit_event(Event::<T, I>::BountyCanceled { index: bounty_id });
+						return Ok(())
+					},
+					BountyStatus::FundingAttempted { .. } => {
+						// Funding is in flight; conclude it first via `check_payment_status`.
+						return Err(Error::<T, I>::Inconclusive.into())
+					},
+					BountyStatus::Funded | BountyStatus::CuratorProposed { .. } => {
+						// Continue to the refund below.
+					},
+					BountyStatus::Active { curator, .. } => {
+						// Cancelled by council, refund deposit of the working curator.
+						Self::release_deposit(
+							curator,
+							&HoldReason::CuratorDeposit.into(),
+							bounty.curator_deposit,
+						);
+						// Continue to the refund below.
+					},
+					BountyStatus::PendingPayout { .. } => {
+						// Bounty is already pending payout. If council wants to cancel
+						// this bounty, it should mean the curator was acting maliciously.
+						// So the council should first unassign the curator, slashing their
+						// deposit.
+						return Err(Error::<T, I>::PendingPayout.into())
+					},
+					BountyStatus::PayoutAttempted { .. } => {
+						return Err(Error::<T, I>::UnexpectedStatus.into())
+					},
+					BountyStatus::RefundAttempted { payment_status: PaymentState::Failed } => {
+						// Continue to retry the refund below.
+					},
+					BountyStatus::RefundAttempted { .. } => {
+						// Refund is in flight; conclude it via `check_payment_status`.
+						return Err(Error::<T, I>::Inconclusive.into())
+					},
+				}
+
+				// Pay back the remaining funds to the funding source. The bounty is removed
+				// once the refund payment concludes.
+				let remaining =
+					bounty.value.saturating_sub(ChildrenClaimedValues::<T, I>::get(bounty_id));
+				let payment_id = T::Paymaster::pay(
+					&Self::bounty_account_id(bounty_id),
+					&T::FundingSource::get(),
+					bounty.asset_kind.clone(),
+					remaining,
+				)
+				.map_err(|_| Error::<T, I>::PayoutError)?;
+				bounty.status = BountyStatus::RefundAttempted {
+					payment_status: PaymentState::Attempted { id: payment_id },
+				};
+				PendingPayments::<T, I>::insert((bounty_id, None::<BountyIndex>), ());
+
+				Self::deposit_event(Event::<T, I>::BountyRefundAttempted {
+					index: bounty_id,
+					payment_id,
+				});
+				Ok(())
+			})
+		}
+
+		/// Extend the expiry time of an active bounty or child bounty.
+		///
+		/// The dispatch origin for this call must be the curator of the bounty, or of the
+		/// child bounty when `child_bounty_id` is given.
+		///
+		/// - `parent_bounty_id`: Bounty ID to extend.
+		/// - `child_bounty_id`: Child bounty ID to extend instead, if any.
+		/// - `remark`: additional information.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::extend_bounty_expiry())]
+		pub fn extend_bounty_expiry(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			child_bounty_id: Option<BountyIndex>,
+			_remark: Vec<u8>,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+
+			let refreshed_due = |update_due: &mut BlockNumberFor<T, I>| {
+				*update_due = Self::current_block_number()
+					.saturating_add(T::BountyUpdatePeriod::get())
+					.max(*update_due);
+			};
+
+			let Some(child_bounty_id) = child_bounty_id else {
+				Bounties::<T, I>::try_mutate_exists(
+					parent_bounty_id,
+					|maybe_bounty| -> DispatchResult {
+						let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+						match bounty.status {
+							BountyStatus::Active { ref curator, ref mut update_due } => {
+								ensure!(*curator == signer, Error::<T, I>::RequireCurator);
+								refreshed_due(update_due);
+							},
+							_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+						}
+
+						Ok(())
+					},
+				)?;
+
+				Self::deposit_event(Event::<T, I>::BountyExtended { index: parent_bounty_id });
+				return Ok(())
+			};
+
+			ChildBounties::<T, I>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child| -> DispatchResult {
+					let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+					match child.status {
+						ChildBountyStatus::Active { ref curator, ref mut update_due } => {
+							ensure!(*curator == signer, Error::<T, I>::RequireCurator);
+							refreshed_due(update_due);
+						},
+						_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+					}
+
+					Ok(())
+				},
+			)?;
+
+			Self::deposit_event(Event::<T, I>::BountyExtended { index: child_bounty_id });
+			Ok(())
+		}
+
+		/// Add an asset kind to the funding allow-list.
+		///
+		/// Note that once the allow-list is non-empty it is exhaustive; see
+		/// [`AllowedAssetKinds`].
+		///
+		/// May only be called from `T::RejectOrigin`.
+		#[pallet::call_index(11)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::allow_asset_kind())]
+		pub fn allow_asset_kind(
+			origin: OriginFor<T>,
+			asset_kind: Box<T::AssetKind>,
+		) -> DispatchResult {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			AllowedAssetKinds::<T, I>::insert(&*asset_kind, ());
+			Self::deposit_event(Event::<T, I>::AssetKindAllowed { asset_kind: *asset_kind });
+			Ok(())
+		}
+
+		/// Remove an asset kind from the funding allow-list.
+		///
+		/// Already-funded bounties are unaffected; the allow-list is only checked when a bounty
+		/// is funded.
+		///
+		/// May only be called from `T::RejectOrigin`.
+		#[pallet::call_index(12)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::disallow_asset_kind())]
+		pub fn disallow_asset_kind(
+			origin: OriginFor<T>,
+			asset_kind: Box<T::AssetKind>,
+		) -> DispatchResult {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			AllowedAssetKinds::<T, I>::remove(&*asset_kind);
+			Self::deposit_event(Event::<T, I>::AssetKindDisallowed { asset_kind: *asset_kind });
+			Ok(())
+		}
+
+		/// Add a new child bounty, carved out of the parent bounty's value.
+		///
+		/// The dispatch origin for this call must be the curator of the parent bounty. The sum
+		/// of all child bounty values must fit within the parent's value minus the parent
+		/// curator fee; the child value stays in the parent's bounty account until the child is
+		/// claimed.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `value`: Value of this child bounty, in the parent's asset kind.
+		/// - `description`: Text description of this child bounty, or its 32 byte hash in
+		///   [`DescriptionStorageMode::HashOnly`] mode.
+		#[pallet::call_index(13)]
+		#[pallet::weight(match T::DescriptionMode::get() {
+			DescriptionStorageMode::Full =>
+				<T as Config<I>>::WeightInfo::add_child_bounty(description.len() as u32),
+			DescriptionStorageMode::HashOnly =>
+				<T as Config<I>>::WeightInfo::add_child_bounty_hash_only(),
+		})]
+		pub fn add_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] value: AssetBalanceOf<T, I>,
+			description: Vec<u8>,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+
+			let description = Self::prepare_description(description)?;
+			ensure!(!value.is_zero(), Error::<T, I>::InvalidValue);
+
+			let bounty =
+				Bounties::<T, I>::get(parent_bounty_id).ok_or(Error::<T, I>::InvalidIndex)?;
+			match &bounty.status {
+				BountyStatus::Active { curator, .. } => {
+					ensure!(signer == *curator, Error::<T, I>::RequireCurator);
+				},
+				_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+			}
+
+			ensure!(
+				ParentChildBounties::<T, I>::get(parent_bounty_id) <
+					T::MaxActiveChildBountyCount::get(),
+				Error::<T, I>::TooManyChildBounties,
+			);
+
+			// The parent curator fee and all child values must remain covered by the parent's
+			// funds.
+			let committed = ChildrenActiveValues::<T, I>::get(parent_bounty_id)
+				.saturating_add(ChildrenClaimedValues::<T, I>::get(parent_bounty_id))
+				.saturating_add(bounty.fee)
+				.saturating_add(value);
+			ensure!(committed <= bounty.value, Error::<T, I>::InsufficientBountyBalance);
+
+			let child_bounty_id = ChildBountyCount::<T, I>::get();
+			ChildBountyCount::<T, I>::put(child_bounty_id.saturating_add(1));
+			ParentChildBounties::<T, I>::mutate(parent_bounty_id, |count| {
+				count.saturating_inc()
+			});
+			ChildrenActiveValues::<T, I>::mutate(parent_bounty_id, |active| {
+				*active = active.saturating_add(value)
+			});
+
+			let child_bounty = ChildBounty {
+				parent_bounty: parent_bounty_id,
+				value,
+				fee: Zero::zero(),
+				curator_deposit: Zero::zero(),
+				status: ChildBountyStatus::Added,
+			};
+			ChildBounties::<T, I>::insert(parent_bounty_id, child_bounty_id, &child_bounty);
+			match description {
+				PreparedDescription::Full(description) =>
+					ChildBountyDescriptions::<T, I>::insert(child_bounty_id, description),
+				PreparedDescription::HashOnly(hash) =>
+					ChildBountyDescriptionHashes::<T, I>::insert(child_bounty_id, hash),
+			}
+
+			Self::deposit_event(Event::<T, I>::ChildBountyAdded {
+				index: parent_bounty_id,
+				child_index: child_bounty_id,
+			});
+			Ok(())
+		}
+
+		/// Propose a curator to a child bounty.
+		///
+		/// The dispatch origin for this call must be the curator of the parent bounty.
+		#[pallet::call_index(14)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::propose_child_curator())]
+		pub fn propose_child_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+			curator: AccountIdLookupOf<T>,
+			#[pallet::compact] fee: AssetBalanceOf<T, I>,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+			let curator = T::Lookup::lookup(curator)?;
+
+			Self::ensure_parent_curator(parent_bounty_id, &signer)?;
+
+			ChildBounties::<T, I>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child| -> DispatchResult {
+					let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+					ensure!(
+						child.status == ChildBountyStatus::Added,
+						Error::<T, I>::UnexpectedStatus
+					);
+					Self::ensure_valid_fee(fee, child.value)?;
+
+					child.fee = fee;
+					child.status = ChildBountyStatus::CuratorProposed { curator: curator.clone() };
+
+					Self::deposit_event(Event::<T, I>::CuratorProposed {
+						bounty_id: child_bounty_id,
+						curator,
+					});
+					Ok(())
+				},
+			)
+		}
+
+		/// Accept the curator role for a child bounty, reserving a native deposit.
+		///
+		/// May only be called from the proposed child curator.
+		#[pallet::call_index(15)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::accept_child_curator())]
+		pub fn accept_child_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+
+			let bounty =
+				Bounties::<T, I>::get(parent_bounty_id).ok_or(Error::<T, I>::InvalidIndex)?;
+
+			ChildBounties::<T, I>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child| -> DispatchResult {
+					let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+					match child.status {
+						ChildBountyStatus::CuratorProposed { ref curator } => {
+							ensure!(signer == *curator, Error::<T, I>::RequireCurator);
+
+							// The parent curator already has their parent deposit at stake, so
+							// accepting one of their own children requires no second deposit.
+							// Children of different parents are independent: curating several
+							// of them as a third party reserves one deposit each.
+							let deposit = if Self::is_parent_curator(&bounty, &signer) {
+								Zero::zero()
+							} else {
+								Self::calculate_curator_deposit(
+									&child.fee,
+									bounty.asset_kind.clone(),
+								)?
+							};
+							if !deposit.is_zero() {
+								T::Currency::hold(
+									&HoldReason::CuratorDeposit.into(),
+									curator,
+									deposit,
+								)?;
+							}
+							child.curator_deposit = deposit;
+
+							let update_due = Self::current_block_number()
+								.saturating_add(T::BountyUpdatePeriod::get());
+							child.status = ChildBountyStatus::Active {
+								curator: curator.clone(),
+								update_due,
+							};
+
+							Self::deposit_event(Event::<T, I>::CuratorAccepted {
+								bounty_id: child_bounty_id,
+								curator: signer,
+							});
+							Ok(())
+						},
+						_ => Err(Error::<T, I>::UnexpectedStatus.into()),
+					}
+				},
+			)
+		}
+
+		/// Award a child bounty to a beneficiary account.
+		///
+		/// The dispatch origin for this call must be the curator of this child bounty.
+		#[pallet::call_index(16)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::award_child_bounty())]
+		pub fn award_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+			beneficiary: AccountIdLookupOf<T>,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			ChildBounties::<T, I>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child| -> DispatchResult {
+					let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+					match &child.status {
+						ChildBountyStatus::Active { curator, .. } => {
+							ensure!(signer == *curator, Error::<T, I>::RequireCurator);
+						},
+						_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+					}
+					child.status = ChildBountyStatus::PendingPayout {
+						curator: signer,
+						beneficiary: beneficiary.clone(),
+						unlock_at: Self::current_block_number() +
+							T::BountyDepositPayoutDelay::get(),
+					};
+					Ok(())
+				},
+			)?;
+
+			Self::deposit_event(Event::<T, I>::ChildBountyAwarded {
+				index: parent_bounty_id,
+				child_index: child_bounty_id,
+				beneficiary,
+			});
+			Ok(())
+		}
+
+		/// Trigger the payout payments of an awarded child bounty after the payout delay.
+		///
+		/// The dispatch origin for this call must be _Signed_; anyone can trigger the claim.
+		/// The payments are made from the parent's bounty account and have to be concluded with
+		/// `check_child_payment_status`. If a payment failed, calling this again retries it.
+		#[pallet::call_index(17)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::claim_child_bounty())]
+		pub fn claim_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResult {
+			ensure_signed(origin)?; // anyone can trigger claim
+
+			let bounty =
+				Bounties::<T, I>::get(parent_bounty_id).ok_or(Error::<T, I>::InvalidIndex)?;
+			let bounty_account = Self::bounty_account_id(parent_bounty_id);
+
+			ChildBounties::<T, I>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child| -> DispatchResult {
+					let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+					let payout = child.value.saturating_sub(child.fee);
+					let fee = child.fee;
+
+					match &mut child.status {
+						ChildBountyStatus::PendingPayout { curator, beneficiary, unlock_at } => {
+							ensure!(
+								Self::current_block_number() >= *unlock_at,
+								Error::<T, I>::Premature
+							);
+
+							let curator_payment = Self::attempt_payment(
+								&bounty_account,
+								curator,
+								bounty.asset_kind.clone(),
+								fee,
+							)?;
+							let beneficiary_payment = Self::attempt_payment(
+								&bounty_account,
+								beneficiary,
+								bounty.asset_kind.clone(),
+								payout,
+							)?;
+
+							child.status = ChildBountyStatus::PayoutAttempted {
+								curator: curator.clone(),
+								beneficiary: beneficiary.clone(),
+								curator_payment,
+								beneficiary_payment,
+							};
+							PendingPayments::<T, I>::insert(
+								(parent_bounty_id, Some(child_bounty_id)),
+								(),
+							);
+							Ok(())
+						},
+						ChildBountyStatus::PayoutAttempted {
+							curator,
+							beneficiary,
+							curator_payment,
+							beneficiary_payment,
+						} => {
+							// Retry any failed payment legs.
+							let mut retried = false;
+							if matches!(curator_payment, PaymentState::Failed) {
+								*curator_payment = Self::attempt_payment(
+									&bounty_account,
+									curator,
+									bounty.asset_kind.clone(),
+									fee,
+								)?;
+								retried = true;
+							}
+							if matches!(beneficiary_payment, PaymentState::Failed) {
+								*beneficiary_payment = Self::attempt_payment(
+									&bounty_account,
+									beneficiary,
+									bounty.asset_kind.clone(),
+									payout,
+								)?;
+								retried = true;
+							}
+							ensure!(retried, Error::<T, I>::UnexpectedStatus);
+							Ok(())
+						},
+						_ => Err(Error::<T, I>::UnexpectedStatus.into()),
+					}
+				},
+			)
+		}
+
+		/// Check and progress the payment state of a child bounty's pending payout.
+		///
+		/// The dispatch origin for this call must be _Signed_ (anyone).
+		#[pallet::call_index(18)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::check_child_payment_status())]
+		pub fn check_child_payment_status(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::do_check_child_payment_status(parent_bounty_id, child_bounty_id)
+		}
+
+		/// Cancel a child bounty, returning its value to the parent bounty.
+		///
+		/// The dispatch origin for this call must be either the parent curator or
+		/// `T::RejectOrigin`.
+		#[pallet::call_index(19)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::close_child_bounty())]
+		pub fn close_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+
+			if let Some(sender) = maybe_sender {
+				Self::ensure_parent_curator(parent_bounty_id, &sender)?;
+			}
+
+			ChildBounties::<T, I>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child| -> DispatchResult {
+					let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+					match &child.status {
+						ChildBountyStatus::Added | ChildBountyStatus::CuratorProposed { .. } => {},
+						ChildBountyStatus::Active { curator, .. } => {
+							// Return the child curator deposit. Zero for children curated by
+							// the parent curator.
+							Self::release_deposit(
+								curator,
+								&HoldReason::CuratorDeposit.into(),
+								child.curator_deposit,
+							);
+						},
+						ChildBountyStatus::PendingPayout { .. } => {
+							return Err(Error::<T, I>::PendingPayout.into())
+						},
+						ChildBountyStatus::PayoutAttempted { .. } => {
+							return Err(Error::<T, I>::UnexpectedStatus.into())
+						},
+					}
+
+					ChildrenActiveValues::<T, I>::mutate(parent_bounty_id, |active| {
+						*active = active.saturating_sub(child.value)
+					});
+					ParentChildBounties::<T, I>::mutate(parent_bounty_id, |count| {
+						count.saturating_dec()
+					});
+
+					*maybe_child = None;
+					Self::remove_child_bounty_description(child_bounty_id);
+
+					Self::deposit_event(Event::<T, I>::ChildBountyCanceled {
+						index: parent_bounty_id,
+						child_index: child_bounty_id,
+					});
+					Ok(())
+				},
+			)
+		}
+
+		/// Remove up to `limit` legacy full descriptions left behind by
+		/// [`migrations::MigrateDescriptionsToHashOnly`]. The hashed copies are unaffected.
+		///
+		/// May only be called from `T::RejectOrigin`.
+		#[pallet::call_index(20)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::prune_legacy_descriptions(*limit))]
+		pub fn prune_legacy_descriptions(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			let mut remaining = limit;
+			{
+				let mut iter = DeprecatedBountyDescriptions::<T, I>::drain();
+				while remaining > 0 && iter.next().is_some() {
+					remaining.saturating_dec();
+				}
+			}
+			{
+				let mut iter = DeprecatedChildBountyDescriptions::<T, I>::drain();
+				while remaining > 0 && iter.next().is_some() {
+					remaining.saturating_dec();
+				}
+			}
+
+			Self::deposit_event(Event::<T, I>::LegacyDescriptionsPruned {
+				pruned: limit.saturating_sub(remaining),
+			});
+			Ok(())
+		}
+
+		/// Propose a multi-signature curator set for a funded bounty.
+		///
+		/// Unlike [`Pallet::propose_curator`], the subsequent curator actions are gated by
+		/// individual member approvals: `accept_curator` and `award_bounty` each only take
+		/// effect once [`Config::CuratorThreshold`] distinct members of `curators` have called
+		/// them. `curator` remains the curator of record - it holds the curator deposit and
+		/// receives the curator fee - and is typically the multisig account composed of
+		/// `curators`.
+		///
+		/// May only be called from `T::SpendOrigin`.
+		///
+		/// - `bounty_id`: Bounty ID to assign the curator set to.
+		/// - `curator`: The curator of record, holding the deposit and receiving the fee.
+		/// - `curators`: The member accounts whose approvals gate curator actions.
+		/// - `fee`: The curator fee, in the bounty's asset kind.
+		#[pallet::call_index(21)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::propose_multi_curator(curators.len() as u32))]
+		pub fn propose_multi_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+			curator: AccountIdLookupOf<T>,
+			curators: Vec<AccountIdLookupOf<T>>,
+			#[pallet::compact] fee: AssetBalanceOf<T, I>,
+		) -> DispatchResult {
+			let max_amount = T::SpendOrigin::ensure_origin(origin)?;
+
+			let curator = T::Lookup::lookup(curator)?;
+			let mut members: BoundedVec<T::AccountId, T::MaxMultiCurators> = BoundedVec::new();
+			for member in curators {
+				let member = T::Lookup::lookup(member)?;
+				ensure!(!members.contains(&member), Error::<T, I>::DuplicateCurator);
+				members.try_push(member).map_err(|_| Error::<T, I>::TooManyCurators)?;
+			}
+			// A zero threshold would make every action pass on its first approval; treat it
+			// as a plain single-approval set rather than a misconfiguration trap.
+			let threshold = T::CuratorThreshold::get().max(1);
+			ensure!(members.len() as u32 >= threshold, Error::<T, I>::TooFewCurators);
+
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+				let native_value =
+					T::BalanceConverter::from_asset_balance(bounty.value, bounty.asset_kind.clone())
+						.map_err(|_| Error::<T, I>::FailedToConvertBalance)?;
+				Self::ensure_within_origin_budget(native_value, max_amount)?;
+
+				match bounty.status {
+					BountyStatus::Funded => {},
+					_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+				};
+
+				Self::ensure_valid_fee(fee, bounty.value)?;
+
+				bounty.status = BountyStatus::CuratorProposed { curator: curator.clone() };
+				bounty.fee = fee;
+				MultiCurators::<T, I>::insert(
+					bounty_id,
+					MultiCuratorSet { curators: members, threshold },
+				);
+
+				Self::deposit_event(Event::<T, I>::MultiCuratorProposed {
+					bounty_id,
+					curator,
+					threshold,
+				});
+
+				Ok(())
+			})
+		}
+
+		/// Delete the auxiliary storage of up to `limit` removed bounties and child bounties
+		/// from the [`PendingCleanup`] queue.
+		///
+		/// Permissionless: cleanup is pure bookkeeping and anyone may pay to advance it ahead
+		/// of the lazy `on_idle` processing. The unused portion of the weight is refunded.
+		#[pallet::call_index(22)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::purge_removed(*limit))]
+		pub fn purge_removed(
+			origin: OriginFor<T>,
+			#[pallet::compact] limit: u32,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let purged = Self::do_purge_removed(limit);
+			if purged > 0 {
+				Self::deposit_event(Event::<T, I>::RemovedBountiesPurged { purged });
+			}
+
+			Ok(Some(<T as Config<I>>::WeightInfo::purge_removed(purged)).into())
+		}
+
+		/// Abandon the pending funding payment of a bounty, marking it as failed.
+		///
+		/// With a remote paymaster a funding payment can stay inconclusive indefinitely, e.g.
+		/// when the destination chain is bricked, leaving the bounty stuck in
+		/// `FundingAttempted`. Abandoning is an explicit operator assertion that the payment
+		/// will never arrive: the funding state becomes failed, so the bounty can be closed
+		/// or its funding retried. Should the payment be delivered belatedly after all, the
+		/// resulting orphaned balance at the bounty account can be recovered with
+		/// `sweep_bounty_account`.
+		///
+		/// May only be called from `T::RejectOrigin`, and only once the payment has stayed
+		/// inconclusive for [`Config::PaymentInconclusiveTimeout`] blocks since the funding
+		/// attempt.
+		#[pallet::call_index(23)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::abandon_payment())]
+		pub fn abandon_payment(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+		) -> DispatchResult {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			Bounties::<T, I>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+				match bounty.status {
+					BountyStatus::FundingAttempted {
+						payment_status: PaymentState::Attempted { id },
+					} => {
+						// Entries are only missing for payments attempted before this stamp
+						// existed; those have long outlived any reasonable timeout.
+						let attempted_at =
+							LastFundingAttempt::<T, I>::get(bounty_id).unwrap_or_default();
+						ensure!(
+							Self::current_block_number() >=
+								attempted_at
+									.saturating_add(T::PaymentInconclusiveTimeout::get()),
+							Error::<T, I>::Premature
+						);
+
+						bounty.status = BountyStatus::FundingAttempted {
+							payment_status: PaymentState::Failed,
+						};
+						LastFundingAttempt::<T, I>::remove(bounty_id);
+						Self::deposit_event(Event::<T, I>::PaymentAbandoned {
+							index: bounty_id,
+							payment_id: id,
+						});
+						Ok(())
+					},
+					_ => Err(Error::<T, I>::UnexpectedStatus.into()),
+				}
+			})
+		}
+
+		/// Attempt to recover an orphaned balance from a bounty's sub-account back to the
+		/// funding source.
+		///
+		/// A balance becomes orphaned when an abandoned funding payment is delivered
+		/// belatedly. Since the bounty asset may live on another chain, neither the balance
+		/// nor the asset kind can be read from here — the bounty record may already be gone —
+		/// so both must be supplied by the caller. The sweep is itself an asynchronous
+		/// payment whose fate is not tracked on chain beyond the emitted
+		/// [`Event::BountyAccountSwept`] carrying the payment id.
+		///
+		/// May only be called from `T::RejectOrigin`, which thereby asserts that the swept
+		/// balance belongs to no live bounty.
+		#[pallet::call_index(24)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::sweep_bounty_account())]
+		pub fn sweep_bounty_account(
+			origin: OriginFor<T>,
+			#[pallet::compact] bounty_id: BountyIndex,
+			asset_kind: Box<T::AssetKind>,
+			#[pallet::compact] amount: AssetBalanceOf<T, I>,
+		) -> DispatchResult {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			let bounty_account = Self::bounty_account_id(bounty_id);
+			let payment_id = T::Paymaster::pay(
+				&bounty_account,
+				&T::FundingSource::get(),
+				*asset_kind,
+				amount,
+			)
+			.map_err(|_| Error::<T, I>::PayoutError)?;
+
+			Self::deposit_event(Event::<T, I>::BountyAccountSwept {
+				index: bounty_id,
+				payment_id,
+			});
+			Ok(())
+		}
+
+		/// Increase the value of a funded bounty or of one of its child bounties, so a
+		/// bounty whose scope grew does not have to be cancelled and recreated, losing its
+		/// curator and child bounty structure.
+		///
+		/// For a parent bounty (`child_bounty_id` is `None`), may only be called from
+		/// `T::SpendOrigin` with a budget covering the increased total value. An additional
+		/// funding payment is made from the funding source to the bounty account and tracked
+		/// in [`PendingTopUps`], leaving the bounty status - including an active curator -
+		/// untouched; the value only grows once the payment is confirmed via
+		/// `check_payment_status`. While a top-up payment is pending the call fails with
+		/// [`Error::Inconclusive`]; a failed one is replaced by calling again. Should the
+		/// bounty conclude before a top-up arrives, the orphaned balance is recoverable with
+		/// `sweep_bounty_account`, like a belated abandoned funding payment.
+		///
+		/// For a child bounty, may only be called from the parent curator: the child's value
+		/// grows by reallocating unassigned parent funds, with no payment involved.
+		#[pallet::call_index(25)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::increase_bounty_value())]
+		pub fn increase_bounty_value(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			child_bounty_id: Option<BountyIndex>,
+			#[pallet::compact] additional_value: AssetBalanceOf<T, I>,
+		) -> DispatchResult {
+			ensure!(!additional_value.is_zero(), Error::<T, I>::InvalidValue);
+
+			let Some(child_bounty_id) = child_bounty_id else {
+				let max_amount = T::SpendOrigin::ensure_origin(origin)?;
+				return Bounties::<T, I>::try_mutate_exists(
+					parent_bounty_id,
+					|maybe_bounty| -> DispatchResult {
+						let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+						match bounty.status {
+							BountyStatus::Funded |
+							BountyStatus::CuratorProposed { .. } |
+							BountyStatus::Active { .. } => {},
+							_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+						}
+						ensure!(
+							!matches!(
+								PendingTopUps::<T, I>::get(parent_bounty_id),
+								Some((_, PaymentState::Attempted { .. }))
+							),
+							Error::<T, I>::Inconclusive
+						);
+
+						let new_value = bounty.value.saturating_add(additional_value);
+						let native_value = T::BalanceConverter::from_asset_balance(
+							new_value,
+							bounty.asset_kind.clone(),
+						)
+						.map_err(|_| Error::<T, I>::FailedToConvertBalance)?;
+						Self::ensure_within_origin_budget(native_value, max_amount)?;
+						ensure!(
+							Self::is_asset_kind_allowed(&bounty.asset_kind),
+							Error::<T, I>::AssetNotAllowed
+						);
+
+						let payment = Self::attempt_payment(
+							&T::FundingSource::get(),
+							&Self::bounty_account_id(parent_bounty_id),
+							bounty.asset_kind.clone(),
+							additional_value,
+						)?;
+						let PaymentState::Attempted { id: payment_id } = payment else {
+							return Err(Error::<T, I>::PayoutError.into())
+						};
+						PendingTopUps::<T, I>::insert(
+							parent_bounty_id,
+							(additional_value, payment),
+						);
+						PendingPayments::<T, I>::insert(
+							(parent_bounty_id, None::<BountyIndex>),
+							(),
+						);
+
+						Self::deposit_event(Event::<T, I>::BountyTopUpAttempted {
+							index: parent_bounty_id,
+							additional_value,
+							payment_id,
+						});
+						Ok(())
+					},
+				)
+			};
+
+			let signer = ensure_signed(origin)?;
+			Self::ensure_parent_curator(parent_bounty_id, &signer)?;
+			let bounty =
+				Bounties::<T, I>::get(parent_bounty_id).ok_or(Error::<T, I>::InvalidIndex)?;
+
+			ChildBounties::<T, I>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child| -> DispatchResult {
+					let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+					match child.status {
+						ChildBountyStatus::Added |
+						ChildBountyStatus::CuratorProposed { .. } |
+						ChildBountyStatus::Active { .. } => {},
+						_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+					}
+
+					// Same coverage check as `add_child_bounty`: the parent curator fee and
+					// all child values must stay within the parent's funds.
+					let committed = ChildrenActiveValues::<T, I>::get(parent_bounty_id)
+						.saturating_add(ChildrenClaimedValues::<T, I>::get(parent_bounty_id))
+						.saturating_add(bounty.fee)
+						.saturating_add(additional_value);
+					ensure!(committed <= bounty.value, Error::<T, I>::InsufficientBountyBalance);
+
+					child.value = child.value.saturating_add(additional_value);
+					ChildrenActiveValues::<T, I>::mutate(parent_bounty_id, |active| {
+						*active = active.saturating_add(additional_value)
+					});
+
+					Self::deposit_event(Event::<T, I>::ChildBountyValueIncreased {
+						index: parent_bounty_id,
+						child_index: child_bounty_id,
+						new_value: child.value,
+					});
+					Ok(())
+				},
+			)
+		}
+
+		/// Migrate a deposit taken as a legacy currency reserve into a fungible hold.
+		///
+		/// Deposits reserved before the pallet switched to fungible holds are migrated lazily
+		/// whenever they are next released or slashed; this call lets anyone convert one
+		/// eagerly, e.g. ahead of retiring the `ReservableCurrency` implementation.
+		///
+		/// ### Parameters
+		/// - `parent_bounty_id`: Index of the bounty whose deposit to migrate.
+		/// - `child_bounty_id`: Index of the child bounty whose curator deposit to migrate, or
+		///   `None` to migrate the parent bounty's deposit.
+		#[pallet::call_index(26)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::migrate_deposit())]
+		pub fn migrate_deposit(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			child_bounty_id: Option<BountyIndex>,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let (who, reason, expected) = if let Some(child_bounty_id) = child_bounty_id {
+				let child = ChildBounties::<T, I>::get(parent_bounty_id, child_bounty_id)
+					.ok_or(Error::<T, I>::InvalidIndex)?;
+				match child.status {
+					ChildBountyStatus::Active { curator, .. } |
+					ChildBountyStatus::PendingPayout { curator, .. } |
+					ChildBountyStatus::PayoutAttempted { curator, .. } =>
+						(curator, HoldReason::CuratorDeposit, child.curator_deposit),
+					_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+				}
+			} else {
+				let bounty =
+					Bounties::<T, I>::get(parent_bounty_id).ok_or(Error::<T, I>::InvalidIndex)?;
+				match bounty.status {
+					BountyStatus::Proposed |
+					BountyStatus::Approved |
+					BountyStatus::FundingAttempted { .. } =>
+						(bounty.proposer, HoldReason::ProposerBond, bounty.bond),
+					BountyStatus::Active { curator, .. } |
+					BountyStatus::PendingPayout { curator, .. } |
+					BountyStatus::PayoutAttempted { curator, .. } =>
+						(curator, HoldReason::CuratorDeposit, bounty.curator_deposit),
+					_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+				}
+			};
+
+			Self::migrate_legacy_deposit(&who, &reason.into(), expected);
+			Ok(())
+		}
+
+		/// Unassign curator from a child bounty.
+		///
+		/// This function can only be called by the `RejectOrigin` or a signed origin.
+		///
+		/// The `RejectOrigin` and the parent curator oversee the child curator; if either
+		/// unassigns them, we assume the child curator is malicious or inactive and slash
+		/// their deposit. The child curator can resign willingly and recover the deposit.
+		/// Anyone else may unassign the child curator only once their `update_due` block has
+		/// passed, slashing the deposit for inactivity.
+		#[pallet::call_index(27)]
+		#[pallet::weight(<T as Config<I>>::WeightInfo::unassign_child_curator())]
+		pub fn unassign_child_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+
+			let bounty =
+				Bounties::<T, I>::get(parent_bounty_id).ok_or(Error::<T, I>::InvalidIndex)?;
+
+			ChildBounties::<T, I>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child| -> DispatchResult {
+					let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+					match child.status {
+						ChildBountyStatus::CuratorProposed { ref curator } => {
+							// A child curator has been proposed, but not accepted yet. The
+							// `RejectOrigin`, the parent curator or the proposed child curator
+							// can unassign them.
+							ensure!(
+								maybe_sender.map_or(true, |sender| sender == *curator ||
+									Self::is_parent_curator(&bounty, &sender)),
+								BadOrigin
+							);
+						},
+						ChildBountyStatus::Active { ref curator, ref update_due } => {
+							match maybe_sender {
+								// The `RejectOrigin` claims the child curator is malicious or
+								// inactive; slash them.
+								None => {
+									Self::slash_deposit(
+										curator,
+										&HoldReason::CuratorDeposit.into(),
+										child.curator_deposit,
+									);
+									child.curator_deposit = Zero::zero();
+								},
+								Some(sender) if sender == *curator => {
+									// The child curator willingly gives up their role; give
+									// back their deposit.
+									Self::release_deposit(
+										curator,
+										&HoldReason::CuratorDeposit.into(),
+										child.curator_deposit,
+									);
+									child.curator_deposit = Zero::zero();
+								},
+								Some(sender) => {
+									// The parent curator oversees their children and can slash
+									// at any time; anyone else only once the child curator has
+									// missed their update.
+									if !Self::is_parent_curator(&bounty, &sender) {
+										ensure!(
+											*update_due < Self::current_block_number(),
+											Error::<T, I>::Premature
+										);
+									}
+									Self::slash_deposit(
+										curator,
+										&HoldReason::CuratorDeposit.into(),
+										child.curator_deposit,
+									);
+									child.curator_deposit = Zero::zero();
+								},
+							}
+						},
+						_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+					};
+
+					child.status = ChildBountyStatus::Added;
+					Ok(())
+				},
+			)?;
+
+			Self::deposit_event(Event::<T, I>::CuratorUnassigned { bounty_id: child_bounty_id });
+			Ok(())
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<SystemBlockNumberFor<T>> for Pallet<T, I> {
+		fn on_idle(_n: SystemBlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let mut used = Self::process_pending_payments(remaining_weight);
+
+			let base = <T as Config<I>>::WeightInfo::purge_removed(0);
+			let per_entry =
+				<T as Config<I>>::WeightInfo::purge_removed(1).saturating_sub(base);
+			let limit = remaining_weight
+				.saturating_sub(used)
+				.saturating_sub(base)
+				.checked_div_per_component(&per_entry)
+				.unwrap_or(0)
+				.min(u32::MAX as u64) as u32;
+			if limit == 0 {
+				return used
+			}
+
+			let purged = Self::do_purge_removed(limit);
+			used.saturating_accrue(base.saturating_add(per_entry.saturating_mul(purged as u64)));
+			used
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: SystemBlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
+
+	#[pallet::view_functions]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// The account holding the funds of bounty `bounty_id`, for integrations monitoring
+		/// bounty balances.
+		///
+		/// A read-only wrapper over [`Pallet::bounty_account_id`] that only answers for
+		/// bounties that actually exist, so the internal derivation does not leak.
+		pub fn bounty_account_of(bounty_id: BountyIndex) -> Option<T::AccountId> {
+			Bounties::<T, I>::contains_key(bounty_id)
+				.then(|| Self::bounty_account_id(bounty_id))
+		}
+
+		/// The account holding the funds of the given child bounty.
+		///
+		/// Child bounties are carved out of their parent's funds and have no account of their
+		/// own, so this is the parent's bounty account for every child that exists.
+		pub fn child_bounty_account_of(
+			parent_bounty_id: BountyIndex,
+			child_bounty_id: BountyIndex,
+		) -> Option<T::AccountId> {
+			ChildBounties::<T, I>::contains_key(parent_bounty_id, child_bounty_id)
+				.then(|| Self::bounty_account_id(parent_bounty_id))
+		}
+
+		/// Dry-run every pre-payment check of the propose/approve/fund pipeline for a
+		/// prospective bounty, so proposal authors can catch errors like
+		/// [`Error::FailedToConvertBalance`] or [`Error::InsufficientPermission`] before a
+		/// referendum is even submitted.
+		///
+		/// `origin_max_amount` is the `Success` value of the spend origin that would approve
+		/// the bounty. The checks are the same functions the extrinsics run: description
+		/// length, balance conversion, [`Config::BountyValueMinimum`], the origin budget and
+		/// the funding allow-list. On success, returns the converted native value, the
+		/// bounty account the funds would be paid into (derived for the next bounty index)
+		/// and an upper bound on the curator deposit.
+		pub fn validate_fund_bounty(
+			origin_max_amount: BalanceOf<T, I>,
+			asset_kind: T::AssetKind,
+			value: AssetBalanceOf<T, I>,
+			description_len: u32,
+		) -> Result<FundBountyPreview<T::AccountId, BalanceOf<T, I>>, DispatchError> {
+			Self::ensure_description_len(description_len)?;
+			let native_value = Self::convert_and_check_value(value, asset_kind.clone())?;
+			Self::ensure_within_origin_budget(native_value, origin_max_amount)?;
+			ensure!(Self::is_asset_kind_allowed(&asset_kind), Error::<T, I>::AssetNotAllowed);
+			let max_curator_deposit = Self::calculate_curator_deposit(&value, asset_kind)?;
+
+			Ok(FundBountyPreview {
+				native_value,
+				bounty_account: Self::bounty_account_id(BountyCount::<T, I>::get()),
+				max_curator_deposit,
+			})
+		}
+	}
+}
+
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Ensure the correctness of the state of this pallet.
+	pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+		let bounties_length = Bounties::<T, I>::iter().count() as u32;
+
+		ensure!(
+			BountyCount::<T, I>::get() >= bounties_length,
+			"`BountyCount` must be greater than or equal to the number of `Bounties` in storage"
+		);
+
+		for (parent_bounty_id, count) in ParentChildBounties::<T, I>::iter() {
+			ensure!(
+				ChildBounties::<T, I>::iter_prefix(parent_bounty_id).count() as u32 == count,
+				"`ParentChildBounties` must match the number of `ChildBounties` in storage"
+			);
+		}
+
+		// Auxiliary storage may only outlive its primary entry while queued for cleanup; the
+		// backlog itself is bounded by `MaxCleanupBacklog`.
+		let pending = PendingCleanup::<T, I>::get();
+
+		let mut aux_bounty_ids: Vec<BountyIndex> = Vec::new();
+		aux_bounty_ids.extend(BountyDescriptions::<T, I>::iter_keys());
+		aux_bounty_ids.extend(BountyDescriptionHashes::<T, I>::iter_keys());
+		aux_bounty_ids.extend(DeprecatedBountyDescriptions::<T, I>::iter_keys());
+		aux_bounty_ids.extend(LastFundingAttempt::<T, I>::iter_keys());
+		aux_bounty_ids.extend(MultiCurators::<T, I>::iter_keys());
+		aux_bounty_ids.extend(PendingTopUps::<T, I>::iter_keys());
+		aux_bounty_ids.extend(CuratorAcceptApprovals::<T, I>::iter_keys());
+		aux_bounty_ids.extend(BountyAwardApprovals::<T, I>::iter_keys());
+		aux_bounty_ids.extend(ChildrenCuratorFees::<T, I>::iter_keys());
+		aux_bounty_ids.extend(ChildrenClaimedValues::<T, I>::iter_keys());
+		aux_bounty_ids.extend(ChildrenActiveValues::<T, I>::iter_keys());
+		aux_bounty_ids.extend(ParentChildBounties::<T, I>::iter_keys());
+		for bounty_id in aux_bounty_ids {
+			ensure!(
+				Bounties::<T, I>::contains_key(bounty_id) ||
+					pending.contains(&PendingCleanupEntry::Bounty(bounty_id)),
+				"auxiliary bounty storage must belong to a live bounty or the cleanup queue"
+			);
+		}
+
+		let live_child_ids: Vec<BountyIndex> =
+			ChildBounties::<T, I>::iter().map(|(_, child_bounty_id, _)| child_bounty_id).collect();
+		let mut aux_child_ids: Vec<BountyIndex> = Vec::new();
+		aux_child_ids.extend(ChildBountyDescriptions::<T, I>::iter_keys());
+		aux_child_ids.extend(ChildBountyDescriptionHashes::<T, I>::iter_keys());
+		aux_child_ids.extend(DeprecatedChildBountyDescriptions::<T, I>::iter_keys());
+		for child_bounty_id in aux_child_ids {
+			ensure!(
+				live_child_ids.contains(&child_bounty_id) ||
+					pending.contains(&PendingCleanupEntry::ChildBounty(child_bounty_id)),
+				"auxiliary child bounty storage must belong to a live child bounty or the cleanup queue"
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Get the block number used by this pallet.
+	///
+	/// It may be configured to use the relay chain block number on a parachain.
+	pub fn current_block_number() -> BlockNumberFor<T, I> {
+		T::BlockNumberProvider::current_block_number()
+	}
+
+	/// Calculate the native deposit required for a curator, from the curator fee denominated in
+	/// the bounty's asset kind.
+	pub fn calculate_curator_deposit(
+		fee: &AssetBalanceOf<T, I>,
+		asset_kind: T::AssetKind,
+	) -> Result<BalanceOf<T, I>, DispatchError> {
+		let native_fee = T::BalanceConverter::from_asset_balance(*fee, asset_kind)
+			.map_err(|_| Error::<T, I>::FailedToConvertBalance)?;
+		let mut deposit = T::CuratorDepositMultiplier::get() * native_fee;
+
+		if let Some(max_deposit) = T::CuratorDepositMax::get() {
+			deposit = deposit.min(max_deposit)
+		}
+
+		if let Some(min_deposit) = T::CuratorDepositMin::get() {
+			deposit = deposit.max(min_deposit)
+		}
+
+		Ok(deposit)
+	}
+
+	/// Convert a deposit still reserved under [`Config::OldCurrency`] into a fungible hold.
+	///
+	/// `expected` is the full deposit recorded in storage; only the part not already held is
+	/// unreserved and re-held, so the helper is idempotent and safe to call on deposits that
+	/// were taken as holds in the first place.
+	fn migrate_legacy_deposit(
+		who: &T::AccountId,
+		reason: &T::RuntimeHoldReason,
+		expected: BalanceOf<T, I>,
+	) {
+		let held = T::Currency::balance_on_hold(reason, who);
+		let shortfall = expected.saturating_sub(held);
+		if shortfall.is_zero() {
+			return
+		}
+		let unreserved = shortfall.saturating_sub(T::OldCurrency::unreserve(who, shortfall));
+		if !unreserved.is_zero() && T::Currency::hold(reason, who, unreserved).is_err() {
+			frame_support::defensive!("unreserved deposit could not be held");
+		}
+	}
+
+	/// Release a held deposit, migrating it from a legacy reserve first if necessary.
+	fn release_deposit(who: &T::AccountId, reason: &T::RuntimeHoldReason, amount: BalanceOf<T, I>) {
+		if amount.is_zero() {
+			return
+		}
+		Self::migrate_legacy_deposit(who, reason, amount);
+		let released =
+			T::Currency::release(reason, who, amount, Precision::BestEffort).unwrap_or_default();
+		if released < amount {
+			frame_support::defensive!("deposit was not fully held");
+		}
+	}
+
+	/// Slash a held deposit, migrating it from a legacy reserve first if necessary.
+	///
+	/// The resulting imbalance is routed to [`Config::OnSlash`].
+	fn slash_deposit(who: &T::AccountId, reason: &T::RuntimeHoldReason, amount: BalanceOf<T, I>) {
+		if amount.is_zero() {
+			return
+		}
+		Self::migrate_legacy_deposit(who, reason, amount);
+		let (credit, _) = T::Currency::slash(reason, who, amount);
+		T::OnSlash::on_unbalanced(credit);
+	}
+
+	/// Check a proposed curator fee against the bounty (or child bounty) value.
+	///
+	/// The fee must be strictly less than the value and is additionally capped at the
+	/// [`Config::MaxCuratorFeeMultiplier`] fraction of it. Shared between the parent and
+	/// child curator proposal extrinsics so the two cannot drift.
+	fn ensure_valid_fee(fee: AssetBalanceOf<T, I>, value: AssetBalanceOf<T, I>) -> DispatchResult {
+		ensure!(fee < value, Error::<T, I>::InvalidFee);
+		ensure!(
+			fee <= T::MaxCuratorFeeMultiplier::get().mul_floor(value),
+			Error::<T, I>::InvalidFee
+		);
+		Ok(())
+	}
+
+	/// Convert a bounty value to the native currency and check it against
+	/// [`Config::BountyValueMinimum`].
+	///
+	/// Shared between `create_bounty` and the [`Pallet::validate_fund_bounty`] preview so the
+	/// two cannot drift.
+	fn convert_and_check_value(
+		value: AssetBalanceOf<T, I>,
+		asset_kind: T::AssetKind,
+	) -> Result<BalanceOf<T, I>, DispatchError> {
+		let native_value = T::BalanceConverter::from_asset_balance(value, asset_kind)
+			.map_err(|_| Error::<T, I>::FailedToConvertBalance)?;
+		ensure!(native_value >= T::BountyValueMinimum::get(), Error::<T, I>::InvalidValue);
+		Ok(native_value)
+	}
+
+	/// Check a native bounty value against the spend origin's budget.
+	///
+	/// Shared between the approval extrinsics and the [`Pallet::validate_fund_bounty`]
+	/// preview so the two cannot drift.
+	fn ensure_within_origin_budget(
+		native_value: BalanceOf<T, I>,
+		max_amount: BalanceOf<T, I>,
+	) -> DispatchResult {
+		ensure!(native_value <= max_amount, Error::<T, I>::InsufficientPermission);
+		Ok(())
+	}
+
+	/// Check a description argument length against the configured
+	/// [`Config::DescriptionMode`].
+	///
+	/// Shared between `prepare_description` and the [`Pallet::validate_fund_bounty`] preview
+	/// so the two cannot drift.
+	fn ensure_description_len(len: u32) -> DispatchResult {
+		match T::DescriptionMode::get() {
+			DescriptionStorageMode::Full =>
+				ensure!(len <= T::MaximumReasonLength::get(), Error::<T, I>::ReasonTooBig),
+			DescriptionStorageMode::HashOnly =>
+				ensure!(len == 32, Error::<T, I>::InvalidDescriptionHash),
+		}
+		Ok(())
+	}
+
+	/// The account ID of the bounties pot.
+	///
+	/// This actually does computation. If you need to keep using it, then make sure you cache
+	/// the value and only call this once.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
+	/// The account ID of a bounty account.
+	pub fn bounty_account_id(id: BountyIndex) -> T::AccountId {
+		// only use two byte prefix to support 16 byte account id (used by test)
+		// "modl" ++ "py/mabnt" ++ "bt" is 14 bytes, and two bytes remaining for bounty index
+		T::PalletId::get().into_sub_account_truncating(("bt", id))
+	}
+
+	/// Whether the given asset kind passes the funding allow-list.
+	///
+	/// An empty allow-list accepts every asset kind.
+	pub fn is_asset_kind_allowed(asset_kind: &T::AssetKind) -> bool {
+		AllowedAssetKinds::<T, I>::count() == 0 ||
+			AllowedAssetKinds::<T, I>::contains_key(asset_kind)
+	}
+
+	/// The final parent curator fee and beneficiary payout for a bounty, accounting for already
+	/// claimed child bounties.
+	fn calculate_curator_fee_and_payout(
+		bounty_id: BountyIndex,
+		fee: AssetBalanceOf<T, I>,
+		value: AssetBalanceOf<T, I>,
+	) -> (AssetBalanceOf<T, I>, AssetBalanceOf<T, I>) {
+		let children_fee = ChildrenCuratorFees::<T, I>::get(bounty_id);
+		let children_value = ChildrenClaimedValues::<T, I>::get(bounty_id);
+		debug_assert!(children_fee <= fee);
+
+		let final_fee = fee.saturating_sub(children_fee);
+		let payout = value.saturating_sub(children_value).saturating_sub(final_fee);
+		(final_fee, payout)
+	}
+
+	/// Progress in-flight payments from the [`PendingPayments`] index within `limit`,
+	/// returning the weight consumed.
+	///
+	/// Each entry gets the same treatment a manual [`Pallet::check_payment_status`] or
+	/// [`Pallet::check_child_payment_status`] call would give it, so e.g. a funded bounty
+	/// advances to `Funded` without anyone calling in. Entries whose payment the paymaster
+	/// still reports as in progress are left for a later block; stale entries are dropped.
+	fn process_pending_payments(limit: Weight) -> Weight {
+		let per_entry = <T as Config<I>>::WeightInfo::check_payment_status()
+			.max(<T as Config<I>>::WeightInfo::check_child_payment_status())
+			.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+		let mut used = Weight::zero();
+		for (bounty_id, maybe_child_id) in PendingPayments::<T, I>::iter_keys() {
+			if used.saturating_add(per_entry).any_gt(limit) {
+				break
+			}
+			used.saturating_accrue(per_entry);
+			let result = match maybe_child_id {
+				Some(child_bounty_id) =>
+					Self::do_check_child_payment_status(bounty_id, child_bounty_id),
+				None => Self::do_check_payment_status(bounty_id),
+			};
+			if let Err(e) = result {
+				// An inconclusive payment is simply revisited later; any other error means
+				// the entry no longer matches a pending payment and is dead weight.
+				if e.error != Error::<T, I>::Inconclusive.into() {
+					PendingPayments::<T, I>::remove((bounty_id, maybe_child_id));
+				}
+			}
+		}
+		used
+	}
+
+	/// Check and progress the pending funding, payout, refund or top-up payment of a
+	/// bounty, as [`Pallet::check_payment_status`] would.
+	fn do_check_payment_status(bounty_id: BountyIndex) -> DispatchResultWithPostInfo {
+		Bounties::<T, I>::try_mutate_exists(
+			bounty_id,
+			|maybe_bounty| -> DispatchResultWithPostInfo {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+				match &mut bounty.status {
+					BountyStatus::FundingAttempted { payment_status } => {
+						let funding_status =
+							Self::advance_payment(bounty_id, payment_status)?;
+						// The payment concluded either way; the attempt stamp only gates
+						// abandoning a payment that is still pending.
+						LastFundingAttempt::<T, I>::remove(bounty_id);
+						if funding_status == PaymentStatus::Success {
+							// Return the proposer's bond now that the bounty is backed by
+							// real funds.
+							Self::release_deposit(
+								&bounty.proposer,
+								&HoldReason::ProposerBond.into(),
+								bounty.bond,
+							);
+							bounty.bond = Zero::zero();
+							bounty.status = BountyStatus::Funded;
+							PendingPayments::<T, I>::remove((bounty_id, None::<BountyIndex>));
+							Self::deposit_event(Event::<T, I>::BountyFunded {
+								index: bounty_id,
+							});
+						}
+					},
+					BountyStatus::PayoutAttempted {
+						curator,
+						beneficiary,
+						curator_payment,
+						beneficiary_payment,
+					} => {
+						let curator_concluded =
+							Self::advance_payment(bounty_id, curator_payment)? ==
+								PaymentStatus::Success;
+						let beneficiary_concluded =
+							Self::advance_payment(bounty_id, beneficiary_payment)? ==
+								PaymentStatus::Success;
+						if curator_concluded && beneficiary_concluded {
+							let (curator, beneficiary) = (curator.clone(), beneficiary.clone());
+							Self::release_deposit(
+								&curator,
+								&HoldReason::CuratorDeposit.into(),
+								bounty.curator_deposit,
+							);
+
+							let (curator_fee, payout) = Self::calculate_curator_fee_and_payout(
+								bounty_id,
+								bounty.fee,
+								bounty.value,
+							);
+
+							*maybe_bounty = None;
+							Self::remove_bounty_records(bounty_id);
+							PendingPayments::<T, I>::remove((bounty_id, None::<BountyIndex>));
+
+							Self::deposit_event(Event::<T, I>::BountyClaimed {
+								index: bounty_id,
+								payout,
+								curator_fee,
+								beneficiary,
+							});
+						}
+					},
+					BountyStatus::RefundAttempted { payment_status } => {
+						if Self::advance_payment(bounty_id, payment_status)? ==
+							PaymentStatus::Success
+						{
+							*maybe_bounty = None;
+							Self::remove_bounty_records(bounty_id);
+							PendingPayments::<T, I>::remove((bounty_id, None::<BountyIndex>));
+							Self::deposit_event(Event::<T, I>::BountyCanceled {
+								index: bounty_id,
+							});
+						}
+					},
+					// No lifecycle payment is pending in the remaining statuses, but a
+					// value top-up may be.
+					_ => {
+						let (additional_value, mut payment) =
+							PendingTopUps::<T, I>::get(bounty_id)
+								.ok_or(Error::<T, I>::UnexpectedStatus)?;
+						if Self::advance_payment(bounty_id, &mut payment)? ==
+							PaymentStatus::Success
+						{
+							PendingTopUps::<T, I>::remove(bounty_id);
+							PendingPayments::<T, I>::remove((bounty_id, None::<BountyIndex>));
+							bounty.value = bounty.value.saturating_add(additional_value);
+							Self::deposit_event(Event::<T, I>::BountyValueIncreased {
+								index: bounty_id,
+								new_value: bounty.value,
+							});
+						} else {
+							PendingTopUps::<T, I>::insert(
+								bounty_id,
+								(additional_value, payment),
+							);
+						}
+					},
+				}
+
+				Ok(Pays::No.into())
+			},
+		)
+	}
+
+
+	/// Check and progress the pending payout payments of a child bounty, as
+	/// [`Pallet::check_child_payment_status`] would.
+	fn do_check_child_payment_status(
+		parent_bounty_id: BountyIndex,
+		child_bounty_id: BountyIndex,
+	) -> DispatchResultWithPostInfo {
+		ChildBounties::<T, I>::try_mutate_exists(
+			parent_bounty_id,
+			child_bounty_id,
+			|maybe_child| -> DispatchResultWithPostInfo {
+				let child = maybe_child.as_mut().ok_or(Error::<T, I>::InvalidIndex)?;
+
+				match &mut child.status {
+					ChildBountyStatus::PayoutAttempted {
+						curator,
+						beneficiary,
+						curator_payment,
+						beneficiary_payment,
+					} => {
+						let curator_concluded =
+							Self::advance_payment(child_bounty_id, curator_payment)? ==
+								PaymentStatus::Success;
+						let beneficiary_concluded =
+							Self::advance_payment(child_bounty_id, beneficiary_payment)? ==
+								PaymentStatus::Success;
+						if curator_concluded && beneficiary_concluded {
+							let beneficiary = beneficiary.clone();
+							// Return the child curator deposit. Zero for children curated
+							// by the parent curator.
+							Self::release_deposit(
+								curator,
+								&HoldReason::CuratorDeposit.into(),
+								child.curator_deposit,
+							);
+
+							let curator_fee = child.fee;
+							let payout = child.value.saturating_sub(curator_fee);
+
+							ChildrenCuratorFees::<T, I>::mutate(parent_bounty_id, |fees| {
+								*fees = fees.saturating_add(child.fee)
+							});
+							ChildrenClaimedValues::<T, I>::mutate(
+								parent_bounty_id,
+								|claimed| *claimed = claimed.saturating_add(child.value),
+							);
+							ChildrenActiveValues::<T, I>::mutate(parent_bounty_id, |active| {
+								*active = active.saturating_sub(child.value)
+							});
+							ParentChildBounties::<T, I>::mutate(parent_bounty_id, |count| {
+								count.saturating_dec()
+							});
+
+							*maybe_child = None;
+							Self::remove_child_bounty_description(child_bounty_id);
+							PendingPayments::<T, I>::remove((
+								parent_bounty_id,
+								Some(child_bounty_id),
+							));
+
+							Self::deposit_event(Event::<T, I>::ChildBountyClaimed {
+								index: parent_bounty_id,
+								child_index: child_bounty_id,
+								payout,
+								curator_fee,
+								beneficiary,
+							});
+						}
+					},
+					_ => return Err(Error::<T, I>::UnexpectedStatus.into()),
+				}
+
+				Ok(Pays::No.into())
+			},
+		)
+	}
+
+
+	/// Dispatch a payment via the paymaster, returning its initial payment state.
+	fn attempt_payment(
+		source: &T::AccountId,
+		beneficiary: &T::AccountId,
+		asset_kind: T::AssetKind,
+		amount: AssetBalanceOf<T, I>,
+	) -> Result<PaymentState<PaymentIdOf<T, I>>, DispatchError> {
+		let id = T::Paymaster::pay(source, beneficiary, asset_kind, amount)
+			.map_err(|_| Error::<T, I>::PayoutError)?;
+		Ok(PaymentState::Attempted { id })
+	}
+
+	/// Poll the paymaster for an in-flight payment and update its state.
+	///
+	/// Returns the observed [`PaymentStatus`], or `Inconclusive` if the payment has not
+	/// concluded yet.
+	fn advance_payment(
+		index: BountyIndex,
+		payment_status: &mut PaymentState<PaymentIdOf<T, I>>,
+	) -> Result<PaymentStatus, DispatchError> {
+		match payment_status {
+			PaymentState::Attempted { id } => match T::Paymaster::check_payment(*id) {
+				PaymentStatus::Success => Ok(PaymentStatus::Success),
+				PaymentStatus::Failure => {
+					let id = *id;
+					*payment_status = PaymentState::Failed;
+					Self::deposit_event(Event::<T, I>::PaymentFailed { index, payment_id: id });
+					Ok(PaymentStatus::Failure)
+				},
+				_ => Err(Error::<T, I>::Inconclusive.into()),
+			},
+			// A failed payment leg does not conclude; it has to be retried first.
+			PaymentState::Failed => Ok(PaymentStatus::Failure),
+		}
+	}
+
+	/// Whether `who` is a member of the bounty's multi-signature curator set, if any.
+	fn is_multi_curator_member(bounty_id: BountyIndex, who: &T::AccountId) -> bool {
+		MultiCurators::<T, I>::get(bounty_id).map_or(false, |set| set.curators.contains(who))
+	}
+
+	/// Defer deletion of the auxiliary storage of a removed bounty.
+	///
+	/// Only the primary [`Bounties`] entry is removed by the caller; the auxiliary maps are
+	/// deleted later in weight-metered batches. Since bounty indices are never reused, the
+	/// stale entries cannot be observed through a live bounty in the meantime.
+	fn remove_bounty_records(bounty_id: BountyIndex) {
+		Self::enqueue_cleanup(PendingCleanupEntry::Bounty(bounty_id));
+	}
+
+	/// Defer deletion of the description storage of a removed child bounty.
+	fn remove_child_bounty_description(child_bounty_id: BountyIndex) {
+		Self::enqueue_cleanup(PendingCleanupEntry::ChildBounty(child_bounty_id));
+	}
+
+	/// Enqueue a removed bounty for deferred cleanup, falling back to deleting its auxiliary
+	/// storage synchronously when the backlog is full.
+	fn enqueue_cleanup(entry: PendingCleanupEntry) {
+		PendingCleanup::<T, I>::mutate(|queue| {
+			if let Err(entry) = queue.try_push(entry) {
+				Self::purge_entry(entry);
+			}
+		});
+	}
+
+	/// Delete the auxiliary storage of one removed bounty or child bounty.
+	fn purge_entry(entry: PendingCleanupEntry) {
+		match entry {
+			PendingCleanupEntry::Bounty(bounty_id) => {
+				BountyDescriptions::<T, I>::remove(bounty_id);
+				LastFundingAttempt::<T, I>::remove(bounty_id);
+				BountyDescriptionHashes::<T, I>::remove(bounty_id);
+				DeprecatedBountyDescriptions::<T, I>::remove(bounty_id);
+				MultiCurators::<T, I>::remove(bounty_id);
+				PendingTopUps::<T, I>::remove(bounty_id);
+				CuratorAcceptApprovals::<T, I>::remove(bounty_id);
+				BountyAwardApprovals::<T, I>::remove(bounty_id);
+				ChildrenCuratorFees::<T, I>::remove(bounty_id);
+				ChildrenClaimedValues::<T, I>::remove(bounty_id);
+				ChildrenActiveValues::<T, I>::remove(bounty_id);
+				ParentChildBounties::<T, I>::remove(bounty_id);
+				PendingPayments::<T, I>::remove((bounty_id, None::<BountyIndex>));
+			},
+			PendingCleanupEntry::ChildBounty(child_bounty_id) => {
+				ChildBountyDescriptions::<T, I>::remove(child_bounty_id);
+				ChildBountyDescriptionHashes::<T, I>::remove(child_bounty_id);
+				DeprecatedChildBountyDescriptions::<T, I>::remove(child_bounty_id);
+			},
+		}
+	}
+
+	/// Delete the auxiliary storage of up to `limit` entries from the cleanup queue,
+	/// returning how many were processed.
+	fn do_purge_removed(limit: u32) -> u32 {
+		if limit == 0 {
+			return 0
+		}
+		let mut queue = PendingCleanup::<T, I>::take().into_inner();
+		let take = queue.len().min(limit as usize);
+		for entry in queue.drain(..take) {
+			Self::purge_entry(entry);
+		}
+		if !queue.is_empty() {
+			PendingCleanup::<T, I>::put(BoundedVec::truncate_from(queue));
+		}
+		take as u32
+	}
+
+	/// The stored full description of a bounty.
+	///
+	/// Auxiliary data of a removed bounty that still awaits deferred cleanup is treated as
+	/// already deleted.
+	pub fn bounty_description(
+		bounty_id: BountyIndex,
+	) -> Option<BoundedVec<u8, T::MaximumReasonLength>> {
+		Bounties::<T, I>::contains_key(bounty_id)
+			.then(|| BountyDescriptions::<T, I>::get(bounty_id))
+			.flatten()
+	}
+
+	/// The stored full description of a child bounty.
+	///
+	/// Auxiliary data of a removed child bounty that still awaits deferred cleanup is treated
+	/// as already deleted.
+	pub fn child_bounty_description(
+		parent_bounty_id: BountyIndex,
+		child_bounty_id: BountyIndex,
+	) -> Option<BoundedVec<u8, T::MaximumReasonLength>> {
+		ChildBounties::<T, I>::contains_key(parent_bounty_id, child_bounty_id)
+			.then(|| ChildBountyDescriptions::<T, I>::get(child_bounty_id))
+			.flatten()
+	}
+
+	/// Validate a description argument against the configured [`Config::DescriptionMode`].
+	///
+	/// In hash-only mode the argument must be exactly the 32 byte hash of the off-chain
+	/// description text.
+	fn prepare_description(
+		description: Vec<u8>,
+	) -> Result<PreparedDescription<BoundedVec<u8, T::MaximumReasonLength>, T::Hash>, DispatchError>
+	{
+		Self::ensure_description_len(description.len() as u32)?;
+		match T::DescriptionMode::get() {
+			DescriptionStorageMode::Full => Ok(PreparedDescription::Full(
+				description.try_into().map_err(|_| Error::<T, I>::ReasonTooBig)?,
+			)),
+			DescriptionStorageMode::HashOnly => {
+				let hash = T::Hash::decode(&mut &description[..])
+					.map_err(|_| Error::<T, I>::InvalidDescriptionHash)?;
+				Ok(PreparedDescription::HashOnly(hash))
+			},
+		}
+	}
+
+	/// Whether `who` is the active curator of `bounty`.
+	fn is_parent_curator(bounty: &BountyOf<T, I>, who: &T::AccountId) -> bool {
+		matches!(&bounty.status, BountyStatus::Active { curator, .. } if curator == who)
+	}
+
+	/// Ensure `who` is the active curator of the given parent bounty.
+	fn ensure_parent_curator(bounty_id: BountyIndex, who: &T::AccountId) -> DispatchResult {
+		let bounty = Bounties::<T, I>::get(bounty_id).ok_or(Error::<T, I>::InvalidIndex)?;
+		match &bounty.status {
+			BountyStatus::Active { curator, .. } => {
+				ensure!(who == curator, Error::<T, I>::RequireCurator);
+				Ok(())
+			},
+			_ => Err(Error::<T, I>::UnexpectedStatus.into()),
+		}
+	}
+
+	fn create_bounty(
+		proposer: T::AccountId,
+		asset_kind: T::AssetKind,
+		value: AssetBalanceOf<T, I>,
+		description: Vec<u8>,
+	) -> DispatchResult {
+		let description = Self::prepare_description(description)?;
+		Self::convert_and_check_value(value, asset_kind.clone())?;
+
+		let index = BountyCount::<T, I>::get();
+
+		// reserve deposit for new bounty
+		let bond = T::BountyDepositBase::get().saturating_add(
+			T::DataDepositPerByte::get().saturating_mul((description.len() as u32).into()),
+		);
+		T::Currency::hold(&HoldReason::ProposerBond.into(), &proposer, bond)
+			.map_err(|_| Error::<T, I>::InsufficientProposersBalance)?;
+
+		BountyCount::<T, I>::put(index + 1);
+
+		let bounty = Bounty {
+			proposer,
+			asset_kind,
+			value,
+			fee: Zero::zero(),
+			curator_deposit: Zero::zero(),
+			bond,
+			status: BountyStatus::Proposed,
+		};
+
+		Bounties::<T, I>::insert(index, &bounty);
+		match description {
+			PreparedDescription::Full(description) =>
+				BountyDescriptions::<T, I>::insert(index, description),
+			PreparedDescription::HashOnly(hash) =>
+				BountyDescriptionHashes::<T, I>::insert(index, hash),
+		}
+
+		Self::deposit_event(Event::<T, I>::BountyProposed { index });
+
+		Ok(())
+	}
+}
+
+/// Storage migrations for switching [`Config::DescriptionMode`] on a live chain.
+pub mod migrations {
+	use super::*;
+	use frame_support::traits::OnRuntimeUpgrade;
+	use sp_runtime::traits::Hash;
+
+	/// Migrates stored descriptions for a runtime switching from
+	/// [`DescriptionStorageMode::Full`] to [`DescriptionStorageMode::HashOnly`].
+	///
+	/// Every stored description is hashed with the runtime's hasher into
+	/// [`BountyDescriptionHashes`] (resp. [`ChildBountyDescriptionHashes`]); the original text
+	/// is parked in the deprecated maps, where it stays available until governance removes it
+	/// with [`Pallet::prune_legacy_descriptions`].
+	pub struct MigrateDescriptionsToHashOnly<T, I = ()>(PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateDescriptionsToHashOnly<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut migrated: u64 = 0;
+			for (index, description) in BountyDescriptions::<T, I>::drain() {
+				BountyDescriptionHashes::<T, I>::insert(index, T::Hashing::hash(&description[..]));
+				DeprecatedBountyDescriptions::<T, I>::insert(index, description);
+				migrated.saturating_inc();
+			}
+			for (index, description) in ChildBountyDescriptions::<T, I>::drain() {
+				ChildBountyDescriptionHashes::<T, I>::insert(
+					index,
+					T::Hashing::hash(&description[..]),
+				);
+				DeprecatedChildBountyDescriptions::<T, I>::insert(index, description);
+				migrated.saturating_inc();
+			}
+			// One read per drained description plus one write each to remove it, store its
+			// hash and park the original.
+			T::DbWeight::get().reads_writes(migrated, migrated.saturating_mul(3))
+		}
+	}
+}
@@ -0,0 +1,1246 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Optimistic Project Funding Pallet
+//!
+//! Distributes a funding pot across whitelisted projects by optimistic voting rounds.
+//!
+//! The [`Config::AdminOrigin`] whitelists project accounts into the current voting round via
+//! [`Pallet::register_projects_batch`], implicitly starting a new round of
+//! [`Config::VotingPeriod`] blocks when none is running. Token holders vote for or against
+//! each project with [`Pallet::vote`], placing a hold on the voted amount until the round
+//! ends.
+//!
+//! When the round is over, `on_idle` releases the held funds and splits the pot among the
+//! projects whose positive votes outweigh their negative ones, proportionally to their net
+//! support. Each winner gets a [`SpendInfo`] it can claim with [`Pallet::claim_reward_for`]
+//! during [`Config::ClaimingPeriod`] blocks; with a non-zero
+//! [`Config::RewardVestingPeriod`] the reward vests linearly from the round's settlement
+//! and only the vested portion is payable at a time. A project can redirect its payouts to
+//! an operational account with [`Pallet::set_payout_account`]; while a spend is pending the
+//! change waits [`Config::PayoutChangeDelay`] blocks. Unclaimed spends are discarded and
+//! their remaining amount stays in the pot for the next round; with a non-zero
+//! [`Config::MaxRelistsPerRound`], up to that many expired projects are relisted into the
+//! next round instead. The admin origin can push a spend's expiry back with
+//! [`Pallet::extend_claim_window`] — e.g. after an outage — by at most
+//! [`Config::MaxClaimExtension`] blocks in total. Each settled round leaves a
+//! [`RoundSummary`] of its accounting, retained for the last [`Config::MaxRetainedRounds`]
+//! rounds.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use alloc::vec::Vec;
+use frame_support::{
+	pallet_prelude::*,
+	traits::{
+		fungible::{Inspect, Mutate, MutateHold},
+		tokens::{Fortitude, Precision, Preservation},
+	},
+	PalletId,
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, UniqueSaturatedInto, Zero},
+	Perbill, Permill,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// Projects are identified by the account the reward is paid to.
+pub type ProjectId<T> = <T as frame_system::Config>::AccountId;
+
+pub type BalanceOf<T> =
+	<<T as Config>::NativeBalance as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The lifecycle phase of a voting round.
+///
+/// `Registration` and `Voting` end at the block thresholds stored on the round;
+/// `Tallying` ends when the cursor-based tally has processed every whitelisted project.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum RoundPhase {
+	/// Projects can be whitelisted; voting has not opened yet.
+	Registration,
+	/// Votes can be cast, replaced and removed.
+	Voting,
+	/// Voting has closed; the tally is releasing held votes and computing allocations.
+	Tallying,
+	/// The round is fully settled and the next round may start.
+	Finalized,
+}
+
+/// A voting round.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct VotingRoundInfo<BlockNumber> {
+	/// The sequence number of the round.
+	pub round_number: u32,
+	/// The block the round started at.
+	pub round_starting_block: BlockNumber,
+	/// The block voting opens at; registration closes here.
+	pub voting_starting_block: BlockNumber,
+	/// The block voting closes at; the tally runs from here on.
+	pub round_ending_block: BlockNumber,
+	/// The floor of the round's vote weight decay, if any. With `Some(floor)` a vote's
+	/// tally weight falls linearly from 100% when cast as voting opens to `floor` when
+	/// cast at the round's end; `None` weights every vote equally. See [`decay_factor`].
+	pub decay_floor: Option<Permill>,
+	/// The lifecycle phase the round was last recorded in. Block-driven transitions may
+	/// not be written back yet; [`Pallet::round_phase`] gives the effective phase.
+	pub phase: RoundPhase,
+}
+
+/// The vote tallies of a project in the current round.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct ProjectFundsInfo<Balance> {
+	/// The total amount voted in favour of the project.
+	pub positive: Balance,
+	/// The total amount voted against the project.
+	pub negative: Balance,
+}
+
+/// A single voter's standing vote on a project.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct VoteInfo<Balance, BlockNumber> {
+	/// The amount held for this vote.
+	pub amount: Balance,
+	/// Whether the vote is in favour of funding the project.
+	pub is_fund: bool,
+	/// The block the vote was cast at. Replacing a vote re-stamps this, so updating a
+	/// vote late in a decaying round deliberately lowers its weight.
+	pub cast_at: BlockNumber,
+}
+
+/// The weight factor of a vote cast at `cast_at` under linear decay.
+///
+/// A vote cast at or before `voting_start` keeps its full weight; the factor then falls
+/// linearly to `floor` for a vote cast at `round_end` and stays there past it. A
+/// degenerate span with `round_end <= voting_start` cannot decay.
+pub fn decay_factor<BlockNumber>(
+	floor: Permill,
+	voting_start: BlockNumber,
+	round_end: BlockNumber,
+	cast_at: BlockNumber,
+) -> Permill
+where
+	BlockNumber: Ord + Saturating + UniqueSaturatedInto<u128> + Copy,
+{
+	if cast_at <= voting_start || round_end <= voting_start {
+		return Permill::one()
+	}
+	if cast_at >= round_end {
+		return floor
+	}
+	let elapsed = Permill::from_rational::<u128>(
+		cast_at.saturating_sub(voting_start).unique_saturated_into(),
+		round_end.saturating_sub(voting_start).unique_saturated_into(),
+	);
+	Permill::one().saturating_sub(elapsed * Permill::one().saturating_sub(floor))
+}
+
+/// A reward awaiting its claim.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct SpendInfo<Balance, BlockNumber> {
+	/// The amount claimable from the pot.
+	pub amount: Balance,
+	/// The part of `amount` already paid out by earlier claims.
+	pub claimed: Balance,
+	/// The block the reward started vesting at: the block the round settled.
+	pub vesting_start: BlockNumber,
+	/// The last block the spend can be claimed at.
+	pub expire: BlockNumber,
+	/// The total claim-window extension granted so far, bounded by
+	/// [`Config::MaxClaimExtension`].
+	pub claim_extension: BlockNumber,
+}
+
+/// The accounting summary of a settled round.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct RoundSummary<Balance> {
+	/// The round the summary belongs to.
+	pub round: u32,
+	/// The distributable pot when the round settled: the pot balance minus the spends still
+	/// committed from earlier rounds.
+	pub budget: Balance,
+	/// The total amount awarded as spends.
+	pub distributed: Balance,
+	/// The part of the budget left in the pot for the next round.
+	pub carried_forward: Balance,
+	/// The number of projects awarded a spend.
+	pub projects_funded: u32,
+	/// The number of whitelisted projects that ended up without a spend.
+	pub projects_rejected: u32,
+	/// The number of distinct accounts that had a standing vote in the round.
+	pub voters: u32,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The origin which may whitelist projects and administer claim windows.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The currency rewards are paid in; voting places holds on it.
+		type NativeBalance: Inspect<Self::AccountId>
+			+ Mutate<Self::AccountId>
+			+ MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+
+		/// The pallet id the reward pot account is derived from.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The number of blocks a round accepts project registrations before voting opens.
+		#[pallet::constant]
+		type RegistrationPeriod: Get<BlockNumberFor<Self>>;
+
+		/// The number of blocks a round accepts votes once voting has opened.
+		#[pallet::constant]
+		type VotingPeriod: Get<BlockNumberFor<Self>>;
+
+		/// The maximum number of projects tallied per block once voting has closed.
+		#[pallet::constant]
+		type MaxTallyStepsPerBlock: Get<u32>;
+
+		/// The number of blocks a funded project has to claim its reward.
+		#[pallet::constant]
+		type ClaimingPeriod: Get<BlockNumberFor<Self>>;
+
+		/// The number of blocks a project's reward vests over, linearly from the block its
+		/// round settled; the vested portion can be claimed repeatedly until the reward is
+		/// fully paid. Zero disables vesting and pays rewards out in one lump sum.
+		#[pallet::constant]
+		type RewardVestingPeriod: Get<BlockNumberFor<Self>>;
+
+		/// The maximum number of whitelisted projects per voting round.
+		#[pallet::constant]
+		type MaxProjects: Get<u32>;
+
+		/// The maximum total extension of a spend's claim window.
+		#[pallet::constant]
+		type MaxClaimExtension: Get<BlockNumberFor<Self>>;
+
+		/// The number of settled rounds whose [`RoundSummary`] is retained; older summaries
+		/// are pruned as new rounds settle.
+		#[pallet::constant]
+		type MaxRetainedRounds: Get<u32>;
+
+		/// The maximum number of projects whose spend expired unclaimed that are relisted
+		/// into the next round; expired projects beyond this budget are fully discarded, so
+		/// relisting can never crowd a round past [`Config::MaxProjects`]. Zero disables
+		/// relisting and discards every expired spend outright.
+		#[pallet::constant]
+		type MaxRelistsPerRound: Get<u32>;
+
+		/// The number of blocks a payout account change waits before taking effect while the
+		/// project has a pending spend, so a compromised project key cannot redirect a
+		/// claimable reward on the spot. Zero applies every change immediately.
+		#[pallet::constant]
+		type PayoutChangeDelay: Get<BlockNumberFor<Self>>;
+
+		/// Weight information for the extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// A reason for this pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The funds are held for a vote until the voting round ends.
+		#[codec(index = 0)]
+		FundsReserved,
+	}
+
+	/// The number of voting rounds started so far. The current round, if any, is this minus
+	/// one.
+	#[pallet::storage]
+	pub type VotingRoundNumber<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The voting rounds, by round number.
+	#[pallet::storage]
+	pub type VotingRounds<T: Config> =
+		StorageMap<_, Twox64Concat, u32, VotingRoundInfo<BlockNumberFor<T>>>;
+
+	/// The projects whitelisted for the current round.
+	#[pallet::storage]
+	pub type WhitelistedProjects<T: Config> =
+		StorageValue<_, BoundedVec<ProjectId<T>, T::MaxProjects>, ValueQuery>;
+
+	/// The vote tallies of the current round's projects.
+	#[pallet::storage]
+	pub type ProjectFunds<T: Config> =
+		StorageMap<_, Blake2_128Concat, ProjectId<T>, ProjectFundsInfo<BalanceOf<T>>, ValueQuery>;
+
+	/// The standing votes of the current round: project, voter.
+	#[pallet::storage]
+	pub type Votes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ProjectId<T>,
+		Blake2_128Concat,
+		T::AccountId,
+		VoteInfo<BalanceOf<T>, BlockNumberFor<T>>,
+	>;
+
+	/// The rewards awaiting their claim.
+	#[pallet::storage]
+	pub type Spends<T: Config> =
+		StorageMap<_, Blake2_128Concat, ProjectId<T>, SpendInfo<BalanceOf<T>, BlockNumberFor<T>>>;
+
+	/// The operational accounts rewards are paid to, for projects that redirected their
+	/// payouts away from the project account itself.
+	#[pallet::storage]
+	pub type PayoutAccounts<T: Config> =
+		StorageMap<_, Blake2_128Concat, ProjectId<T>, T::AccountId, OptionQuery>;
+
+	/// Payout account changes scheduled while the project had a pending spend, with the
+	/// block they take effect at.
+	#[pallet::storage]
+	pub type PendingPayoutChanges<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		ProjectId<T>,
+		(T::AccountId, BlockNumberFor<T>),
+		OptionQuery,
+	>;
+
+	/// The whitelist index the running tally continues from.
+	#[pallet::storage]
+	pub type TallyCursor<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The net support of the projects the running tally has found funded so far.
+	#[pallet::storage]
+	pub type FundedProjects<T: Config> = StorageValue<
+		_,
+		BoundedVec<(ProjectId<T>, BalanceOf<T>), T::MaxProjects>,
+		ValueQuery,
+	>;
+
+	/// The number of distinct voters the running tally has counted so far.
+	#[pallet::storage]
+	pub type TallyVoterCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The voters the running tally has already counted, so an account voting on several
+	/// projects only counts once towards the round's voter total.
+	#[pallet::storage]
+	pub type CountedVoters<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The accounting summaries of settled rounds, by round number. The last
+	/// [`Config::MaxRetainedRounds`] rounds are retained.
+	#[pallet::storage]
+	pub type RoundSummaries<T: Config> =
+		StorageMap<_, Twox64Concat, u32, RoundSummary<BalanceOf<T>>>;
+
+	/// The vote weight decay floor applied to rounds started from here on; `None` keeps
+	/// every vote at full weight. The running round keeps the floor it was scheduled
+	/// with.
+	#[pallet::storage]
+	pub type NextRoundDecayFloor<T: Config> = StorageValue<_, Permill, OptionQuery>;
+
+	/// Projects whose spend expired unclaimed, queued to be relisted into the next round.
+	/// Holds at most [`Config::MaxRelistsPerRound`] entries between rounds.
+	#[pallet::storage]
+	pub type RelistQueue<T: Config> =
+		StorageValue<_, BoundedVec<ProjectId<T>, T::MaxProjects>, ValueQuery>;
+
+	/// Projects registered while the previous round was still tallying; they seed the next
+	/// round as soon as the tally finalizes.
+	#[pallet::storage]
+	pub type QueuedProjects<T: Config> =
+		StorageValue<_, BoundedVec<ProjectId<T>, T::MaxProjects>, ValueQuery>;
+
+	/// Expired claims whose unclaimed amount was moved to a community escrow instead of
+	/// being forfeited to the pot: the escrowed amount, the escrow account and the block
+	/// of the takeover. Entries stay until [`Pallet::release_escrow`] returns the funds.
+	#[pallet::storage]
+	pub type EscrowedClaims<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		ProjectId<T>,
+		(BalanceOf<T>, T::AccountId, BlockNumberFor<T>),
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A new voting round has started.
+		RoundStarted { round_number: u32, round_ending_block: BlockNumberFor<T> },
+		/// Projects have been whitelisted for the current round.
+		ProjectsRegistered { round_number: u32, projects: BoundedVec<ProjectId<T>, T::MaxProjects> },
+		/// A vote has been cast or replaced.
+		VoteCast { who: T::AccountId, project_id: ProjectId<T>, amount: BalanceOf<T>, is_fund: bool },
+		/// A vote has been removed and its hold released.
+		VoteRemoved { who: T::AccountId, project_id: ProjectId<T> },
+		/// A project has been awarded a reward, claimable until `expire`.
+		SpendCreated { project_id: ProjectId<T>, amount: BalanceOf<T>, expire: BlockNumberFor<T> },
+		/// A vested part of a project's reward has been paid out.
+		RewardClaimed { project_id: ProjectId<T>, amount: BalanceOf<T> },
+		/// A reward has expired; its unclaimed amount stays in the pot.
+		SpendDiscarded { project_id: ProjectId<T>, amount: BalanceOf<T> },
+		/// A spend's claim window has been extended.
+		ClaimWindowExtended { project_id: ProjectId<T>, new_expire: BlockNumberFor<T> },
+		/// A round has fully settled; the next round may start.
+		RoundFinalized { round_number: u32 },
+		/// The accounting summary of a settled round.
+		RoundSummary {
+			round: u32,
+			budget: BalanceOf<T>,
+			distributed: BalanceOf<T>,
+			carried_forward: BalanceOf<T>,
+			projects_funded: u32,
+			projects_rejected: u32,
+			voters: u32,
+		},
+		/// The next round cannot start until the named round's tally finalizes; the
+		/// registered projects are queued for it.
+		RoundStartDelayed { waiting_for_round: u32 },
+		/// The vote weight decay floor for subsequently started rounds has been updated.
+		VoteDecayUpdated { decay_floor: Option<Permill> },
+		/// An expired project has been queued for relisting into the next round.
+		ProjectRelisted { project_id: ProjectId<T> },
+		/// An expired project exceeded the relisting budget and has been fully discarded.
+		ProjectDiscarded { project_id: ProjectId<T> },
+		/// A project's rewards are now paid to a designated payout account.
+		PayoutAccountSet { project_id: ProjectId<T>, payout: T::AccountId },
+		/// A payout account change has been scheduled and takes effect at `effective_at`.
+		PayoutAccountChangeScheduled {
+			project_id: ProjectId<T>,
+			payout: T::AccountId,
+			effective_at: BlockNumberFor<T>,
+		},
+		/// An expired claim's unclaimed amount has been moved to a community escrow,
+		/// preserving the allocation for the project under new stewardship.
+		ClaimEscrowed {
+			project_id: ProjectId<T>,
+			amount: BalanceOf<T>,
+			escrow: T::AccountId,
+			at: BlockNumberFor<T>,
+		},
+		/// An escrowed claim has been returned to the pot.
+		EscrowReleased { project_id: ProjectId<T>, amount: BalanceOf<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// No voting round is currently accepting votes.
+		NoActiveRound,
+		/// The project is already whitelisted for the current round.
+		ProjectAlreadyWhitelisted,
+		/// The round already holds the maximum number of projects.
+		MaximumProjectsReached,
+		/// The project is not whitelisted for the current round.
+		ProjectNotWhitelisted,
+		/// The vote amount must not be zero.
+		InvalidAmount,
+		/// The caller has no standing vote on this project.
+		NoVoteForProject,
+		/// The project has no reward awaiting its claim.
+		NoPendingSpend,
+		/// The claim window of the spend has expired.
+		ExpiredClaim,
+		/// The extension would exceed the maximum total claim-window extension.
+		ExtensionTooLarge,
+		/// The round is still in its registration phase; voting has not opened yet.
+		VotingNotStarted,
+		/// The round's registration phase is over; new projects must wait for the next
+		/// round.
+		RegistrationClosed,
+		/// No further part of the reward has vested since the last claim.
+		NothingVested,
+		/// The claim window of the spend has not yet expired.
+		ClaimNotExpired,
+		/// The project has no escrowed claim.
+		NoEscrowedClaim,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: BlockNumberFor<T>, _remaining_weight: Weight) -> Weight {
+			let mut writes = Self::advance_round(now);
+			writes = writes.saturating_add(Self::discard_expired_spends(now));
+			T::DbWeight::get().reads_writes(writes.saturating_add(2), writes)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Whitelist `projects` for the current voting round, starting a new round first if
+		/// none is running.
+		///
+		/// Only allowed while the round is in its registration phase. If the previous
+		/// round's tally has not finalized yet, the new round cannot start; the batch is
+		/// queued to seed it and [`Event::RoundStartDelayed`] is emitted.
+		///
+		/// Requires [`Config::AdminOrigin`].
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::register_projects_batch())]
+		pub fn register_projects_batch(
+			origin: OriginFor<T>,
+			projects: BoundedVec<ProjectId<T>, T::MaxProjects>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let round_number = match Self::current_round() {
+				None => Self::start_new_round(now),
+				Some(round) => match Self::round_phase(&round, now) {
+					RoundPhase::Registration => round.round_number,
+					RoundPhase::Voting => return Err(Error::<T>::RegistrationClosed.into()),
+					RoundPhase::Tallying => {
+						QueuedProjects::<T>::try_mutate(|queued| {
+							Self::append_projects(queued, &projects)
+						})?;
+						Self::deposit_event(Event::<T>::RoundStartDelayed {
+							waiting_for_round: round.round_number,
+						});
+						return Ok(())
+					},
+					RoundPhase::Finalized => Self::start_new_round(now),
+				},
+			};
+
+			WhitelistedProjects::<T>::try_mutate(|whitelisted| {
+				Self::append_projects(whitelisted, &projects)
+			})?;
+
+			Self::deposit_event(Event::<T>::ProjectsRegistered { round_number, projects });
+			Ok(())
+		}
+
+		/// Vote `amount` for (`is_fund`) or against a whitelisted project. The amount is held
+		/// until the round ends; a standing vote on the same project is replaced. Replacing
+		/// a vote re-stamps its cast block, so updating a vote late in a decaying round
+		/// deliberately lowers its tally weight.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::vote())]
+		pub fn vote(
+			origin: OriginFor<T>,
+			project_id: ProjectId<T>,
+			amount: BalanceOf<T>,
+			is_fund: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+			let now = frame_system::Pallet::<T>::block_number();
+			Self::ensure_voting_phase()?;
+			ensure!(
+				WhitelistedProjects::<T>::get().contains(&project_id),
+				Error::<T>::ProjectNotWhitelisted,
+			);
+
+			Votes::<T>::try_mutate(&project_id, &who, |maybe_vote| -> DispatchResult {
+				if let Some(old) = maybe_vote.take() {
+					Self::retract_vote(&project_id, &who, &old)?;
+				}
+				T::NativeBalance::hold(&HoldReason::FundsReserved.into(), &who, amount)?;
+				ProjectFunds::<T>::mutate(&project_id, |funds| {
+					if is_fund {
+						funds.positive.saturating_accrue(amount);
+					} else {
+						funds.negative.saturating_accrue(amount);
+					}
+				});
+				*maybe_vote = Some(VoteInfo { amount, is_fund, cast_at: now });
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::VoteCast { who, project_id, amount, is_fund });
+			Ok(())
+		}
+
+		/// Remove the caller's standing vote on `project_id`, releasing the held amount.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::remove_vote())]
+		pub fn remove_vote(origin: OriginFor<T>, project_id: ProjectId<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_voting_phase()?;
+
+			let vote =
+				Votes::<T>::take(&project_id, &who).ok_or(Error::<T>::NoVoteForProject)?;
+			Self::retract_vote(&project_id, &who, &vote)?;
+
+			Self::deposit_event(Event::<T>::VoteRemoved { who, project_id });
+			Ok(())
+		}
+
+		/// Pay out the vested part of `project_id`'s pending reward from the pot. Callable
+		/// by anyone while the claim window is open, repeatedly as more of the reward vests
+		/// over [`Config::RewardVestingPeriod`]; the spend is removed once fully paid.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::claim_reward_for())]
+		pub fn claim_reward_for(origin: OriginFor<T>, project_id: ProjectId<T>) -> DispatchResult {
+			ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut spend = Spends::<T>::get(&project_id).ok_or(Error::<T>::NoPendingSpend)?;
+			ensure!(now <= spend.expire, Error::<T>::ExpiredClaim);
+
+			let payable = Self::vested_amount(&spend, now).saturating_sub(spend.claimed);
+			ensure!(!payable.is_zero(), Error::<T>::NothingVested);
+
+			let payout = Self::payout_account(&project_id, now);
+			T::NativeBalance::transfer(
+				&Self::pot_account(),
+				&payout,
+				payable,
+				Preservation::Expendable,
+			)?;
+			spend.claimed.saturating_accrue(payable);
+			if spend.claimed >= spend.amount {
+				Spends::<T>::remove(&project_id);
+			} else {
+				Spends::<T>::insert(&project_id, spend);
+			}
+
+			Self::deposit_event(Event::<T>::RewardClaimed { project_id, amount: payable });
+			Ok(())
+		}
+
+		/// Push the claim expiry of `project_id`'s pending spend `extra_blocks` later, e.g.
+		/// after an outage. The total extension of a spend is bounded by
+		/// [`Config::MaxClaimExtension`].
+		///
+		/// Requires [`Config::AdminOrigin`].
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::extend_claim_window())]
+		pub fn extend_claim_window(
+			origin: OriginFor<T>,
+			project_id: ProjectId<T>,
+			extra_blocks: BlockNumberFor<T>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let new_expire =
+				Spends::<T>::try_mutate(&project_id, |maybe_spend| -> Result<_, DispatchError> {
+					let spend = maybe_spend.as_mut().ok_or(Error::<T>::NoPendingSpend)?;
+					let total_extension = spend.claim_extension.saturating_add(extra_blocks);
+					ensure!(
+						total_extension <= T::MaxClaimExtension::get(),
+						Error::<T>::ExtensionTooLarge,
+					);
+					spend.claim_extension = total_extension;
+					spend.expire.saturating_accrue(extra_blocks);
+					Ok(spend.expire)
+				})?;
+
+			Self::deposit_event(Event::<T>::ClaimWindowExtended { project_id, new_expire });
+			Ok(())
+		}
+
+		/// Set the vote weight decay floor applied to rounds started from here on; `None`
+		/// disables decay. The running round keeps the floor it was scheduled with.
+		///
+		/// Requires [`Config::AdminOrigin`].
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::set_vote_decay())]
+		pub fn set_vote_decay(
+			origin: OriginFor<T>,
+			decay_floor: Option<Permill>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			NextRoundDecayFloor::<T>::set(decay_floor);
+
+			Self::deposit_event(Event::<T>::VoteDecayUpdated { decay_floor });
+			Ok(())
+		}
+
+		/// Pay the caller's project rewards to `payout` instead of the project account
+		/// itself, e.g. to an operational treasury while the project is identified by a
+		/// well-known multisig.
+		///
+		/// Callable only by the project account. While the project has a pending spend the
+		/// change only takes effect [`Config::PayoutChangeDelay`] blocks later, so a
+		/// compromised project key cannot redirect a claimable reward on the spot.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::set_payout_account())]
+		pub fn set_payout_account(origin: OriginFor<T>, payout: T::AccountId) -> DispatchResult {
+			let project_id = ensure_signed(origin)?;
+
+			let delay = T::PayoutChangeDelay::get();
+			if Spends::<T>::contains_key(&project_id) && !delay.is_zero() {
+				let effective_at =
+					frame_system::Pallet::<T>::block_number().saturating_add(delay);
+				PendingPayoutChanges::<T>::insert(&project_id, (payout.clone(), effective_at));
+				Self::deposit_event(Event::<T>::PayoutAccountChangeScheduled {
+					project_id,
+					payout,
+					effective_at,
+				});
+			} else {
+				// An immediate change supersedes whatever may still be scheduled.
+				PendingPayoutChanges::<T>::remove(&project_id);
+				PayoutAccounts::<T>::insert(&project_id, payout.clone());
+				Self::deposit_event(Event::<T>::PayoutAccountSet { project_id, payout });
+			}
+			Ok(())
+		}
+
+		/// Move the unclaimed amount of `project_id`'s expired spend to `escrow_account`
+		/// instead of forfeiting it to the pot, preserving the allocation for the project
+		/// under new stewardship. Only possible in the window between the claim expiry and
+		/// the lazy purge of the spend.
+		///
+		/// Requires [`Config::AdminOrigin`].
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::escrow_unclaimed())]
+		pub fn escrow_unclaimed(
+			origin: OriginFor<T>,
+			project_id: ProjectId<T>,
+			escrow_account: T::AccountId,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let spend = Spends::<T>::get(&project_id).ok_or(Error::<T>::NoPendingSpend)?;
+			ensure!(spend.expire < now, Error::<T>::ClaimNotExpired);
+
+			let amount = spend.amount.saturating_sub(spend.claimed);
+			T::NativeBalance::transfer(
+				&Self::pot_account(),
+				&escrow_account,
+				amount,
+				Preservation::Expendable,
+			)?;
+			Spends::<T>::remove(&project_id);
+			EscrowedClaims::<T>::insert(&project_id, (amount, escrow_account.clone(), now));
+
+			Self::deposit_event(Event::<T>::ClaimEscrowed {
+				project_id,
+				amount,
+				escrow: escrow_account,
+				at: now,
+			});
+			Ok(())
+		}
+
+		/// Return the escrowed claim of `project_id` to the pot, if new stewardship never
+		/// materialized.
+		///
+		/// Requires [`Config::AdminOrigin`].
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::release_escrow())]
+		pub fn release_escrow(
+			origin: OriginFor<T>,
+			project_id: ProjectId<T>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let (amount, escrow, _) =
+				EscrowedClaims::<T>::get(&project_id).ok_or(Error::<T>::NoEscrowedClaim)?;
+
+			T::NativeBalance::transfer(
+				&escrow,
+				&Self::pot_account(),
+				amount,
+				Preservation::Expendable,
+			)?;
+			EscrowedClaims::<T>::remove(&project_id);
+
+			Self::deposit_event(Event::<T>::EscrowReleased { project_id, amount });
+			Ok(())
+		}
+	}
+
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T> {
+		/// The accounting summary of round `index`, if the round has settled and its summary
+		/// is still retained.
+		pub fn round_summary(index: u32) -> Option<RoundSummary<BalanceOf<T>>> {
+			RoundSummaries::<T>::get(index)
+		}
+
+		/// The allocations the current round would produce were it tallied on the spot:
+		/// each whitelisted project whose support outweighs its opposition, with the share
+		/// of the distributable pot it would receive. Applies the round's decay model to
+		/// the standing votes, exactly like the eventual tally.
+		pub fn projected_allocations() -> Vec<(ProjectId<T>, BalanceOf<T>)> {
+			let Some(round) = Self::current_round() else { return Vec::new() };
+			let mut nets = Vec::new();
+			let mut total_net = BalanceOf::<T>::zero();
+			for project_id in WhitelistedProjects::<T>::get() {
+				let mut funds = ProjectFundsInfo::<BalanceOf<T>>::default();
+				for vote in Votes::<T>::iter_prefix_values(&project_id) {
+					let weighted = Self::weighted_vote_amount(&round, &vote);
+					if vote.is_fund {
+						funds.positive.saturating_accrue(weighted);
+					} else {
+						funds.negative.saturating_accrue(weighted);
+					}
+				}
+				if funds.positive > funds.negative {
+					let net = funds.positive.saturating_sub(funds.negative);
+					total_net.saturating_accrue(net);
+					nets.push((project_id, net));
+				}
+			}
+			let distributable = Self::distributable_pot();
+			nets.into_iter()
+				.map(|(project_id, net)| {
+					(project_id, Perbill::from_rational(net, total_net).mul_floor(distributable))
+				})
+				.collect()
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account holding the funding pot.
+	pub fn pot_account() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
+	/// The latest voting round, if any round has ever started.
+	pub fn current_round() -> Option<VotingRoundInfo<BlockNumberFor<T>>> {
+		VotingRounds::<T>::get(VotingRoundNumber::<T>::get().checked_sub(1)?)
+	}
+
+	/// The effective phase of `round` at `now`: the stored phase plus the block-driven
+	/// transitions that may not be written back yet. `Tallying` only ends through tally
+	/// completion, never by block threshold.
+	pub fn round_phase(
+		round: &VotingRoundInfo<BlockNumberFor<T>>,
+		now: BlockNumberFor<T>,
+	) -> RoundPhase {
+		match round.phase {
+			RoundPhase::Registration | RoundPhase::Voting if now >= round.round_ending_block =>
+				RoundPhase::Tallying,
+			RoundPhase::Registration if now >= round.voting_starting_block => RoundPhase::Voting,
+			phase => phase,
+		}
+	}
+
+	/// Start a new voting round at `now` and return its number.
+	fn start_new_round(now: BlockNumberFor<T>) -> u32 {
+		let round_number = VotingRoundNumber::<T>::mutate(|n| {
+			let number = *n;
+			*n += 1;
+			number
+		});
+		let voting_starting_block = now.saturating_add(T::RegistrationPeriod::get());
+		let round_ending_block = voting_starting_block.saturating_add(T::VotingPeriod::get());
+		VotingRounds::<T>::insert(
+			round_number,
+			VotingRoundInfo {
+				round_number,
+				round_starting_block: now,
+				voting_starting_block,
+				round_ending_block,
+				decay_floor: NextRoundDecayFloor::<T>::get(),
+				phase: RoundPhase::Registration,
+			},
+		);
+		// Projects relisted after their spend expired seed the new round's whitelist.
+		let relisted = RelistQueue::<T>::take();
+		if !relisted.is_empty() {
+			WhitelistedProjects::<T>::put(&relisted);
+			Self::deposit_event(Event::<T>::ProjectsRegistered {
+				round_number,
+				projects: relisted,
+			});
+		}
+		Self::deposit_event(Event::<T>::RoundStarted { round_number, round_ending_block });
+		round_number
+	}
+
+	/// Append `projects` to `list`, rejecting duplicates and overflow.
+	fn append_projects(
+		list: &mut BoundedVec<ProjectId<T>, T::MaxProjects>,
+		projects: &BoundedVec<ProjectId<T>, T::MaxProjects>,
+	) -> DispatchResult {
+		for project_id in projects {
+			ensure!(!list.contains(project_id), Error::<T>::ProjectAlreadyWhitelisted);
+			list.try_push(project_id.clone()).map_err(|_| Error::<T>::MaximumProjectsReached)?;
+		}
+		Ok(())
+	}
+
+	/// Ensure a round is running and in its voting phase.
+	fn ensure_voting_phase() -> DispatchResult {
+		let round = Self::current_round().ok_or(Error::<T>::NoActiveRound)?;
+		match Self::round_phase(&round, frame_system::Pallet::<T>::block_number()) {
+			RoundPhase::Voting => Ok(()),
+			RoundPhase::Registration => Err(Error::<T>::VotingNotStarted.into()),
+			RoundPhase::Tallying | RoundPhase::Finalized =>
+				Err(Error::<T>::NoActiveRound.into()),
+		}
+	}
+
+	/// The tally weight of `vote` in `round`: its amount scaled by the round's decay
+	/// model, or the raw amount when the round has none.
+	fn weighted_vote_amount(
+		round: &VotingRoundInfo<BlockNumberFor<T>>,
+		vote: &VoteInfo<BalanceOf<T>, BlockNumberFor<T>>,
+	) -> BalanceOf<T> {
+		match round.decay_floor {
+			Some(floor) => decay_factor(
+				floor,
+				round.voting_starting_block,
+				round.round_ending_block,
+				vote.cast_at,
+			)
+			.mul_floor(vote.amount),
+			None => vote.amount,
+		}
+	}
+
+	/// Undo `vote` of `who` on `project_id`: release the hold and roll back the tallies.
+	fn retract_vote(
+		project_id: &ProjectId<T>,
+		who: &T::AccountId,
+		vote: &VoteInfo<BalanceOf<T>, BlockNumberFor<T>>,
+	) -> DispatchResult {
+		T::NativeBalance::release(
+			&HoldReason::FundsReserved.into(),
+			who,
+			vote.amount,
+			Precision::Exact,
+		)?;
+		ProjectFunds::<T>::mutate(project_id, |funds| {
+			if vote.is_fund {
+				funds.positive.saturating_reduce(vote.amount);
+			} else {
+				funds.negative.saturating_reduce(vote.amount);
+			}
+		});
+		Ok(())
+	}
+
+	/// Drive the current round's lifecycle at `now`: write back block-driven phase
+	/// transitions and, while tallying, process up to [`Config::MaxTallyStepsPerBlock`]
+	/// projects before finalizing the round. Returns the number of storage writes for
+	/// weight accounting.
+	fn advance_round(now: BlockNumberFor<T>) -> u64 {
+		let Some(mut round) = Self::current_round() else { return 0 };
+		let effective = Self::round_phase(&round, now);
+		let mut writes: u64 = 0;
+		if effective != round.phase {
+			round.phase = effective;
+			VotingRounds::<T>::insert(round.round_number, round.clone());
+			writes.saturating_inc();
+		}
+		if effective == RoundPhase::Tallying {
+			writes = writes.saturating_add(Self::tally_step(round, now));
+		}
+		writes
+	}
+
+	/// Run up to [`Config::MaxTallyStepsPerBlock`] tally steps of `round`, finalizing it
+	/// once every whitelisted project has been processed. Each step releases one project's
+	/// held votes and records its net support.
+	fn tally_step(round: VotingRoundInfo<BlockNumberFor<T>>, now: BlockNumberFor<T>) -> u64 {
+		let whitelisted = WhitelistedProjects::<T>::get();
+		let mut cursor = TallyCursor::<T>::get() as usize;
+		let mut writes: u64 = 0;
+		for _ in 0..T::MaxTallyStepsPerBlock::get() {
+			let Some(project_id) = whitelisted.get(cursor) else { break };
+			// `ProjectFunds` tracks raw amounts for inspection during the round; the
+			// decisive sums are recomputed from the individual votes here, so the round's
+			// decay model, if any, can weight each vote by its cast block.
+			ProjectFunds::<T>::remove(project_id);
+			let mut funds = ProjectFundsInfo::<BalanceOf<T>>::default();
+			for (who, vote) in Votes::<T>::drain_prefix(project_id) {
+				let _ = T::NativeBalance::release(
+					&HoldReason::FundsReserved.into(),
+					&who,
+					vote.amount,
+					Precision::BestEffort,
+				);
+				let weighted = Self::weighted_vote_amount(&round, &vote);
+				if vote.is_fund {
+					funds.positive.saturating_accrue(weighted);
+				} else {
+					funds.negative.saturating_accrue(weighted);
+				}
+				// Count each account once for the round's summary, however many projects
+				// it voted on.
+				if !CountedVoters::<T>::contains_key(&who) {
+					CountedVoters::<T>::insert(&who, ());
+					TallyVoterCount::<T>::mutate(|count| count.saturating_inc());
+					writes.saturating_inc();
+				}
+				writes.saturating_inc();
+			}
+			if funds.positive > funds.negative {
+				let net = funds.positive.saturating_sub(funds.negative);
+				// Cannot overflow: at most one entry per whitelisted project.
+				let _ = FundedProjects::<T>::mutate(|funded| {
+					funded.try_push((project_id.clone(), net))
+				});
+			}
+			cursor += 1;
+			writes.saturating_inc();
+		}
+
+		if cursor >= whitelisted.len() {
+			writes.saturating_add(Self::finalize_round(round, now))
+		} else {
+			TallyCursor::<T>::put(cursor as u32);
+			writes.saturating_add(1)
+		}
+	}
+
+	/// Settle the tallied `round`: award each funded project a share of the pot
+	/// proportional to its net support, record the round's [`RoundSummary`], clear the
+	/// round's working state and start the queued round, if any. Returns the number of
+	/// storage writes for weight accounting.
+	fn finalize_round(mut round: VotingRoundInfo<BlockNumberFor<T>>, now: BlockNumberFor<T>) -> u64 {
+		let whitelisted_count = WhitelistedProjects::<T>::decode_len().unwrap_or_default() as u32;
+		let funded = FundedProjects::<T>::take();
+		let total_net = funded
+			.iter()
+			.fold(BalanceOf::<T>::zero(), |acc, (_, net)| acc.saturating_add(*net));
+
+		let distributable = Self::distributable_pot();
+
+		let mut writes: u64 = 4;
+		let expire = now.saturating_add(T::ClaimingPeriod::get());
+		let mut distributed = BalanceOf::<T>::zero();
+		let mut projects_funded: u32 = 0;
+		for (project_id, net) in funded {
+			let amount = Perbill::from_rational(net, total_net).mul_floor(distributable);
+			if amount.is_zero() {
+				continue
+			}
+			Spends::<T>::insert(
+				&project_id,
+				SpendInfo {
+					amount,
+					claimed: Zero::zero(),
+					vesting_start: now,
+					expire,
+					claim_extension: Zero::zero(),
+				},
+			);
+			Self::deposit_event(Event::<T>::SpendCreated { project_id, amount, expire });
+			distributed.saturating_accrue(amount);
+			projects_funded.saturating_inc();
+			writes.saturating_inc();
+		}
+
+		let summary = RoundSummary {
+			round: round.round_number,
+			budget: distributable,
+			distributed,
+			carried_forward: distributable.saturating_sub(distributed),
+			projects_funded,
+			projects_rejected: whitelisted_count.saturating_sub(projects_funded),
+			voters: TallyVoterCount::<T>::take(),
+		};
+		Self::deposit_event(Event::<T>::RoundSummary {
+			round: summary.round,
+			budget: summary.budget,
+			distributed: summary.distributed,
+			carried_forward: summary.carried_forward,
+			projects_funded: summary.projects_funded,
+			projects_rejected: summary.projects_rejected,
+			voters: summary.voters,
+		});
+		RoundSummaries::<T>::insert(round.round_number, summary);
+		if let Some(evicted) = round.round_number.checked_sub(T::MaxRetainedRounds::get()) {
+			RoundSummaries::<T>::remove(evicted);
+		}
+		let _ = CountedVoters::<T>::clear(u32::MAX, None);
+		writes.saturating_accrue(3);
+
+		WhitelistedProjects::<T>::kill();
+		TallyCursor::<T>::kill();
+		round.phase = RoundPhase::Finalized;
+		let round_number = round.round_number;
+		VotingRounds::<T>::insert(round_number, round);
+		Self::deposit_event(Event::<T>::RoundFinalized { round_number });
+
+		// A batch registered during the tally seeds the next round right away.
+		let queued = QueuedProjects::<T>::take();
+		if !queued.is_empty() {
+			let round_number = Self::start_new_round(now);
+			// The admin's batch is authoritative: if it cannot coexist with the relisted
+			// seeds (a duplicate or overflowing the round), the seeds are dropped in its
+			// favour.
+			let appended = WhitelistedProjects::<T>::try_mutate(|whitelisted| {
+				Self::append_projects(whitelisted, &queued)
+			});
+			if appended.is_err() {
+				WhitelistedProjects::<T>::put(&queued);
+			}
+			Self::deposit_event(Event::<T>::ProjectsRegistered {
+				round_number,
+				projects: queued,
+			});
+			writes.saturating_accrue(2);
+		}
+		writes
+	}
+
+	/// The part of the pot balance not already committed to the unpaid remainder of
+	/// pending spends; only this is up for distribution.
+	fn distributable_pot() -> BalanceOf<T> {
+		let committed = Spends::<T>::iter_values().fold(BalanceOf::<T>::zero(), |acc, spend| {
+			acc.saturating_add(spend.amount.saturating_sub(spend.claimed))
+		});
+		T::NativeBalance::reducible_balance(
+			&Self::pot_account(),
+			Preservation::Expendable,
+			Fortitude::Polite,
+		)
+		.saturating_sub(committed)
+	}
+
+	/// The part of `spend`'s reward vested at `now`: the full amount once
+	/// [`Config::RewardVestingPeriod`] blocks have passed since the round settled, or when
+	/// vesting is disabled; a linear fraction of it before that.
+	fn vested_amount(
+		spend: &SpendInfo<BalanceOf<T>, BlockNumberFor<T>>,
+		now: BlockNumberFor<T>,
+	) -> BalanceOf<T> {
+		let period = T::RewardVestingPeriod::get();
+		if period.is_zero() || now >= spend.vesting_start.saturating_add(period) {
+			return spend.amount
+		}
+		Permill::from_rational::<u128>(
+			now.saturating_sub(spend.vesting_start).unique_saturated_into(),
+			period.unique_saturated_into(),
+		)
+		.mul_floor(spend.amount)
+	}
+
+	/// The account `project_id`'s rewards are paid to: the designated payout account if one
+	/// is set, the project account itself otherwise. A scheduled change whose delay has
+	/// passed by `now` is promoted first.
+	fn payout_account(project_id: &ProjectId<T>, now: BlockNumberFor<T>) -> T::AccountId {
+		if let Some((payout, effective_at)) = PendingPayoutChanges::<T>::get(project_id) {
+			if now >= effective_at {
+				PendingPayoutChanges::<T>::remove(project_id);
+				PayoutAccounts::<T>::insert(project_id, payout.clone());
+				Self::deposit_event(Event::<T>::PayoutAccountSet {
+					project_id: project_id.clone(),
+					payout,
+				});
+			}
+		}
+		PayoutAccounts::<T>::get(project_id).unwrap_or_else(|| project_id.clone())
+	}
+
+	/// Lazily purge spends whose claim window has passed; their unclaimed amount simply
+	/// stays in the pot. With a non-zero [`Config::MaxRelistsPerRound`], up to that many
+	/// expired projects are queued for relisting into the next round; the rest are fully
+	/// discarded. Returns the number of storage writes for weight accounting.
+	fn discard_expired_spends(now: BlockNumberFor<T>) -> u64 {
+		let expired: Vec<_> = Spends::<T>::iter()
+			// Escrowed claims were already moved out of the pot; never forfeit them again.
+			.filter(|(project_id, spend)| {
+				spend.expire < now && !EscrowedClaims::<T>::contains_key(project_id)
+			})
+			.map(|(project_id, spend)| (project_id, spend.amount.saturating_sub(spend.claimed)))
+			.collect();
+		let mut writes = expired.len() as u64;
+		let budget = T::MaxRelistsPerRound::get() as usize;
+		for (project_id, amount) in expired {
+			Spends::<T>::remove(&project_id);
+			Self::deposit_event(Event::<T>::SpendDiscarded {
+				project_id: project_id.clone(),
+				amount,
+			});
+			if budget == 0 {
+				continue
+			}
+			let queued = RelistQueue::<T>::mutate(|queue| {
+				queue.len() < budget && queue.try_push(project_id.clone()).is_ok()
+			});
+			if queued {
+				writes.saturating_inc();
+				Self::deposit_event(Event::<T>::ProjectRelisted { project_id });
+			} else {
+				Self::deposit_event(Event::<T>::ProjectDiscarded { project_id });
+			}
+		}
+		writes
+	}
+}
@@ -0,0 +1,125 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocks for the optimistic project funding pallet.
+
+use super::*;
+use crate as pallet_opf;
+
+use frame_support::{derive_impl, parameter_types, traits::ConstU32};
+use frame_system::EnsureRoot;
+use sp_runtime::BuildStorage;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const PROJECT_A: AccountId = 10;
+pub const PROJECT_B: AccountId = 11;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Opf: pallet_opf,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+	type RuntimeHoldReason = RuntimeHoldReason;
+}
+
+parameter_types! {
+	pub const OpfPalletId: PalletId = PalletId(*b"py/opfpt");
+	pub static RegistrationPeriod: u64 = 2;
+	pub static VotingPeriod: u64 = 10;
+	pub static ClaimingPeriod: u64 = 20;
+	pub static RewardVestingPeriod: u64 = 0;
+	pub static MaxClaimExtension: u64 = 30;
+	pub static MaxTallyStepsPerBlock: u32 = 10;
+	pub static MaxRetainedRounds: u32 = 8;
+	pub static PayoutChangeDelay: u64 = 0;
+	pub static MaxRelistsPerRound: u32 = 0;
+}
+
+impl pallet_opf::Config for Test {
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type NativeBalance = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type PalletId = OpfPalletId;
+	type RegistrationPeriod = RegistrationPeriod;
+	type VotingPeriod = VotingPeriod;
+	type MaxTallyStepsPerBlock = MaxTallyStepsPerBlock;
+	type ClaimingPeriod = ClaimingPeriod;
+	type RewardVestingPeriod = RewardVestingPeriod;
+	type MaxProjects = ConstU32<4>;
+	type MaxClaimExtension = MaxClaimExtension;
+	type MaxRetainedRounds = MaxRetainedRounds;
+	type MaxRelistsPerRound = MaxRelistsPerRound;
+	type PayoutChangeDelay = PayoutChangeDelay;
+	type WeightInfo = ();
+}
+
+/// Run to block `n`, driving this pallet's `on_idle`.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		let now = System::block_number() + 1;
+		System::set_block_number(now);
+		<Opf as frame_support::traits::OnIdle<u64>>::on_idle(now, Weight::MAX);
+	}
+}
+
+#[derive(Default)]
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		RegistrationPeriod::set(2);
+		VotingPeriod::set(10);
+		ClaimingPeriod::set(20);
+		RewardVestingPeriod::set(0);
+		MaxClaimExtension::set(30);
+		MaxTallyStepsPerBlock::set(10);
+		MaxRetainedRounds::set(8);
+		PayoutChangeDelay::set(0);
+		MaxRelistsPerRound::set(0);
+
+		let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			for who in [ALICE, BOB, CAROL] {
+				let _ = Balances::mint_into(&who, 1_000);
+			}
+			let _ = Balances::mint_into(&Opf::pot_account(), 1_000);
+			System::set_block_number(1);
+		});
+		ext
+	}
+}
@@ -0,0 +1,841 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the optimistic project funding pallet.
+
+use super::*;
+use mock::*;
+
+use frame_support::{assert_noop, assert_ok};
+
+fn register(projects: &[AccountId]) {
+	assert_ok!(Opf::register_projects_batch(
+		RuntimeOrigin::root(),
+		projects.to_vec().try_into().unwrap(),
+	));
+}
+
+#[test]
+fn register_projects_batch_starts_a_round() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Opf::register_projects_batch(
+				RuntimeOrigin::signed(ALICE),
+				vec![PROJECT_A].try_into().unwrap(),
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		// Registration at 1, voting from 3, tally from 13.
+		register(&[PROJECT_A, PROJECT_B]);
+		System::assert_has_event(
+			Event::<Test>::RoundStarted { round_number: 0, round_ending_block: 13 }.into(),
+		);
+		let round = Opf::current_round().unwrap();
+		assert_eq!(round.round_starting_block, 1);
+		assert_eq!(round.voting_starting_block, 3);
+		assert_eq!(round.round_ending_block, 13);
+		assert_eq!(round.phase, RoundPhase::Registration);
+		assert_eq!(WhitelistedProjects::<Test>::get().to_vec(), vec![PROJECT_A, PROJECT_B]);
+
+		// Registering within the registration phase appends to the running round.
+		assert_noop!(
+			Opf::register_projects_batch(
+				RuntimeOrigin::root(),
+				vec![PROJECT_A].try_into().unwrap(),
+			),
+			Error::<Test>::ProjectAlreadyWhitelisted
+		);
+		register(&[20, 21]);
+		assert_eq!(VotingRoundNumber::<Test>::get(), 1);
+		// `MaxProjects` caps the round size.
+		assert_noop!(
+			Opf::register_projects_batch(RuntimeOrigin::root(), vec![22].try_into().unwrap()),
+			Error::<Test>::MaximumProjectsReached
+		);
+
+		// Once voting has opened, the round accepts no more projects.
+		run_to_block(3);
+		assert_noop!(
+			Opf::register_projects_batch(RuntimeOrigin::root(), vec![22].try_into().unwrap()),
+			Error::<Test>::RegistrationClosed
+		);
+
+		// Once the round is finalized, the next registration starts a new round.
+		run_to_block(13);
+		assert_eq!(Opf::current_round().unwrap().phase, RoundPhase::Finalized);
+		register(&[PROJECT_A]);
+		System::assert_has_event(
+			Event::<Test>::RoundStarted { round_number: 1, round_ending_block: 25 }.into(),
+		);
+	});
+}
+
+#[test]
+fn vote_holds_funds_and_tallies() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		assert_eq!(Balances::free_balance(ALICE), 900);
+		assert_eq!(
+			ProjectFunds::<Test>::get(PROJECT_A),
+			ProjectFundsInfo { positive: 100, negative: 0 }
+		);
+		System::assert_last_event(
+			Event::<Test>::VoteCast { who: ALICE, project_id: PROJECT_A, amount: 100, is_fund: true }
+				.into(),
+		);
+
+		// A new vote on the same project replaces the old one entirely.
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 50, false));
+		assert_eq!(Balances::free_balance(ALICE), 950);
+		assert_eq!(
+			ProjectFunds::<Test>::get(PROJECT_A),
+			ProjectFundsInfo { positive: 0, negative: 50 }
+		);
+
+		assert_ok!(Opf::remove_vote(RuntimeOrigin::signed(ALICE), PROJECT_A));
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+		assert_eq!(ProjectFunds::<Test>::get(PROJECT_A), ProjectFundsInfo::default());
+		assert_noop!(
+			Opf::remove_vote(RuntimeOrigin::signed(ALICE), PROJECT_A),
+			Error::<Test>::NoVoteForProject
+		);
+	});
+}
+
+#[test]
+fn voting_is_restricted_to_the_voting_phase() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true),
+			Error::<Test>::NoActiveRound
+		);
+
+		// Voting has not opened during the registration phase.
+		register(&[PROJECT_A]);
+		assert_noop!(
+			Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true),
+			Error::<Test>::VotingNotStarted
+		);
+
+		run_to_block(3);
+		assert_noop!(
+			Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 0, true),
+			Error::<Test>::InvalidAmount
+		);
+		assert_noop!(
+			Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_B, 100, true),
+			Error::<Test>::ProjectNotWhitelisted
+		);
+
+		// Voting closes at the round's ending block, even before the tally has run.
+		System::set_block_number(13);
+		assert_noop!(
+			Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true),
+			Error::<Test>::NoActiveRound
+		);
+	});
+}
+
+#[test]
+fn round_end_distributes_the_pot_proportionally() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A, PROJECT_B]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 300, true));
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(BOB), PROJECT_B, 100, true));
+
+		run_to_block(13);
+
+		// The pot of 1000 is split 300:100 and the held votes are released.
+		assert_eq!(
+			Spends::<Test>::get(PROJECT_A),
+			Some(SpendInfo { amount: 750, claimed: 0, vesting_start: 13, expire: 33, claim_extension: 0 })
+		);
+		assert_eq!(
+			Spends::<Test>::get(PROJECT_B),
+			Some(SpendInfo { amount: 250, claimed: 0, vesting_start: 13, expire: 33, claim_extension: 0 })
+		);
+		System::assert_has_event(
+			Event::<Test>::SpendCreated { project_id: PROJECT_A, amount: 750, expire: 33 }.into(),
+		);
+		System::assert_has_event(Event::<Test>::RoundFinalized { round_number: 0 }.into());
+		assert_eq!(Opf::current_round().unwrap().phase, RoundPhase::Finalized);
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+		assert!(WhitelistedProjects::<Test>::get().is_empty());
+		assert_eq!(ProjectFunds::<Test>::get(PROJECT_A), ProjectFundsInfo::default());
+
+		// Anyone can trigger the payout while the claim window is open.
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 750);
+		assert_eq!(Balances::free_balance(Opf::pot_account()), 250);
+		assert_eq!(Spends::<Test>::get(PROJECT_A), None);
+		System::assert_last_event(
+			Event::<Test>::RewardClaimed { project_id: PROJECT_A, amount: 750 }.into(),
+		);
+		assert_noop!(
+			Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A),
+			Error::<Test>::NoPendingSpend
+		);
+	});
+}
+
+#[test]
+fn outvoted_projects_get_nothing() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A, PROJECT_B]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(BOB), PROJECT_A, 200, false));
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(CAROL), PROJECT_B, 100, true));
+
+		run_to_block(13);
+
+		// PROJECT_A's opposition outweighs its support, so PROJECT_B takes the whole pot.
+		assert_eq!(Spends::<Test>::get(PROJECT_A), None);
+		assert_eq!(
+			Spends::<Test>::get(PROJECT_B),
+			Some(SpendInfo { amount: 1_000, claimed: 0, vesting_start: 13, expire: 33, claim_extension: 0 })
+		);
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+	});
+}
+
+#[test]
+fn slow_tally_delays_the_next_round() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaxTallyStepsPerBlock::set(1);
+		register(&[PROJECT_A, PROJECT_B]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 300, true));
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(BOB), PROJECT_B, 100, true));
+
+		// One project is tallied per block, so the round is still tallying after its
+		// ending block: PROJECT_A's votes are released, PROJECT_B's still held.
+		run_to_block(13);
+		assert_eq!(Opf::current_round().unwrap().phase, RoundPhase::Tallying);
+		assert_eq!(TallyCursor::<Test>::get(), 1);
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+		assert_eq!(Balances::free_balance(BOB), 900);
+		assert_eq!(Spends::<Test>::get(PROJECT_A), None);
+
+		// No new round can interleave with the unfinished tally: the batch is queued.
+		register(&[20]);
+		System::assert_last_event(
+			Event::<Test>::RoundStartDelayed { waiting_for_round: 0 }.into(),
+		);
+		assert_eq!(VotingRoundNumber::<Test>::get(), 1);
+		assert_eq!(QueuedProjects::<Test>::get().to_vec(), vec![20]);
+		assert!(WhitelistedProjects::<Test>::get().contains(&PROJECT_B));
+		assert_noop!(
+			Opf::vote(RuntimeOrigin::signed(CAROL), PROJECT_B, 100, true),
+			Error::<Test>::NoActiveRound
+		);
+
+		// The next block finishes the tally: allocations only reflect round 0's votes,
+		// and the queued batch seeds round 1.
+		run_to_block(14);
+		assert_eq!(
+			Spends::<Test>::get(PROJECT_A),
+			Some(SpendInfo { amount: 750, claimed: 0, vesting_start: 14, expire: 34, claim_extension: 0 })
+		);
+		assert_eq!(
+			Spends::<Test>::get(PROJECT_B),
+			Some(SpendInfo { amount: 250, claimed: 0, vesting_start: 14, expire: 34, claim_extension: 0 })
+		);
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+		System::assert_has_event(Event::<Test>::RoundFinalized { round_number: 0 }.into());
+		System::assert_has_event(
+			Event::<Test>::RoundStarted { round_number: 1, round_ending_block: 26 }.into(),
+		);
+		let round = Opf::current_round().unwrap();
+		assert_eq!(round.round_number, 1);
+		assert_eq!(round.phase, RoundPhase::Registration);
+		assert_eq!(WhitelistedProjects::<Test>::get().to_vec(), vec![20]);
+		assert!(QueuedProjects::<Test>::get().is_empty());
+		assert_eq!(ProjectFunds::<Test>::get(20), ProjectFundsInfo::default());
+	});
+}
+
+#[test]
+fn unclaimed_spends_expire_and_are_discarded() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+		assert!(Spends::<Test>::get(PROJECT_A).is_some());
+
+		// The claim window is open up to and including the expiry block.
+		run_to_block(33);
+		System::set_block_number(34);
+		assert_noop!(
+			Opf::claim_reward_for(RuntimeOrigin::signed(ALICE), PROJECT_A),
+			Error::<Test>::ExpiredClaim
+		);
+
+		// The lazy purge discards the spend; its amount simply stays in the pot.
+		run_to_block(35);
+		assert_eq!(Spends::<Test>::get(PROJECT_A), None);
+		System::assert_has_event(
+			Event::<Test>::SpendDiscarded { project_id: PROJECT_A, amount: 1_000 }.into(),
+		);
+		assert_eq!(Balances::free_balance(Opf::pot_account()), 1_000);
+	});
+}
+
+#[test]
+fn escrow_unclaimed_preserves_an_expired_allocation() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+		let escrow_before = Balances::free_balance(BOB);
+
+		// The claim window is still open; the takeover only applies to expired claims.
+		assert_noop!(
+			Opf::escrow_unclaimed(RuntimeOrigin::signed(ALICE), PROJECT_A, BOB),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Opf::escrow_unclaimed(RuntimeOrigin::root(), PROJECT_A, BOB),
+			Error::<Test>::ClaimNotExpired
+		);
+
+		// Expired at 34, but the lazy purge has not run yet.
+		run_to_block(33);
+		System::set_block_number(34);
+		assert_ok!(Opf::escrow_unclaimed(RuntimeOrigin::root(), PROJECT_A, BOB));
+		assert_eq!(Balances::free_balance(BOB), escrow_before + 1_000);
+		assert_eq!(Balances::free_balance(Opf::pot_account()), 0);
+		assert_eq!(Spends::<Test>::get(PROJECT_A), None);
+		assert_eq!(EscrowedClaims::<Test>::get(PROJECT_A), Some((1_000, BOB, 34)));
+		System::assert_last_event(
+			Event::<Test>::ClaimEscrowed { project_id: PROJECT_A, amount: 1_000, escrow: BOB, at: 34 }
+				.into(),
+		);
+
+		// The purge must not forfeit the escrowed claim on top.
+		run_to_block(35);
+		assert!(!System::events().iter().any(|record| record.event ==
+			Event::<Test>::SpendDiscarded { project_id: PROJECT_A, amount: 1_000 }.into()));
+		assert_eq!(EscrowedClaims::<Test>::get(PROJECT_A), Some((1_000, BOB, 34)));
+		assert_eq!(Balances::free_balance(Opf::pot_account()), 0);
+	});
+}
+
+#[test]
+fn escrow_loses_the_race_against_the_purge() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+
+		// The purge got there first: the spend is gone and its amount stays in the pot.
+		run_to_block(35);
+		assert_eq!(Spends::<Test>::get(PROJECT_A), None);
+		assert_noop!(
+			Opf::escrow_unclaimed(RuntimeOrigin::root(), PROJECT_A, BOB),
+			Error::<Test>::NoPendingSpend
+		);
+		assert_eq!(Balances::free_balance(Opf::pot_account()), 1_000);
+	});
+}
+
+#[test]
+fn release_escrow_returns_the_funds_to_the_pot() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+		let escrow_before = Balances::free_balance(BOB);
+
+		assert_noop!(
+			Opf::release_escrow(RuntimeOrigin::root(), PROJECT_A),
+			Error::<Test>::NoEscrowedClaim
+		);
+
+		run_to_block(33);
+		System::set_block_number(34);
+		assert_ok!(Opf::escrow_unclaimed(RuntimeOrigin::root(), PROJECT_A, BOB));
+
+		// Stewardship never materialized: the escrow flows back into the pot.
+		assert_ok!(Opf::release_escrow(RuntimeOrigin::root(), PROJECT_A));
+		assert_eq!(Balances::free_balance(BOB), escrow_before);
+		assert_eq!(Balances::free_balance(Opf::pot_account()), 1_000);
+		assert_eq!(EscrowedClaims::<Test>::get(PROJECT_A), None);
+		System::assert_last_event(
+			Event::<Test>::EscrowReleased { project_id: PROJECT_A, amount: 1_000 }.into(),
+		);
+		assert_noop!(
+			Opf::release_escrow(RuntimeOrigin::root(), PROJECT_A),
+			Error::<Test>::NoEscrowedClaim
+		);
+	});
+}
+
+#[test]
+fn extend_claim_window_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+
+		assert_noop!(
+			Opf::extend_claim_window(RuntimeOrigin::signed(ALICE), PROJECT_A, 10),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Opf::extend_claim_window(RuntimeOrigin::root(), PROJECT_B, 10),
+			Error::<Test>::NoPendingSpend
+		);
+
+		assert_ok!(Opf::extend_claim_window(RuntimeOrigin::root(), PROJECT_A, 10));
+		System::assert_last_event(
+			Event::<Test>::ClaimWindowExtended { project_id: PROJECT_A, new_expire: 43 }.into(),
+		);
+
+		// The spend survives past its original expiry and can still be claimed.
+		run_to_block(37);
+		assert!(Spends::<Test>::get(PROJECT_A).is_some());
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(ALICE), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 1_000);
+	});
+}
+
+#[test]
+fn extend_claim_window_is_bounded_cumulatively() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+
+		// Extensions accumulate against `MaxClaimExtension` (30 in the mock).
+		assert_ok!(Opf::extend_claim_window(RuntimeOrigin::root(), PROJECT_A, 20));
+		assert_noop!(
+			Opf::extend_claim_window(RuntimeOrigin::root(), PROJECT_A, 15),
+			Error::<Test>::ExtensionTooLarge
+		);
+		assert_ok!(Opf::extend_claim_window(RuntimeOrigin::root(), PROJECT_A, 10));
+		assert_eq!(
+			Spends::<Test>::get(PROJECT_A),
+			Some(SpendInfo { amount: 1_000, claimed: 0, vesting_start: 13, expire: 63, claim_extension: 30 })
+		);
+		assert_noop!(
+			Opf::extend_claim_window(RuntimeOrigin::root(), PROJECT_A, 1),
+			Error::<Test>::ExtensionTooLarge
+		);
+	});
+}
+
+#[test]
+fn finalization_records_the_round_summary() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaxTallyStepsPerBlock::set(1);
+		register(&[PROJECT_A, PROJECT_B]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 300, true));
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(BOB), PROJECT_A, 100, true));
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_B, 50, true));
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(CAROL), PROJECT_B, 200, false));
+
+		// The tally spans two blocks; after the first, only PROJECT_A's voters are counted.
+		run_to_block(13);
+		assert_eq!(TallyVoterCount::<Test>::get(), 2);
+		assert!(CountedVoters::<Test>::contains_key(ALICE));
+
+		// ALICE voted on both projects but counts once; PROJECT_B is outvoted, so the sole
+		// funded project takes the whole pot and nothing is carried forward.
+		run_to_block(14);
+		let summary = RoundSummary {
+			round: 0,
+			budget: 1_000,
+			distributed: 1_000,
+			carried_forward: 0,
+			projects_funded: 1,
+			projects_rejected: 1,
+			voters: 3,
+		};
+		assert_eq!(RoundSummaries::<Test>::get(0), Some(summary.clone()));
+		assert_eq!(Opf::round_summary(0), Some(summary));
+		System::assert_has_event(
+			Event::<Test>::RoundSummary {
+				round: 0,
+				budget: 1_000,
+				distributed: 1_000,
+				carried_forward: 0,
+				projects_funded: 1,
+				projects_rejected: 1,
+				voters: 3,
+			}
+			.into(),
+		);
+
+		// The tally's voter bookkeeping does not leak into the next round.
+		assert_eq!(TallyVoterCount::<Test>::get(), 0);
+		assert!(CountedVoters::<Test>::iter().next().is_none());
+	});
+}
+
+#[test]
+fn decay_factor_is_linear_with_clamped_boundaries() {
+	let floor = Permill::from_percent(40);
+	// A vote cast at or before the opening of voting keeps its full weight.
+	assert_eq!(decay_factor(floor, 10u64, 20, 5), Permill::one());
+	assert_eq!(decay_factor(floor, 10u64, 20, 10), Permill::one());
+	// Halfway through the span, half of the decayable weight is gone.
+	assert_eq!(decay_factor(floor, 10u64, 20, 15), Permill::from_percent(70));
+	// At or past the round's end only the floor remains.
+	assert_eq!(decay_factor(floor, 10u64, 20, 20), floor);
+	assert_eq!(decay_factor(floor, 10u64, 20, 25), floor);
+	// A degenerate span cannot decay.
+	assert_eq!(decay_factor(floor, 10u64, 10, 12), Permill::one());
+}
+
+#[test]
+fn replacing_a_vote_re_stamps_its_cast_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		assert_eq!(Votes::<Test>::get(PROJECT_A, ALICE).unwrap().cast_at, 3);
+
+		// Updating the vote moves the stamp: late changes cost weight on purpose.
+		run_to_block(7);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		assert_eq!(Votes::<Test>::get(PROJECT_A, ALICE).unwrap().cast_at, 7);
+	});
+}
+
+#[test]
+fn decaying_rounds_weight_early_votes_heavier() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Opf::set_vote_decay(RuntimeOrigin::signed(ALICE), Some(Permill::from_percent(50))),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Opf::set_vote_decay(RuntimeOrigin::root(), Some(Permill::from_percent(50))));
+		System::assert_last_event(
+			Event::<Test>::VoteDecayUpdated { decay_floor: Some(Permill::from_percent(50)) }
+				.into(),
+		);
+
+		// Voting runs from 3 to 13 with weight decaying from 100% to 50%.
+		register(&[PROJECT_A, PROJECT_B]);
+		assert_eq!(Opf::current_round().unwrap().decay_floor, Some(Permill::from_percent(50)));
+
+		// Two identical votes: ALICE's at the opening block, BOB's near the end. At
+		// block 11 eight of the ten voting blocks have elapsed, so BOB's vote weighs
+		// 1 - 0.8 * 0.5 = 60%.
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(11);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(BOB), PROJECT_B, 100, true));
+
+		// The projection applies the same weighting as the eventual tally: the pot of
+		// 1000 splits 100:60.
+		assert_eq!(Opf::projected_allocations(), vec![(PROJECT_A, 625), (PROJECT_B, 375)]);
+
+		run_to_block(13);
+		assert_eq!(Spends::<Test>::get(PROJECT_A).unwrap().amount, 625);
+		assert_eq!(Spends::<Test>::get(PROJECT_B).unwrap().amount, 375);
+		// Holds are released in full, whatever the decayed tally weight.
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+
+		// Clearing the floor restores equal weights for subsequently started rounds.
+		assert_ok!(Opf::set_vote_decay(RuntimeOrigin::root(), None));
+		register(&[PROJECT_A]);
+		assert_eq!(Opf::current_round().unwrap().decay_floor, None);
+	});
+}
+
+#[test]
+fn round_summaries_are_pruned() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaxRetainedRounds::set(2);
+
+		// Three voteless rounds back to back: nothing is funded, the whole budget is
+		// carried forward each time.
+		for ending_block in [13, 25, 37] {
+			register(&[PROJECT_A]);
+			run_to_block(ending_block);
+		}
+		assert_eq!(
+			Opf::round_summary(2),
+			Some(RoundSummary {
+				round: 2,
+				budget: 1_000,
+				distributed: 0,
+				carried_forward: 1_000,
+				projects_funded: 0,
+				projects_rejected: 1,
+				voters: 0,
+			})
+		);
+		assert!(RoundSummaries::<Test>::get(1).is_some());
+		// Only the last `MaxRetainedRounds` summaries are kept.
+		assert_eq!(Opf::round_summary(0), None);
+	});
+}
+
+#[test]
+fn partial_claims_release_only_the_vested_amount() {
+	ExtBuilder::default().build().execute_with(|| {
+		RewardVestingPeriod::set(10);
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		// The round settles at 13; the reward of 1000 vests linearly until 23.
+		run_to_block(13);
+		assert_eq!(Spends::<Test>::get(PROJECT_A).unwrap().amount, 1_000);
+
+		// Nothing has vested at the settlement block itself.
+		assert_noop!(
+			Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A),
+			Error::<Test>::NothingVested
+		);
+
+		// Four of the ten vesting blocks have passed: 40% is payable.
+		run_to_block(17);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 400);
+		System::assert_last_event(
+			Event::<Test>::RewardClaimed { project_id: PROJECT_A, amount: 400 }.into(),
+		);
+		assert_eq!(Spends::<Test>::get(PROJECT_A).unwrap().claimed, 400);
+
+		// Claiming again in the same block finds nothing newly vested.
+		assert_noop!(
+			Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A),
+			Error::<Test>::NothingVested
+		);
+
+		// Three blocks later only the newly vested 30% is released.
+		run_to_block(20);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 700);
+		assert_eq!(Spends::<Test>::get(PROJECT_A).unwrap().claimed, 700);
+	});
+}
+
+#[test]
+fn full_reward_is_claimable_only_after_the_vesting_period() {
+	ExtBuilder::default().build().execute_with(|| {
+		RewardVestingPeriod::set(10);
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+
+		// One block before the period ends, a sliver is still unvested.
+		run_to_block(22);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 900);
+		assert!(Spends::<Test>::get(PROJECT_A).is_some());
+
+		// Once the period has passed, the remainder pays out and the spend is removed.
+		run_to_block(23);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 1_000);
+		assert_eq!(Spends::<Test>::get(PROJECT_A), None);
+
+		// A fully vested, untouched reward pays out in a single claim.
+		let _ = Balances::mint_into(&Opf::pot_account(), 1_000);
+		register(&[PROJECT_B]);
+		run_to_block(25);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(BOB), PROJECT_B, 100, true));
+		run_to_block(35);
+		run_to_block(46);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_B));
+		assert_eq!(Spends::<Test>::get(PROJECT_B), None);
+	});
+}
+
+#[test]
+fn partially_claimed_spends_expire_with_their_remainder() {
+	ExtBuilder::default().build().execute_with(|| {
+		RewardVestingPeriod::set(10);
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+
+		run_to_block(18);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 500);
+
+		// Only the unclaimed half is discarded at expiry and stays in the pot.
+		run_to_block(35);
+		assert_eq!(Spends::<Test>::get(PROJECT_A), None);
+		System::assert_has_event(
+			Event::<Test>::SpendDiscarded { project_id: PROJECT_A, amount: 500 }.into(),
+		);
+		assert_eq!(Balances::free_balance(Opf::pot_account()), 500);
+	});
+}
+
+#[test]
+fn payout_account_redirects_claims() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+
+		// No spend is pending yet, so the redirection applies immediately.
+		assert_ok!(Opf::set_payout_account(RuntimeOrigin::signed(PROJECT_A), BOB));
+		System::assert_last_event(
+			Event::<Test>::PayoutAccountSet { project_id: PROJECT_A, payout: BOB }.into(),
+		);
+		assert_eq!(PayoutAccounts::<Test>::get(PROJECT_A), Some(BOB));
+
+		// The reward lands on the payout account, not on the project account.
+		run_to_block(13);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 0);
+		assert_eq!(Balances::free_balance(BOB), 2_000);
+	});
+}
+
+#[test]
+fn payout_change_with_pending_spend_waits_for_the_delay() {
+	ExtBuilder::default().build().execute_with(|| {
+		PayoutChangeDelay::set(5);
+		RewardVestingPeriod::set(10);
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+
+		// The round settles at 13; with a claimable spend on the books the change is only
+		// scheduled.
+		run_to_block(13);
+		assert!(Spends::<Test>::contains_key(PROJECT_A));
+		assert_ok!(Opf::set_payout_account(RuntimeOrigin::signed(PROJECT_A), BOB));
+		System::assert_last_event(
+			Event::<Test>::PayoutAccountChangeScheduled {
+				project_id: PROJECT_A,
+				payout: BOB,
+				effective_at: 18,
+			}
+			.into(),
+		);
+		assert_eq!(PayoutAccounts::<Test>::get(PROJECT_A), None);
+
+		// Claims inside the delay window still pay the old destination.
+		run_to_block(15);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 200);
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+
+		// Once the delay has passed, the next claim promotes the change and pays the new
+		// account.
+		run_to_block(18);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		System::assert_has_event(
+			Event::<Test>::PayoutAccountSet { project_id: PROJECT_A, payout: BOB }.into(),
+		);
+		assert_eq!(Balances::free_balance(PROJECT_A), 200);
+		assert_eq!(Balances::free_balance(BOB), 1_300);
+		assert_eq!(PayoutAccounts::<Test>::get(PROJECT_A), Some(BOB));
+		assert_eq!(PendingPayoutChanges::<Test>::get(PROJECT_A), None);
+	});
+}
+
+#[test]
+fn rewards_default_to_the_project_account() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+		run_to_block(13);
+
+		// Without a designated payout account the project account itself is paid.
+		assert_eq!(PayoutAccounts::<Test>::get(PROJECT_A), None);
+		assert_ok!(Opf::claim_reward_for(RuntimeOrigin::signed(CAROL), PROJECT_A));
+		assert_eq!(Balances::free_balance(PROJECT_A), 1_000);
+	});
+}
+
+#[test]
+fn relisting_of_expired_projects_respects_the_budget() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaxRelistsPerRound::set(2);
+		register(&[PROJECT_A, PROJECT_B, 20]);
+		run_to_block(3);
+		for project_id in [PROJECT_A, PROJECT_B, 20] {
+			assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), project_id, 100, true));
+		}
+
+		// All three spends expire unclaimed at 33; only two fit the relisting budget.
+		run_to_block(34);
+		assert!(Spends::<Test>::iter().next().is_none());
+		let events = System::events();
+		let relisted = events
+			.iter()
+			.filter(|r| matches!(r.event, RuntimeEvent::Opf(Event::ProjectRelisted { .. })))
+			.count();
+		let discarded = events
+			.iter()
+			.filter(|r| matches!(r.event, RuntimeEvent::Opf(Event::ProjectDiscarded { .. })))
+			.count();
+		assert_eq!((relisted, discarded), (2, 1));
+		assert_eq!(RelistQueue::<Test>::get().len(), 2);
+
+		// The queued projects seed the next round's whitelist alongside the new batch.
+		let queued = RelistQueue::<Test>::get();
+		register(&[21]);
+		let whitelisted = WhitelistedProjects::<Test>::get();
+		assert_eq!(whitelisted.len(), 3);
+		assert!(whitelisted.contains(&21));
+		for project_id in queued {
+			assert!(whitelisted.contains(&project_id));
+		}
+		assert!(RelistQueue::<Test>::get().is_empty());
+	});
+}
+
+#[test]
+fn zero_relisting_budget_discards_expired_projects_outright() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(&[PROJECT_A]);
+		run_to_block(3);
+		assert_ok!(Opf::vote(RuntimeOrigin::signed(ALICE), PROJECT_A, 100, true));
+
+		run_to_block(34);
+		System::assert_last_event(
+			Event::<Test>::SpendDiscarded { project_id: PROJECT_A, amount: 1_000 }.into(),
+		);
+		assert!(RelistQueue::<Test>::get().is_empty());
+		let noise = System::events().iter().any(|r| {
+			matches!(
+				r.event,
+				RuntimeEvent::Opf(Event::ProjectRelisted { .. }) |
+					RuntimeEvent::Opf(Event::ProjectDiscarded { .. })
+			)
+		});
+		assert!(!noise);
+	});
+}
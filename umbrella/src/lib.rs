@@ -292,6 +292,10 @@ pub use frame_try_runtime;
 #[cfg(feature = "generate-bags")]
 pub use generate_bags;
 
+/// Shared types and traits for the Honzon (CDP) pallets.
+#[cfg(feature = "honzon-support")]
+pub use honzon_support;
+
 /// MMR Client gadget for substrate.
 #[cfg(feature = "mmr-gadget")]
 pub use mmr_gadget;
@@ -348,6 +352,14 @@ pub use pallet_assets_precompiles;
 #[cfg(feature = "pallet-atomic-swap")]
 pub use pallet_atomic_swap;
 
+/// FRAME pallet implementing a generic auction scheduler driven by an auction handler.
+#[cfg(feature = "pallet-auction")]
+pub use pallet_auction;
+
+/// FRAME pallet managing the collateral auctions of the Honzon protocol.
+#[cfg(feature = "pallet-auction-manager")]
+pub use pallet_auction_manager;
+
 /// FRAME AURA consensus pallet.
 #[cfg(feature = "pallet-aura")]
 pub use pallet_aura;
@@ -406,6 +418,14 @@ pub use pallet_bridge_relayers;
 #[cfg(feature = "pallet-broker")]
 pub use pallet_broker;
 
+/// FRAME pallet implementing the risk management and liquidation engine for CDPs.
+#[cfg(feature = "pallet-cdp-engine")]
+pub use pallet_cdp_engine;
+
+/// Runtime API for the CDP engine pallet.
+#[cfg(feature = "pallet-cdp-engine-runtime-api")]
+pub use pallet_cdp_engine_runtime_api;
+
 /// FRAME pallet to manage child bounties.
 #[cfg(feature = "pallet-child-bounties")]
 pub use pallet_child_bounties;
@@ -483,6 +503,10 @@ pub use pallet_election_provider_support_benchmarking;
 #[cfg(feature = "pallet-elections-phragmen")]
 pub use pallet_elections_phragmen;
 
+/// FRAME pallet implementing the emergency shutdown procedure of the Honzon protocol.
+#[cfg(feature = "pallet-emergency-shutdown")]
+pub use pallet_emergency_shutdown;
+
 /// FRAME fast unstake pallet.
 #[cfg(feature = "pallet-fast-unstake")]
 pub use pallet_fast_unstake;
@@ -495,6 +519,10 @@ pub use pallet_glutton;
 #[cfg(feature = "pallet-grandpa")]
 pub use pallet_grandpa;
 
+/// FRAME pallet providing the user-facing extrinsics of the Honzon protocol.
+#[cfg(feature = "pallet-honzon")]
+pub use pallet_honzon;
+
 /// FRAME identity management pallet.
 #[cfg(feature = "pallet-identity")]
 pub use pallet_identity;
@@ -511,6 +539,10 @@ pub use pallet_indices;
 #[cfg(feature = "pallet-insecure-randomness-collective-flip")]
 pub use pallet_insecure_randomness_collective_flip;
 
+/// FRAME pallet that manages the debit and collateral bookkeeping of CDPs.
+#[cfg(feature = "pallet-loans")]
+pub use pallet_loans;
+
 /// FRAME Participation Lottery Pallet.
 #[cfg(feature = "pallet-lottery")]
 pub use pallet_lottery;
@@ -539,6 +571,10 @@ pub use pallet_mixnet;
 #[cfg(feature = "pallet-mmr")]
 pub use pallet_mmr;
 
+/// FRAME pallet to manage bounties denominated in arbitrary asset kinds.
+#[cfg(feature = "pallet-multi-asset-bounties")]
+pub use pallet_multi_asset_bounties;
+
 /// FRAME multi-signature dispatch pallet.
 #[cfg(feature = "pallet-multisig")]
 pub use pallet_multisig;
@@ -583,6 +619,10 @@ pub use pallet_offences;
 #[cfg(feature = "pallet-offences-benchmarking")]
 pub use pallet_offences_benchmarking;
 
+/// FRAME pallet distributing a funding pot across projects by optimistic voting rounds.
+#[cfg(feature = "pallet-opf")]
+pub use pallet_opf;
+
 /// FRAME oracle pallet for off-chain data.
 #[cfg(feature = "pallet-oracle")]
 pub use pallet_oracle;